@@ -0,0 +1,547 @@
+use super::constant::*;
+use super::stack;
+use super::{Lines, Tokens};
+use crate::disassembler::constant_pool::*;
+use crate::disassembler::descriptor;
+use crate::disassembler::instruction::{self, Instruction};
+
+use std::collections::HashMap;
+use std::io::BufReader;
+
+const ARRAY_TYPES: [&str; 8] = [
+    "boolean", "char", "float", "double", "byte", "short", "int", "long",
+];
+
+struct PendingInstruction {
+    instruction: Instruction,
+    // label names of the branch targets, for switches the default comes first
+    targets: Vec<String>,
+    line: usize,
+}
+
+struct Catch {
+    line: usize,
+    start: String,
+    end: String,
+    handler: String,
+    catch_type: u16,
+}
+
+#[derive(Default)]
+pub struct MethodBody {
+    instructions: Vec<PendingInstruction>,
+    // label name to the index of the instruction following it
+    labels: HashMap<String, usize>,
+    catches: Vec<Catch>,
+    line_numbers: Vec<(usize, u16)>,
+    pub max_stack: Option<u16>,
+    pub max_locals: Option<u16>,
+}
+
+pub struct Code {
+    pub max_stack: u16,
+    pub max_locals: u16,
+    pub code: Vec<u8>,
+    // start_pc, end_pc, handler_pc and catch_type
+    pub exception_table: Vec<[u16; 4]>,
+    // start_pc and line_number
+    pub line_numbers: Vec<[u16; 2]>,
+}
+
+impl MethodBody {
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+    pub fn add_label(&mut self, name: &str) -> Result<(), String> {
+        if self.labels.contains_key(name) {
+            return Err(format!("Duplicate label: {}", name));
+        }
+        self.labels
+            .insert(name.to_string(), self.instructions.len());
+        Ok(())
+    }
+
+    pub fn add_line_number(&mut self, line_number: u16) {
+        self.line_numbers
+            .push((self.instructions.len(), line_number));
+    }
+
+    // .catch <class|any> from <label> to <label> using <label>
+    pub fn add_catch(
+        &mut self,
+        constant_pool: &mut ConstantPool,
+        tokens: &mut Tokens,
+        lines: &Lines,
+    ) -> Result<(), String> {
+        let catch_type = if tokens.peek() == Some("any") {
+            tokens.next()?;
+            0
+        } else {
+            read_class(constant_pool, tokens)?
+        };
+
+        tokens.expect("from")?;
+        let start = tokens.next()?;
+        tokens.expect("to")?;
+        let end = tokens.next()?;
+        tokens.expect("using")?;
+        let handler = tokens.next()?;
+        tokens.end()?;
+
+        self.catches.push(Catch {
+            line: lines.number,
+            start,
+            end,
+            handler,
+            catch_type,
+        });
+        Ok(())
+    }
+
+    pub fn add_instruction(
+        &mut self,
+        constant_pool: &mut ConstantPool,
+        tokens: &mut Tokens,
+        lines: &mut Lines,
+    ) -> Result<(), String> {
+        let line = lines.number;
+        let name = tokens.next()?;
+        let opcode =
+            opcode(name.as_str()).ok_or_else(|| format!("Unknown instruction: {}", name))?;
+        let mut targets = Vec::new();
+
+        let instruction = match opcode {
+            0x10 => Instruction::BiPush(parse_number::<i8>(&tokens.next()?, "")? as u8),
+            0x11 => Instruction::SiPush(parse_number::<i16>(&tokens.next()?, "")? as u16),
+            0x12 | 0x13 => {
+                let index = read_constant(constant_pool, tokens)?;
+                // an ldc that can't reach its constant is widened
+                if opcode == 0x12 && index <= u8::MAX as u16 {
+                    Instruction::Ldc(index as u8)
+                } else {
+                    Instruction::LdcW(index)
+                }
+            }
+            0x14 => Instruction::Ldc2W(read_constant(constant_pool, tokens)?),
+
+            0x15 => Instruction::ILoad(read_local(tokens)?),
+            0x16 => Instruction::LLoad(read_local(tokens)?),
+            0x17 => Instruction::FLoad(read_local(tokens)?),
+            0x18 => Instruction::DLoad(read_local(tokens)?),
+            0x19 => Instruction::ALoad(read_local(tokens)?),
+            0x36 => Instruction::IStore(read_local(tokens)?),
+            0x37 => Instruction::LStore(read_local(tokens)?),
+            0x38 => Instruction::FStore(read_local(tokens)?),
+            0x39 => Instruction::DStore(read_local(tokens)?),
+            0x3a => Instruction::AStore(read_local(tokens)?),
+            0xa9 => Instruction::Ret(read_local(tokens)?),
+            0x84 => Instruction::IInc(
+                read_local(tokens)?,
+                parse_number::<i16>(&tokens.next()?, "")?,
+            ),
+
+            0x99..=0xa8 | 0xc6 | 0xc7 => {
+                targets.push(tokens.next()?);
+                match opcode {
+                    0x99 => Instruction::IfEq(0),
+                    0x9a => Instruction::IfNe(0),
+                    0x9b => Instruction::IfLt(0),
+                    0x9c => Instruction::IfGe(0),
+                    0x9d => Instruction::IfGt(0),
+                    0x9e => Instruction::IfLe(0),
+                    0x9f => Instruction::IfICmpEq(0),
+                    0xa0 => Instruction::IfICmpNe(0),
+                    0xa1 => Instruction::IfICmpLt(0),
+                    0xa2 => Instruction::IfICmpGe(0),
+                    0xa3 => Instruction::IfICmpGt(0),
+                    0xa4 => Instruction::IfICmpLe(0),
+                    0xa5 => Instruction::IfACmpEq(0),
+                    0xa6 => Instruction::IfACmpNe(0),
+                    0xa7 => Instruction::GoTo(0),
+                    0xa8 => Instruction::Jsr(0),
+                    0xc6 => Instruction::IfNull(0),
+                    _ => Instruction::IfNonNull(0),
+                }
+            }
+            0xc8 => {
+                targets.push(tokens.next()?);
+                Instruction::GoToW(0)
+            }
+            0xc9 => {
+                targets.push(tokens.next()?);
+                Instruction::JsrW(0)
+            }
+            0xaa | 0xab => read_switch(opcode, tokens, lines, &mut targets)?,
+
+            0xb2 => Instruction::GetStatic(read_member(constant_pool, tokens, &["Field"])?),
+            0xb3 => Instruction::PutStatic(read_member(constant_pool, tokens, &["Field"])?),
+            0xb4 => Instruction::GetField(read_member(constant_pool, tokens, &["Field"])?),
+            0xb5 => Instruction::PutField(read_member(constant_pool, tokens, &["Field"])?),
+            0xb6 => Instruction::InvokeVirtual(read_member(
+                constant_pool,
+                tokens,
+                &["Method", "InterfaceMethod"],
+            )?),
+            0xb7 => Instruction::InvokeSpecial(read_member(
+                constant_pool,
+                tokens,
+                &["Method", "InterfaceMethod"],
+            )?),
+            0xb8 => Instruction::InvokeStatic(read_member(
+                constant_pool,
+                tokens,
+                &["Method", "InterfaceMethod"],
+            )?),
+            0xb9 => {
+                let index = read_member(constant_pool, tokens, &["InterfaceMethod"])?;
+                // the count includes the receiver
+                let method_descriptor = stack::member_descriptor(constant_pool, index);
+                let count = descriptor::argument_slots(method_descriptor.as_str()) + 1;
+                Instruction::InvokeInterface(index, count as u8)
+            }
+            0xba => Instruction::InvokeDynamic(read_invoke_dynamic(constant_pool, tokens)?),
+
+            0xbb => Instruction::New(read_class(constant_pool, tokens)?),
+            0xbd => Instruction::ANewArray(read_class(constant_pool, tokens)?),
+            0xc0 => Instruction::CheckCast(read_class(constant_pool, tokens)?),
+            0xc1 => Instruction::InstanceOf(read_class(constant_pool, tokens)?),
+            0xc5 => Instruction::MultiANewArray(
+                read_class(constant_pool, tokens)?,
+                parse_number::<u8>(&tokens.next()?, "")?,
+            ),
+            0xbc => {
+                let name = tokens.next()?;
+                let atype = ARRAY_TYPES
+                    .iter()
+                    .position(|array_type| *array_type == name.as_str())
+                    .ok_or_else(|| format!("Invalid array type: {}", name))?;
+                Instruction::NewArray(atype as u8 + 4)
+            }
+
+            0xc4 => return Err("wide is added automatically where it is needed".to_string()),
+
+            // everything else has no operands and can be decoded from the opcode alone
+            opcode => {
+                instruction::parse(BufReader::new(&[opcode][..]), 1)
+                    .map_err(|error| error.to_string())?
+                    .remove(0)
+                    .instruction
+            }
+        };
+        tokens.end()?;
+
+        self.instructions.push(PendingInstruction {
+            instruction,
+            targets,
+            line,
+        });
+        Ok(())
+    }
+
+    pub fn assemble(
+        mut self,
+        constant_pool: &ConstantPool,
+        argument_slots: u16,
+    ) -> Result<Code, (usize, String)> {
+        // the instruction lengths don't depend on the branch offsets, so the
+        // positions can be computed before the labels are resolved
+        let mut pcs = Vec::with_capacity(self.instructions.len() + 1);
+        let mut pc = 0;
+        for pending in &self.instructions {
+            pcs.push(pc);
+            pc += pending.instruction.length(pc);
+        }
+        pcs.push(pc);
+
+        let labels = &self.labels;
+        let label_pc = |name: &str| -> Result<usize, String> {
+            labels
+                .get(name)
+                .map(|index| pcs[*index])
+                .ok_or_else(|| format!("Unknown label: {}", name))
+        };
+
+        for (index, pending) in self.instructions.iter_mut().enumerate() {
+            let pc = pcs[index] as i64;
+            let mut offsets = Vec::new();
+            for target in &pending.targets {
+                offsets
+                    .push(label_pc(target).map_err(|message| (pending.line, message))? as i64 - pc);
+            }
+
+            resolve_offsets(&mut pending.instruction, &offsets)
+                .map_err(|message| (pending.line, message))?;
+        }
+
+        let mut exception_table = Vec::new();
+        for catch in &self.catches {
+            let pc = |name: &str| {
+                label_pc(name)
+                    .map(|pc| pc as u16)
+                    .map_err(|message| (catch.line, message))
+            };
+            exception_table.push([
+                pc(catch.start.as_str())?,
+                pc(catch.end.as_str())?,
+                pc(catch.handler.as_str())?,
+                catch.catch_type,
+            ]);
+        }
+
+        let mut code = Vec::new();
+        for (index, pending) in self.instructions.iter().enumerate() {
+            pending.instruction.write(pcs[index], &mut code);
+        }
+
+        let max_stack = match self.max_stack {
+            Some(max_stack) => max_stack,
+            None => self.compute_max_stack(constant_pool, &pcs, &exception_table)?,
+        };
+
+        let max_locals = match self.max_locals {
+            Some(max_locals) => max_locals,
+            None => self
+                .instructions
+                .iter()
+                .filter_map(|pending| stack::local_slots(&pending.instruction))
+                .map(|(index, size)| index + size)
+                .fold(argument_slots, u16::max),
+        };
+
+        Ok(Code {
+            max_stack,
+            max_locals,
+            code,
+            exception_table,
+            line_numbers: self
+                .line_numbers
+                .iter()
+                .filter(|(index, _)| *index < self.instructions.len())
+                .map(|(index, line_number)| [pcs[*index] as u16, *line_number])
+                .collect(),
+        })
+    }
+
+    // Follows every path through the code and records the operand stack height at each instruction
+    fn compute_max_stack(
+        &self,
+        constant_pool: &ConstantPool,
+        pcs: &[usize],
+        exception_table: &[[u16; 4]],
+    ) -> Result<u16, (usize, String)> {
+        let index_of = |pc: usize| pcs.binary_search(&pc).unwrap_or(0);
+        let mut heights: Vec<Option<i32>> = vec![None; self.instructions.len()];
+        let mut work = vec![(0, 0)];
+        let mut max_stack = 0;
+
+        // handlers start with the exception on the stack
+        for entry in exception_table {
+            work.push((index_of(entry[2] as usize), 1));
+        }
+
+        while let Some((index, height)) = work.pop() {
+            if index >= self.instructions.len() {
+                continue;
+            }
+
+            let pending = &self.instructions[index];
+            match heights[index] {
+                Some(known) if known == height => continue,
+                Some(known) => {
+                    return Err((
+                        pending.line,
+                        format!("Inconsistent stack height {} != {}", known, height),
+                    ))
+                }
+                None => heights[index] = Some(height),
+            }
+
+            let after = height + stack::stack_effect(&pending.instruction, constant_pool);
+            if after < 0 {
+                return Err((pending.line, "Operand stack underflow".to_string()));
+            }
+            max_stack = max_stack.max(after);
+
+            for target in &pending.targets {
+                work.push((self.labels[target], after));
+            }
+
+            match pending.instruction {
+                Instruction::GoTo(_)
+                | Instruction::GoToW(_)
+                | Instruction::Ret(_)
+                | Instruction::TableSwitch { .. }
+                | Instruction::LookUpSwitch { .. }
+                | Instruction::IReturn
+                | Instruction::LReturn
+                | Instruction::FReturn
+                | Instruction::DReturn
+                | Instruction::AReturn
+                | Instruction::Return
+                | Instruction::AThrow => {}
+                // the subroutine pops its return address before it returns
+                Instruction::Jsr(_) | Instruction::JsrW(_) => work.push((index + 1, height)),
+                _ => work.push((index + 1, after)),
+            }
+        }
+
+        Ok(max_stack as u16)
+    }
+}
+
+// Looks up an opcode by its mnemonic, the `_w` forms javap prints for wide instructions are accepted too
+pub fn opcode(name: &str) -> Option<u8> {
+    let find =
+        |name: &str| (0..=u8::MAX).find(|opcode| instruction::mnemonic(*opcode) == Some(name));
+
+    find(name).or_else(|| match name.strip_suffix("_w") {
+        Some(
+            base @ ("iload" | "lload" | "fload" | "dload" | "aload" | "istore" | "lstore"
+            | "fstore" | "dstore" | "astore" | "ret" | "iinc"),
+        ) => find(base),
+        _ => None,
+    })
+}
+
+fn read_local(tokens: &mut Tokens) -> Result<u16, String> {
+    parse_number::<u16>(&tokens.next()?, "")
+}
+
+// InvokeDynamic #<bootstrap>:<name>:<descriptor>
+fn read_invoke_dynamic(
+    constant_pool: &mut ConstantPool,
+    tokens: &mut Tokens,
+) -> Result<u16, String> {
+    if tokens.peek() == Some("InvokeDynamic") {
+        tokens.next()?;
+    }
+
+    let token = tokens.next()?;
+    let invalid = || format!("Invalid invokedynamic operand: {}", token);
+    let (bootstrap, name_and_type) = token
+        .strip_prefix('#')
+        .and_then(|rest| rest.split_once(':'))
+        .ok_or_else(invalid)?;
+
+    let boostrap_method_attr_index = bootstrap.parse::<u16>().map_err(|_| invalid())?;
+    let (name, descriptor) = split_name_and_type(name_and_type)?;
+    let name_and_type_index = constant_pool.add_name_and_type(name.as_str(), descriptor.as_str());
+
+    Ok(constant_pool.add(CPIndexType::InvokeDynamic {
+        boostrap_method_attr_index,
+        name_and_type_index,
+    }))
+}
+
+// The cases follow on their own lines like javap prints them:
+// tableswitch {
+//     0: label
+//     default: label
+// }
+fn read_switch(
+    opcode: u8,
+    tokens: &mut Tokens,
+    lines: &mut Lines,
+    targets: &mut Vec<String>,
+) -> Result<Instruction, String> {
+    tokens.expect("{")?;
+
+    let mut default = None;
+    let mut cases: Vec<(i32, String)> = Vec::new();
+
+    loop {
+        let mut tokens = lines.next()?.ok_or("Unterminated switch")?;
+        let key = tokens.next()?;
+        if key == "}" {
+            tokens.end()?;
+            break;
+        }
+
+        let key = key.strip_suffix(':').ok_or("Expected a switch case")?;
+        let target = tokens.next()?;
+        tokens.end()?;
+
+        if key == "default" {
+            default = Some(target);
+        } else {
+            cases.push((parse_number::<i32>(key, "")?, target));
+        }
+    }
+
+    targets.push(default.ok_or("Switch without a default case")?);
+
+    if opcode == 0xaa {
+        let low = cases.first().map_or(0, |case| case.0);
+        for (i, (key, _)) in cases.iter().enumerate() {
+            if *key as i64 != low as i64 + i as i64 {
+                return Err("tableswitch keys have to be consecutive".to_string());
+            }
+        }
+        let high = low + cases.len() as i32 - 1;
+
+        targets.extend(cases.into_iter().map(|(_, target)| target));
+        Ok(Instruction::TableSwitch {
+            default: 0,
+            low,
+            high,
+            offsets: vec![0; (high - low + 1) as usize],
+        })
+    } else {
+        cases.sort_by_key(|case| case.0);
+        let pairs = cases.iter().map(|(key, _)| (*key, 0)).collect();
+
+        targets.extend(cases.into_iter().map(|(_, target)| target));
+        Ok(Instruction::LookUpSwitch { default: 0, pairs })
+    }
+}
+
+fn resolve_offsets(instruction: &mut Instruction, offsets: &[i64]) -> Result<(), String> {
+    let short = |offset: i64| -> Result<i16, String> {
+        if offset < i16::MIN as i64 || offset > i16::MAX as i64 {
+            return Err("Branch offset too large, use goto_w".to_string());
+        }
+        Ok(offset as i16)
+    };
+
+    match instruction {
+        Instruction::IfEq(offset)
+        | Instruction::IfNe(offset)
+        | Instruction::IfLt(offset)
+        | Instruction::IfGe(offset)
+        | Instruction::IfGt(offset)
+        | Instruction::IfLe(offset)
+        | Instruction::IfICmpEq(offset)
+        | Instruction::IfICmpNe(offset)
+        | Instruction::IfICmpLt(offset)
+        | Instruction::IfICmpGe(offset)
+        | Instruction::IfICmpGt(offset)
+        | Instruction::IfICmpLe(offset)
+        | Instruction::IfACmpEq(offset)
+        | Instruction::IfACmpNe(offset)
+        | Instruction::GoTo(offset)
+        | Instruction::Jsr(offset)
+        | Instruction::IfNull(offset)
+        | Instruction::IfNonNull(offset) => *offset = short(offsets[0])?,
+        Instruction::GoToW(offset) | Instruction::JsrW(offset) => *offset = offsets[0] as i32,
+        Instruction::TableSwitch {
+            default,
+            offsets: cases,
+            ..
+        } => {
+            *default = offsets[0] as i32;
+            for (case, offset) in cases.iter_mut().zip(&offsets[1..]) {
+                *case = *offset as i32;
+            }
+        }
+        Instruction::LookUpSwitch { default, pairs } => {
+            *default = offsets[0] as i32;
+            for (pair, offset) in pairs.iter_mut().zip(&offsets[1..]) {
+                pair.1 = *offset as i32;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
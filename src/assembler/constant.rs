@@ -0,0 +1,176 @@
+use super::lexer::unquote;
+use super::Tokens;
+use crate::disassembler::constant_pool::*;
+
+const REFERENCE_KINDS: [&str; 9] = [
+    "REF_getField",
+    "REF_getStatic",
+    "REF_putField",
+    "REF_putStatic",
+    "REF_invokeVirtual",
+    "REF_invokeStatic",
+    "REF_invokeSpecial",
+    "REF_newInvokeSpecial",
+    "REF_invokeInterface",
+];
+
+// Splits `owner.name:descriptor`, the owner and name may be quoted like javap prints them
+pub fn split_member(token: &str) -> Result<(String, String, String), String> {
+    let invalid = || format!("Invalid member reference: {}", token);
+
+    let colon = match token.rfind("\":") {
+        Some(quote) => quote + 1,
+        None => token.find(':').ok_or_else(invalid)?,
+    };
+    let (owner_and_name, descriptor) = (&token[..colon], &token[colon + 1..]);
+
+    let dot = match owner_and_name.strip_suffix('"') {
+        Some(quoted) => quoted.rfind(".\"").ok_or_else(invalid)?,
+        None => owner_and_name.rfind('.').ok_or_else(invalid)?,
+    };
+
+    Ok((
+        unquote(&owner_and_name[..dot])?,
+        unquote(&owner_and_name[dot + 1..])?,
+        descriptor.to_string(),
+    ))
+}
+
+// Splits `name:descriptor` of a NameAndType
+pub fn split_name_and_type(token: &str) -> Result<(String, String), String> {
+    let colon = match token.rfind("\":") {
+        Some(quote) => quote + 1,
+        None => token
+            .find(':')
+            .ok_or_else(|| format!("Invalid name and type: {}", token))?,
+    };
+
+    Ok((unquote(&token[..colon])?, token[colon + 1..].to_string()))
+}
+
+pub fn add_member(
+    constant_pool: &mut ConstantPool,
+    kind: &str,
+    token: &str,
+) -> Result<u16, String> {
+    let (owner, name, descriptor) = split_member(token)?;
+    let class_index = constant_pool.add_class(owner.as_str());
+    let name_and_type_index = constant_pool.add_name_and_type(name.as_str(), descriptor.as_str());
+
+    Ok(constant_pool.add(match kind {
+        "Field" => CPIndexType::FieldRef {
+            class_index,
+            name_and_type_index,
+        },
+        "Method" => CPIndexType::MethodRef {
+            class_index,
+            name_and_type_index,
+        },
+        "InterfaceMethod" => CPIndexType::InterfaceMethodRef {
+            class_index,
+            name_and_type_index,
+        },
+        kind => return Err(format!("Invalid member kind: {}", kind)),
+    }))
+}
+
+// Reads a member reference, the kind in front of it is optional
+pub fn read_member(
+    constant_pool: &mut ConstantPool,
+    tokens: &mut Tokens,
+    kinds: &[&str],
+) -> Result<u16, String> {
+    let mut kind = kinds[0];
+    if let Some(token) = tokens.peek() {
+        if let Some(explicit) = kinds.iter().find(|kind| **kind == token) {
+            kind = explicit;
+            tokens.next()?;
+        }
+    }
+
+    add_member(constant_pool, kind, &tokens.next()?)
+}
+
+// Reads a class name, optionally preceded by `class` like in the javap comments
+pub fn read_class(constant_pool: &mut ConstantPool, tokens: &mut Tokens) -> Result<u16, String> {
+    if tokens.peek() == Some("class") {
+        tokens.next()?;
+    }
+
+    let name = unquote(&tokens.next()?)?;
+    Ok(constant_pool.add_class(name.as_str()))
+}
+
+pub fn read_method_handle(
+    constant_pool: &mut ConstantPool,
+    tokens: &mut Tokens,
+) -> Result<u16, String> {
+    let kind = tokens.next()?;
+    let reference_type = REFERENCE_KINDS
+        .iter()
+        .position(|name| *name == kind.as_str())
+        .ok_or_else(|| format!("Invalid reference kind: {}", kind))? as u8
+        + 1;
+
+    let default_kind = match reference_type {
+        1..=4 => "Field",
+        9 => "InterfaceMethod",
+        _ => "Method",
+    };
+    let reference_index = read_member(
+        constant_pool,
+        tokens,
+        &[default_kind, "Field", "Method", "InterfaceMethod"],
+    )?;
+
+    Ok(constant_pool.add(CPIndexType::MethodHandle {
+        reference_type,
+        reference_index,
+    }))
+}
+
+// Reads a loadable constant in the form javap prints them in its comments,
+// e.g. `int 5`, `String "text"`, `class java/lang/Object` or `MethodType ()V`
+pub fn read_constant(constant_pool: &mut ConstantPool, tokens: &mut Tokens) -> Result<u16, String> {
+    let kind = tokens.next()?;
+
+    let entry = match kind.as_str() {
+        "int" => CPIndexType::Integer(parse_number::<i32>(&tokens.next()?, "")? as u32),
+        "float" => CPIndexType::Float(parse_number::<f32>(&tokens.next()?, "f")?),
+        "long" => CPIndexType::Long(parse_number::<i64>(&tokens.next()?, "l")? as u64),
+        "double" => CPIndexType::Double(parse_number::<f64>(&tokens.next()?, "d")?),
+        "String" => {
+            let string_index = constant_pool.add_utf8(unquote(&tokens.next()?)?.as_str());
+            CPIndexType::String(string_index)
+        }
+        "class" => {
+            let name_index = constant_pool.add_utf8(unquote(&tokens.next()?)?.as_str());
+            CPIndexType::Class(name_index)
+        }
+        "MethodType" => {
+            let descriptor_index = constant_pool.add_utf8(&tokens.next()?);
+            CPIndexType::MethodType { descriptor_index }
+        }
+        "MethodHandle" => return read_method_handle(constant_pool, tokens),
+        kind => return Err(format!("Invalid constant kind: {}", kind)),
+    };
+
+    Ok(constant_pool.add(entry))
+}
+
+// Parses numbers with an optional type suffix, `Infinity` and `NaN` are accepted for floats
+pub fn parse_number<T: std::str::FromStr>(token: &str, suffix: &str) -> Result<T, String> {
+    let number = if suffix.is_empty() {
+        token
+    } else {
+        token
+            .strip_suffix(suffix)
+            .or_else(|| token.strip_suffix(suffix.to_uppercase().as_str()))
+            .unwrap_or(token)
+    };
+
+    number
+        .replace("Infinity", "inf")
+        .parse::<T>()
+        .map_err(|_| format!("Invalid number: {}", token))
+}
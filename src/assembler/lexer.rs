@@ -0,0 +1,80 @@
+// Splits a line into whitespace separated tokens. Commas are treated like whitespace,
+// `//` starts a comment and double quoted parts of a token may contain anything.
+pub fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '"' => {
+                token.push(char);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => {
+                            token.push('\\');
+                            token.push(chars.next().ok_or("Unterminated string")?);
+                        }
+                        Some(char) => token.push(char),
+                        None => return Err("Unterminated string".to_string()),
+                    }
+                }
+                token.push('"');
+            }
+            '/' if token.is_empty() && chars.peek() == Some(&'/') => break,
+            char if char.is_whitespace() || char == ',' => {
+                if !token.is_empty() {
+                    tokens.push(token);
+                    token = String::new();
+                }
+            }
+            char => token.push(char),
+        }
+    }
+
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+// Removes the quotes around a token and resolves the escapes the disassembler emits
+pub fn unquote(token: &str) -> Result<String, String> {
+    if token.len() < 2 || !token.starts_with('"') || !token.ends_with('"') {
+        return Ok(token.to_string());
+    }
+
+    let mut units: Vec<u16> = Vec::new();
+    let mut chars = token[1..token.len() - 1].chars();
+
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            let mut buf = [0u16; 2];
+            units.extend_from_slice(char.encode_utf16(&mut buf));
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => units.push('\t' as u16),
+            Some('n') => units.push('\n' as u16),
+            Some('r') => units.push('\r' as u16),
+            Some('"') => units.push('"' as u16),
+            Some('\\') => units.push('\\' as u16),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                units.push(
+                    u16::from_str_radix(hex.as_str(), 16)
+                        .map_err(|_| format!("Invalid escape: \\u{}", hex))?,
+                );
+            }
+            escape => return Err(format!("Invalid escape: \\{}", escape.unwrap_or(' '))),
+        }
+    }
+
+    // escaped surrogates are recombined, lone ones can't be represented
+    Ok(std::char::decode_utf16(units)
+        .map(|c| c.unwrap_or(std::char::REPLACEMENT_CHARACTER))
+        .collect())
+}
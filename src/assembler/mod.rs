@@ -0,0 +1,876 @@
+mod code;
+mod constant;
+mod lexer;
+mod stack;
+
+use crate::disassembler::attribute::*;
+use crate::disassembler::constant_pool::*;
+use crate::disassembler::descriptor;
+use crate::disassembler::field::*;
+use crate::disassembler::interface::InterfacePool;
+use crate::disassembler::method::*;
+use crate::disassembler::ClassFile;
+
+// class files up to version 49 don't need a StackMapTable, which isn't generated
+const DEFAULT_MAJOR: u16 = 49;
+
+const CLASS_FLAGS: [(u16, &str); 9] = [
+    (0x0001, "public"),
+    (0x0010, "final"),
+    (0x0020, "super"),
+    (0x0200, "interface"),
+    (0x0400, "abstract"),
+    (0x1000, "synthetic"),
+    (0x2000, "annotation"),
+    (0x4000, "enum"),
+    (0x8000, "module"),
+];
+
+const FIELD_FLAGS: [(u16, &str); 9] = [
+    (0x0001, "public"),
+    (0x0002, "private"),
+    (0x0004, "protected"),
+    (0x0008, "static"),
+    (0x0010, "final"),
+    (0x0040, "volatile"),
+    (0x0080, "transient"),
+    (0x1000, "synthetic"),
+    (0x4000, "enum"),
+];
+
+const METHOD_FLAGS: [(u16, &str); 12] = [
+    (0x0001, "public"),
+    (0x0002, "private"),
+    (0x0004, "protected"),
+    (0x0008, "static"),
+    (0x0010, "final"),
+    (0x0020, "synchronized"),
+    (0x0040, "bridge"),
+    (0x0080, "varargs"),
+    (0x0100, "native"),
+    (0x0400, "abstract"),
+    (0x0800, "strict"),
+    (0x1000, "synthetic"),
+];
+
+// Builds a class file from its textual form. The format is line based and mirrors
+// the javap output, see the README for an example. Errors carry the line number.
+pub fn assemble(source: &str) -> Result<ClassFile, String> {
+    let mut lines = Lines {
+        lines: source.lines(),
+        number: 0,
+    };
+
+    Assembler::default()
+        .run(&mut lines)
+        .map_err(|message| format!("line {}: {}", lines.number, message))
+}
+
+pub struct Tokens {
+    tokens: Vec<String>,
+    index: usize,
+}
+
+impl Tokens {
+    pub fn next(&mut self) -> Result<String, String> {
+        let token = self.tokens.get(self.index).ok_or("Missing operand")?;
+        self.index += 1;
+        Ok(token.clone())
+    }
+
+    // Takes the last token, the name and descriptor of a directive are read from the end
+    pub fn pop(&mut self) -> Option<String> {
+        match self.remaining() {
+            0 => None,
+            _ => self.tokens.pop(),
+        }
+    }
+
+    pub fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.index).map(|token| token.as_str())
+    }
+
+    pub fn expect(&mut self, expected: &str) -> Result<(), String> {
+        match self.next()? {
+            token if token == expected => Ok(()),
+            token => Err(format!("Expected {}, found {}", expected, token)),
+        }
+    }
+
+    pub fn end(&self) -> Result<(), String> {
+        match self.peek() {
+            Some(token) => Err(format!("Unexpected token: {}", token)),
+            None => Ok(()),
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.tokens.len() - self.index
+    }
+}
+
+pub struct Lines<'a> {
+    lines: std::str::Lines<'a>,
+    pub number: usize,
+}
+
+impl<'a> Lines<'a> {
+    // Returns the tokens of the next line that isn't empty or a comment
+    pub fn next(&mut self) -> Result<Option<Tokens>, String> {
+        for line in self.lines.by_ref() {
+            self.number += 1;
+
+            let tokens = lexer::tokenize(line)?;
+            if !tokens.is_empty() {
+                return Ok(Some(Tokens { tokens, index: 0 }));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[derive(Default)]
+struct Assembler {
+    constant_pool: ConstantPool,
+    version: Option<(u16, u16)>,
+    access_flags: u16,
+    this_class: Option<u16>,
+    super_class: Option<u16>,
+    interfaces: Vec<u16>,
+    fields: Vec<FieldInfo>,
+    methods: Vec<MethodInfo>,
+    attributes: Vec<AttributeInfo>,
+    // method handle and argument indexes
+    bootstrap_methods: Vec<(u16, Vec<u16>)>,
+}
+
+impl Assembler {
+    fn run(mut self, lines: &mut Lines) -> Result<ClassFile, String> {
+        while let Some(mut tokens) = lines.next()? {
+            match tokens.next()?.as_str() {
+                ".version" => {
+                    let major = constant::parse_number::<u16>(&tokens.next()?, "")?;
+                    let minor = match tokens.peek() {
+                        Some(_) => constant::parse_number::<u16>(&tokens.next()?, "")?,
+                        None => 0,
+                    };
+                    self.version = Some((major, minor));
+                }
+                ".class" => {
+                    let name = tokens.pop().ok_or("Missing class name")?;
+                    self.access_flags = read_flags(&mut tokens, &CLASS_FLAGS)?;
+                    self.this_class = Some(self.constant_pool.add_class(name.as_str()));
+                }
+                ".super" => {
+                    self.super_class =
+                        Some(constant::read_class(&mut self.constant_pool, &mut tokens)?)
+                }
+                ".implements" => {
+                    let interface = constant::read_class(&mut self.constant_pool, &mut tokens)?;
+                    self.interfaces.push(interface);
+                }
+                ".sourcefile" => {
+                    let source_file = lexer::unquote(&tokens.next()?)?;
+                    let index = self.constant_pool.add_utf8(source_file.as_str());
+                    let attribute = self.attribute("SourceFile", index.to_be_bytes().to_vec());
+                    self.attributes.push(attribute);
+                }
+                ".signature" => {
+                    let attribute = self.signature(&mut tokens)?;
+                    self.attributes.push(attribute);
+                }
+                ".field" => self.read_field(&mut tokens)?,
+                ".method" => self.read_method(&mut tokens, lines)?,
+                ".bootstrap" => self.read_bootstrap(&mut tokens, lines)?,
+                directive => return Err(format!("Unknown directive: {}", directive)),
+            }
+            tokens.end()?;
+        }
+
+        self.finish()
+    }
+
+    fn finish(mut self) -> Result<ClassFile, String> {
+        let this_class = self.this_class.ok_or("Missing .class directive")?;
+        let super_class = match self.super_class {
+            Some(super_class) => super_class,
+            None => self.constant_pool.add_class("java/lang/Object"),
+        };
+
+        if !self.bootstrap_methods.is_empty() {
+            let mut info = Vec::new();
+            info.extend_from_slice(&(self.bootstrap_methods.len() as u16).to_be_bytes());
+            for (method_handle, arguments) in &self.bootstrap_methods {
+                info.extend_from_slice(&method_handle.to_be_bytes());
+                info.extend_from_slice(&(arguments.len() as u16).to_be_bytes());
+                for argument in arguments {
+                    info.extend_from_slice(&argument.to_be_bytes());
+                }
+            }
+            let attribute = self.attribute("BootstrapMethods", info);
+            self.attributes.push(attribute);
+        }
+
+        let (major, minor) = self.version.unwrap_or((DEFAULT_MAJOR, 0));
+
+        Ok(ClassFile {
+            magic: 0xcafebabe,
+            minor,
+            major,
+            constant_pool: self.constant_pool,
+            access_flags: self.access_flags,
+            this_class,
+            super_class,
+            interfaces: InterfacePool {
+                interface_count: self.interfaces.len() as u16,
+                interfaces: self.interfaces,
+            },
+            fields: FieldPool {
+                field_count: self.fields.len() as u16,
+                fields: self.fields,
+            },
+            methods: MethodPool {
+                method_count: self.methods.len() as u16,
+                methods: self.methods,
+            },
+            attributes: AttributePool {
+                attribute_count: self.attributes.len() as u16,
+                attributes: self.attributes,
+            },
+        })
+    }
+
+    // .field <flags> <name> <descriptor> [= <constant>]
+    fn read_field(&mut self, tokens: &mut Tokens) -> Result<(), String> {
+        let constant = match tokens.tokens.iter().position(|token| token == "=") {
+            Some(position) => Some(Tokens {
+                tokens: tokens.tokens.split_off(position + 1),
+                index: 0,
+            }),
+            None => None,
+        };
+        if constant.is_some() {
+            tokens.tokens.pop();
+        }
+
+        let descriptor = tokens.pop().ok_or("Missing field descriptor")?;
+        let name = tokens.pop().ok_or("Missing field name")?;
+        let access_flags = read_flags(tokens, &FIELD_FLAGS)?;
+
+        let mut attributes = Vec::new();
+        if let Some(mut constant) = constant {
+            let index = constant::read_constant(&mut self.constant_pool, &mut constant)?;
+            constant.end()?;
+            attributes.push(self.attribute("ConstantValue", index.to_be_bytes().to_vec()));
+        }
+
+        let field = FieldInfo {
+            access_flags,
+            name_index: self.constant_pool.add_utf8(name.as_str()),
+            descriptor_index: self.constant_pool.add_utf8(descriptor.as_str()),
+            attributes_count: attributes.len() as u16,
+            attributes,
+        };
+        self.fields.push(field);
+        Ok(())
+    }
+
+    // .method <flags> <name> <descriptor>, followed by the code up to .end method
+    fn read_method(&mut self, tokens: &mut Tokens, lines: &mut Lines) -> Result<(), String> {
+        let method_descriptor = tokens.pop().ok_or("Missing method descriptor")?;
+        let name = lexer::unquote(&tokens.pop().ok_or("Missing method name")?)?;
+        let access_flags = read_flags(tokens, &METHOD_FLAGS)?;
+
+        let name_index = self.constant_pool.add_utf8(name.as_str());
+        let descriptor_index = self.constant_pool.add_utf8(method_descriptor.as_str());
+        let mut body = code::MethodBody::default();
+        let mut attributes = Vec::new();
+
+        loop {
+            let mut tokens = lines.next()?.ok_or("Missing .end method")?;
+
+            match tokens.peek().unwrap_or_default() {
+                ".end" => {
+                    tokens.next()?;
+                    tokens.expect("method")?;
+                    tokens.end()?;
+                    break;
+                }
+                ".limit" => {
+                    tokens.next()?;
+                    let kind = tokens.next()?;
+                    let value = Some(constant::parse_number::<u16>(&tokens.next()?, "")?);
+                    match kind.as_str() {
+                        "stack" => body.max_stack = value,
+                        "locals" => body.max_locals = value,
+                        kind => return Err(format!("Unknown limit: {}", kind)),
+                    }
+                }
+                ".catch" => {
+                    tokens.next()?;
+                    body.add_catch(&mut self.constant_pool, &mut tokens, lines)?;
+                }
+                ".line" => {
+                    tokens.next()?;
+                    body.add_line_number(constant::parse_number::<u16>(&tokens.next()?, "")?);
+                }
+                ".signature" => {
+                    tokens.next()?;
+                    attributes.push(self.signature(&mut tokens)?);
+                }
+                token if token.ends_with(':') => {
+                    let label = tokens.next()?;
+                    body.add_label(&label[..label.len() - 1])?;
+                    if tokens.peek().is_some() {
+                        body.add_instruction(&mut self.constant_pool, &mut tokens, lines)?;
+                    }
+                }
+                _ => body.add_instruction(&mut self.constant_pool, &mut tokens, lines)?,
+            }
+            tokens.end()?;
+        }
+
+        // abstract and native methods have no code
+        if !body.is_empty() {
+            let argument_slots = descriptor::argument_slots(method_descriptor.as_str())
+                + (access_flags & 0x0008 == 0) as u16;
+            let code =
+                body.assemble(&self.constant_pool, argument_slots)
+                    .map_err(|(line, message)| {
+                        lines.number = line;
+                        message
+                    })?;
+
+            let code_attribute = self.code_attribute(code);
+            attributes.insert(0, code_attribute);
+        }
+
+        self.methods.push(MethodInfo {
+            access_flags,
+            name_index,
+            descriptor_index,
+            attributes_count: attributes.len() as u16,
+            attributes,
+        });
+        Ok(())
+    }
+
+    // .bootstrap <method handle>, followed by one constant argument per line up to .end bootstrap
+    fn read_bootstrap(&mut self, tokens: &mut Tokens, lines: &mut Lines) -> Result<(), String> {
+        let method_handle = constant::read_method_handle(&mut self.constant_pool, tokens)?;
+        let mut arguments = Vec::new();
+
+        loop {
+            let mut tokens = lines.next()?.ok_or("Missing .end bootstrap")?;
+            if tokens.peek() == Some(".end") {
+                tokens.next()?;
+                tokens.expect("bootstrap")?;
+                tokens.end()?;
+                break;
+            }
+
+            arguments.push(constant::read_constant(
+                &mut self.constant_pool,
+                &mut tokens,
+            )?);
+            tokens.end()?;
+        }
+
+        self.bootstrap_methods.push((method_handle, arguments));
+        Ok(())
+    }
+
+    fn code_attribute(&mut self, code: code::Code) -> AttributeInfo {
+        let mut info = Vec::new();
+        info.extend_from_slice(&code.max_stack.to_be_bytes());
+        info.extend_from_slice(&code.max_locals.to_be_bytes());
+        info.extend_from_slice(&(code.code.len() as u32).to_be_bytes());
+        info.extend_from_slice(&code.code);
+
+        info.extend_from_slice(&(code.exception_table.len() as u16).to_be_bytes());
+        for entry in &code.exception_table {
+            for value in entry {
+                info.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+
+        if code.line_numbers.is_empty() {
+            info.extend_from_slice(&0u16.to_be_bytes());
+        } else {
+            let mut line_number_table = Vec::new();
+            line_number_table.extend_from_slice(&(code.line_numbers.len() as u16).to_be_bytes());
+            for entry in &code.line_numbers {
+                for value in entry {
+                    line_number_table.extend_from_slice(&value.to_be_bytes());
+                }
+            }
+            let attribute = self.attribute("LineNumberTable", line_number_table);
+
+            info.extend_from_slice(&1u16.to_be_bytes());
+            info.extend_from_slice(&attribute.attribute_name_index.index.to_be_bytes());
+            info.extend_from_slice(&attribute.attribute_length.to_be_bytes());
+            info.extend_from_slice(&attribute.info);
+        }
+
+        self.attribute("Code", info)
+    }
+
+    fn signature(&mut self, tokens: &mut Tokens) -> Result<AttributeInfo, String> {
+        let signature = lexer::unquote(&tokens.next()?)?;
+        let index = self.constant_pool.add_utf8(signature.as_str());
+        Ok(self.attribute("Signature", index.to_be_bytes().to_vec()))
+    }
+
+    fn attribute(&mut self, name: &str, info: Vec<u8>) -> AttributeInfo {
+        let index = self.constant_pool.add_utf8(name);
+
+        AttributeInfo {
+            attribute_name_index: AttributeNameIndex {
+                value: CPIndexType::Utf8(Utf8String::from(name)),
+                index,
+            },
+            attribute_length: info.len() as u32,
+            info,
+        }
+    }
+}
+
+// All remaining tokens have to be access flags
+fn read_flags(tokens: &mut Tokens, flags: &[(u16, &str)]) -> Result<u16, String> {
+    let mut access_flags = 0;
+
+    while tokens.remaining() > 0 {
+        let token = tokens.next()?;
+        access_flags |= flags
+            .iter()
+            .find(|(_, name)| *name == token.as_str())
+            .map(|(flag, _)| *flag)
+            .ok_or_else(|| format!("Unknown access flag: {}", token))?;
+    }
+
+    Ok(access_flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::assemble;
+    use crate::disassembler::code::CodeAttribute;
+    use crate::disassembler::constant_pool::CPIndexType;
+    use crate::disassembler::instruction::{self, DecodedInstruction};
+    use crate::disassembler::ClassFile;
+    use std::io::BufReader;
+
+    const SAMPLE: &str = r#"
+.class public super Sample
+.implements java/lang/Runnable
+.sourcefile "Sample.java"
+.field private static final COUNT I = int 3
+.field public big J = long 10000000000
+
+.method public <init> ()V
+    aload_0
+    invokespecial Method java/lang/Object."<init>":()V
+    return
+.end method
+
+.method public run ()V
+    return
+.end method
+
+// the cases jump forwards and backwards
+.method public static pick (I)I
+    iload_0
+    tableswitch {
+        1: One
+        2: Two
+        default: Other
+    }
+One:
+    iconst_1
+    ireturn
+Two:
+    iload_0
+    lookupswitch {
+        100: One
+        -5: Other
+        default: Other
+    }
+Other:
+    iconst_m1
+    ireturn
+.end method
+
+.method public static wide ()J
+    .line 10
+    iconst_0
+    istore 300
+    iinc 300, 1000
+    .line 11
+    iload 300
+    i2l
+    lreturn
+.end method
+
+.method public static safe (I)I
+    .catch java/lang/RuntimeException from Start to End using Handler
+Start:
+    iload_0
+    invokestatic Method Sample.pick:(I)I
+End:
+    ireturn
+Handler:
+    astore_1
+    iconst_0
+    ireturn
+.end method
+"#;
+
+    fn code(class_file: &ClassFile, name: &str) -> CodeAttribute {
+        let constant_pool = &class_file.constant_pool;
+        let method = class_file
+            .methods
+            .methods
+            .iter()
+            .find(|method| match constant_pool.get_index(method.name_index) {
+                CPIndexType::Utf8(utf8) => utf8.string == name,
+                _ => false,
+            })
+            .unwrap();
+        CodeAttribute::new(&method.attributes[0].info, constant_pool).unwrap()
+    }
+
+    fn instructions(code: &CodeAttribute) -> Vec<DecodedInstruction> {
+        instruction::parse(BufReader::new(&code.code[..]), code.code.len()).unwrap()
+    }
+
+    fn error(source: &str) -> String {
+        assemble(source).err().unwrap()
+    }
+
+    #[test]
+    fn assembled_class_survives_a_round_trip() {
+        let assembled = assemble(SAMPLE).unwrap();
+        let bytes = assembled.to_bytes().unwrap();
+        let parsed = ClassFile::new(&bytes).unwrap();
+
+        assert_eq!(parsed.to_bytes().unwrap(), bytes);
+        assert_eq!(parsed.disassemble(), assembled.disassemble());
+        assert_eq!(parsed.methods.method_count, 5);
+        assert_eq!(parsed.fields.field_count, 2);
+    }
+
+    #[test]
+    fn labels_resolve_to_switch_offsets() {
+        let code = code(&assemble(SAMPLE).unwrap(), "pick");
+        let instructions = instructions(&code);
+
+        let pcs: Vec<usize> = instructions.iter().map(|decoded| decoded.pc).collect();
+        assert_eq!(pcs, vec![0, 1, 24, 25, 26, 27, 52, 53]);
+        // the cases in key order, then the default
+        assert_eq!(instructions[1].branch_targets(), vec![24, 26, 52]);
+        assert_eq!(instructions[5].branch_targets(), vec![52, 24, 52]);
+        assert_eq!((code.max_stack, code.max_locals), (1, 1));
+    }
+
+    #[test]
+    fn wide_is_used_for_large_operands() {
+        let code = code(&assemble(SAMPLE).unwrap(), "wide");
+        let pcs: Vec<usize> = instructions(&code)
+            .iter()
+            .map(|decoded| decoded.pc)
+            .collect();
+
+        assert_eq!(pcs, vec![0, 1, 5, 11, 15, 16]);
+        assert_eq!(&code.code[1..5], &[0xc4, 0x36, 0x01, 0x2c]);
+        assert_eq!(&code.code[5..11], &[0xc4, 0x84, 0x01, 0x2c, 0x03, 0xe8]);
+        assert_eq!((code.max_stack, code.max_locals), (2, 301));
+
+        let line_numbers: Vec<(u16, u16)> = code
+            .line_numbers()
+            .iter()
+            .map(|line| (line.start_pc, line.line_number))
+            .collect();
+        assert_eq!(line_numbers, vec![(0, 10), (11, 11)]);
+    }
+
+    #[test]
+    fn handlers_start_with_the_exception_on_the_stack() {
+        let class_file = assemble(SAMPLE).unwrap();
+        let code = code(&class_file, "safe");
+        let entry = &code.exception_table[0];
+
+        assert_eq!((entry.start_pc, entry.end_pc, entry.handler_pc), (0, 4, 5));
+        assert_eq!((code.max_stack, code.max_locals), (1, 2));
+    }
+
+    #[test]
+    fn limits_override_the_computed_values() {
+        let class_file = assemble(
+            ".class A
+.method static f ()V
+    .limit stack 7
+    .limit locals 9
+    return
+.end method",
+        )
+        .unwrap();
+        let code = code(&class_file, "f");
+
+        assert_eq!((code.max_stack, code.max_locals), (7, 9));
+    }
+
+    #[test]
+    fn missing_class_directive() {
+        assert_eq!(
+            error(".field static f I"),
+            "line 1: Missing .class directive"
+        );
+    }
+
+    #[test]
+    fn missing_class_name() {
+        assert_eq!(error(".class"), "line 1: Missing class name");
+    }
+
+    #[test]
+    fn missing_field_name() {
+        assert_eq!(error(".class A\n.field I"), "line 2: Missing field name");
+    }
+
+    #[test]
+    fn missing_method_name() {
+        assert_eq!(
+            error(".class A\n.method ()V"),
+            "line 2: Missing method name"
+        );
+    }
+
+    #[test]
+    fn missing_end_method() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    return"),
+            "line 3: Missing .end method"
+        );
+    }
+
+    #[test]
+    fn missing_end_bootstrap() {
+        assert_eq!(
+            error(".class A\n.bootstrap REF_invokeStatic A.f:()V\nint 1"),
+            "line 3: Missing .end bootstrap"
+        );
+    }
+
+    #[test]
+    fn unknown_directive() {
+        assert_eq!(error(".klass A"), "line 1: Unknown directive: .klass");
+    }
+
+    #[test]
+    fn unknown_access_flag() {
+        assert_eq!(error(".class open A"), "line 1: Unknown access flag: open");
+    }
+
+    #[test]
+    fn unknown_limit() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n.limit heap 1\n.end method"),
+            "line 3: Unknown limit: heap"
+        );
+    }
+
+    #[test]
+    fn unknown_instruction() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    jump\n.end method"),
+            "line 3: Unknown instruction: jump"
+        );
+    }
+
+    #[test]
+    fn explicit_wide() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    wide\n.end method"),
+            "line 3: wide is added automatically where it is needed"
+        );
+    }
+
+    #[test]
+    fn missing_operand() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    bipush\n.end method"),
+            "line 3: Missing operand"
+        );
+    }
+
+    #[test]
+    fn unexpected_token() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    return 1\n.end method"),
+            "line 3: Unexpected token: 1"
+        );
+    }
+
+    #[test]
+    fn expected_token() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    return\n.end class"),
+            "line 4: Expected method, found class"
+        );
+    }
+
+    #[test]
+    fn invalid_number() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    bipush 300\n.end method"),
+            "line 3: Invalid number: 300"
+        );
+    }
+
+    #[test]
+    fn invalid_array_type() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    iconst_1\n    newarray void\n.end method"),
+            "line 4: Invalid array type: void"
+        );
+    }
+
+    #[test]
+    fn invalid_member_reference() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    invokestatic f\n.end method"),
+            "line 3: Invalid member reference: f"
+        );
+    }
+
+    #[test]
+    fn invalid_constant_kind() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    ldc byte 1\n.end method"),
+            "line 3: Invalid constant kind: byte"
+        );
+    }
+
+    #[test]
+    fn invalid_reference_kind() {
+        assert_eq!(
+            error(".class A\n.bootstrap REF_call A.f:()V\n.end bootstrap"),
+            "line 2: Invalid reference kind: REF_call"
+        );
+    }
+
+    #[test]
+    fn invalid_invokedynamic_operand() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    invokedynamic run:()V\n.end method"),
+            "line 3: Invalid invokedynamic operand: run:()V"
+        );
+    }
+
+    #[test]
+    fn unterminated_string() {
+        assert_eq!(
+            error(".class A\n.sourcefile \"A.java"),
+            "line 2: Unterminated string"
+        );
+    }
+
+    #[test]
+    fn invalid_escape() {
+        assert_eq!(
+            error(".class A\n.sourcefile \"A\\q\""),
+            "line 2: Invalid escape: \\q"
+        );
+    }
+
+    #[test]
+    fn duplicate_label() {
+        assert_eq!(
+            error(".class A\n.method f ()V\nL:\nL:\n    return\n.end method"),
+            "line 4: Duplicate label: L"
+        );
+    }
+
+    #[test]
+    fn unknown_label() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    goto Nowhere\n.end method"),
+            "line 3: Unknown label: Nowhere"
+        );
+    }
+
+    #[test]
+    fn unknown_catch_label() {
+        assert_eq!(
+            error(
+                ".class A\n.method f ()V\n.catch any from L to M using L\nL:\n    return\n.end method"
+            ),
+            "line 3: Unknown label: M"
+        );
+    }
+
+    #[test]
+    fn branch_offset_too_large() {
+        let mut source = String::from(".class A\n.method f ()V\n    goto End\n");
+        source.push_str("    nop\n".repeat(40000).as_str());
+        source.push_str("End:\n    return\n.end method");
+
+        assert_eq!(
+            error(source.as_str()),
+            "line 3: Branch offset too large, use goto_w"
+        );
+    }
+
+    #[test]
+    fn unterminated_switch() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    iconst_0\n    tableswitch {\n        0: L"),
+            "line 5: Unterminated switch"
+        );
+    }
+
+    #[test]
+    fn switch_case_without_colon() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    iconst_0\n    lookupswitch {\n        0 L"),
+            "line 5: Expected a switch case"
+        );
+    }
+
+    #[test]
+    fn switch_without_default() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    iconst_0\n    lookupswitch {\n    }"),
+            "line 5: Switch without a default case"
+        );
+    }
+
+    #[test]
+    fn tableswitch_keys_not_consecutive() {
+        assert_eq!(
+            error(
+                ".class A\n.method f ()V\n    iconst_0\n    tableswitch {\n        0: L\n        2: L\n        default: L\n    }\nL:\n    return\n.end method"
+            ),
+            "line 8: tableswitch keys have to be consecutive"
+        );
+    }
+
+    #[test]
+    fn stack_underflow() {
+        assert_eq!(
+            error(".class A\n.method f ()V\n    pop\n    return\n.end method"),
+            "line 3: Operand stack underflow"
+        );
+    }
+
+    #[test]
+    fn inconsistent_stack_height() {
+        assert_eq!(
+            error(
+                ".class A\n.method f (I)V\n    iload_0\n    ifeq L\n    iconst_1\nL:\n    return\n.end method"
+            ),
+            "line 7: Inconsistent stack height 1 != 0"
+        );
+    }
+}
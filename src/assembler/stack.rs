@@ -0,0 +1,325 @@
+use crate::disassembler::constant_pool::*;
+use crate::disassembler::descriptor;
+use crate::disassembler::instruction::Instruction;
+
+// How many operand stack slots the instruction changes the stack height by
+pub fn stack_effect(instruction: &Instruction, constant_pool: &ConstantPool) -> i32 {
+    match *instruction {
+        Instruction::Nop
+        | Instruction::Breakpoint
+        | Instruction::ImpDep1
+        | Instruction::ImpDep2
+        | Instruction::Swap
+        | Instruction::IInc(..)
+        | Instruction::GoTo(_)
+        | Instruction::GoToW(_)
+        | Instruction::Ret(_)
+        | Instruction::Return
+        | Instruction::LALoad
+        | Instruction::DALoad
+        | Instruction::INeg
+        | Instruction::LNeg
+        | Instruction::FNeg
+        | Instruction::DNeg
+        | Instruction::I2F
+        | Instruction::L2D
+        | Instruction::F2I
+        | Instruction::D2L
+        | Instruction::I2B
+        | Instruction::I2C
+        | Instruction::I2S
+        | Instruction::NewArray(_)
+        | Instruction::ANewArray(_)
+        | Instruction::ArrayLength
+        | Instruction::CheckCast(_)
+        | Instruction::InstanceOf(_) => 0,
+
+        Instruction::AConstNull
+        | Instruction::IConstM1
+        | Instruction::IConst0
+        | Instruction::IConst1
+        | Instruction::IConst2
+        | Instruction::IConst3
+        | Instruction::IConst4
+        | Instruction::IConst5
+        | Instruction::FConst0
+        | Instruction::FConst1
+        | Instruction::FConst2
+        | Instruction::BiPush(_)
+        | Instruction::SiPush(_)
+        | Instruction::Ldc(_)
+        | Instruction::LdcW(_)
+        | Instruction::ILoad(_)
+        | Instruction::ILoad0
+        | Instruction::ILoad1
+        | Instruction::ILoad2
+        | Instruction::ILoad3
+        | Instruction::FLoad(_)
+        | Instruction::FLoad0
+        | Instruction::FLoad1
+        | Instruction::FLoad2
+        | Instruction::FLoad3
+        | Instruction::ALoad(_)
+        | Instruction::ALoad0
+        | Instruction::ALoad1
+        | Instruction::ALoad2
+        | Instruction::ALoad3
+        | Instruction::Dup
+        | Instruction::DupX1
+        | Instruction::DupX2
+        | Instruction::I2L
+        | Instruction::I2D
+        | Instruction::F2L
+        | Instruction::F2D
+        | Instruction::Jsr(_)
+        | Instruction::JsrW(_)
+        | Instruction::New(_) => 1,
+
+        Instruction::LConst0
+        | Instruction::LConst1
+        | Instruction::DConst0
+        | Instruction::DConst1
+        | Instruction::Ldc2W(_)
+        | Instruction::LLoad(_)
+        | Instruction::LLoad0
+        | Instruction::LLoad1
+        | Instruction::LLoad2
+        | Instruction::LLoad3
+        | Instruction::DLoad(_)
+        | Instruction::DLoad0
+        | Instruction::DLoad1
+        | Instruction::DLoad2
+        | Instruction::DLoad3
+        | Instruction::Dup2
+        | Instruction::Dup2X1
+        | Instruction::Dup2X2 => 2,
+
+        Instruction::IStore(_)
+        | Instruction::IStore0
+        | Instruction::IStore1
+        | Instruction::IStore2
+        | Instruction::IStore3
+        | Instruction::FStore(_)
+        | Instruction::FStore0
+        | Instruction::FStore1
+        | Instruction::FStore2
+        | Instruction::FStore3
+        | Instruction::AStore(_)
+        | Instruction::AStore0
+        | Instruction::AStore1
+        | Instruction::AStore2
+        | Instruction::AStore3
+        | Instruction::IALoad
+        | Instruction::FALoad
+        | Instruction::AALoad
+        | Instruction::BALoad
+        | Instruction::CALoad
+        | Instruction::SALoad
+        | Instruction::Pop
+        | Instruction::IAdd
+        | Instruction::FAdd
+        | Instruction::ISub
+        | Instruction::FSub
+        | Instruction::IMul
+        | Instruction::FMul
+        | Instruction::IDiv
+        | Instruction::FDiv
+        | Instruction::IRem
+        | Instruction::FRem
+        | Instruction::IShl
+        | Instruction::IShr
+        | Instruction::IUShr
+        | Instruction::LShl
+        | Instruction::LShr
+        | Instruction::LUShr
+        | Instruction::IAnd
+        | Instruction::IOr
+        | Instruction::IXor
+        | Instruction::L2I
+        | Instruction::L2F
+        | Instruction::D2I
+        | Instruction::D2F
+        | Instruction::FCmpL
+        | Instruction::FCmpG
+        | Instruction::IfEq(_)
+        | Instruction::IfNe(_)
+        | Instruction::IfLt(_)
+        | Instruction::IfGe(_)
+        | Instruction::IfGt(_)
+        | Instruction::IfLe(_)
+        | Instruction::IfNull(_)
+        | Instruction::IfNonNull(_)
+        | Instruction::TableSwitch { .. }
+        | Instruction::LookUpSwitch { .. }
+        | Instruction::IReturn
+        | Instruction::FReturn
+        | Instruction::AReturn
+        | Instruction::AThrow
+        | Instruction::MonitorEnter
+        | Instruction::MonitorExit => -1,
+
+        Instruction::LStore(_)
+        | Instruction::LStore0
+        | Instruction::LStore1
+        | Instruction::LStore2
+        | Instruction::LStore3
+        | Instruction::DStore(_)
+        | Instruction::DStore0
+        | Instruction::DStore1
+        | Instruction::DStore2
+        | Instruction::DStore3
+        | Instruction::Pop2
+        | Instruction::LAdd
+        | Instruction::DAdd
+        | Instruction::LSub
+        | Instruction::DSub
+        | Instruction::LMul
+        | Instruction::DMul
+        | Instruction::LDiv
+        | Instruction::DDiv
+        | Instruction::LRem
+        | Instruction::DRem
+        | Instruction::LAnd
+        | Instruction::LOr
+        | Instruction::LXor
+        | Instruction::IfICmpEq(_)
+        | Instruction::IfICmpNe(_)
+        | Instruction::IfICmpLt(_)
+        | Instruction::IfICmpGe(_)
+        | Instruction::IfICmpGt(_)
+        | Instruction::IfICmpLe(_)
+        | Instruction::IfACmpEq(_)
+        | Instruction::IfACmpNe(_)
+        | Instruction::LReturn
+        | Instruction::DReturn => -2,
+
+        Instruction::IAStore
+        | Instruction::FAStore
+        | Instruction::AAStore
+        | Instruction::BAStore
+        | Instruction::CAStore
+        | Instruction::SAStore
+        | Instruction::LCmp
+        | Instruction::DCmpL
+        | Instruction::DCmpG => -3,
+
+        Instruction::LAStore | Instruction::DAStore => -4,
+
+        Instruction::MultiANewArray(_, dimensions) => 1 - dimensions as i32,
+
+        Instruction::GetStatic(index) => field_size(constant_pool, index),
+        Instruction::PutStatic(index) => -field_size(constant_pool, index),
+        Instruction::GetField(index) => field_size(constant_pool, index) - 1,
+        Instruction::PutField(index) => -field_size(constant_pool, index) - 1,
+
+        Instruction::InvokeStatic(index) | Instruction::InvokeDynamic(index) => {
+            invoke_effect(constant_pool, index)
+        }
+        Instruction::InvokeVirtual(index)
+        | Instruction::InvokeSpecial(index)
+        | Instruction::InvokeInterface(index, _) => invoke_effect(constant_pool, index) - 1,
+    }
+}
+
+// The local variable slots written or read by the instruction as (first slot, slot count)
+pub fn local_slots(instruction: &Instruction) -> Option<(u16, u16)> {
+    Some(match *instruction {
+        Instruction::ILoad(index)
+        | Instruction::FLoad(index)
+        | Instruction::ALoad(index)
+        | Instruction::IStore(index)
+        | Instruction::FStore(index)
+        | Instruction::AStore(index)
+        | Instruction::Ret(index)
+        | Instruction::IInc(index, _) => (index, 1),
+        Instruction::LLoad(index)
+        | Instruction::DLoad(index)
+        | Instruction::LStore(index)
+        | Instruction::DStore(index) => (index, 2),
+
+        Instruction::ILoad0
+        | Instruction::FLoad0
+        | Instruction::ALoad0
+        | Instruction::IStore0
+        | Instruction::FStore0
+        | Instruction::AStore0 => (0, 1),
+        Instruction::ILoad1
+        | Instruction::FLoad1
+        | Instruction::ALoad1
+        | Instruction::IStore1
+        | Instruction::FStore1
+        | Instruction::AStore1 => (1, 1),
+        Instruction::ILoad2
+        | Instruction::FLoad2
+        | Instruction::ALoad2
+        | Instruction::IStore2
+        | Instruction::FStore2
+        | Instruction::AStore2 => (2, 1),
+        Instruction::ILoad3
+        | Instruction::FLoad3
+        | Instruction::ALoad3
+        | Instruction::IStore3
+        | Instruction::FStore3
+        | Instruction::AStore3 => (3, 1),
+
+        Instruction::LLoad0 | Instruction::DLoad0 | Instruction::LStore0 | Instruction::DStore0 => {
+            (0, 2)
+        }
+        Instruction::LLoad1 | Instruction::DLoad1 | Instruction::LStore1 | Instruction::DStore1 => {
+            (1, 2)
+        }
+        Instruction::LLoad2 | Instruction::DLoad2 | Instruction::LStore2 | Instruction::DStore2 => {
+            (2, 2)
+        }
+        Instruction::LLoad3 | Instruction::DLoad3 | Instruction::LStore3 | Instruction::DStore3 => {
+            (3, 2)
+        }
+
+        _ => return None,
+    })
+}
+
+pub fn member_descriptor(constant_pool: &ConstantPool, index: u16) -> String {
+    let name_and_type_index = match constant_pool.get_index(index) {
+        CPIndexType::FieldRef {
+            name_and_type_index,
+            ..
+        }
+        | CPIndexType::MethodRef {
+            name_and_type_index,
+            ..
+        }
+        | CPIndexType::InterfaceMethodRef {
+            name_and_type_index,
+            ..
+        }
+        | CPIndexType::InvokeDynamic {
+            name_and_type_index,
+            ..
+        } => name_and_type_index,
+        _ => panic!("Invalid Type in Constant Pool"),
+    };
+
+    match constant_pool.get_index(name_and_type_index) {
+        CPIndexType::NameAndType {
+            descriptor_index, ..
+        } => match constant_pool.get_index(descriptor_index) {
+            CPIndexType::Utf8(utf8) => utf8.string,
+            _ => panic!("Invalid Type in Constant Pool"),
+        },
+        _ => panic!("Invalid Type in Constant Pool"),
+    }
+}
+
+fn field_size(constant_pool: &ConstantPool, index: u16) -> i32 {
+    descriptor::slot_size(member_descriptor(constant_pool, index).as_str()) as i32
+}
+
+// the arguments are popped and the return value is pushed, the receiver is not included
+fn invoke_effect(constant_pool: &ConstantPool, index: u16) -> i32 {
+    let method_descriptor = member_descriptor(constant_pool, index);
+    let (_, return_type) = descriptor::split_method_descriptor(method_descriptor.as_str());
+
+    descriptor::slot_size(return_type.as_str()) as i32
+        - descriptor::argument_slots(method_descriptor.as_str()) as i32
+}
@@ -0,0 +1,1097 @@
+// The Java syntax tree the decompiler builds, printed by `pretty`
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Class(String),
+    Short,
+    Boolean,
+    Array(Box<Type>),
+    Void,
+    Unknown,
+}
+
+impl Type {
+    pub fn from_descriptor(descriptor: &str) -> Type {
+        match descriptor.as_bytes().first().copied().unwrap_or_default() {
+            b'B' => Type::Byte,
+            b'C' => Type::Char,
+            b'D' => Type::Double,
+            b'F' => Type::Float,
+            b'I' => Type::Int,
+            b'J' => Type::Long,
+            b'S' => Type::Short,
+            b'V' => Type::Void,
+            b'Z' => Type::Boolean,
+            b'[' => Type::Array(Box::new(Type::from_descriptor(&descriptor[1..]))),
+            _ => match descriptor
+                .strip_prefix('L')
+                .and_then(|name| name.strip_suffix(';'))
+            {
+                Some(name) => Type::Class(name.replace('/', ".")),
+                None => Type::Unknown,
+            },
+        }
+    }
+
+    // Class constants hold internal names, array classes are given as descriptors
+    pub fn from_class_name(name: &str) -> Type {
+        if name.starts_with('[') {
+            Type::from_descriptor(name)
+        } else {
+            Type::Class(name.replace('/', "."))
+        }
+    }
+
+    // long and double take up two operand stack slots
+    pub fn is_wide(&self) -> bool {
+        matches!(self, Type::Long | Type::Double)
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Type::Byte => "byte",
+            Type::Char => "char",
+            Type::Double => "double",
+            Type::Float => "float",
+            Type::Int => "int",
+            Type::Long => "long",
+            Type::Class(ty) => ty,
+            Type::Short => "short",
+            Type::Boolean => "boolean",
+            Type::Array(ty) => {
+                let ty: &Type = ty.as_ref();
+                return f.write_str(format!("{}[]", ty).as_str());
+            }
+            Type::Void => "void",
+            Type::Unknown => "unknown",
+        })
+    }
+}
+
+impl From<u8> for Type {
+    fn from(value: u8) -> Self {
+        match value {
+            4 => Type::Boolean,
+            5 => Type::Char,
+            6 => Type::Float,
+            7 => Type::Double,
+            8 => Type::Byte,
+            9 => Type::Short,
+            10 => Type::Int,
+            11 => Type::Long,
+            _ => panic!("Unknown value given: {}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Null,
+    Boolean(bool),
+    Char(u16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Class(Type),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Shl,
+    Shr,
+    UShr,
+    And,
+    Or,
+    Xor,
+    ConditionalAnd,
+    ConditionalOr,
+    Eq,
+    Ne,
+    Lt,
+    Ge,
+    Gt,
+    Le,
+}
+
+impl BinaryOperator {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Sub => "-",
+            BinaryOperator::Mul => "*",
+            BinaryOperator::Div => "/",
+            BinaryOperator::Rem => "%",
+            BinaryOperator::Shl => "<<",
+            BinaryOperator::Shr => ">>",
+            BinaryOperator::UShr => ">>>",
+            BinaryOperator::And => "&",
+            BinaryOperator::Or => "|",
+            BinaryOperator::Xor => "^",
+            BinaryOperator::ConditionalAnd => "&&",
+            BinaryOperator::ConditionalOr => "||",
+            BinaryOperator::Eq => "==",
+            BinaryOperator::Ne => "!=",
+            BinaryOperator::Lt => "<",
+            BinaryOperator::Ge => ">=",
+            BinaryOperator::Gt => ">",
+            BinaryOperator::Le => "<=",
+        }
+    }
+
+    // The comparison that holds exactly when this one doesn't
+    // Whether there is an assignment operator like `+=` for the operator
+    pub fn is_compound(self) -> bool {
+        matches!(
+            self,
+            BinaryOperator::Add
+                | BinaryOperator::Sub
+                | BinaryOperator::Mul
+                | BinaryOperator::Div
+                | BinaryOperator::Rem
+                | BinaryOperator::Shl
+                | BinaryOperator::Shr
+                | BinaryOperator::UShr
+                | BinaryOperator::And
+                | BinaryOperator::Or
+                | BinaryOperator::Xor
+        )
+    }
+
+    pub fn negated(self) -> Option<BinaryOperator> {
+        Some(match self {
+            BinaryOperator::Eq => BinaryOperator::Ne,
+            BinaryOperator::Ne => BinaryOperator::Eq,
+            BinaryOperator::Lt => BinaryOperator::Ge,
+            BinaryOperator::Ge => BinaryOperator::Lt,
+            BinaryOperator::Gt => BinaryOperator::Le,
+            BinaryOperator::Le => BinaryOperator::Gt,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOperator {
+    Neg,
+    Not,
+}
+
+impl UnaryOperator {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            UnaryOperator::Neg => "-",
+            UnaryOperator::Not => "!",
+        }
+    }
+}
+
+// lcmp, fcmpl, fcmpg, dcmpl and dcmpg push -1, 0 or 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareKind {
+    LCmp,
+    FCmpL,
+    FCmpG,
+    DCmpL,
+    DCmpG,
+}
+
+impl CompareKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            CompareKind::LCmp => "lcmp",
+            CompareKind::FCmpL => "fcmpl",
+            CompareKind::FCmpG => "fcmpg",
+            CompareKind::DCmpL => "dcmpl",
+            CompareKind::DCmpG => "dcmpg",
+        }
+    }
+}
+
+// A local variable slot or a temporary introduced for the operand stack
+#[derive(Debug, Clone, PartialEq)]
+pub struct Local {
+    pub index: u16,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Literal(Literal),
+    Local(Local),
+    This,
+    Super,
+    // the exception a handler starts with on the stack
+    CaughtException,
+    // the result of `new` until its constructor has been called
+    Uninitialized(usize),
+    Field {
+        target: Box<Expression>,
+        name: String,
+    },
+    StaticField {
+        class: String,
+        name: String,
+    },
+    ArrayElement {
+        array: Box<Expression>,
+        index: Box<Expression>,
+    },
+    ArrayLength(Box<Expression>),
+    Unary {
+        operator: UnaryOperator,
+        operand: Box<Expression>,
+    },
+    Binary {
+        operator: BinaryOperator,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    // `operator` is None for the -1, 0 or 1 the instruction pushes. Fused with a branch, it is
+    // an ordering of floating point values, which is false for NaN, so negating it doesn't
+    // flip the operator.
+    Compare {
+        kind: CompareKind,
+        operator: Option<BinaryOperator>,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    // `widening` casts convert to a bigger primitive type, which assignments and arithmetic
+    // with a value of that type do implicitly
+    Cast {
+        ty: Type,
+        expression: Box<Expression>,
+        widening: bool,
+    },
+    // `binding` is what `x instanceof T name` or `x instanceof R(int a, String b)` binds
+    InstanceOf {
+        expression: Box<Expression>,
+        ty: Type,
+        binding: Option<Binding>,
+    },
+    // `target` is None for static methods
+    Invoke {
+        target: Option<Box<Expression>>,
+        class: String,
+        name: String,
+        descriptor: String,
+        arguments: Vec<Expression>,
+    },
+    New {
+        class: String,
+        descriptor: String,
+        arguments: Vec<Expression>,
+    },
+    // `ty` is the element type, `extra` counts the trailing dimensions without a size, with
+    // `elements` the only dimension is the length of the initializer
+    NewArray {
+        ty: Type,
+        dimensions: Vec<Expression>,
+        extra: usize,
+        elements: Option<Vec<Expression>>,
+    },
+    Assign {
+        target: Box<Expression>,
+        operator: Option<BinaryOperator>,
+        value: Box<Expression>,
+    },
+    // `++x` or `--x` if `prefix`, else `x++` or `x--`, `operator` is Add or Sub
+    Increment {
+        target: Box<Expression>,
+        operator: BinaryOperator,
+        prefix: bool,
+    },
+    Conditional {
+        condition: Box<Expression>,
+        then: Box<Expression>,
+        otherwise: Box<Expression>,
+    },
+    Lambda {
+        parameters: Vec<String>,
+        body: Box<Expression>,
+    },
+    // a lambda with statements for its body, they are not visited
+    LambdaBlock {
+        parameters: Vec<String>,
+        body: Vec<Statement>,
+    },
+    // the cases end in `yield` or `throw`, their statements are not visited
+    Switch {
+        value: Box<Expression>,
+        cases: Vec<SwitchCase>,
+    },
+}
+
+impl Expression {
+    pub fn local(index: u16, name: String) -> Expression {
+        Expression::Local(Local { index, name })
+    }
+
+    // Calls every sub-expression in evaluation order
+    pub fn visit(&self, f: &mut dyn FnMut(&Expression)) {
+        f(self);
+        for child in self.children() {
+            child.visit(f);
+        }
+    }
+
+    // The operands of the expression in evaluation order
+    pub fn children(&self) -> Vec<&Expression> {
+        match self {
+            Expression::Field { target, .. }
+            | Expression::ArrayLength(target)
+            | Expression::Increment { target, .. } => vec![target],
+            Expression::ArrayElement { array, index } => vec![array, index],
+            Expression::Unary { operand, .. } => vec![operand],
+            Expression::Binary { left, right, .. } | Expression::Compare { left, right, .. } => {
+                vec![left, right]
+            }
+            Expression::Cast { expression, .. } | Expression::InstanceOf { expression, .. } => {
+                vec![expression]
+            }
+            Expression::Invoke {
+                target, arguments, ..
+            } => target
+                .iter()
+                .map(|target| target.as_ref())
+                .chain(arguments)
+                .collect(),
+            Expression::New { arguments, .. } => arguments.iter().collect(),
+            Expression::NewArray {
+                dimensions,
+                elements,
+                ..
+            } => dimensions.iter().chain(elements.iter().flatten()).collect(),
+            Expression::Assign { target, value, .. } => vec![target, value],
+            Expression::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => vec![condition, then, otherwise],
+            Expression::Lambda { body, .. } => vec![body],
+            Expression::Switch { value, .. } => vec![value],
+            _ => Vec::new(),
+        }
+    }
+
+    // Like `visit`, but `f` may replace the expressions. Children are visited after `f`
+    // has seen their parent.
+    pub fn visit_mut(&mut self, f: &mut dyn FnMut(&mut Expression)) {
+        f(self);
+        match self {
+            Expression::Field { target, .. } | Expression::ArrayLength(target) => {
+                target.visit_mut(f)
+            }
+            Expression::ArrayElement { array, index } => {
+                array.visit_mut(f);
+                index.visit_mut(f);
+            }
+            Expression::Unary { operand, .. } => operand.visit_mut(f),
+            Expression::Binary { left, right, .. } | Expression::Compare { left, right, .. } => {
+                left.visit_mut(f);
+                right.visit_mut(f);
+            }
+            Expression::Cast { expression, .. } | Expression::InstanceOf { expression, .. } => {
+                expression.visit_mut(f)
+            }
+            Expression::Invoke {
+                target, arguments, ..
+            } => {
+                if let Some(target) = target {
+                    target.visit_mut(f);
+                }
+                arguments
+                    .iter_mut()
+                    .for_each(|argument| argument.visit_mut(f));
+            }
+            Expression::New { arguments, .. } => arguments
+                .iter_mut()
+                .for_each(|argument| argument.visit_mut(f)),
+            Expression::NewArray {
+                dimensions,
+                elements,
+                ..
+            } => dimensions
+                .iter_mut()
+                .chain(elements.iter_mut().flatten())
+                .for_each(|dimension| dimension.visit_mut(f)),
+            Expression::Assign { target, value, .. } => {
+                target.visit_mut(f);
+                value.visit_mut(f);
+            }
+            Expression::Increment { target, .. } => target.visit_mut(f),
+            Expression::Conditional {
+                condition,
+                then,
+                otherwise,
+            } => {
+                condition.visit_mut(f);
+                then.visit_mut(f);
+                otherwise.visit_mut(f);
+            }
+            Expression::Lambda { body, .. } | Expression::Switch { value: body, .. } => {
+                body.visit_mut(f)
+            }
+            _ => {}
+        }
+    }
+
+    pub fn effects(&self) -> Effects {
+        let mut effects = Effects::default();
+        self.visit(&mut |expression| match expression {
+            Expression::Local(local) => effects.locals_read.push(local.index),
+            Expression::Field { .. }
+            | Expression::StaticField { .. }
+            | Expression::ArrayElement { .. }
+            | Expression::ArrayLength(_) => effects.reads_heap = true,
+            Expression::Invoke {
+                target: None,
+                class,
+                name,
+                descriptor,
+                ..
+            } if is_boxing(class, name, descriptor) => {}
+            Expression::Invoke { .. } | Expression::New { .. } => effects.calls = true,
+            Expression::Assign { target, .. } | Expression::Increment { target, .. } => {
+                effects.calls = true;
+                if let Expression::Local(local) = target.as_ref() {
+                    effects.locals_written.push(local.index);
+                }
+            }
+            _ => {}
+        });
+        effects
+    }
+
+    // Whether copying the expression is the same as evaluating it once
+    pub fn is_trivial(&self) -> bool {
+        matches!(
+            self,
+            Expression::Literal(_)
+                | Expression::Local(_)
+                | Expression::This
+                | Expression::Uninitialized(_)
+        )
+    }
+}
+
+// `Integer.valueOf(int)` and the like, which box a primitive value without other effects
+fn is_boxing(class: &str, name: &str, descriptor: &str) -> bool {
+    let wrapper = matches!(
+        class,
+        "java.lang.Boolean"
+            | "java.lang.Byte"
+            | "java.lang.Character"
+            | "java.lang.Short"
+            | "java.lang.Integer"
+            | "java.lang.Long"
+            | "java.lang.Float"
+            | "java.lang.Double"
+    );
+    wrapper
+        && name == "valueOf"
+        && descriptor.get(2..3) == Some(")")
+        && descriptor.get(3..) == Some(format!("L{};", class.replace('.', "/")).as_str())
+}
+
+// What evaluating an expression depends on, used to decide whether it may be moved
+// past a statement
+#[derive(Debug, Default)]
+pub struct Effects {
+    pub locals_read: Vec<u16>,
+    pub reads_heap: bool,
+    // method calls, constructors and assignments, anything that may change state
+    pub calls: bool,
+    // the variables assignments inside the expression change
+    pub locals_written: Vec<u16>,
+}
+
+// The first and last pc of the instructions a statement was compiled from. Statements are
+// compared by what they do, copies of the same code at other pcs are equal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pcs(pub Option<(usize, usize)>);
+
+impl PartialEq for Pcs {
+    fn eq(&self, _: &Pcs) -> bool {
+        true
+    }
+}
+
+impl Pcs {
+    pub fn new(first: usize, last: usize) -> Pcs {
+        Pcs(Some((first, last)))
+    }
+
+    // The pcs of both statements
+    pub fn join(self, other: Pcs) -> Pcs {
+        match (self.0, other.0) {
+            (Some((first, last)), Some((other_first, other_last))) => {
+                Pcs::new(first.min(other_first), last.max(other_last))
+            }
+            (pcs, other) => Pcs(pcs.or(other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Expression(Expression, Pcs),
+    Declaration {
+        ty: Type,
+        local: Local,
+        value: Option<Expression>,
+        pcs: Pcs,
+    },
+    Return(Option<Expression>, Pcs),
+    // the value of a case of a switch expression
+    Yield(Expression, Pcs),
+    Throw(Expression, Pcs),
+    Assert {
+        condition: Expression,
+        message: Option<Expression>,
+        pcs: Pcs,
+    },
+    MonitorEnter(Expression, Pcs),
+    MonitorExit(Expression, Pcs),
+    Comment(String),
+    If {
+        condition: Expression,
+        then: Vec<Statement>,
+        otherwise: Option<Vec<Statement>>,
+        pcs: Pcs,
+    },
+    While {
+        condition: Expression,
+        body: Vec<Statement>,
+        pcs: Pcs,
+    },
+    DoWhile {
+        body: Vec<Statement>,
+        condition: Expression,
+        pcs: Pcs,
+    },
+    // only built when a `continue` has to run the update
+    For {
+        condition: Option<Expression>,
+        update: Vec<Expression>,
+        body: Vec<Statement>,
+        pcs: Pcs,
+    },
+    // the type of the variable is filled in with the declarations
+    ForEach {
+        ty: Type,
+        local: Local,
+        iterable: Expression,
+        body: Vec<Statement>,
+        pcs: Pcs,
+    },
+    Switch {
+        value: Expression,
+        cases: Vec<SwitchCase>,
+        pcs: Pcs,
+    },
+    // the resources are declarations, their types are filled in with the other declarations
+    Try {
+        resources: Vec<Statement>,
+        body: Vec<Statement>,
+        catches: Vec<Catch>,
+        finally: Option<Vec<Statement>>,
+    },
+    Synchronized {
+        lock: Expression,
+        body: Vec<Statement>,
+        pcs: Pcs,
+    },
+    Labeled {
+        label: String,
+        body: Box<Statement>,
+    },
+    Break(Option<String>, Pcs),
+    Continue(Option<String>, Pcs),
+    // unstructured control flow that is left over, pcs are used as labels
+    Label(usize),
+    Goto(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchCase {
+    pub labels: Vec<Expression>,
+    pub default: bool,
+    // `case T name when guard`, which has no other labels
+    pub pattern: Option<Pattern>,
+    pub body: Vec<Statement>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    pub ty: Type,
+    pub binding: Binding,
+    pub guard: Option<Expression>,
+}
+
+// The variable of a type pattern, or the components of a record pattern with the type each
+// is matched against, like `int x` and `Q(String s)` in `R(int x, Q(String s))`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Binding {
+    Variable(Local),
+    Record(Vec<(Type, Binding)>),
+}
+
+impl Binding {
+    // Calls `f` on the types of the components, nested ones too
+    pub fn types_mut(&mut self, f: &mut dyn FnMut(&mut Type)) {
+        if let Binding::Record(components) = self {
+            for (ty, binding) in components {
+                f(ty);
+                binding.types_mut(f);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Catch {
+    pub types: Vec<Type>,
+    pub local: Local,
+    pub body: Vec<Statement>,
+}
+
+impl Statement {
+    // Where the statement was compiled from, statements that aren't compiled from code of their
+    // own have none
+    pub fn pcs(&self) -> Pcs {
+        match self {
+            Statement::Labeled { body, .. } => body.pcs(),
+            Statement::Expression(_, pcs)
+            | Statement::Return(_, pcs)
+            | Statement::Yield(_, pcs)
+            | Statement::Throw(_, pcs)
+            | Statement::MonitorEnter(_, pcs)
+            | Statement::MonitorExit(_, pcs)
+            | Statement::Declaration { pcs, .. }
+            | Statement::Assert { pcs, .. }
+            | Statement::If { pcs, .. }
+            | Statement::While { pcs, .. }
+            | Statement::DoWhile { pcs, .. }
+            | Statement::For { pcs, .. }
+            | Statement::ForEach { pcs, .. }
+            | Statement::Switch { pcs, .. }
+            | Statement::Synchronized { pcs, .. }
+            | Statement::Break(_, pcs)
+            | Statement::Continue(_, pcs) => *pcs,
+            _ => Pcs::default(),
+        }
+    }
+
+    pub fn pcs_mut(&mut self) -> Option<&mut Pcs> {
+        match self {
+            Statement::Labeled { body, .. } => body.pcs_mut(),
+            Statement::Expression(_, pcs)
+            | Statement::Return(_, pcs)
+            | Statement::Yield(_, pcs)
+            | Statement::Throw(_, pcs)
+            | Statement::MonitorEnter(_, pcs)
+            | Statement::MonitorExit(_, pcs)
+            | Statement::Declaration { pcs, .. }
+            | Statement::Assert { pcs, .. }
+            | Statement::If { pcs, .. }
+            | Statement::While { pcs, .. }
+            | Statement::DoWhile { pcs, .. }
+            | Statement::For { pcs, .. }
+            | Statement::ForEach { pcs, .. }
+            | Statement::Switch { pcs, .. }
+            | Statement::Synchronized { pcs, .. }
+            | Statement::Break(_, pcs)
+            | Statement::Continue(_, pcs) => Some(pcs),
+            _ => None,
+        }
+    }
+
+    // Calls `f` on this statement and every statement nested in it
+    pub fn walk(&self, f: &mut dyn FnMut(&Statement)) {
+        f(self);
+        let children: Vec<&Vec<Statement>> = match self {
+            Statement::If {
+                then, otherwise, ..
+            } => {
+                let mut children = vec![then];
+                if let Some(otherwise) = otherwise {
+                    children.push(otherwise);
+                }
+                children
+            }
+            Statement::While { body, .. }
+            | Statement::DoWhile { body, .. }
+            | Statement::For { body, .. }
+            | Statement::ForEach { body, .. }
+            | Statement::Synchronized { body, .. } => vec![body],
+            Statement::Switch { cases, .. } => cases.iter().map(|case| &case.body).collect(),
+            Statement::Try {
+                body,
+                catches,
+                finally,
+                ..
+            } => {
+                let mut children = vec![body];
+                children.extend(catches.iter().map(|catch| &catch.body));
+                children.extend(finally);
+                children
+            }
+            Statement::Labeled { body, .. } => return body.walk(f),
+            _ => Vec::new(),
+        };
+        for statement in children.into_iter().flatten() {
+            statement.walk(f);
+        }
+    }
+
+    // Calls `f` on this statement and every statement nested in it, parents first
+    pub fn walk_mut(&mut self, f: &mut dyn FnMut(&mut Statement)) {
+        f(self);
+        for statement in self.children_mut().into_iter().flatten() {
+            statement.walk_mut(f);
+        }
+    }
+
+    // The statement lists directly nested in this statement
+    pub fn children_mut(&mut self) -> Vec<&mut Vec<Statement>> {
+        match self {
+            Statement::If {
+                then, otherwise, ..
+            } => {
+                let mut children = vec![then];
+                if let Some(otherwise) = otherwise {
+                    children.push(otherwise);
+                }
+                children
+            }
+            Statement::While { body, .. }
+            | Statement::DoWhile { body, .. }
+            | Statement::For { body, .. }
+            | Statement::ForEach { body, .. }
+            | Statement::Synchronized { body, .. } => vec![body],
+            Statement::Switch { cases, .. } => {
+                cases.iter_mut().map(|case| &mut case.body).collect()
+            }
+            Statement::Try {
+                body,
+                catches,
+                finally,
+                ..
+            } => {
+                let mut children = vec![body];
+                children.extend(catches.iter_mut().map(|catch| &mut catch.body));
+                children.extend(finally);
+                children
+            }
+            Statement::Labeled { body, .. } => body.children_mut(),
+            _ => Vec::new(),
+        }
+    }
+
+    // Calls `f` on the expressions of this statement, not the ones of nested statements
+    pub fn expressions_mut(&mut self, f: &mut dyn FnMut(&mut Expression)) {
+        match self {
+            Statement::Expression(expression, _)
+            | Statement::Declaration {
+                value: Some(expression),
+                ..
+            }
+            | Statement::Return(Some(expression), _)
+            | Statement::Yield(expression, _)
+            | Statement::Throw(expression, _)
+            | Statement::MonitorEnter(expression, _)
+            | Statement::MonitorExit(expression, _)
+            | Statement::If {
+                condition: expression,
+                ..
+            }
+            | Statement::While {
+                condition: expression,
+                ..
+            }
+            | Statement::DoWhile {
+                condition: expression,
+                ..
+            }
+            | Statement::Switch {
+                value: expression, ..
+            }
+            | Statement::Synchronized {
+                lock: expression, ..
+            }
+            | Statement::ForEach {
+                iterable: expression,
+                ..
+            } => f(expression),
+            Statement::For {
+                condition, update, ..
+            } => {
+                if let Some(condition) = condition {
+                    f(condition);
+                }
+                update.iter_mut().for_each(f);
+            }
+            Statement::Try { resources, .. } => {
+                for resource in resources {
+                    resource.expressions_mut(f);
+                }
+            }
+            Statement::Assert {
+                condition, message, ..
+            } => {
+                f(condition);
+                if let Some(message) = message {
+                    f(message);
+                }
+            }
+            Statement::Labeled { body, .. } => body.expressions_mut(f),
+            _ => {}
+        }
+    }
+}
+
+// Logical negation, comparisons are flipped instead of wrapped
+pub fn negate(expression: Expression) -> Expression {
+    match expression {
+        Expression::Binary {
+            operator: operator @ (BinaryOperator::ConditionalAnd | BinaryOperator::ConditionalOr),
+            left,
+            right,
+        } => Expression::Binary {
+            operator: if operator == BinaryOperator::ConditionalAnd {
+                BinaryOperator::ConditionalOr
+            } else {
+                BinaryOperator::ConditionalAnd
+            },
+            left: Box::new(negate(*left)),
+            right: Box::new(negate(*right)),
+        },
+        Expression::Binary {
+            operator,
+            left,
+            right,
+        } if operator.negated().is_some() => Expression::Binary {
+            operator: operator.negated().unwrap(),
+            left,
+            right,
+        },
+        Expression::Unary {
+            operator: UnaryOperator::Not,
+            operand,
+        } => *operand,
+        Expression::Literal(Literal::Boolean(value)) => {
+            Expression::Literal(Literal::Boolean(!value))
+        }
+        Expression::Conditional {
+            condition,
+            then,
+            otherwise,
+        } => Expression::Conditional {
+            condition,
+            then: Box::new(negate(*then)),
+            otherwise: Box::new(negate(*otherwise)),
+        },
+        expression => Expression::Unary {
+            operator: UnaryOperator::Not,
+            operand: Box::new(expression),
+        },
+    }
+}
+
+impl std::fmt::Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::Null => f.write_str("null"),
+            Literal::Boolean(value) => write!(f, "{}", value),
+            Literal::Char(value) => match char::from_u32(*value as u32) {
+                Some('\'') => f.write_str("'\\''"),
+                Some('"') => f.write_str("'\"'"),
+                Some(char) => write!(f, "'{}'", escape(char.to_string().as_str())),
+                // surrogates
+                None => write!(f, "'\\u{:04x}'", value),
+            },
+            Literal::Int(value) => write!(f, "{}", value),
+            Literal::Long(value) => write!(f, "{}L", value),
+            Literal::Float(value) => {
+                if value.is_nan() {
+                    f.write_str("Float.NaN")
+                } else if value.is_infinite() {
+                    f.write_str(if *value > 0.0 {
+                        "Float.POSITIVE_INFINITY"
+                    } else {
+                        "Float.NEGATIVE_INFINITY"
+                    })
+                } else {
+                    write!(f, "{:?}f", value)
+                }
+            }
+            Literal::Double(value) => {
+                if value.is_nan() {
+                    f.write_str("Double.NaN")
+                } else if value.is_infinite() {
+                    f.write_str(if *value > 0.0 {
+                        "Double.POSITIVE_INFINITY"
+                    } else {
+                        "Double.NEGATIVE_INFINITY"
+                    })
+                } else {
+                    write!(f, "{:?}", value)
+                }
+            }
+            Literal::String(value) => write!(f, "\"{}\"", escape(value)),
+            Literal::Class(ty) => write!(f, "{}.class", ty),
+        }
+    }
+}
+
+impl Literal {
+    // An int constant as a literal of the int type it is used as, if it is one of its values
+    pub fn of_type(value: i32, ty: &Type) -> Literal {
+        match ty {
+            _ if !super::codegen::fits(value, ty) => Literal::Int(value),
+            Type::Boolean => Literal::Boolean(value != 0),
+            Type::Char => Literal::Char(value as u16),
+            _ => Literal::Int(value),
+        }
+    }
+}
+
+// Escapes a string for a Java string literal
+pub fn escape(string: &str) -> String {
+    let mut escaped = String::new();
+    for char in string.chars() {
+        match char {
+            '\u{8}' => escaped.push_str("\\b"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\u{c}' => escaped.push_str("\\f"),
+            '\r' => escaped.push_str("\\r"),
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            char if char.is_control() => {
+                escaped.push_str(format!("\\u{:04x}", char as u32).as_str())
+            }
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompilationUnit {
+    pub package: Option<String>,
+    pub imports: Vec<String>,
+    // the descriptor of a module-info class, which has no types
+    pub module: Option<ModuleDeclaration>,
+    pub types: Vec<TypeDeclaration>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleDeclaration {
+    pub open: bool,
+    pub name: String,
+    pub version: Option<String>,
+    pub requires: Vec<Requires>,
+    pub exports: Vec<PackageDirective>,
+    pub opens: Vec<PackageDirective>,
+    pub uses: Vec<Type>,
+    // the services with their implementations
+    pub provides: Vec<(Type, Vec<Type>)>,
+    // the packages of the module and its main class have no syntax, they are set by tools
+    pub packages: Vec<String>,
+    pub main_class: Option<Type>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requires {
+    pub modifiers: Vec<String>,
+    pub name: String,
+    // the version the module was compiled against
+    pub version: Option<String>,
+}
+
+// `exports` or `opens`, to every module if `to` is empty
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageDirective {
+    pub name: String,
+    pub to: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TypeKind {
+    Class,
+    Interface,
+    Annotation,
+    Enum,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeDeclaration {
+    pub modifiers: Vec<String>,
+    pub kind: TypeKind,
+    pub name: String,
+    pub extends: Vec<Type>,
+    pub implements: Vec<Type>,
+    pub members: Vec<Member>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Member {
+    Field(FieldDeclaration),
+    Method(MethodDeclaration),
+    // the static initializer with the source lines of its code
+    Initializer(Vec<Statement>, Vec<LineSite>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDeclaration {
+    pub modifiers: Vec<String>,
+    pub ty: Type,
+    pub name: String,
+    pub value: Option<Expression>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodDeclaration {
+    pub modifiers: Vec<String>,
+    // None for constructors
+    pub return_type: Option<Type>,
+    pub name: String,
+    pub descriptor: String,
+    pub parameters: Vec<Parameter>,
+    // the last parameter is variable arity
+    pub varargs: bool,
+    pub throws: Vec<Type>,
+    // None for abstract and native methods
+    pub body: Option<Vec<Statement>>,
+    pub lines: Vec<LineSite>,
+}
+
+// An instruction with the source line it was compiled from
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineSite {
+    pub pc: usize,
+    // None without a LineNumberTable
+    pub line: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub ty: Type,
+    pub name: String,
+}
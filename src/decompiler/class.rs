@@ -1,3 +1,7 @@
+use super::ast::{Type, TypeDeclaration, TypeKind};
+use crate::disassembler::attribute::read_inner_classes;
+use std::collections::HashMap;
+
 const PUBLIC: u16 = 0x0001;
 const FINAL: u16 = 0x0010;
 // TODO: Use this shit lol
@@ -7,76 +11,125 @@ const ABSTRACT: u16 = 0x0400;
 const SYNTHETIC: u16 = 0x1000;
 const ANNOTATION: u16 = 0x2000;
 const ENUM: u16 = 0x4000;
+const MODULE: u16 = 0x8000;
 
-pub fn generate_signature_code(class_file: &mut super::ClassFile) -> String {
-    let mut signature = String::new();
+pub fn generate_type_declaration(class_file: &super::ClassFile) -> TypeDeclaration {
+    let mut modifiers = Vec::new();
 
     if class_file.access_flags & PUBLIC != 0 {
-        signature.push_str("public ");
+        modifiers.push("public");
     }
 
     if class_file.access_flags & SYNTHETIC != 0 {
-        signature.push_str("/* synthetic */");
+        modifiers.push("/* synthetic */");
     }
 
-    if class_file.access_flags & ABSTRACT != 0 {
-        if class_file.access_flags & INTERFACE == 0 {
-            signature.push_str("abstract ");
+    let kind = if class_file.access_flags & INTERFACE != 0 {
+        if class_file.access_flags & ANNOTATION != 0 {
+            TypeKind::Annotation
         } else {
-            if class_file.access_flags & ANNOTATION != 0 {
-                signature.push('@');
-            }
-            signature.push_str("interface ");
+            TypeKind::Interface
         }
     } else if class_file.access_flags & ENUM != 0 {
-        signature.push_str("enum ");
+        TypeKind::Enum
     } else {
-        if class_file.access_flags & FINAL != 0 {
-            signature.push_str("final ");
+        if class_file.access_flags & ABSTRACT != 0 {
+            modifiers.push("abstract");
+        } else if class_file.access_flags & FINAL != 0 {
+            modifiers.push("final");
+        }
+        TypeKind::Class
+    };
+
+    let name = get_class_name(class_file.this_class, &class_file.constant_pool)
+        .split('/')
+        .next_back()
+        .unwrap()
+        .to_string();
+
+    // java.lang.Object has no super class, and the one of enums is implicit
+    let mut extends = Vec::new();
+    if class_file.super_class != 0 && kind == TypeKind::Class {
+        let super_class = get_class_name(class_file.super_class, &class_file.constant_pool);
+        if super_class != "java/lang/Object" {
+            extends.push(Type::from_class_name(super_class.as_str()));
         }
-        signature.push_str("class ")
     }
 
-    signature.push_str(
-        format!(
-            "{}",
-            super::class::get_class_name(class_file.this_class, &mut class_file.constant_pool)
-                .split('/')
-                .last()
-                .unwrap()
-        )
-        .as_str(),
-    );
-
-    signature.push_str(
-        format!(
-            " extends {} ",
-            get_class_name(class_file.super_class, &mut class_file.constant_pool).replace("/", ".")
-        )
-        .as_str(),
-    );
-
-    if class_file.interfaces.interface_count > 0 {
-        signature.push_str(
-            format!(
-                "implements {}",
-                super::interface::get_interfaces(class_file)
-            )
-            .as_str(),
+    let mut implements = super::interface::get_interfaces(class_file);
+    if kind == TypeKind::Interface {
+        extends = implements;
+        implements = Vec::new();
+    } else if kind == TypeKind::Annotation {
+        // every annotation implements java.lang.annotation.Annotation
+        implements = Vec::new();
+    }
+
+    TypeDeclaration {
+        modifiers: modifiers.into_iter().map(String::from).collect(),
+        kind,
+        name,
+        extends,
+        implements,
+        members: Vec::new(),
+    }
+}
+
+// Members of interfaces leave out the modifiers they have implicitly
+pub fn is_interface(class_file: &super::ClassFile) -> bool {
+    class_file.access_flags & INTERFACE != 0
+}
+
+// module-info classes only describe a module
+pub fn is_module(class_file: &super::ClassFile) -> bool {
+    class_file.access_flags & MODULE != 0
+}
+
+// The source names of the member classes the class refers to, `java.util.Map.Entry` for
+// `java.util.Map$Entry`. Local and anonymous classes have none.
+pub fn nested_names(class_file: &super::ClassFile) -> HashMap<String, String> {
+    let mut outers = HashMap::new();
+    for inner in read_inner_classes(&class_file.attributes.attributes).unwrap_or_default() {
+        if inner.outer_class == 0 || inner.inner_name == 0 {
+            continue;
+        }
+        let name = match class_file.constant_pool.get_index(inner.inner_name) {
+            super::CPIndexType::Utf8(utf8) => utf8.string,
+            _ => panic!("Invalid Type in Constant Pool"),
+        };
+        outers.insert(
+            get_class_name(inner.inner_class, &class_file.constant_pool).replace('/', "."),
+            (
+                get_class_name(inner.outer_class, &class_file.constant_pool).replace('/', "."),
+                name,
+            ),
         );
     }
 
-    signature
+    outers
+        .keys()
+        .map(|binary| (binary.clone(), source_name(binary, &outers, 0)))
+        .collect()
+}
+
+fn source_name(binary: &str, outers: &HashMap<String, (String, String)>, depth: usize) -> String {
+    match outers.get(binary) {
+        // a class can't be nested in itself, the depth bounds broken attributes
+        Some((outer, name)) if depth < outers.len() => {
+            format!("{}.{}", source_name(outer, outers, depth + 1), name)
+        }
+        _ => binary.to_string(),
+    }
 }
 
-pub fn get_class_name(index: u16, constant_pool: &mut super::ConstantPool) -> String {
+pub fn get_class_name(index: u16, constant_pool: &super::ConstantPool) -> String {
     let class_name_index = match constant_pool.get_index(index) {
         super::CPIndexType::Class(a) => a,
         _ => panic!("Invalid Type in Constant Pool"),
     };
 
     match constant_pool.get_index(class_name_index) {
-        super::CPIndexType::Utf8(a) => a,
+        super::CPIndexType::Utf8(utf8) => utf8.string,
         _ => panic!("Invalid Type in Constant Pool"),
     }
 }
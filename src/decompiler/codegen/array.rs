@@ -1,3 +0,0 @@
-pub fn get_array_name() {
-    
-}
\ No newline at end of file
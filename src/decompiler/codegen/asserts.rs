@@ -0,0 +1,182 @@
+use super::super::ast::*;
+
+// The static field javac adds to classes with assert statements
+pub const ASSERTIONS_DISABLED: &str = "$assertionsDisabled";
+
+// javac compiles `assert c : m;` to
+// `if (!$assertionsDisabled && !c) throw new AssertionError(m);`, or to
+// `if ($assertionsDisabled || c) goto next; throw new AssertionError(m);` before a jump
+pub fn assertions(statements: &mut Vec<Statement>) {
+    for statement in statements.iter_mut() {
+        for child in statement.children_mut() {
+            assertions(child);
+        }
+        if let Some(assertion) = assertion(statement) {
+            *statement = assertion;
+        }
+    }
+
+    let mut i = 0;
+    while i + 1 < statements.len() {
+        if let Some(assertion) = skipped_assertion(&statements[i], &statements[i + 1]) {
+            let exit = match statements.remove(i) {
+                Statement::If { then, .. } => then,
+                _ => unreachable!(),
+            };
+            statements[i] = assertion;
+            let count = exit.len();
+            statements.splice(i + 1..i + 1, exit);
+            i += count;
+        }
+        i += 1;
+    }
+}
+
+fn assertion(statement: &Statement) -> Option<Statement> {
+    let (condition, mut then) = match statement {
+        Statement::If {
+            condition,
+            then,
+            otherwise: None,
+            ..
+        } => (condition, then),
+        _ => return None,
+    };
+    let conditions = operands(condition, BinaryOperator::ConditionalAnd);
+    let position = conditions.iter().position(|condition| {
+        matches!(condition, Expression::Unary {
+            operator: UnaryOperator::Not,
+            operand,
+        } if is_flag(operand))
+    })?;
+    let mut failed = conditions[position + 1..].to_vec();
+    // `if (!$assertionsDisabled) { if (!c) throw ...; }`
+    while let [Statement::If {
+        condition,
+        then: inner,
+        otherwise: None,
+        ..
+    }] = then.as_slice()
+    {
+        failed.extend(operands(condition, BinaryOperator::ConditionalAnd));
+        then = inner;
+    }
+    let (message, thrown_pcs) = match then.as_slice() {
+        [thrown_statement] => (thrown(thrown_statement)?, thrown_statement.pcs()),
+        _ => return None,
+    };
+    let pcs = statement.pcs().join(thrown_pcs);
+
+    let assertion = Statement::Assert {
+        condition: match joined(failed, BinaryOperator::ConditionalAnd) {
+            Some(failed) => negate(failed),
+            None => Expression::Literal(Literal::Boolean(false)),
+        },
+        message,
+        pcs,
+    };
+    // the assertion is inside an if whose condition was combined with the check
+    Some(
+        match joined(
+            conditions[..position].to_vec(),
+            BinaryOperator::ConditionalAnd,
+        ) {
+            Some(condition) => Statement::If {
+                condition,
+                then: vec![assertion],
+                otherwise: None,
+                pcs,
+            },
+            None => assertion,
+        },
+    )
+}
+
+fn skipped_assertion(statement: &Statement, next: &Statement) -> Option<Statement> {
+    let condition = match statement {
+        Statement::If {
+            condition,
+            otherwise: None,
+            ..
+        } => condition,
+        _ => return None,
+    };
+    let conditions = operands(condition, BinaryOperator::ConditionalOr);
+    if !is_flag(&conditions[0]) {
+        return None;
+    }
+    let message = thrown(next)?;
+    Some(Statement::Assert {
+        condition: joined(conditions[1..].to_vec(), BinaryOperator::ConditionalOr)
+            .unwrap_or(Expression::Literal(Literal::Boolean(false))),
+        message,
+        pcs: statement.pcs().join(next.pcs()),
+    })
+}
+
+// Interfaces keep the field in a nested class
+fn is_flag(expression: &Expression) -> bool {
+    matches!(expression, Expression::StaticField { name, .. } if name == ASSERTIONS_DISABLED)
+}
+
+// The message of `throw new AssertionError(m);`
+fn thrown(statement: &Statement) -> Option<Option<Expression>> {
+    match statement {
+        Statement::Throw(
+            Expression::New {
+                class, arguments, ..
+            },
+            _,
+        ) if class == "java.lang.AssertionError" && arguments.len() <= 1 => {
+            Some(arguments.first().cloned())
+        }
+        _ => None,
+    }
+}
+
+// `a && b && c` as [a, b, c]
+fn operands(expression: &Expression, operator: BinaryOperator) -> Vec<Expression> {
+    match expression {
+        Expression::Binary {
+            operator: kind,
+            left,
+            right,
+        } if *kind == operator => {
+            let mut all = operands(left, operator);
+            all.extend(operands(right, operator));
+            all
+        }
+        expression => vec![expression.clone()],
+    }
+}
+
+fn joined(operands: Vec<Expression>, operator: BinaryOperator) -> Option<Expression> {
+    operands
+        .into_iter()
+        .reduce(|left, right| Expression::Binary {
+            operator,
+            left: Box::new(left),
+            right: Box::new(right),
+        })
+}
+
+// Removes `$assertionsDisabled = !C.class.desiredAssertionStatus();` from the static
+// initializer, the field is left out of the declarations. Interfaces read the field of their
+// nested class instead, to initialize it.
+pub fn remove_flag(statements: &mut Vec<Statement>, class_name: &str) {
+    let class_name = class_name.replace('/', ".");
+    statements.retain(|statement| match statement {
+        Statement::Expression(Expression::Assign { target, .. }, _) => !matches!(
+            target.as_ref(),
+            Expression::StaticField { class, name }
+                if *class == class_name && name == ASSERTIONS_DISABLED
+        ),
+        Statement::If {
+            condition,
+            then,
+            otherwise: None,
+            ..
+        } => !(is_flag(condition) && then.is_empty()),
+        _ => true,
+    });
+}
@@ -0,0 +1,146 @@
+use super::super::instruction::{index_of, DecodedInstruction, Instruction};
+use crate::disassembler::exception::ExceptionTableIndex;
+
+// A run of instructions that is only entered at the top and only left at the bottom
+#[derive(Debug, Clone)]
+pub struct BasicBlock {
+    pub pc: usize,
+    // indexes into the instruction list, `end` is exclusive
+    pub start: usize,
+    pub end: usize,
+    pub successors: Vec<usize>,
+    pub predecessors: Vec<usize>,
+    pub is_handler: bool,
+}
+
+pub fn build_blocks(
+    instructions: &[DecodedInstruction],
+    exception_table: &[ExceptionTableIndex],
+) -> Vec<BasicBlock> {
+    if instructions.is_empty() {
+        return Vec::new();
+    }
+
+    let mut leaders = vec![false; instructions.len()];
+    leaders[0] = true;
+
+    for (index, decoded) in instructions.iter().enumerate() {
+        for target in decoded.branch_targets() {
+            if let Some(target) = index_of(instructions, target) {
+                leaders[target] = true;
+            }
+        }
+
+        if ends_block(&decoded.instruction) && index + 1 < instructions.len() {
+            leaders[index + 1] = true;
+        }
+    }
+
+    let mut handlers = Vec::new();
+    for entry in exception_table {
+        for pc in [entry.start_pc, entry.end_pc, entry.handler_pc].iter() {
+            if let Some(index) = index_of(instructions, *pc as usize) {
+                leaders[index] = true;
+            }
+        }
+        if let Some(index) = index_of(instructions, entry.handler_pc as usize) {
+            handlers.push(index);
+        }
+    }
+
+    // maps every instruction index to its block
+    let mut block_of = Vec::with_capacity(instructions.len());
+    let mut blocks: Vec<BasicBlock> = Vec::new();
+    for (index, leader) in leaders.iter().enumerate() {
+        if *leader {
+            if let Some(block) = blocks.last_mut() {
+                block.end = index;
+            }
+            blocks.push(BasicBlock {
+                pc: instructions[index].pc,
+                start: index,
+                end: instructions.len(),
+                successors: Vec::new(),
+                predecessors: Vec::new(),
+                is_handler: handlers.contains(&index),
+            });
+        }
+        block_of.push(blocks.len() - 1);
+    }
+
+    for block_index in 0..blocks.len() {
+        let last = &instructions[blocks[block_index].end - 1];
+        let mut successors = Vec::new();
+
+        if falls_through(&last.instruction) {
+            if let Some(next) = index_of(instructions, last.pc + last.length) {
+                successors.push(block_of[next]);
+            }
+        }
+        for target in last.branch_targets() {
+            if let Some(target) = index_of(instructions, target) {
+                if !successors.contains(&block_of[target]) {
+                    successors.push(block_of[target]);
+                }
+            }
+        }
+
+        for successor in &successors {
+            if !blocks[*successor].predecessors.contains(&block_index) {
+                blocks[*successor].predecessors.push(block_index);
+            }
+        }
+        blocks[block_index].successors = successors;
+    }
+
+    blocks
+}
+
+// Whether the instruction after this one starts a new block
+fn ends_block(instruction: &Instruction) -> bool {
+    !falls_through(instruction) || is_conditional(instruction) || is_jsr(instruction)
+}
+
+pub fn falls_through(instruction: &Instruction) -> bool {
+    !matches!(
+        instruction,
+        Instruction::GoTo(_)
+            | Instruction::GoToW(_)
+            | Instruction::TableSwitch { .. }
+            | Instruction::LookUpSwitch { .. }
+            | Instruction::Ret(_)
+            | Instruction::Return
+            | Instruction::AReturn
+            | Instruction::DReturn
+            | Instruction::FReturn
+            | Instruction::IReturn
+            | Instruction::LReturn
+            | Instruction::AThrow
+    )
+}
+
+fn is_conditional(instruction: &Instruction) -> bool {
+    matches!(
+        instruction,
+        Instruction::IfACmpEq(_)
+            | Instruction::IfACmpNe(_)
+            | Instruction::IfICmpEq(_)
+            | Instruction::IfICmpGe(_)
+            | Instruction::IfICmpGt(_)
+            | Instruction::IfICmpLe(_)
+            | Instruction::IfICmpLt(_)
+            | Instruction::IfICmpNe(_)
+            | Instruction::IfEq(_)
+            | Instruction::IfGe(_)
+            | Instruction::IfGt(_)
+            | Instruction::IfLe(_)
+            | Instruction::IfLt(_)
+            | Instruction::IfNe(_)
+            | Instruction::IfNonNull(_)
+            | Instruction::IfNull(_)
+    )
+}
+
+fn is_jsr(instruction: &Instruction) -> bool {
+    matches!(instruction, Instruction::Jsr(_) | Instruction::JsrW(_))
+}
@@ -0,0 +1,479 @@
+use super::super::ast::{
+    negate, BinaryOperator, Binding, Expression, Literal, Statement, Type, UnaryOperator,
+};
+use super::cfg::BasicBlock;
+use super::stack::{typed_value, BlockOutput, StackValue, Terminator};
+use super::types::{common_supertype, fits, is_int};
+
+use std::collections::HashSet;
+
+// javac compiles `&&`, `||`, `?:` and boolean values to chains of branches. The blocks that
+// only hold such a branch or value are folded into the block before them and removed.
+pub struct Conditions<'a> {
+    pub blocks: Vec<BasicBlock>,
+    pub removed: Vec<bool>,
+    // blocks that start at these pcs are kept, exception ranges refer to them
+    pinned: &'a HashSet<usize>,
+    // the variables that can become pattern variables, by index
+    assigned_once: &'a [bool],
+}
+
+impl<'a> Conditions<'a> {
+    pub fn new(
+        blocks: &[BasicBlock],
+        pinned: &'a HashSet<usize>,
+        assigned_once: &'a [bool],
+    ) -> Self {
+        Conditions {
+            blocks: blocks.to_vec(),
+            removed: vec![false; blocks.len()],
+            pinned,
+            assigned_once,
+        }
+    }
+
+    // Folds the condition of `index` into the condition of the block falling into it:
+    // `if (a) goto x; if (b) goto x;` is `if (a || b) goto x;` and
+    // `if (a) goto y; if (b) goto x; y:` is `if (!a && b) goto x;`. The combined block may
+    // then be folded into the one before it, which groups `a && (b || c)`.
+    pub fn combine(&mut self, index: usize, outputs: &mut [BlockOutput]) {
+        let mut index = index;
+        while let Some(previous) = self
+            .combine_two(index, outputs)
+            .or_else(|| self.combine_conditional(index, outputs))
+            .or_else(|| self.join(index, outputs))
+        {
+            index = previous;
+        }
+    }
+
+    // Returns the block the condition was folded into
+    fn combine_two(&mut self, index: usize, outputs: &mut [BlockOutput]) -> Option<usize> {
+        if !self.removable(index) || !outputs[index].statements.is_empty() {
+            return None;
+        }
+        let previous = self.previous(index)?;
+        let next_pc = self.blocks[self.next(index)?].pc;
+        if self.blocks[index].predecessors != [previous]
+            || outputs[index].exit_stack != outputs[previous].exit_stack
+        {
+            return None;
+        }
+
+        let condition = match (&outputs[previous].terminator, &outputs[index].terminator) {
+            (
+                Terminator::If {
+                    condition: first,
+                    target: first_target,
+                },
+                Terminator::If {
+                    condition: second,
+                    target: second_target,
+                },
+            ) => {
+                let (operator, first) = if first_target == second_target {
+                    (BinaryOperator::ConditionalOr, first.clone())
+                } else if *first_target == next_pc {
+                    (BinaryOperator::ConditionalAnd, negate(first.clone()))
+                } else {
+                    return None;
+                };
+                Expression::Binary {
+                    operator,
+                    left: Box::new(first),
+                    right: Box::new(second.clone()),
+                }
+            }
+            _ => return None,
+        };
+
+        let terminator = std::mem::replace(&mut outputs[index].terminator, Terminator::Fallthrough);
+        outputs[previous].pcs = outputs[previous].pcs.join(outputs[index].pcs);
+        outputs[previous].terminator = match terminator {
+            Terminator::If { target, .. } => Terminator::If { condition, target },
+            terminator => terminator,
+        };
+        self.remove(index, previous);
+        Some(previous)
+    }
+
+    // A block that a folded conditional value falls into is the rest of the block with the
+    // branch, like `if (a || (b = c ? x : y)) goto z;`
+    fn join(&mut self, index: usize, outputs: &mut [BlockOutput]) -> Option<usize> {
+        let previous = self.previous(index)?;
+        if !self.removable(index)
+            || self.blocks[index].predecessors != [previous]
+            || self.blocks[previous].successors != [index]
+            || !matches!(outputs[previous].terminator, Terminator::Fallthrough)
+            || !matches!(outputs[index].terminator, Terminator::If { .. })
+        {
+            return None;
+        }
+
+        let statements = std::mem::take(&mut outputs[index].statements);
+        outputs[previous].statements.extend(statements);
+        outputs[previous].exit_stack = std::mem::take(&mut outputs[index].exit_stack);
+        outputs[previous].pcs = outputs[previous].pcs.join(outputs[index].pcs);
+        outputs[previous].terminator =
+            std::mem::replace(&mut outputs[index].terminator, Terminator::Fallthrough);
+        self.remove(index, previous);
+        Some(previous)
+    }
+
+    // A conditional used as a condition, like `(c ? a : b) ? x : y`, has a branch for each arm:
+    // `if (c) goto e; if (a) goto x; goto y; e: if (b) goto x; y:` is
+    // `if (c ? b : a) goto x;` where `index` is the block of the `else` arm
+    fn combine_conditional(&mut self, index: usize, outputs: &mut [BlockOutput]) -> Option<usize> {
+        let jump = self.previous(index)?;
+        let then = self.previous(jump)?;
+        let branch = self.previous(then)?;
+        let next_pc = self.blocks[self.next(index)?].pc;
+        let arms = [then, jump, index];
+        if arms.iter().any(|arm| {
+            !self.removable(*arm)
+                || !outputs[*arm].statements.is_empty()
+                || outputs[*arm].exit_stack != outputs[branch].exit_stack
+        }) || self.blocks[then].predecessors != [branch]
+            || self.blocks[jump].predecessors != [then]
+            || self.blocks[index].predecessors != [branch]
+        {
+            return None;
+        }
+
+        let (condition, then_condition, target, other_target, else_condition) = match (
+            &outputs[branch].terminator,
+            &outputs[then].terminator,
+            &outputs[jump].terminator,
+            &outputs[index].terminator,
+        ) {
+            (
+                Terminator::If {
+                    condition,
+                    target: else_pc,
+                },
+                Terminator::If {
+                    condition: then_condition,
+                    target,
+                },
+                Terminator::Goto(other_target),
+                Terminator::If {
+                    condition: else_condition,
+                    target: else_target,
+                },
+            ) if *else_pc == self.blocks[index].pc && else_target == target => (
+                condition,
+                then_condition,
+                *target,
+                *other_target,
+                else_condition,
+            ),
+            _ => return None,
+        };
+        // the `then` arm goes to where the `else` arm falls through to
+        if other_target != next_pc {
+            return None;
+        }
+        let then_condition = then_condition.clone();
+
+        let condition = match condition {
+            Expression::Unary {
+                operator: UnaryOperator::Not,
+                operand,
+            } => Expression::Conditional {
+                condition: operand.clone(),
+                then: Box::new(then_condition),
+                otherwise: Box::new(else_condition.clone()),
+            },
+            condition => Expression::Conditional {
+                condition: Box::new(condition.clone()),
+                then: Box::new(else_condition.clone()),
+                otherwise: Box::new(then_condition),
+            },
+        };
+        outputs[branch].terminator = Terminator::If { condition, target };
+        for arm in arms.iter() {
+            outputs[branch].pcs = outputs[branch].pcs.join(outputs[*arm].pcs);
+            outputs[*arm].terminator = Terminator::Fallthrough;
+        }
+        self.remove(then, branch);
+        self.remove(jump, branch);
+        self.remove(index, branch);
+        Some(branch)
+    }
+
+    // `x instanceof T` followed by `s = (T) x` in the block the check falls into, or jumps to
+    // when it holds, is the pattern `x instanceof T s`
+    pub fn pattern(&mut self, index: usize, outputs: &mut [BlockOutput]) {
+        let (branch, taken) = match self.blocks[index].predecessors.as_slice() {
+            [branch] if Some(*branch) == self.previous(index) => (*branch, false),
+            [branch] if *branch < index => (*branch, true),
+            _ => return,
+        };
+        let (variable, checked, ty) = match outputs[index].statements.first() {
+            Some(Statement::Expression(
+                Expression::Assign {
+                    target,
+                    operator: None,
+                    value,
+                },
+                _,
+            )) => match (target.as_ref(), value.as_ref()) {
+                (
+                    Expression::Local(binding),
+                    Expression::Cast {
+                        ty,
+                        expression,
+                        widening: false,
+                    },
+                ) if self.assigned_once.get(binding.index as usize) == Some(&true) => {
+                    (binding.clone(), expression.as_ref().clone(), ty.clone())
+                }
+                _ => return,
+            },
+            _ => return,
+        };
+
+        let pc = self.blocks[index].pc;
+        let checked_instance = match &mut outputs[branch].terminator {
+            Terminator::If { condition, target } if (*target == pc) == taken => {
+                if taken {
+                    checked_when_true(condition)
+                } else {
+                    checked_when_false(condition)
+                }
+            }
+            _ => return,
+        };
+        if let Some(Expression::InstanceOf {
+            expression,
+            ty: checked_type,
+            binding: binding @ None,
+        }) = checked_instance
+        {
+            if **expression == checked && *checked_type == ty {
+                *binding = Some(Binding::Variable(variable));
+                outputs[index].statements.remove(0);
+            }
+        }
+    }
+
+    // Folds `cond ? a : b` where the blocks pushing `a` and `b` jump to `index` into the block
+    // with the branch, until there are none left. `cond ? 1 : 0` is a boolean. javac jumps
+    // from the inner arms of nested conditionals straight to the end of the outer one.
+    pub fn conditional_values(&mut self, index: usize, outputs: &mut [BlockOutput]) {
+        while let Some(otherwise) = self.blocks[index]
+            .predecessors
+            .iter()
+            .copied()
+            .find(|otherwise| self.conditional(*otherwise, index, outputs).is_some())
+        {
+            let then = self.previous(otherwise).unwrap();
+            let branch = self.previous(then).unwrap();
+            let condition = self.conditional(otherwise, index, outputs).unwrap().clone();
+            let then_value = outputs[then].exit_stack.last().unwrap().clone();
+            let otherwise_value = outputs[otherwise].exit_stack.last().unwrap().clone();
+
+            // the branch is taken when the condition holds, so the fallthrough block is its
+            // `else`
+            let value = match (&then_value.expression, &otherwise_value.expression) {
+                (Expression::Literal(Literal::Int(1)), Expression::Literal(Literal::Int(0))) => {
+                    StackValue {
+                        expression: negate(condition),
+                        ty: Type::Boolean,
+                    }
+                }
+                (Expression::Literal(Literal::Int(0)), Expression::Literal(Literal::Int(1))) => {
+                    StackValue {
+                        expression: condition,
+                        ty: Type::Boolean,
+                    }
+                }
+                _ => {
+                    let ty = conditional_type(&then_value, &otherwise_value);
+                    StackValue {
+                        expression: Expression::Conditional {
+                            condition: Box::new(negate(condition)),
+                            then: Box::new(typed_value(then_value.clone(), &ty)),
+                            otherwise: Box::new(typed_value(otherwise_value.clone(), &ty)),
+                        },
+                        ty,
+                    }
+                }
+            };
+
+            outputs[branch].exit_stack.push(value);
+            outputs[branch].pcs = outputs[branch].pcs.join(outputs[then].pcs);
+            outputs[branch].pcs = outputs[branch].pcs.join(outputs[otherwise].pcs);
+            outputs[branch].terminator = match outputs[otherwise].terminator {
+                Terminator::Fallthrough => Terminator::Fallthrough,
+                _ => Terminator::Goto(self.blocks[index].pc),
+            };
+            self.blocks[branch].successors = vec![index];
+            let predecessors = &mut self.blocks[index].predecessors;
+            predecessors.retain(|predecessor| *predecessor != then && *predecessor != otherwise);
+            predecessors.push(branch);
+            for block in [then, otherwise].iter() {
+                self.blocks[*block].predecessors.clear();
+                self.blocks[*block].successors.clear();
+                self.removed[*block] = true;
+            }
+        }
+    }
+
+    // The condition of the branch in front of `otherwise` and the block before it, if they are
+    // the arms of a conditional ending at `join` that each push a value
+    fn conditional<'o>(
+        &self,
+        otherwise: usize,
+        join: usize,
+        outputs: &'o [BlockOutput],
+    ) -> Option<&'o Expression> {
+        let then = self.previous(otherwise)?;
+        let branch = self.previous(then)?;
+        if otherwise >= outputs.len()
+            || !self.removable(then)
+            || !self.removable(otherwise)
+            || self.blocks[then].predecessors != [branch]
+            || self.blocks[otherwise].predecessors != [branch]
+            || !outputs[then].statements.is_empty()
+            || !outputs[otherwise].statements.is_empty()
+        {
+            return None;
+        }
+
+        let join_pc = self.blocks[join].pc;
+        let condition = match &outputs[branch].terminator {
+            Terminator::If { condition, target } if *target == self.blocks[otherwise].pc => {
+                condition
+            }
+            _ => return None,
+        };
+        let jumps =
+            |terminator: &Terminator| matches!(terminator, Terminator::Goto(pc) if *pc == join_pc);
+        let falls_through = matches!(outputs[otherwise].terminator, Terminator::Fallthrough)
+            && self.next(otherwise) == Some(join);
+        if !jumps(&outputs[then].terminator)
+            || !(jumps(&outputs[otherwise].terminator) || falls_through)
+        {
+            return None;
+        }
+
+        // both push a value onto what the branch left
+        let stack = outputs[branch].exit_stack.as_slice();
+        let pushes = |output: &BlockOutput| {
+            output.exit_stack.len() == stack.len() + 1 && output.exit_stack[..stack.len()] == *stack
+        };
+        if pushes(&outputs[then]) && pushes(&outputs[otherwise]) {
+            Some(condition)
+        } else {
+            None
+        }
+    }
+
+    // Moves the edges of a removed block to the block that took over its terminator
+    fn remove(&mut self, index: usize, into: usize) {
+        let successors = std::mem::take(&mut self.blocks[index].successors);
+        for successor in &successors {
+            let predecessors = &mut self.blocks[*successor].predecessors;
+            predecessors.retain(|predecessor| *predecessor != index);
+            if !predecessors.contains(&into) {
+                predecessors.push(into);
+            }
+        }
+        self.blocks[into].successors = successors;
+        self.blocks[index].predecessors.clear();
+        self.removed[index] = true;
+    }
+
+    fn removable(&self, index: usize) -> bool {
+        !self.blocks[index].is_handler && !self.pinned.contains(&self.blocks[index].pc)
+    }
+
+    fn previous(&self, index: usize) -> Option<usize> {
+        (0..index).rev().find(|index| !self.removed[*index])
+    }
+
+    fn next(&self, index: usize) -> Option<usize> {
+        (index + 1..self.blocks.len()).find(|index| !self.removed[*index])
+    }
+
+    // The blocks and outputs that are left, with the edges renumbered
+    pub fn finish(self, outputs: Vec<BlockOutput>) -> (Vec<BasicBlock>, Vec<BlockOutput>) {
+        let mut numbers = Vec::with_capacity(self.blocks.len());
+        let mut count = 0;
+        for removed in &self.removed {
+            numbers.push(count);
+            if !removed {
+                count += 1;
+            }
+        }
+        let renumber = |edges: Vec<usize>| edges.into_iter().map(|edge| numbers[edge]).collect();
+
+        let removed = self.removed;
+        let blocks = self
+            .blocks
+            .into_iter()
+            .zip(removed.iter())
+            .filter(|(_, removed)| !**removed)
+            .map(|(block, _)| BasicBlock {
+                successors: renumber(block.successors),
+                predecessors: renumber(block.predecessors),
+                ..block
+            })
+            .collect();
+        let outputs = outputs
+            .into_iter()
+            .zip(removed.iter())
+            .filter(|(_, removed)| !**removed)
+            .map(|(output, _)| output)
+            .collect();
+        (blocks, outputs)
+    }
+}
+
+// An int constant fits the type of the other value, `c ? 'a' : d` is a char
+fn conditional_type(then: &StackValue, otherwise: &StackValue) -> Type {
+    match (&then.expression, &otherwise.expression) {
+        (Expression::Literal(Literal::Int(value)), _) if fits(*value, &otherwise.ty) => {
+            otherwise.ty.clone()
+        }
+        (_, Expression::Literal(Literal::Int(value))) if fits(*value, &then.ty) => then.ty.clone(),
+        // a folded `c ? 0 : 1` next to other ints is an int again
+        _ if (then.ty == Type::Boolean) != (otherwise.ty == Type::Boolean)
+            && is_int(&then.ty)
+            && is_int(&otherwise.ty) =>
+        {
+            Type::Int
+        }
+        _ => common_supertype(&then.ty, &otherwise.ty),
+    }
+}
+
+// The instanceof that has to hold for the branch to be taken, the last operand of `&&`
+fn checked_when_true(condition: &mut Expression) -> Option<&mut Expression> {
+    match condition {
+        Expression::InstanceOf { .. } => Some(condition),
+        Expression::Binary {
+            operator: BinaryOperator::ConditionalAnd,
+            right,
+            ..
+        } => checked_when_true(right),
+        _ => None,
+    }
+}
+
+// The instanceof that has to hold for the branch not to be taken, the last operand of `||`
+fn checked_when_false(condition: &mut Expression) -> Option<&mut Expression> {
+    match condition {
+        Expression::Unary {
+            operator: UnaryOperator::Not,
+            operand,
+        } if matches!(operand.as_ref(), Expression::InstanceOf { .. }) => Some(operand),
+        Expression::Binary {
+            operator: BinaryOperator::ConditionalOr,
+            right,
+            ..
+        } => checked_when_false(right),
+        _ => None,
+    }
+}
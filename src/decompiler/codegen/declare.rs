@@ -0,0 +1,355 @@
+use super::super::ast::{Binding, Expression, Local, Pcs, Statement, Type};
+
+use std::collections::{HashMap, HashSet};
+
+// Declares every variable in the innermost block that contains all of its uses, at its
+// first use. Variables whose declarations end up in overlapping scopes with the same name
+// are renamed.
+pub fn declare_variables(statements: &mut Vec<Statement>, types: &[Type], parameters: &[Local]) {
+    let mut skipped: HashSet<u16> = parameters.iter().map(|local| local.index).collect();
+    let mut pending = Vec::new();
+    for statement in statements.iter_mut() {
+        statement.walk_mut(&mut |statement| {
+            let mut statement = statement;
+            while let Statement::Labeled { body, .. } = statement {
+                statement = body.as_mut();
+            }
+            if let Statement::Try {
+                resources, catches, ..
+            } = statement
+            {
+                skipped.extend(catches.iter().map(|catch| catch.local.index));
+                for resource in resources {
+                    if let Statement::Declaration { ty, local, .. } = resource {
+                        if let Some(found) = types.get(local.index as usize) {
+                            *ty = found.clone();
+                        }
+                        skipped.insert(local.index);
+                    }
+                }
+            }
+            if let Statement::ForEach { ty, local, .. } = statement {
+                if let Some(found) = types.get(local.index as usize) {
+                    *ty = found.clone();
+                }
+                skipped.insert(local.index);
+            }
+            // pattern variables are declared by their pattern
+            statement.expressions_mut(&mut |expression| {
+                expression.visit(&mut |expression| {
+                    if let Expression::InstanceOf {
+                        binding: Some(Binding::Variable(binding)),
+                        ..
+                    } = expression
+                    {
+                        skipped.insert(binding.index);
+                    }
+                })
+            });
+        });
+        for local in locals(statement) {
+            if !pending.contains(&local) {
+                pending.push(local);
+            }
+        }
+    }
+    pending.retain(|local| !skipped.contains(local) && (*local as usize) < types.len());
+    pending.sort_unstable();
+    place(statements, pending, types);
+
+    let mut visible: Vec<String> = parameters.iter().map(|local| local.name.clone()).collect();
+    let mut taken: HashSet<String> = visible.iter().cloned().collect();
+    for statement in statements.iter_mut() {
+        statement.walk_mut(&mut |statement| {
+            statement.expressions_mut(&mut |expression| {
+                expression.visit(&mut |expression| {
+                    if let Expression::Local(local) = expression {
+                        taken.insert(local.name.clone());
+                    }
+                })
+            })
+        });
+    }
+    let mut renames = HashMap::new();
+    rename(statements, &mut visible, &mut taken, &mut renames, true);
+    if renames.is_empty() {
+        return;
+    }
+    for statement in statements.iter_mut() {
+        statement.walk_mut(&mut |statement| {
+            statement.expressions_mut(&mut |expression| {
+                expression.visit_mut(&mut |expression| {
+                    if let Expression::Local(local) = expression {
+                        if let Some(name) = renames.get(&local.index) {
+                            local.name = name.clone();
+                        }
+                    }
+                })
+            })
+        });
+    }
+}
+
+// The variables used anywhere in the statement
+fn locals(statement: &mut Statement) -> Vec<u16> {
+    let mut locals = Vec::new();
+    statement.walk_mut(&mut |statement| own_locals(statement, &mut locals));
+    locals
+}
+
+// The variables used by the statement's own expressions, not by nested statements
+fn own_locals(statement: &mut Statement, locals: &mut Vec<u16>) {
+    statement.expressions_mut(&mut |expression| {
+        expression.visit(&mut |expression| {
+            if let Expression::Local(local) = expression {
+                if !locals.contains(&local.index) {
+                    locals.push(local.index);
+                }
+            }
+        })
+    });
+}
+
+fn place(statements: &mut Vec<Statement>, pending: Vec<u16>, types: &[Type]) {
+    if pending.is_empty() {
+        return;
+    }
+
+    let uses: Vec<Vec<u16>> = statements.iter_mut().map(locals).collect();
+    // (statement, child list) to the variables declared in it
+    let mut nested: HashMap<(usize, usize), Vec<u16>> = HashMap::new();
+    let mut here: Vec<(usize, u16)> = Vec::new();
+    for local in pending {
+        let users: Vec<usize> = (0..statements.len())
+            .filter(|i| uses[*i].contains(&local))
+            .collect();
+        let first = match users.first() {
+            Some(first) => *first,
+            None => continue,
+        };
+        match (users.len(), inner_scope(&mut statements[first], local)) {
+            (1, Some(child)) => nested.entry((first, child)).or_default().push(local),
+            _ => here.push((first, local)),
+        }
+    }
+
+    for ((statement, child), pending) in nested {
+        if let Some(child) = statements[statement].children_mut().into_iter().nth(child) {
+            place(child, pending, types);
+        }
+    }
+
+    // back to front, so the positions stay valid, and an assignment that can become the
+    // declaration before the other declarations are inserted in front of it
+    let mut here: Vec<(usize, bool, u16, String)> = here
+        .into_iter()
+        .map(|(position, local)| {
+            let statement = &mut statements[position];
+            (
+                position,
+                assigns(statement, local),
+                local,
+                name(statement, local),
+            )
+        })
+        .collect();
+    here.sort_by_key(|(position, assigned, ..)| std::cmp::Reverse((*position, *assigned)));
+    for (position, assigned, local, name) in here {
+        let ty = types[local as usize].clone();
+        if assigned {
+            if let Statement::Expression(Expression::Assign { value, .. }, pcs) =
+                &statements[position]
+            {
+                statements[position] = Statement::Declaration {
+                    ty,
+                    local: Local { index: local, name },
+                    value: Some(value.as_ref().clone()),
+                    pcs: *pcs,
+                };
+                continue;
+            }
+        }
+        statements.insert(
+            position,
+            Statement::Declaration {
+                ty,
+                local: Local { index: local, name },
+                value: None,
+                pcs: Pcs::default(),
+            },
+        );
+    }
+}
+
+// The name a statement uses for a variable
+fn name(statement: &mut Statement, local: u16) -> String {
+    let mut name = String::new();
+    statement.walk_mut(&mut |statement| {
+        statement.expressions_mut(&mut |expression| {
+            expression.visit(&mut |expression| match expression {
+                Expression::Local(found) if found.index == local && name.is_empty() => {
+                    name = found.name.clone()
+                }
+                _ => {}
+            })
+        })
+    });
+    name
+}
+
+// The child list of a statement that can hold the declaration of a variable the statement
+// uses, if the variable is only used in one of them
+fn inner_scope(statement: &mut Statement, local: u16) -> Option<usize> {
+    let mut own = Vec::new();
+    own_locals(statement, &mut own);
+    if own.contains(&local) {
+        return None;
+    }
+
+    let is_loop = matches!(
+        statement,
+        Statement::While { .. }
+            | Statement::DoWhile { .. }
+            | Statement::For { .. }
+            | Statement::ForEach { .. }
+    ) || matches!(statement, Statement::Labeled { body, .. } if matches!(
+        body.as_ref(),
+        Statement::While { .. }
+            | Statement::DoWhile { .. }
+            | Statement::For { .. }
+            | Statement::ForEach { .. }
+    ));
+
+    let mut found = None;
+    for (i, child) in statement.children_mut().into_iter().enumerate() {
+        if !child
+            .iter_mut()
+            .any(|statement| locals(statement).contains(&local))
+        {
+            continue;
+        }
+        if found.is_some() {
+            return None;
+        }
+        // a value from the previous iteration can't be kept in a variable of the body
+        if is_loop && !initializes(child, local) {
+            return None;
+        }
+        found = Some(i);
+    }
+    found
+}
+
+// Whether the declaration of the variable in these statements is an assignment that can take
+// an initializer, so every use comes after it
+fn initializes(statements: &mut [Statement], local: u16) -> bool {
+    let users: Vec<usize> = (0..statements.len())
+        .filter(|i| locals(&mut statements[*i]).contains(&local))
+        .collect();
+    match users.as_slice() {
+        [] => false,
+        [only] => match inner_scope(&mut statements[*only], local) {
+            Some(child) => initializes(statements[*only].children_mut()[child], local),
+            None => assigns(&statements[*only], local),
+        },
+        [first, ..] => assigns(&statements[*first], local),
+    }
+}
+
+// Whether the statement is a plain assignment to the variable that doesn't read it
+fn assigns(statement: &Statement, local: u16) -> bool {
+    match statement {
+        Statement::Expression(
+            Expression::Assign {
+                target,
+                operator: None,
+                value,
+            },
+            _,
+        ) => {
+            matches!(target.as_ref(), Expression::Local(target) if target.index == local)
+                && !value.effects().locals_read.contains(&local)
+        }
+        _ => false,
+    }
+}
+
+fn rename(
+    statements: &mut [Statement],
+    visible: &mut Vec<String>,
+    taken: &mut HashSet<String>,
+    renames: &mut HashMap<u16, String>,
+    scoped: bool,
+) {
+    let mark = visible.len();
+    for statement in statements.iter_mut() {
+        let mut statement = statement;
+        while let Statement::Labeled { body, .. } = statement {
+            statement = body.as_mut();
+        }
+        match statement {
+            Statement::Declaration { local, .. } => declare(local, visible, taken, renames),
+            Statement::Switch { cases, .. } => {
+                // the cases share a scope
+                let mark = visible.len();
+                for case in cases.iter_mut() {
+                    rename(&mut case.body, visible, taken, renames, false);
+                }
+                visible.truncate(mark);
+            }
+            Statement::ForEach { local, body, .. } => {
+                let mark = visible.len();
+                declare(local, visible, taken, renames);
+                rename(body, visible, taken, renames, true);
+                visible.truncate(mark);
+            }
+            Statement::Try {
+                resources,
+                body,
+                catches,
+                finally,
+            } => {
+                // the resources are only visible in the body
+                let mark = visible.len();
+                rename(resources, visible, taken, renames, false);
+                rename(body, visible, taken, renames, true);
+                visible.truncate(mark);
+                for catch in catches.iter_mut() {
+                    let mark = visible.len();
+                    declare(&mut catch.local, visible, taken, renames);
+                    rename(&mut catch.body, visible, taken, renames, true);
+                    visible.truncate(mark);
+                }
+                if let Some(finally) = finally {
+                    rename(finally, visible, taken, renames, true);
+                }
+            }
+            statement => {
+                for child in statement.children_mut() {
+                    rename(child, visible, taken, renames, true);
+                }
+            }
+        }
+    }
+    if scoped {
+        visible.truncate(mark);
+    }
+}
+
+fn declare(
+    local: &mut Local,
+    visible: &mut Vec<String>,
+    taken: &mut HashSet<String>,
+    renames: &mut HashMap<u16, String>,
+) {
+    if visible.contains(&local.name) {
+        let name = (1..)
+            .map(|n| format!("{}_{}", local.name, n))
+            .find(|name| !taken.contains(name))
+            .unwrap();
+        taken.insert(name.clone());
+        renames.insert(local.index, name.clone());
+        local.name = name;
+    }
+    visible.push(local.name.clone());
+}
@@ -0,0 +1,253 @@
+use super::super::ast::*;
+
+use std::collections::HashMap;
+
+// javac expands `for (T x : array)` to copies of the array and its length and an index, and
+// `for (T x : iterable)` to an iterator. The loops are rewritten back if those variables
+// aren't used anywhere else.
+pub fn enhanced_loops(statements: &mut Vec<Statement>) {
+    let mut uses = HashMap::new();
+    for statement in statements.iter_mut() {
+        count(statement, &mut uses);
+    }
+    rewrite(statements, &uses);
+}
+
+// Counts how often every variable is used or assigned
+pub fn count(statement: &mut Statement, uses: &mut HashMap<u16, usize>) {
+    statement.walk_mut(&mut |statement| {
+        statement.expressions_mut(&mut |expression| {
+            expression.visit(&mut |expression| {
+                if let Expression::Local(local) = expression {
+                    *uses.entry(local.index).or_insert(0) += 1;
+                }
+            })
+        })
+    });
+}
+
+fn rewrite(statements: &mut Vec<Statement>, uses: &HashMap<u16, usize>) {
+    for statement in statements.iter_mut() {
+        for child in statement.children_mut() {
+            rewrite(child, uses);
+        }
+    }
+    for position in 0..statements.len() {
+        if position >= statements.len() {
+            break;
+        }
+        if !array_loop(statements, position, uses) {
+            iterator_loop(statements, position, uses);
+        }
+    }
+}
+
+// `a = array; length = a.length; i = 0; while (i < length) { x = a[i]; ...; i++; }`, with a
+// `continue` in the body the increment is the update of a for loop
+fn array_loop(
+    statements: &mut Vec<Statement>,
+    position: usize,
+    uses: &HashMap<u16, usize>,
+) -> bool {
+    if position + 3 >= statements.len() {
+        return false;
+    }
+    let (array, iterable) = match assignment(&statements[position]) {
+        Some((local, value)) => (local.clone(), value.clone()),
+        None => return false,
+    };
+    let length = match assignment(&statements[position + 1]) {
+        Some((local, Expression::ArrayLength(target)))
+            if **target == Expression::Local(array.clone()) =>
+        {
+            local.clone()
+        }
+        _ => return false,
+    };
+    let index = match assignment(&statements[position + 2]) {
+        Some((local, Expression::Literal(Literal::Int(0)))) => local.clone(),
+        _ => return false,
+    };
+    if uses.get(&array.index) != Some(&3)
+        || uses.get(&length.index) != Some(&2)
+        || uses.get(&index.index) != Some(&4)
+    {
+        return false;
+    }
+
+    let bounded = Expression::Binary {
+        operator: BinaryOperator::Lt,
+        left: Box::new(Expression::Local(index.clone())),
+        right: Box::new(Expression::Local(length)),
+    };
+    let element = Expression::ArrayElement {
+        array: Box::new(Expression::Local(array)),
+        index: Box::new(Expression::Local(index.clone())),
+    };
+    let statement = unlabeled(&mut statements[position + 3]);
+    let loop_pcs = statement.pcs();
+    let body = match statement {
+        Statement::While {
+            condition, body, ..
+        } if *condition == bounded
+            && matches!(body.last(), Some(Statement::Expression(increment, _))
+                    if is_increment(increment, &index)) =>
+        {
+            body.pop();
+            body
+        }
+        Statement::For {
+            condition: Some(condition),
+            update,
+            body,
+            ..
+        } if *condition == bounded
+            && matches!(update.as_slice(), [increment] if is_increment(increment, &index)) =>
+        {
+            body
+        }
+        _ => return false,
+    };
+    let local = match body.first().and_then(assignment) {
+        Some((local, value)) if *widened(value) == element => local.clone(),
+        _ => return false,
+    };
+    if !inside(body, &local, uses) {
+        return false;
+    }
+
+    // the loop is compiled from the statements of the header and the ones it takes from the body
+    let pcs = loop_pcs.join(body.remove(0).pcs());
+    *statement = Statement::ForEach {
+        ty: Type::Unknown,
+        local,
+        iterable,
+        body: std::mem::take(body),
+        pcs,
+    };
+    let header = statements.drain(position..position + 3);
+    let pcs = header.fold(pcs, |pcs, statement| pcs.join(statement.pcs()));
+    if let Some(loop_pcs) = statements[position].pcs_mut() {
+        *loop_pcs = pcs;
+    }
+    true
+}
+
+// `it = iterable.iterator(); while (it.hasNext()) { x = it.next(); ... }`. Iterables are raw,
+// so loops over elements that are cast or unboxed keep their iterator.
+fn iterator_loop(
+    statements: &mut Vec<Statement>,
+    position: usize,
+    uses: &HashMap<u16, usize>,
+) -> bool {
+    if position + 1 >= statements.len() {
+        return false;
+    }
+    let (iterator, iterable) = match assignment(&statements[position]) {
+        Some((
+            local,
+            Expression::Invoke {
+                target: Some(target),
+                name,
+                arguments,
+                ..
+            },
+        )) if name == "iterator" && arguments.is_empty() => (local.clone(), (**target).clone()),
+        _ => return false,
+    };
+    if uses.get(&iterator.index) != Some(&3) {
+        return false;
+    }
+
+    let statement = unlabeled(&mut statements[position + 1]);
+    let loop_pcs = statement.pcs();
+    let body = match statement {
+        Statement::While {
+            condition, body, ..
+        } if is_call(condition, &iterator, "hasNext") => body,
+        _ => return false,
+    };
+    let local = match body.first().and_then(assignment) {
+        Some((local, value)) if is_call(value, &iterator, "next") => local.clone(),
+        _ => return false,
+    };
+    if !inside(body, &local, uses) {
+        return false;
+    }
+
+    let pcs = loop_pcs.join(body.remove(0).pcs());
+    *statement = Statement::ForEach {
+        ty: Type::Unknown,
+        local,
+        iterable,
+        body: std::mem::take(body),
+        pcs,
+    };
+    let pcs = pcs.join(statements.remove(position).pcs());
+    if let Some(loop_pcs) = statements[position].pcs_mut() {
+        *loop_pcs = pcs;
+    }
+    true
+}
+
+// The variable and value of `x = value;`
+fn assignment(statement: &Statement) -> Option<(&Local, &Expression)> {
+    match statement {
+        Statement::Expression(
+            Expression::Assign {
+                target,
+                operator: None,
+                value,
+            },
+            _,
+        ) => match target.as_ref() {
+            Expression::Local(local) => Some((local, value)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn unlabeled(statement: &mut Statement) -> &mut Statement {
+    match statement {
+        Statement::Labeled { body, .. } => unlabeled(body),
+        statement => statement,
+    }
+}
+
+fn is_increment(expression: &Expression, local: &Local) -> bool {
+    matches!(expression, Expression::Increment {
+        target,
+        operator: BinaryOperator::Add,
+        ..
+    } if **target == Expression::Local(local.clone()))
+}
+
+fn is_call(expression: &Expression, local: &Local, method: &str) -> bool {
+    matches!(expression, Expression::Invoke {
+        target: Some(target),
+        name,
+        arguments,
+        ..
+    } if name == method && arguments.is_empty() && **target == Expression::Local(local.clone()))
+}
+
+fn widened(expression: &Expression) -> &Expression {
+    match expression {
+        Expression::Cast {
+            expression,
+            widening: true,
+            ..
+        } => widened(expression),
+        expression => expression,
+    }
+}
+
+// Whether every use of the variable is in the statements
+fn inside(statements: &mut [Statement], local: &Local, uses: &HashMap<u16, usize>) -> bool {
+    let mut found = HashMap::new();
+    for statement in statements.iter_mut() {
+        count(statement, &mut found);
+    }
+    found.get(&local.index) == uses.get(&local.index)
+}
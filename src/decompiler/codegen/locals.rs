@@ -0,0 +1,522 @@
+use super::super::ast::Type;
+use super::super::instruction::{DecodedInstruction, Instruction};
+use super::super::{CPIndexType, CodeAttribute, ConstantPool, Naming};
+use super::cfg::BasicBlock;
+use crate::disassembler::descriptor;
+
+use std::collections::HashMap;
+
+// A source variable, all loads and stores of a slot that reach each other belong to the same
+// variable. javac reuses slots for unrelated variables, which become separate variables here.
+#[derive(Debug)]
+pub struct Variable {
+    pub slot: u16,
+    pub name: String,
+    // from the LocalVariableTable or the method descriptor
+    pub declared_type: Option<Type>,
+    pub is_parameter: bool,
+}
+
+pub struct Variables {
+    pub variables: Vec<Variable>,
+    // the variable every load, store and iinc accesses, by pc
+    accesses: HashMap<usize, usize>,
+    parameters: HashMap<u16, usize>,
+}
+
+impl Variables {
+    pub fn at(&self, pc: usize) -> Option<usize> {
+        self.accesses.get(&pc).copied()
+    }
+
+    pub fn parameter(&self, slot: u16) -> Option<usize> {
+        self.parameters.get(&slot).copied()
+    }
+
+    // Whether every variable is given its value by a single store, parameters are given theirs
+    // by the caller
+    pub fn assigned_once(&self, instructions: &[DecodedInstruction]) -> Vec<bool> {
+        let mut stores = vec![0; self.variables.len()];
+        for decoded in instructions {
+            if let (Some(Access::Store(_)) | Some(Access::Update(_)), Some(id)) =
+                (access(&decoded.instruction), self.at(decoded.pc))
+            {
+                stores[id] += 1;
+            }
+        }
+        stores
+            .iter()
+            .zip(self.variables.iter())
+            .map(|(stores, variable)| *stores == 1 && !variable.is_parameter)
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Access {
+    Load(u16),
+    Store(u16),
+    // iinc reads and writes the slot
+    Update(u16),
+}
+
+fn access(instruction: &Instruction) -> Option<Access> {
+    Some(match *instruction {
+        Instruction::ILoad(index)
+        | Instruction::LLoad(index)
+        | Instruction::FLoad(index)
+        | Instruction::DLoad(index)
+        | Instruction::ALoad(index)
+        | Instruction::Ret(index) => Access::Load(index),
+        Instruction::ILoad0
+        | Instruction::LLoad0
+        | Instruction::FLoad0
+        | Instruction::DLoad0
+        | Instruction::ALoad0 => Access::Load(0),
+        Instruction::ILoad1
+        | Instruction::LLoad1
+        | Instruction::FLoad1
+        | Instruction::DLoad1
+        | Instruction::ALoad1 => Access::Load(1),
+        Instruction::ILoad2
+        | Instruction::LLoad2
+        | Instruction::FLoad2
+        | Instruction::DLoad2
+        | Instruction::ALoad2 => Access::Load(2),
+        Instruction::ILoad3
+        | Instruction::LLoad3
+        | Instruction::FLoad3
+        | Instruction::DLoad3
+        | Instruction::ALoad3 => Access::Load(3),
+        Instruction::IStore(index)
+        | Instruction::LStore(index)
+        | Instruction::FStore(index)
+        | Instruction::DStore(index)
+        | Instruction::AStore(index) => Access::Store(index),
+        Instruction::IStore0
+        | Instruction::LStore0
+        | Instruction::FStore0
+        | Instruction::DStore0
+        | Instruction::AStore0 => Access::Store(0),
+        Instruction::IStore1
+        | Instruction::LStore1
+        | Instruction::FStore1
+        | Instruction::DStore1
+        | Instruction::AStore1 => Access::Store(1),
+        Instruction::IStore2
+        | Instruction::LStore2
+        | Instruction::FStore2
+        | Instruction::DStore2
+        | Instruction::AStore2 => Access::Store(2),
+        Instruction::IStore3
+        | Instruction::LStore3
+        | Instruction::FStore3
+        | Instruction::DStore3
+        | Instruction::AStore3 => Access::Store(3),
+        Instruction::IInc(index, _) => Access::Update(index),
+        _ => return None,
+    })
+}
+
+// The kind of value a load or store moves: the int types, long, float, double or references
+fn kind(instruction: &Instruction) -> Option<Type> {
+    Some(match *instruction {
+        Instruction::ILoad(_)
+        | Instruction::ILoad0
+        | Instruction::ILoad1
+        | Instruction::ILoad2
+        | Instruction::ILoad3
+        | Instruction::IStore(_)
+        | Instruction::IStore0
+        | Instruction::IStore1
+        | Instruction::IStore2
+        | Instruction::IStore3
+        | Instruction::IInc(_, _) => Type::Int,
+        Instruction::LLoad(_)
+        | Instruction::LLoad0
+        | Instruction::LLoad1
+        | Instruction::LLoad2
+        | Instruction::LLoad3
+        | Instruction::LStore(_)
+        | Instruction::LStore0
+        | Instruction::LStore1
+        | Instruction::LStore2
+        | Instruction::LStore3 => Type::Long,
+        Instruction::FLoad(_)
+        | Instruction::FLoad0
+        | Instruction::FLoad1
+        | Instruction::FLoad2
+        | Instruction::FLoad3
+        | Instruction::FStore(_)
+        | Instruction::FStore0
+        | Instruction::FStore1
+        | Instruction::FStore2
+        | Instruction::FStore3 => Type::Float,
+        Instruction::DLoad(_)
+        | Instruction::DLoad0
+        | Instruction::DLoad1
+        | Instruction::DLoad2
+        | Instruction::DLoad3
+        | Instruction::DStore(_)
+        | Instruction::DStore0
+        | Instruction::DStore1
+        | Instruction::DStore2
+        | Instruction::DStore3 => Type::Double,
+        Instruction::ALoad(_)
+        | Instruction::ALoad0
+        | Instruction::ALoad1
+        | Instruction::ALoad2
+        | Instruction::ALoad3
+        | Instruction::AStore(_)
+        | Instruction::AStore0
+        | Instruction::AStore1
+        | Instruction::AStore2
+        | Instruction::AStore3 => Type::Unknown,
+        _ => return None,
+    })
+}
+
+fn kind_of(ty: &Type) -> Type {
+    match ty {
+        Type::Long | Type::Float | Type::Double => ty.clone(),
+        Type::Class(_) | Type::Array(_) => Type::Unknown,
+        _ => Type::Int,
+    }
+}
+
+// A LocalVariableTable entry with its name and type resolved
+struct DebugVariable {
+    start: usize,
+    end: usize,
+    slot: u16,
+    name: String,
+    ty: Type,
+}
+
+// The definitions of every slot that may reach a point, instruction indexes or one past the
+// instructions for the parameters
+type Definitions = Vec<Vec<usize>>;
+
+pub fn find_variables(
+    instructions: &[DecodedInstruction],
+    blocks: &[BasicBlock],
+    code_attribute: &CodeAttribute,
+    constant_pool: &ConstantPool,
+    is_static: bool,
+    method_descriptor: &str,
+    naming: Naming,
+) -> Variables {
+    let slots = code_attribute.max_locals as usize;
+    let parameter_node = |slot: u16| instructions.len() + slot as usize;
+
+    let mut parameter_types = Vec::new();
+    let mut slot = 0;
+    if !is_static {
+        parameter_types.push((0, None));
+        slot += 1;
+    }
+    let (arguments, _) = descriptor::split_method_descriptor(method_descriptor);
+    for argument in arguments {
+        parameter_types.push((slot, Some(Type::from_descriptor(argument.as_str()))));
+        slot += descriptor::slot_size(argument.as_str());
+    }
+
+    let mut entry: Definitions = vec![Vec::new(); slots];
+    for (slot, _) in &parameter_types {
+        if (*slot as usize) < slots {
+            entry[*slot as usize].push(parameter_node(*slot));
+        }
+    }
+
+    // handlers can be entered from anywhere in the blocks they protect
+    let mut protecting: Vec<Vec<usize>> = vec![Vec::new(); blocks.len()];
+    for exception in &code_attribute.exception_table {
+        let handler = match blocks
+            .iter()
+            .position(|block| block.pc == exception.handler_pc as usize)
+        {
+            Some(handler) => handler,
+            None => continue,
+        };
+        for (index, block) in blocks.iter().enumerate() {
+            if block.pc >= exception.start_pc as usize
+                && block.pc < exception.end_pc as usize
+                && !protecting[index].contains(&handler)
+            {
+                protecting[index].push(handler);
+            }
+        }
+    }
+
+    let mut states: Vec<Option<Definitions>> = vec![None; blocks.len()];
+    let mut worklist = Vec::new();
+    if !blocks.is_empty() {
+        states[0] = Some(entry);
+        worklist.push(0);
+    }
+    while let Some(index) = worklist.pop() {
+        let mut state = states[index].clone().unwrap();
+        // everything the handlers may see
+        let mut seen = state.clone();
+        for (i, decoded) in instructions[blocks[index].start..blocks[index].end]
+            .iter()
+            .enumerate()
+        {
+            if let Some(Access::Store(slot)) | Some(Access::Update(slot)) =
+                access(&decoded.instruction)
+            {
+                let slot = slot as usize;
+                if slot < slots {
+                    state[slot] = vec![blocks[index].start + i];
+                    merge(&mut seen[slot], &state[slot]);
+                }
+            }
+        }
+
+        let targets = blocks[index]
+            .successors
+            .iter()
+            .map(|successor| (*successor, &state))
+            .chain(protecting[index].iter().map(|handler| (*handler, &seen)));
+        for (target, definitions) in targets {
+            let changed = match &mut states[target] {
+                Some(existing) => existing
+                    .iter_mut()
+                    .zip(definitions.iter())
+                    .fold(false, |changed, (existing, definitions)| {
+                        merge(existing, definitions) | changed
+                    }),
+                target_state => {
+                    *target_state = Some(definitions.clone());
+                    true
+                }
+            };
+            if changed && !worklist.contains(&target) {
+                worklist.push(target);
+            }
+        }
+    }
+
+    let mut webs = UnionFind::new(instructions.len() + slots);
+    for (block, state) in blocks.iter().zip(states) {
+        let mut state = match state {
+            Some(state) => state,
+            None => continue,
+        };
+        // the loads since the last store, `x = x + y` stores into the same variable it reads
+        // even if the old value isn't used afterwards
+        let mut loaded: Vec<Option<usize>> = vec![None; slots];
+        for (index, decoded) in instructions
+            .iter()
+            .enumerate()
+            .take(block.end)
+            .skip(block.start)
+        {
+            let (slot, reads, writes) = match access(&decoded.instruction) {
+                Some(Access::Load(slot)) => (slot, true, false),
+                Some(Access::Store(slot)) => (slot, false, true),
+                Some(Access::Update(slot)) => (slot, true, true),
+                None => continue,
+            };
+            let slot = slot as usize;
+            if slot >= slots {
+                continue;
+            }
+            if reads {
+                for definition in &state[slot] {
+                    webs.union(index, *definition);
+                }
+            }
+            if writes {
+                state[slot] = vec![index];
+            }
+            match access(&decoded.instruction) {
+                Some(Access::Load(_)) => loaded[slot] = Some(index),
+                Some(Access::Store(_)) => {
+                    if let Some(load) = loaded[slot] {
+                        if kind(&instructions[load].instruction) == kind(&decoded.instruction) {
+                            webs.union(index, load);
+                        }
+                    }
+                    loaded = vec![None; slots];
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // javac never reuses the slot of a parameter, everything stored into it is the parameter
+    for (index, decoded) in instructions.iter().enumerate() {
+        if let Some(Access::Store(slot)) = access(&decoded.instruction) {
+            let parameter = parameter_types.iter().find(|(s, _)| *s == slot);
+            if let Some((_, Some(ty))) = parameter {
+                if (slot as usize) < slots && Some(kind_of(ty)) == kind(&decoded.instruction) {
+                    webs.union(index, parameter_node(slot));
+                }
+            }
+        }
+    }
+
+    // accesses covered by the same LocalVariableTable entry are the same variable, even if
+    // the dataflow can't tell
+    let debug_variables = debug_variables(code_attribute, constant_pool);
+    let mut debug_of: HashMap<usize, usize> = HashMap::new();
+    let mut first_node: HashMap<usize, usize> = HashMap::new();
+    let mut nodes: Vec<(usize, u16)> = parameter_types
+        .iter()
+        .filter(|(slot, _)| (*slot as usize) < slots)
+        .map(|(slot, _)| (parameter_node(*slot), *slot))
+        .collect();
+    for (index, decoded) in instructions.iter().enumerate() {
+        if let Some(Access::Load(slot)) | Some(Access::Store(slot)) | Some(Access::Update(slot)) =
+            access(&decoded.instruction)
+        {
+            if (slot as usize) < slots {
+                nodes.push((index, slot));
+            }
+        }
+    }
+    for (node, slot) in &nodes {
+        let found = debug_variables.iter().position(|variable| {
+            if variable.slot != *slot {
+                return false;
+            }
+            if *node >= instructions.len() {
+                return variable.start == 0;
+            }
+            let decoded = &instructions[*node];
+            let covers = variable.start <= decoded.pc && decoded.pc < variable.end;
+            match access(&decoded.instruction) {
+                // the scope of a variable starts after its first store
+                Some(Access::Store(_)) => covers || variable.start == decoded.pc + decoded.length,
+                _ => covers,
+            }
+        });
+        if let Some(found) = found {
+            debug_of.insert(*node, found);
+            match first_node.get(&found) {
+                Some(first) => webs.union(*first, *node),
+                None => {
+                    first_node.insert(found, *node);
+                }
+            }
+        }
+    }
+
+    let mut variables: Vec<Variable> = Vec::new();
+    let mut ids: HashMap<usize, usize> = HashMap::new();
+    let mut accesses = HashMap::new();
+    let mut parameters = HashMap::new();
+    for (node, slot) in &nodes {
+        let root = webs.find(*node);
+        let id = *ids.entry(root).or_insert_with(|| {
+            variables.push(Variable {
+                slot: *slot,
+                name: String::new(),
+                declared_type: None,
+                is_parameter: false,
+            });
+            variables.len() - 1
+        });
+
+        let variable = &mut variables[id];
+        if let Some(debug) = debug_of.get(node) {
+            if naming == Naming::DebugInfo {
+                variable.name = debug_variables[*debug].name.clone();
+            }
+            variable.declared_type = Some(debug_variables[*debug].ty.clone());
+        }
+        if *node >= instructions.len() {
+            variable.is_parameter = true;
+            if let Some((_, Some(ty))) = parameter_types.iter().find(|(s, _)| s == slot) {
+                variable.declared_type = Some(ty.clone());
+            }
+            parameters.insert(*slot, id);
+        } else {
+            accesses.insert(instructions[*node].pc, id);
+        }
+    }
+
+    for variable in variables.iter_mut() {
+        if variable.name.is_empty() {
+            variable.name = if variable.is_parameter {
+                format!("arg{}", variable.slot)
+            } else {
+                format!("var{}", variable.slot)
+            };
+        }
+    }
+
+    Variables {
+        variables,
+        accesses,
+        parameters,
+    }
+}
+
+fn debug_variables(
+    code_attribute: &CodeAttribute,
+    constant_pool: &ConstantPool,
+) -> Vec<DebugVariable> {
+    let utf8 = |index: u16| match constant_pool.get_index(index) {
+        CPIndexType::Utf8(utf8) => utf8.string,
+        _ => panic!("Invalid Type in Constant Pool"),
+    };
+    code_attribute
+        .local_variables()
+        .into_iter()
+        .map(|variable| DebugVariable {
+            start: variable.start_pc as usize,
+            end: variable.start_pc as usize + variable.length as usize,
+            slot: variable.index,
+            name: utf8(variable.name_index),
+            ty: Type::from_descriptor(utf8(variable.descriptor_index).as_str()),
+        })
+        .collect()
+}
+
+// Adds the definitions that aren't there yet, returns whether there were any
+fn merge(existing: &mut Vec<usize>, definitions: &[usize]) -> bool {
+    let mut changed = false;
+    for definition in definitions {
+        if !existing.contains(definition) {
+            existing.push(*definition);
+            changed = true;
+        }
+    }
+    changed
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        UnionFind {
+            parents: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, node: usize) -> usize {
+        let mut root = node;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut node = node;
+        while self.parents[node] != root {
+            let parent = self.parents[node];
+            self.parents[node] = root;
+            node = parent;
+        }
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let a = self.find(a);
+        let b = self.find(b);
+        if a != b {
+            self.parents[a.max(b)] = a.min(b);
+        }
+    }
+}
@@ -1,235 +1,371 @@
-pub mod variable;
+mod asserts;
+mod cfg;
+mod conditions;
+mod declare;
+mod foreach;
+mod locals;
+mod records;
+mod stack;
+mod structure;
+mod sugar;
+mod switches;
+mod tries;
+mod types;
 
-mod field;
-mod other;
-mod store;
-mod value;
+use super::ast::{Expression, Local, Pcs, Statement, Type};
+use super::instruction::DecodedInstruction;
+use crate::disassembler::attribute::BootstrapMethod;
+use crate::disassembler::descriptor;
+use stack::{BlockOutput, StackValue, Terminator};
 
-pub struct MethodInformation {
+pub use asserts::{remove_flag, ASSERTIONS_DISABLED};
+pub use types::fits;
+
+use std::collections::HashSet;
+
+pub struct MethodInformation<'a> {
     pub is_static: bool,
-    pub arg_count: u8,
+    // internal name of the class declaring the method
+    pub class_name: String,
+    pub descriptor: String,
+    // the class file version, newer syntax is only recovered from classes that may use it
+    pub major: u16,
+    pub bootstrap_methods: &'a [BootstrapMethod],
+    // the methods of the class, calls of them can be checked against their declarations
+    pub methods: &'a [MethodSignature],
+    pub options: &'a super::DecompilerOptions,
+    pub switch_maps: &'a super::switch_maps::SwitchMaps,
 }
 
-// TODO: AThrow, CheckCast, *Cmp*, Dup*, GoTo*, if*, impdep*, Invoke*, Jsr*, Ldc*, LookUpSwitch, Monitor*, Ret, Swap, Tableswitch, Wide
+pub struct MethodSignature {
+    pub name: String,
+    pub descriptor: String,
+    pub varargs: bool,
+    // bridge methods and the like can't be called from the source
+    pub synthetic: bool,
+}
+
+pub struct MethodBody {
+    pub parameter_names: Vec<String>,
+    pub statements: Vec<Statement>,
+    // what went wrong, the statements are what could be recovered anyway
+    pub problems: Vec<String>,
+}
+
+// Variable types feed back into the simulation, a few rounds settle chains of assignments
+const TYPE_PASSES: usize = 4;
 
 pub fn generate_code(
-    instructions: Vec<super::instruction::Instruction>,
-    constant_pool: &mut super::ConstantPool,
+    mut instructions: Vec<DecodedInstruction>,
+    code_attribute: &super::CodeAttribute,
+    constant_pool: &super::ConstantPool,
     method_information: &MethodInformation,
-) -> String {
-    println!("{:?}", instructions);
-
-    let mut instructions_stack = Vec::<super::instruction::Instruction>::new();
-    let mut variable_table: Vec<variable::Variable> = Vec::new();
-
-    let mut code = String::new();
-
-    for instruction in instructions.as_slice() {
-        match instruction {
-            super::instruction::Instruction::AALoad
-            | super::instruction::Instruction::AConstNull
-            | super::instruction::Instruction::ALoad0
-            | super::instruction::Instruction::ALoad1
-            | super::instruction::Instruction::ALoad2
-            | super::instruction::Instruction::ALoad3
-            | super::instruction::Instruction::ArrayLength
-            | super::instruction::Instruction::BALoad
-            | super::instruction::Instruction::BiPush(_)
-            | super::instruction::Instruction::CALoad
-            | super::instruction::Instruction::D2F
-            | super::instruction::Instruction::D2I
-            | super::instruction::Instruction::D2L
-            | super::instruction::Instruction::DAdd
-            | super::instruction::Instruction::DALoad
-            | super::instruction::Instruction::DCmpG
-            | super::instruction::Instruction::DCmpL
-            | super::instruction::Instruction::DConst0
-            | super::instruction::Instruction::DConst1
-            | super::instruction::Instruction::DDiv
-            | super::instruction::Instruction::DMul
-            | super::instruction::Instruction::DNeg
-            | super::instruction::Instruction::DRem
-            | super::instruction::Instruction::DSub
-            | super::instruction::Instruction::Dup
-            | super::instruction::Instruction::DupX1
-            | super::instruction::Instruction::DupX2
-            | super::instruction::Instruction::Dup2
-            | super::instruction::Instruction::Dup2X1
-            | super::instruction::Instruction::Dup2X2
-            | super::instruction::Instruction::F2D
-            | super::instruction::Instruction::F2I
-            | super::instruction::Instruction::F2L
-            | super::instruction::Instruction::FAdd
-            | super::instruction::Instruction::FALoad
-            | super::instruction::Instruction::FCmpG
-            | super::instruction::Instruction::FCmpL
-            | super::instruction::Instruction::FConst0
-            | super::instruction::Instruction::FConst1
-            | super::instruction::Instruction::FConst2
-            | super::instruction::Instruction::FDiv
-            | super::instruction::Instruction::FLoad0
-            | super::instruction::Instruction::FLoad1
-            | super::instruction::Instruction::FLoad2
-            | super::instruction::Instruction::FLoad3
-            | super::instruction::Instruction::FMul
-            | super::instruction::Instruction::FNeg
-            | super::instruction::Instruction::FRem
-            | super::instruction::Instruction::FSub
-            | super::instruction::Instruction::I2C
-            | super::instruction::Instruction::I2B
-            | super::instruction::Instruction::I2D
-            | super::instruction::Instruction::I2F
-            | super::instruction::Instruction::I2S
-            | super::instruction::Instruction::IAdd
-            | super::instruction::Instruction::IALoad
-            | super::instruction::Instruction::IAnd
-            | super::instruction::Instruction::IConstM1
-            | super::instruction::Instruction::IConst0
-            | super::instruction::Instruction::IConst1
-            | super::instruction::Instruction::IConst2
-            | super::instruction::Instruction::IConst3
-            | super::instruction::Instruction::IConst4
-            | super::instruction::Instruction::IConst5
-            | super::instruction::Instruction::IDiv
-            | super::instruction::Instruction::ILoad0
-            | super::instruction::Instruction::ILoad1
-            | super::instruction::Instruction::ILoad2
-            | super::instruction::Instruction::ILoad3
-            | super::instruction::Instruction::ImpDep1
-            | super::instruction::Instruction::ImpDep2
-            | super::instruction::Instruction::IMul
-            | super::instruction::Instruction::INeg
-            | super::instruction::Instruction::IOr
-            | super::instruction::Instruction::IRem
-            | super::instruction::Instruction::IShl
-            | super::instruction::Instruction::IShr
-            | super::instruction::Instruction::ISub
-            | super::instruction::Instruction::IUShr
-            | super::instruction::Instruction::IXor
-            | super::instruction::Instruction::L2D
-            | super::instruction::Instruction::L2F
-            | super::instruction::Instruction::L2I
-            | super::instruction::Instruction::LAdd
-            | super::instruction::Instruction::LALoad
-            | super::instruction::Instruction::LAnd
-            | super::instruction::Instruction::LCmp
-            | super::instruction::Instruction::LConst0
-            | super::instruction::Instruction::LConst1
-            | super::instruction::Instruction::Ldc(_)
-            | super::instruction::Instruction::LdcW(_)
-            | super::instruction::Instruction::Ldc2W(_)
-            | super::instruction::Instruction::LDiv
-            | super::instruction::Instruction::LLoad0
-            | super::instruction::Instruction::LLoad1
-            | super::instruction::Instruction::LLoad2
-            | super::instruction::Instruction::LLoad3
-            | super::instruction::Instruction::LMul
-            | super::instruction::Instruction::LNeg
-            | super::instruction::Instruction::LOr
-            | super::instruction::Instruction::LRem
-            | super::instruction::Instruction::LShl
-            | super::instruction::Instruction::LShr
-            | super::instruction::Instruction::LSub
-            | super::instruction::Instruction::LUShr
-            | super::instruction::Instruction::LXor
-            | super::instruction::Instruction::MonitorEnter
-            | super::instruction::Instruction::MonitorExit
-            | super::instruction::Instruction::SALoad
-            | super::instruction::Instruction::Swap
-            | super::instruction::Instruction::ANewArray(_)
-            | super::instruction::Instruction::GetStatic(_)
-            | super::instruction::Instruction::MultiANewArray(_, _) => {
-                instructions_stack.push(*instruction)
+) -> MethodBody {
+    let mut exception_table = code_attribute.exception_table.clone();
+    let record_patterns =
+        records::remove_match_handlers(&mut instructions, &mut exception_table, constant_pool);
+    let blocks = cfg::build_blocks(&instructions, &exception_table);
+    let variables = locals::find_variables(
+        &instructions,
+        &blocks,
+        code_attribute,
+        constant_pool,
+        method_information.is_static,
+        method_information.descriptor.as_str(),
+        method_information.options.naming,
+    );
+
+    let handlers: Vec<structure::Handler> = exception_table
+        .iter()
+        .map(|entry| structure::Handler {
+            start: entry.start_pc as usize,
+            end: entry.end_pc as usize,
+            handler: entry.handler_pc as usize,
+            ty: match entry.catch_type {
+                0 => None,
+                index => Some(Type::from_class_name(
+                    super::class::get_class_name(index, constant_pool).as_str(),
+                )),
+            },
+        })
+        .collect();
+
+    let assigned_once = variables.assigned_once(&instructions);
+    let mut pinned = HashSet::new();
+    for handler in &handlers {
+        pinned.extend([handler.start, handler.end, handler.handler].iter());
+    }
+
+    let declared_types: Vec<Option<Type>> = variables
+        .variables
+        .iter()
+        .map(|variable| variable.declared_type.clone())
+        .collect();
+    let mut types: Vec<Type> = declared_types
+        .iter()
+        .map(|ty| ty.clone().unwrap_or(Type::Unknown))
+        .collect();
+    let mut pass = 1;
+    let (blocks, outputs, mut problems, types) = loop {
+        let mut simulator =
+            stack::Simulator::new(constant_pool, method_information, &variables, &types);
+        let mut conditions = conditions::Conditions::new(&blocks, &pinned, &assigned_once);
+        let outputs = simulate(&mut conditions, &instructions, &handlers, &mut simulator);
+
+        // temporaries come after the variables
+        let mut resolved: Vec<Type> = (0..simulator.variable_count())
+            .map(|id| match declared_types.get(id) {
+                Some(Some(ty)) => ty.clone(),
+                _ => simulator.constraints.resolve(id as u16),
+            })
+            .collect();
+        if resolved[..types.len()] == types[..] || pass == TYPE_PASSES {
+            let (blocks, outputs) = conditions.finish(outputs);
+            break (blocks, outputs, simulator.problems, resolved);
+        }
+        resolved.truncate(types.len());
+        types = resolved;
+        pass += 1;
+    };
+
+    let mut parameters = Vec::new();
+    let mut slot = if method_information.is_static { 0 } else { 1 };
+    let (arguments, _) =
+        descriptor::split_method_descriptor(method_information.descriptor.as_str());
+    for argument in arguments {
+        match variables.parameter(slot) {
+            Some(id) => parameters.push(Local {
+                index: id as u16,
+                name: variables.variables[id].name.clone(),
+            }),
+            None => parameters.push(Local {
+                index: u16::MAX,
+                name: format!("arg{}", slot),
+            }),
+        }
+        slot += descriptor::slot_size(argument.as_str());
+    }
+
+    let mut statements = structure::structure(&blocks, &outputs, &handlers);
+    asserts::assertions(&mut statements);
+    foreach::enhanced_loops(&mut statements);
+    sugar::resugar(&mut statements, &types, method_information);
+    declare::declare_variables(&mut statements, &types, &parameters);
+    switches::modern_switches(
+        &mut statements,
+        variables.variables.len(),
+        method_information.major,
+    );
+    switches::enum_switches(&mut statements, method_information.switch_maps);
+    if record_patterns {
+        records::record_patterns(&mut statements);
+    }
+    if is_unstructured(&mut statements) {
+        problems.push("Control flow could not be structured".to_string());
+    }
+
+    if !problems.is_empty() && !method_information.options.hide_problems {
+        statements.push(Statement::Comment("Decompilation problems:".to_string()));
+        for problem in &problems {
+            statements.push(Statement::Comment(problem.clone()));
+        }
+        statements.push(Statement::Comment("Disassembly:".to_string()));
+        for line in super::printer::print_code(code_attribute, constant_pool).lines() {
+            statements.push(Statement::Comment(line.to_string()));
+        }
+    }
+    MethodBody {
+        parameter_names: parameters.into_iter().map(|local| local.name).collect(),
+        statements,
+        problems,
+    }
+}
+
+// Jumps the structuring couldn't turn into statements are left as labels and gotos, and the
+// exceptions of their handlers aren't caught by a catch
+fn is_unstructured(statements: &mut [Statement]) -> bool {
+    let mut unstructured = false;
+    for statement in statements {
+        statement.walk_mut(&mut |statement| {
+            if let Statement::Label(_) | Statement::Goto(_) = statement {
+                unstructured = true;
             }
+            statement.expressions_mut(&mut |expression| {
+                expression.visit(&mut |expression| {
+                    if let Expression::CaughtException = expression {
+                        unstructured = true;
+                    }
+                })
+            });
+        });
+    }
+    unstructured
+}
 
-            super::instruction::Instruction::PutStatic(_)
-            | super::instruction::Instruction::PutField(_) => code.push_str(
-                field::decompile_field_instruction(
-                    &instruction,
-                    &mut instructions_stack,
-                    constant_pool,
-                    method_information,
-                )
-                .as_str(),
-            ),
-            super::instruction::Instruction::AAStore
-            | super::instruction::Instruction::AStore(_)
-            | super::instruction::Instruction::AStore0
-            | super::instruction::Instruction::AStore1
-            | super::instruction::Instruction::AStore2
-            | super::instruction::Instruction::AStore3
-            | super::instruction::Instruction::BAStore
-            | super::instruction::Instruction::CAStore
-            | super::instruction::Instruction::DAStore
-            | super::instruction::Instruction::DStore(_)
-            | super::instruction::Instruction::DStore0
-            | super::instruction::Instruction::DStore1
-            | super::instruction::Instruction::DStore2
-            | super::instruction::Instruction::DStore3
-            | super::instruction::Instruction::FAStore
-            | super::instruction::Instruction::FStore(_)
-            | super::instruction::Instruction::FStore0
-            | super::instruction::Instruction::FStore1
-            | super::instruction::Instruction::FStore2
-            | super::instruction::Instruction::FStore3
-            | super::instruction::Instruction::IAStore
-            | super::instruction::Instruction::IStore(_)
-            | super::instruction::Instruction::IStore0
-            | super::instruction::Instruction::IStore1
-            | super::instruction::Instruction::IStore2
-            | super::instruction::Instruction::IStore3
-            | super::instruction::Instruction::LAStore
-            | super::instruction::Instruction::LStore(_)
-            | super::instruction::Instruction::LStore0
-            | super::instruction::Instruction::LStore1
-            | super::instruction::Instruction::LStore2
-            | super::instruction::Instruction::LStore3
-            | super::instruction::Instruction::SAStore => code.push_str(
-                store::decompile_store_instruction(
-                    instruction,
-                    &mut instructions_stack,
-                    constant_pool,
-                    method_information,
-                    &mut variable_table,
-                )
-                .as_str(),
-            ),
-
-            super::instruction::Instruction::Return => code.push_str("return;"),
-            super::instruction::Instruction::AReturn
-            | super::instruction::Instruction::DReturn
-            | super::instruction::Instruction::FReturn
-            | super::instruction::Instruction::IReturn
-            | super::instruction::Instruction::LReturn
-            | super::instruction::Instruction::AThrow
-            | super::instruction::Instruction::Breakpoint
-            | super::instruction::Instruction::Nop
-            | super::instruction::Instruction::Pop
-            | super::instruction::Instruction::Pop2 => code.push_str(
-                other::decompile_instruction(
-                    instruction,
-                    &mut instructions_stack,
-                    constant_pool,
-                    method_information,
-                )
-                .as_str(),
-            ),
-
-            _ => {}
+fn simulate(
+    conditions: &mut conditions::Conditions,
+    instructions: &[DecodedInstruction],
+    handlers: &[structure::Handler],
+    simulator: &mut stack::Simulator,
+) -> Vec<BlockOutput> {
+    let mut outputs: Vec<BlockOutput> = Vec::with_capacity(conditions.blocks.len());
+    for index in 0..conditions.blocks.len() {
+        let block = &conditions.blocks[index];
+        let (pc, start, end, is_handler) = (block.pc, block.start, block.end, block.is_handler);
+        let (entry, open) = if is_handler {
+            let exception = StackValue {
+                expression: Expression::CaughtException,
+                ty: catch_type(handlers, pc),
+            };
+            (vec![exception], Pcs::default())
+        } else {
+            conditions.conditional_values(index, &mut outputs);
+            let predecessors = conditions.blocks[index].predecessors.clone();
+            let entry = merge_stacks(&predecessors, &mut outputs, simulator);
+            // the values on the stack were pushed by the rest of the predecessors
+            let open = match entry.is_empty() {
+                true => Pcs::default(),
+                false => predecessors
+                    .iter()
+                    .filter(|predecessor| **predecessor < outputs.len())
+                    .fold(Pcs::default(), |pcs, predecessor| {
+                        pcs.join(outputs[*predecessor].pcs)
+                    }),
+            };
+            (entry, open)
+        };
+
+        let mut output = simulator.run(&instructions[start..end], entry, open);
+        if is_handler {
+            name_exception(&mut output, handlers, pc, simulator);
         }
+        outputs.push(output);
+        conditions.pattern(index, &mut outputs);
+        conditions.combine(index, &mut outputs);
     }
-    if !instructions_stack.is_empty() {
-        code.push_str("\n// Instructions Stack is not empty.\n");
-        code.push_str(
-            format!("// Instructions Stack Dump: {:#?}\n", instructions_stack)
-                .replace('\n', "\n// ")
-                .as_str(),
-        );
-        code.push_str(
-            format!("Instructions Dump: {:#?}", &instructions.as_slice())
-                .replace('\n', "\n// ")
-                .as_str(),
-        )
+    outputs
+}
+
+fn catch_type(handlers: &[structure::Handler], pc: usize) -> Type {
+    handlers
+        .iter()
+        .find(|handler| handler.handler == pc)
+        .and_then(|handler| handler.ty.clone())
+        .unwrap_or_else(|| Type::Class("java.lang.Throwable".to_string()))
+}
+
+// The variable a statement stores a caught exception in
+fn catch_parameter(statement: &Statement) -> Option<&Local> {
+    match statement {
+        Statement::Expression(
+            Expression::Assign {
+                target,
+                operator: None,
+                value,
+            },
+            _,
+        ) if **value == Expression::CaughtException => match target.as_ref() {
+            Expression::Local(local) => Some(local),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Makes a handler start by storing the exception in a variable, which becomes the catch
+// parameter
+fn name_exception(
+    output: &mut BlockOutput,
+    handlers: &[structure::Handler],
+    pc: usize,
+    simulator: &mut stack::Simulator,
+) {
+    if output
+        .statements
+        .first()
+        .and_then(catch_parameter)
+        .is_some()
+    {
+        return;
+    }
+
+    let local = simulator.new_temporary();
+    let mut replace = |expression: &mut Expression| {
+        expression.visit_mut(&mut |expression| {
+            if *expression == Expression::CaughtException {
+                *expression = Expression::Local(local.clone());
+            }
+        })
+    };
+    for statement in output.statements.iter_mut() {
+        statement.expressions_mut(&mut replace);
     }
-    code
+    match &mut output.terminator {
+        Terminator::If {
+            condition: value, ..
+        }
+        | Terminator::Switch { value, .. }
+        | Terminator::Return(Some(value))
+        | Terminator::Throw(value) => replace(value),
+        _ => {}
+    }
+
+    let exception = StackValue {
+        expression: Expression::CaughtException,
+        ty: catch_type(handlers, pc),
+    };
+    let statement = simulator.assign_temporary(&local, &exception);
+    output.statements.insert(0, statement);
+}
+
+// The stack a block starts with. Values that all predecessors leave the same are kept,
+// the others are assigned to a shared temporary at the end of every predecessor.
+fn merge_stacks(
+    predecessors: &[usize],
+    outputs: &mut [BlockOutput],
+    simulator: &mut stack::Simulator,
+) -> Vec<StackValue> {
+    // back edges come from blocks that haven't been simulated yet, javac never leaves
+    // values on the stack across them
+    let predecessors: Vec<usize> = predecessors
+        .iter()
+        .copied()
+        .filter(|predecessor| *predecessor < outputs.len())
+        .collect();
+
+    let depth = match predecessors
+        .iter()
+        .map(|predecessor| outputs[*predecessor].exit_stack.len())
+        .min()
+    {
+        Some(depth) => depth,
+        None => return Vec::new(),
+    };
+
+    let mut entry = Vec::new();
+    for slot in 0..depth {
+        let first = outputs[predecessors[0]].exit_stack[slot].clone();
+        let same = predecessors.iter().all(|predecessor| {
+            outputs[*predecessor].exit_stack[slot].expression == first.expression
+        });
+        if same {
+            entry.push(first);
+            continue;
+        }
+
+        let local: Local = simulator.new_temporary();
+        let mut ty = first.ty.clone();
+        for predecessor in predecessors.iter() {
+            let value = outputs[*predecessor].exit_stack[slot].clone();
+            ty = types::common_supertype(&ty, &value.ty);
+            let statement = simulator.assign_temporary(&local, &value);
+            outputs[*predecessor].statements.push(statement);
+        }
+        entry.push(StackValue {
+            expression: Expression::Local(local),
+            ty,
+        });
+    }
+
+    entry
 }
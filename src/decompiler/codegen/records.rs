@@ -0,0 +1,256 @@
+use super::super::ast::*;
+use super::super::instruction::{DecodedInstruction, Instruction};
+use super::foreach::count;
+use crate::disassembler::exception::ExceptionTableIndex;
+
+use std::collections::HashMap;
+
+// javac matches record patterns by calling the accessors of the record, which are protected by
+// a handler that wraps what they throw: `catch (Throwable t) { throw new MatchException(
+// t.toString(), t); }`. The handlers are dropped, and the variables the accessors are stored in
+// become the components of the pattern.
+
+// The instructions of `astore t; new MatchException; dup; aload t; invokevirtual toString;
+// aload t; invokespecial <init>; athrow`
+const HANDLER_LENGTH: usize = 8;
+
+// Removes the handlers and the exception ranges that lead to them, returns whether there were
+// any
+pub fn remove_match_handlers(
+    instructions: &mut Vec<DecodedInstruction>,
+    exception_table: &mut Vec<ExceptionTableIndex>,
+    constant_pool: &super::super::ConstantPool,
+) -> bool {
+    let handlers: Vec<usize> = exception_table
+        .iter()
+        .map(|entry| entry.handler_pc as usize)
+        .filter(|pc| is_match_handler(instructions, *pc, constant_pool))
+        .collect();
+    if handlers.is_empty() {
+        return false;
+    }
+    exception_table.retain(|entry| !handlers.contains(&(entry.handler_pc as usize)));
+    let ranges: Vec<(usize, usize)> = handlers
+        .iter()
+        .map(|pc| {
+            let start = instructions.iter().position(|decoded| decoded.pc == *pc);
+            (*pc, instructions[start.unwrap() + HANDLER_LENGTH - 1].pc)
+        })
+        .collect();
+    instructions.retain(|decoded| {
+        !ranges
+            .iter()
+            .any(|(start, end)| decoded.pc >= *start && decoded.pc <= *end)
+    });
+    true
+}
+
+fn is_match_handler(
+    instructions: &[DecodedInstruction],
+    pc: usize,
+    constant_pool: &super::super::ConstantPool,
+) -> bool {
+    let start = match instructions.iter().position(|decoded| decoded.pc == pc) {
+        Some(start) if start + HANDLER_LENGTH <= instructions.len() => start,
+        _ => return false,
+    };
+    let handler = &instructions[start..start + HANDLER_LENGTH];
+    let jumped_to = instructions
+        .iter()
+        .any(|decoded| decoded.branch_targets().contains(&pc));
+    match (
+        &handler[1].instruction,
+        &handler[HANDLER_LENGTH - 1].instruction,
+    ) {
+        (Instruction::New(index), Instruction::AThrow) => {
+            !jumped_to
+                && super::super::class::get_class_name(*index, constant_pool)
+                    == "java/lang/MatchException"
+        }
+        _ => false,
+    }
+}
+
+// `x instanceof R r` around statements that start by reading the components of r is the
+// record pattern `x instanceof R(...)`
+pub fn record_patterns(statements: &mut [Statement]) {
+    let mut uses = HashMap::new();
+    for statement in statements.iter_mut() {
+        count(statement, &mut uses);
+    }
+    rewrite(statements, &uses);
+}
+
+fn rewrite(statements: &mut [Statement], uses: &HashMap<u16, usize>) {
+    for statement in statements.iter_mut() {
+        if let Statement::If {
+            condition,
+            then,
+            otherwise,
+            ..
+        } = statement
+        {
+            if let Some(Expression::InstanceOf {
+                binding: binding @ Some(Binding::Variable(_)),
+                ..
+            }) = last_operand(condition)
+            {
+                let record = match binding {
+                    Some(Binding::Variable(record)) => record.clone(),
+                    _ => unreachable!(),
+                };
+                // with an `else` the checks of the components can't be nested ifs, as a
+                // component that doesn't match would skip it
+                let nested = otherwise.is_none();
+                if let Some(components) = components(&record, then, uses, nested, &mut |_| None) {
+                    *binding = Some(Binding::Record(components));
+                }
+            }
+        }
+        for child in statement.children_mut() {
+            rewrite(child, uses);
+        }
+    }
+}
+
+// The instanceof that has to hold for the statements of `if (a && x instanceof R r)` to run
+fn last_operand(condition: &mut Expression) -> Option<&mut Expression> {
+    match condition {
+        Expression::InstanceOf { .. } => Some(condition),
+        Expression::Binary {
+            operator: BinaryOperator::ConditionalAnd,
+            right,
+            ..
+        } => last_operand(right),
+        _ => None,
+    }
+}
+
+// The components of `record`, read from the statements the pattern starts with, which are
+// removed. `T c = record.x(); T x = c;` binds x. A component that has to match a pattern of its
+// own is checked by an `if (c instanceof U u)` around the rest of the statements if they may be
+// `nested`, or, in a switch, by a statement that fails the case when `failed` returns the
+// condition it fails on.
+pub fn components(
+    record: &Local,
+    body: &mut Vec<Statement>,
+    uses: &HashMap<u16, usize>,
+    nested: bool,
+    failed: &mut dyn FnMut(&Statement) -> Option<Expression>,
+) -> Option<Vec<(Type, Binding)>> {
+    let mut statements = body.clone();
+    let mut components = Vec::new();
+    let mut records = vec![(record.clone(), 0)];
+    while let Some((record, accessed)) = records.last_mut() {
+        let component = match statements.first() {
+            Some(statement) => accessor(statement, record, uses),
+            None => None,
+        };
+        let component = match component {
+            Some(component) => component,
+            None if *accessed == uses.get(&record.index).copied().unwrap_or(0) => {
+                // all the components of a nested record were read
+                let (_, accessed) = records.pop().unwrap();
+                if records.is_empty() {
+                    break;
+                }
+                let nested = components.split_off(components.len() - accessed);
+                match components.last_mut() {
+                    Some((_, binding)) => *binding = Binding::Record(nested),
+                    None => return None,
+                }
+                continue;
+            }
+            None => return None,
+        };
+        *accessed += 1;
+        statements.remove(0);
+
+        // the variable of the component or the check of its pattern
+        let failing = statements.first().and_then(&mut *failed);
+        let (ty, binding) = match (statements.first(), failing) {
+            (_, Some(condition)) => {
+                let binding = checked(&negate(condition), &component)?;
+                statements.remove(0);
+                binding
+            }
+            (
+                Some(Statement::Declaration {
+                    ty,
+                    local,
+                    value: Some(Expression::Local(value)),
+                    ..
+                }),
+                None,
+            ) if *value == component => {
+                let binding = (ty.clone(), Binding::Variable(local.clone()));
+                statements.remove(0);
+                binding
+            }
+            (
+                Some(Statement::If {
+                    condition,
+                    then,
+                    otherwise: None,
+                    ..
+                }),
+                None,
+            ) if nested && statements.len() == 1 => {
+                let binding = checked(condition, &component)?;
+                statements = then.clone();
+                binding
+            }
+            _ => return None,
+        };
+        // a record is matched by reading its components next
+        if let Binding::Variable(local) = &binding {
+            if let Some(first) = statements.first() {
+                if accessor(first, local, uses).is_some() {
+                    records.push((local.clone(), 0));
+                }
+            }
+        }
+        components.push((ty, binding));
+    }
+    if components.is_empty() {
+        return None;
+    }
+    *body = statements;
+    Some(components)
+}
+
+// The variable `T c = record.x();` reads a component into, if it is only read once
+fn accessor(statement: &Statement, record: &Local, uses: &HashMap<u16, usize>) -> Option<Local> {
+    match statement {
+        Statement::Declaration {
+            local,
+            value:
+                Some(Expression::Invoke {
+                    target: Some(target),
+                    arguments,
+                    ..
+                }),
+            ..
+        } if **target == Expression::Local(record.clone())
+            && arguments.is_empty()
+            && uses.get(&local.index) == Some(&1) =>
+        {
+            Some(local.clone())
+        }
+        _ => None,
+    }
+}
+
+// The pattern of `c instanceof U u`
+fn checked(condition: &Expression, component: &Local) -> Option<(Type, Binding)> {
+    match condition {
+        Expression::InstanceOf {
+            expression,
+            ty,
+            binding: Some(binding),
+        } if **expression == Expression::Local(component.clone()) => {
+            Some((ty.clone(), binding.clone()))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,2152 @@
+use super::super::ast::*;
+use super::super::instruction::{DecodedInstruction, Instruction};
+use super::super::{CPIndexType, ConstantPool};
+use super::locals::Variables;
+use super::sugar;
+use super::types::{is_int, Constraints};
+use super::MethodInformation;
+use crate::disassembler::attribute::BootstrapMethod;
+use crate::disassembler::descriptor;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackValue {
+    pub expression: Expression,
+    pub ty: Type,
+}
+
+// How control leaves a block
+#[derive(Debug)]
+pub enum Terminator {
+    Fallthrough,
+    Goto(usize),
+    If {
+        condition: Expression,
+        target: usize,
+    },
+    Switch {
+        value: Expression,
+        // the type of the value, case labels are literals of it
+        ty: Type,
+        cases: Vec<(i32, usize)>,
+        default: usize,
+    },
+    Return(Option<Expression>),
+    Throw(Expression),
+    Ret,
+}
+
+pub struct BlockOutput {
+    pub statements: Vec<Statement>,
+    pub terminator: Terminator,
+    // the instructions the terminator was compiled from
+    pub pcs: Pcs,
+    pub exit_stack: Vec<StackValue>,
+}
+
+// Simulates the operand stack with expression trees instead of values. Values stay on the
+// stack as long as possible and are only assigned to temporaries when they are duplicated
+// or a statement would otherwise change their result.
+pub struct Simulator<'a> {
+    constant_pool: &'a ConstantPool,
+    method_information: &'a MethodInformation<'a>,
+    stack: Vec<StackValue>,
+    statements: Vec<Statement>,
+    variables: &'a Variables,
+    // the types of the variables as far as they are known yet
+    types: &'a [Type],
+    pub constraints: Constraints,
+    temporaries: u16,
+    // the number of statements after an iinc in the middle of an expression, a load of the
+    // variable right after it is a prefix increment
+    increment: Option<usize>,
+    // the classes of the objects created by `new`, indexed by their id
+    uninitialized: Vec<String>,
+    // the pc of the instruction being run and of the first one since the last statement
+    pc: usize,
+    start: Option<usize>,
+    pub problems: Vec<String>,
+}
+
+impl<'a> Simulator<'a> {
+    pub fn new(
+        constant_pool: &'a ConstantPool,
+        method_information: &'a MethodInformation<'a>,
+        variables: &'a Variables,
+        types: &'a [Type],
+    ) -> Self {
+        Simulator {
+            constant_pool,
+            method_information,
+            stack: Vec::new(),
+            statements: Vec::new(),
+            variables,
+            types,
+            constraints: Constraints::default(),
+            temporaries: 0,
+            increment: None,
+            uninitialized: Vec::new(),
+            pc: 0,
+            start: None,
+            problems: Vec::new(),
+        }
+    }
+
+    // `open` has the pcs of the instructions that pushed the entry values
+    pub fn run(
+        &mut self,
+        instructions: &[DecodedInstruction],
+        entry: Vec<StackValue>,
+        open: Pcs,
+    ) -> BlockOutput {
+        self.stack = entry;
+        self.statements = Vec::new();
+        self.increment = None;
+        self.start = open.0.map(|(first, _)| first);
+
+        let mut terminator = Terminator::Fallthrough;
+        for decoded in instructions {
+            self.pc = decoded.pc;
+            self.start.get_or_insert(decoded.pc);
+            if let Some(end) = self.execute(decoded) {
+                terminator = end;
+            }
+        }
+
+        BlockOutput {
+            statements: std::mem::take(&mut self.statements),
+            terminator,
+            pcs: self
+                .start
+                .map_or(Pcs::default(), |start| Pcs::new(start, self.pc)),
+            exit_stack: std::mem::take(&mut self.stack),
+        }
+    }
+
+    // The number of variables and temporaries, which are numbered after the variables
+    pub fn variable_count(&self) -> usize {
+        self.variables.variables.len() + self.temporaries as usize
+    }
+
+    // A fresh variable for a value that has to be kept around
+    pub fn new_temporary(&mut self) -> Local {
+        let local = Local {
+            index: self.variable_count() as u16,
+            name: format!("tmp{}", self.temporaries),
+        };
+        self.temporaries += 1;
+        local
+    }
+
+    fn execute(&mut self, decoded: &DecodedInstruction) -> Option<Terminator> {
+        match decoded.instruction {
+            Instruction::Nop
+            | Instruction::Breakpoint
+            | Instruction::ImpDep1
+            | Instruction::ImpDep2 => {}
+
+            Instruction::AConstNull => self.push(Expression::Literal(Literal::Null), Type::Unknown),
+            Instruction::IConstM1 => self.push_int(-1),
+            Instruction::IConst0 => self.push_int(0),
+            Instruction::IConst1 => self.push_int(1),
+            Instruction::IConst2 => self.push_int(2),
+            Instruction::IConst3 => self.push_int(3),
+            Instruction::IConst4 => self.push_int(4),
+            Instruction::IConst5 => self.push_int(5),
+            Instruction::LConst0 => self.push(Expression::Literal(Literal::Long(0)), Type::Long),
+            Instruction::LConst1 => self.push(Expression::Literal(Literal::Long(1)), Type::Long),
+            Instruction::FConst0 => {
+                self.push(Expression::Literal(Literal::Float(0.0)), Type::Float)
+            }
+            Instruction::FConst1 => {
+                self.push(Expression::Literal(Literal::Float(1.0)), Type::Float)
+            }
+            Instruction::FConst2 => {
+                self.push(Expression::Literal(Literal::Float(2.0)), Type::Float)
+            }
+            Instruction::DConst0 => {
+                self.push(Expression::Literal(Literal::Double(0.0)), Type::Double)
+            }
+            Instruction::DConst1 => {
+                self.push(Expression::Literal(Literal::Double(1.0)), Type::Double)
+            }
+            Instruction::BiPush(value) => self.push_int(value as i8 as i32),
+            Instruction::SiPush(value) => self.push_int(value as i16 as i32),
+            Instruction::Ldc(index) => self.push_constant(index as u16),
+            Instruction::LdcW(index) | Instruction::Ldc2W(index) => self.push_constant(index),
+
+            Instruction::ILoad(index) => self.load(decoded.pc, index, Type::Int),
+            Instruction::ILoad0 => self.load(decoded.pc, 0, Type::Int),
+            Instruction::ILoad1 => self.load(decoded.pc, 1, Type::Int),
+            Instruction::ILoad2 => self.load(decoded.pc, 2, Type::Int),
+            Instruction::ILoad3 => self.load(decoded.pc, 3, Type::Int),
+            Instruction::LLoad(index) => self.load(decoded.pc, index, Type::Long),
+            Instruction::LLoad0 => self.load(decoded.pc, 0, Type::Long),
+            Instruction::LLoad1 => self.load(decoded.pc, 1, Type::Long),
+            Instruction::LLoad2 => self.load(decoded.pc, 2, Type::Long),
+            Instruction::LLoad3 => self.load(decoded.pc, 3, Type::Long),
+            Instruction::FLoad(index) => self.load(decoded.pc, index, Type::Float),
+            Instruction::FLoad0 => self.load(decoded.pc, 0, Type::Float),
+            Instruction::FLoad1 => self.load(decoded.pc, 1, Type::Float),
+            Instruction::FLoad2 => self.load(decoded.pc, 2, Type::Float),
+            Instruction::FLoad3 => self.load(decoded.pc, 3, Type::Float),
+            Instruction::DLoad(index) => self.load(decoded.pc, index, Type::Double),
+            Instruction::DLoad0 => self.load(decoded.pc, 0, Type::Double),
+            Instruction::DLoad1 => self.load(decoded.pc, 1, Type::Double),
+            Instruction::DLoad2 => self.load(decoded.pc, 2, Type::Double),
+            Instruction::DLoad3 => self.load(decoded.pc, 3, Type::Double),
+            Instruction::ALoad(index) => self.load(decoded.pc, index, Type::Unknown),
+            Instruction::ALoad0 => self.load(decoded.pc, 0, Type::Unknown),
+            Instruction::ALoad1 => self.load(decoded.pc, 1, Type::Unknown),
+            Instruction::ALoad2 => self.load(decoded.pc, 2, Type::Unknown),
+            Instruction::ALoad3 => self.load(decoded.pc, 3, Type::Unknown),
+
+            Instruction::IStore(index) => self.store(decoded.pc, index, Type::Int),
+            Instruction::IStore0 => self.store(decoded.pc, 0, Type::Int),
+            Instruction::IStore1 => self.store(decoded.pc, 1, Type::Int),
+            Instruction::IStore2 => self.store(decoded.pc, 2, Type::Int),
+            Instruction::IStore3 => self.store(decoded.pc, 3, Type::Int),
+            Instruction::LStore(index) => self.store(decoded.pc, index, Type::Long),
+            Instruction::LStore0 => self.store(decoded.pc, 0, Type::Long),
+            Instruction::LStore1 => self.store(decoded.pc, 1, Type::Long),
+            Instruction::LStore2 => self.store(decoded.pc, 2, Type::Long),
+            Instruction::LStore3 => self.store(decoded.pc, 3, Type::Long),
+            Instruction::FStore(index) => self.store(decoded.pc, index, Type::Float),
+            Instruction::FStore0 => self.store(decoded.pc, 0, Type::Float),
+            Instruction::FStore1 => self.store(decoded.pc, 1, Type::Float),
+            Instruction::FStore2 => self.store(decoded.pc, 2, Type::Float),
+            Instruction::FStore3 => self.store(decoded.pc, 3, Type::Float),
+            Instruction::DStore(index) => self.store(decoded.pc, index, Type::Double),
+            Instruction::DStore0 => self.store(decoded.pc, 0, Type::Double),
+            Instruction::DStore1 => self.store(decoded.pc, 1, Type::Double),
+            Instruction::DStore2 => self.store(decoded.pc, 2, Type::Double),
+            Instruction::DStore3 => self.store(decoded.pc, 3, Type::Double),
+            Instruction::AStore(index) => self.store(decoded.pc, index, Type::Unknown),
+            Instruction::AStore0 => self.store(decoded.pc, 0, Type::Unknown),
+            Instruction::AStore1 => self.store(decoded.pc, 1, Type::Unknown),
+            Instruction::AStore2 => self.store(decoded.pc, 2, Type::Unknown),
+            Instruction::AStore3 => self.store(decoded.pc, 3, Type::Unknown),
+
+            Instruction::IInc(index, value) => self.increment(decoded.pc, index, value as i32),
+
+            Instruction::IALoad => self.array_load(Type::Int),
+            Instruction::LALoad => self.array_load(Type::Long),
+            Instruction::FALoad => self.array_load(Type::Float),
+            Instruction::DALoad => self.array_load(Type::Double),
+            Instruction::AALoad => self.array_load(Type::Unknown),
+            Instruction::BALoad => self.array_load(Type::Byte),
+            Instruction::CALoad => self.array_load(Type::Char),
+            Instruction::SALoad => self.array_load(Type::Short),
+
+            Instruction::IAStore
+            | Instruction::LAStore
+            | Instruction::FAStore
+            | Instruction::DAStore
+            | Instruction::AAStore
+            | Instruction::BAStore
+            | Instruction::CAStore
+            | Instruction::SAStore => {
+                let mut value = self.pop();
+                let index = self.pop();
+                let array = self.pop();
+                if let (Expression::Local(array), Expression::Literal(Literal::Int(index))) =
+                    (&array.expression, &index.expression)
+                {
+                    self.inline_temporaries(&mut value.expression);
+                    if self.initialize(array, *index, &value) {
+                        return None;
+                    }
+                }
+                let target = Expression::ArrayElement {
+                    array: Box::new(array.expression),
+                    index: Box::new(index.expression),
+                };
+                match &array.ty {
+                    Type::Array(element) => {
+                        self.constraints
+                            .hint(&value.expression, element.as_ref().clone());
+                        self.assign(target, value, &element.clone());
+                    }
+                    _ => self.assign(target, value, &Type::Unknown),
+                }
+            }
+
+            Instruction::Pop => {
+                let value = self.pop();
+                self.discard(value);
+            }
+            Instruction::Pop2 => {
+                let value = self.pop();
+                if !value.ty.is_wide() {
+                    let second = self.pop();
+                    self.discard(second);
+                }
+                self.discard(value);
+            }
+            Instruction::Dup => {
+                let value = self.pop();
+                let value = self.duplicate(value);
+                self.push_value(value.clone());
+                self.push_value(value);
+            }
+            Instruction::DupX1 => {
+                let value1 = self.pop();
+                let value2 = self.pop();
+                let value1 = self.duplicate(value1);
+                self.push_values(vec![value1.clone(), value2, value1]);
+            }
+            Instruction::DupX2 => {
+                let value1 = self.pop();
+                let value2 = self.pop();
+                let value1 = self.duplicate(value1);
+                if value2.ty.is_wide() {
+                    self.push_values(vec![value1.clone(), value2, value1]);
+                } else {
+                    let value3 = self.pop();
+                    self.push_values(vec![value1.clone(), value3, value2, value1]);
+                }
+            }
+            Instruction::Dup2 => {
+                let value1 = self.pop();
+                if value1.ty.is_wide() {
+                    let value1 = self.duplicate(value1);
+                    self.push_values(vec![value1.clone(), value1]);
+                } else {
+                    let value2 = self.pop();
+                    let value2 = self.duplicate(value2);
+                    let value1 = self.duplicate(value1);
+                    self.push_values(vec![value2.clone(), value1.clone(), value2, value1]);
+                }
+            }
+            Instruction::Dup2X1 => {
+                let value1 = self.pop();
+                if value1.ty.is_wide() {
+                    let value2 = self.pop();
+                    let value1 = self.duplicate(value1);
+                    self.push_values(vec![value1.clone(), value2, value1]);
+                } else {
+                    let value2 = self.pop();
+                    let value3 = self.pop();
+                    let value2 = self.duplicate(value2);
+                    let value1 = self.duplicate(value1);
+                    self.push_values(vec![value2.clone(), value1.clone(), value3, value2, value1]);
+                }
+            }
+            Instruction::Dup2X2 => {
+                let value1 = self.pop();
+                let value2 = self.pop();
+                if value1.ty.is_wide() && value2.ty.is_wide() {
+                    let value1 = self.duplicate(value1);
+                    self.push_values(vec![value1.clone(), value2, value1]);
+                } else if value1.ty.is_wide() {
+                    let value3 = self.pop();
+                    let value1 = self.duplicate(value1);
+                    self.push_values(vec![value1.clone(), value3, value2, value1]);
+                } else {
+                    let value3 = self.pop();
+                    let value2 = self.duplicate(value2);
+                    let value1 = self.duplicate(value1);
+                    if value3.ty.is_wide() {
+                        self.push_values(vec![
+                            value2.clone(),
+                            value1.clone(),
+                            value3,
+                            value2,
+                            value1,
+                        ]);
+                    } else {
+                        let value4 = self.pop();
+                        self.push_values(vec![
+                            value2.clone(),
+                            value1.clone(),
+                            value4,
+                            value3,
+                            value2,
+                            value1,
+                        ]);
+                    }
+                }
+            }
+            Instruction::Swap => {
+                let value1 = self.pop();
+                let mut value2 = self.pop();
+                // the lower value was evaluated first and has to stay that way
+                if !value1.expression.is_trivial() && !value2.expression.is_trivial() {
+                    value2 = self.materialize(value2);
+                }
+                self.push_values(vec![value1, value2]);
+            }
+
+            Instruction::IAdd => self.binary(BinaryOperator::Add, Type::Int),
+            Instruction::LAdd => self.binary(BinaryOperator::Add, Type::Long),
+            Instruction::FAdd => self.binary(BinaryOperator::Add, Type::Float),
+            Instruction::DAdd => self.binary(BinaryOperator::Add, Type::Double),
+            Instruction::ISub => self.binary(BinaryOperator::Sub, Type::Int),
+            Instruction::LSub => self.binary(BinaryOperator::Sub, Type::Long),
+            Instruction::FSub => self.binary(BinaryOperator::Sub, Type::Float),
+            Instruction::DSub => self.binary(BinaryOperator::Sub, Type::Double),
+            Instruction::IMul => self.binary(BinaryOperator::Mul, Type::Int),
+            Instruction::LMul => self.binary(BinaryOperator::Mul, Type::Long),
+            Instruction::FMul => self.binary(BinaryOperator::Mul, Type::Float),
+            Instruction::DMul => self.binary(BinaryOperator::Mul, Type::Double),
+            Instruction::IDiv => self.binary(BinaryOperator::Div, Type::Int),
+            Instruction::LDiv => self.binary(BinaryOperator::Div, Type::Long),
+            Instruction::FDiv => self.binary(BinaryOperator::Div, Type::Float),
+            Instruction::DDiv => self.binary(BinaryOperator::Div, Type::Double),
+            Instruction::IRem => self.binary(BinaryOperator::Rem, Type::Int),
+            Instruction::LRem => self.binary(BinaryOperator::Rem, Type::Long),
+            Instruction::FRem => self.binary(BinaryOperator::Rem, Type::Float),
+            Instruction::DRem => self.binary(BinaryOperator::Rem, Type::Double),
+            Instruction::IShl => self.binary(BinaryOperator::Shl, Type::Int),
+            Instruction::LShl => self.binary(BinaryOperator::Shl, Type::Long),
+            Instruction::IShr => self.binary(BinaryOperator::Shr, Type::Int),
+            Instruction::LShr => self.binary(BinaryOperator::Shr, Type::Long),
+            Instruction::IUShr => self.binary(BinaryOperator::UShr, Type::Int),
+            Instruction::LUShr => self.binary(BinaryOperator::UShr, Type::Long),
+            Instruction::IAnd => self.binary(BinaryOperator::And, Type::Int),
+            Instruction::LAnd => self.binary(BinaryOperator::And, Type::Long),
+            Instruction::IOr => self.binary(BinaryOperator::Or, Type::Int),
+            Instruction::LOr => self.binary(BinaryOperator::Or, Type::Long),
+            Instruction::IXor => self.binary(BinaryOperator::Xor, Type::Int),
+            Instruction::LXor => self.binary(BinaryOperator::Xor, Type::Long),
+
+            Instruction::INeg | Instruction::LNeg | Instruction::FNeg | Instruction::DNeg => {
+                let value = self.pop();
+                self.push(
+                    Expression::Unary {
+                        operator: UnaryOperator::Neg,
+                        operand: Box::new(value.expression),
+                    },
+                    value.ty,
+                );
+            }
+
+            Instruction::LCmp => self.compare(CompareKind::LCmp),
+            Instruction::FCmpL => self.compare(CompareKind::FCmpL),
+            Instruction::FCmpG => self.compare(CompareKind::FCmpG),
+            Instruction::DCmpL => self.compare(CompareKind::DCmpL),
+            Instruction::DCmpG => self.compare(CompareKind::DCmpG),
+
+            Instruction::I2L | Instruction::F2L | Instruction::D2L => self.cast(Type::Long),
+            Instruction::I2F | Instruction::L2F | Instruction::D2F => self.cast(Type::Float),
+            Instruction::I2D | Instruction::L2D | Instruction::F2D => self.cast(Type::Double),
+            Instruction::L2I | Instruction::F2I | Instruction::D2I => self.cast(Type::Int),
+            Instruction::I2B => self.cast(Type::Byte),
+            Instruction::I2C => self.cast(Type::Char),
+            Instruction::I2S => self.cast(Type::Short),
+
+            Instruction::IfEq(offset) => {
+                return Some(self.branch_zero(BinaryOperator::Eq, decoded, offset))
+            }
+            Instruction::IfNe(offset) => {
+                return Some(self.branch_zero(BinaryOperator::Ne, decoded, offset))
+            }
+            Instruction::IfLt(offset) => {
+                return Some(self.branch_zero(BinaryOperator::Lt, decoded, offset))
+            }
+            Instruction::IfGe(offset) => {
+                return Some(self.branch_zero(BinaryOperator::Ge, decoded, offset))
+            }
+            Instruction::IfGt(offset) => {
+                return Some(self.branch_zero(BinaryOperator::Gt, decoded, offset))
+            }
+            Instruction::IfLe(offset) => {
+                return Some(self.branch_zero(BinaryOperator::Le, decoded, offset))
+            }
+            Instruction::IfNull(offset) => {
+                return Some(self.branch_null(BinaryOperator::Eq, decoded, offset))
+            }
+            Instruction::IfNonNull(offset) => {
+                return Some(self.branch_null(BinaryOperator::Ne, decoded, offset))
+            }
+            Instruction::IfICmpEq(offset) | Instruction::IfACmpEq(offset) => {
+                return Some(self.branch(BinaryOperator::Eq, decoded, offset))
+            }
+            Instruction::IfICmpNe(offset) | Instruction::IfACmpNe(offset) => {
+                return Some(self.branch(BinaryOperator::Ne, decoded, offset))
+            }
+            Instruction::IfICmpLt(offset) => {
+                return Some(self.branch(BinaryOperator::Lt, decoded, offset))
+            }
+            Instruction::IfICmpGe(offset) => {
+                return Some(self.branch(BinaryOperator::Ge, decoded, offset))
+            }
+            Instruction::IfICmpGt(offset) => {
+                return Some(self.branch(BinaryOperator::Gt, decoded, offset))
+            }
+            Instruction::IfICmpLe(offset) => {
+                return Some(self.branch(BinaryOperator::Le, decoded, offset))
+            }
+
+            Instruction::GoTo(offset) => {
+                return Some(Terminator::Goto(decoded.target(offset as i32)))
+            }
+            Instruction::GoToW(offset) => return Some(Terminator::Goto(decoded.target(offset))),
+            // the return address is only used by `ret`, subroutines are shown as plain jumps
+            Instruction::Jsr(_) | Instruction::JsrW(_) => {
+                self.push(Expression::Literal(Literal::Null), Type::Unknown);
+                let target = decoded.branch_targets()[0];
+                self.statements
+                    .push(Statement::Comment(format!("jsr L{}", target)));
+                return Some(Terminator::Goto(target));
+            }
+            Instruction::Ret(_) => return Some(Terminator::Ret),
+
+            Instruction::TableSwitch { low, .. } => {
+                let value = self.pop();
+                let mut targets = decoded.branch_targets();
+                let default = targets.pop().unwrap();
+                let cases = targets
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, target)| (low + i as i32, target))
+                    .collect();
+                return Some(self.switch(value, cases, default));
+            }
+            Instruction::LookUpSwitch { ref pairs, .. } => {
+                let value = self.pop();
+                let mut targets = decoded.branch_targets();
+                let default = targets.pop().unwrap();
+                let cases = pairs.iter().map(|(key, _)| *key).zip(targets).collect();
+                return Some(self.switch(value, cases, default));
+            }
+
+            Instruction::IReturn
+            | Instruction::LReturn
+            | Instruction::FReturn
+            | Instruction::DReturn
+            | Instruction::AReturn => {
+                let value = self.pop();
+                let (_, return_type) = descriptor::split_method_descriptor(
+                    self.method_information.descriptor.as_str(),
+                );
+                let return_type = Type::from_descriptor(return_type.as_str());
+                self.constraints
+                    .hint(&value.expression, return_type.clone());
+                let mut value = implicit(typed_value(value, &return_type), &return_type);
+                self.inline_temporaries(&mut value);
+                self.spill_conflicts(&Statement::Return(Some(value.clone()), Pcs::default()));
+                return Some(Terminator::Return(Some(value)));
+            }
+            Instruction::Return => return Some(Terminator::Return(None)),
+            Instruction::AThrow => {
+                let mut value = self.pop();
+                self.inline_temporaries(&mut value.expression);
+                self.spill_conflicts(&Statement::Throw(value.expression.clone(), Pcs::default()));
+                return Some(Terminator::Throw(value.expression));
+            }
+
+            Instruction::GetStatic(index) => {
+                let (class, name, field_descriptor) = self.member(index);
+                self.push(
+                    Expression::StaticField {
+                        class: class.replace('/', "."),
+                        name,
+                    },
+                    Type::from_descriptor(field_descriptor.as_str()),
+                );
+            }
+            Instruction::GetField(index) => {
+                let (class, name, field_descriptor) = self.member(index);
+                let target = self.pop();
+                self.constraints
+                    .hint(&target.expression, Type::from_class_name(class.as_str()));
+                self.push(
+                    Expression::Field {
+                        target: Box::new(target.expression),
+                        name,
+                    },
+                    Type::from_descriptor(field_descriptor.as_str()),
+                );
+            }
+            Instruction::PutStatic(index) => {
+                let (class, name, field_descriptor) = self.member(index);
+                let value = self.pop();
+                let field_type = Type::from_descriptor(field_descriptor.as_str());
+                self.constraints.hint(&value.expression, field_type.clone());
+                let target = Expression::StaticField {
+                    class: class.replace('/', "."),
+                    name,
+                };
+                self.assign(target, value, &field_type);
+            }
+            Instruction::PutField(index) => {
+                let (class, name, field_descriptor) = self.member(index);
+                let value = self.pop();
+                let target = self.pop();
+                let field_type = Type::from_descriptor(field_descriptor.as_str());
+                self.constraints.hint(&value.expression, field_type.clone());
+                self.constraints
+                    .hint(&target.expression, Type::from_class_name(class.as_str()));
+                let target = Expression::Field {
+                    target: Box::new(target.expression),
+                    name,
+                };
+                self.assign(target, value, &field_type);
+            }
+
+            Instruction::InvokeVirtual(index) | Instruction::InvokeInterface(index, _) => {
+                self.invoke(index, true, false)
+            }
+            Instruction::InvokeSpecial(index) => self.invoke(index, true, true),
+            Instruction::InvokeStatic(index) => self.invoke(index, false, false),
+            Instruction::InvokeDynamic(index) => self.invoke_dynamic(index),
+
+            Instruction::New(index) => {
+                let class = self.class_name(index);
+                self.push(
+                    Expression::Uninitialized(self.uninitialized.len()),
+                    Type::Class(class.replace('/', ".")),
+                );
+                self.uninitialized.push(class);
+            }
+            Instruction::NewArray(atype) => {
+                let count = self.pop();
+                self.new_array(
+                    Type::Array(Box::new(Type::from(atype))),
+                    vec![count.expression],
+                );
+            }
+            Instruction::ANewArray(index) => {
+                let count = self.pop();
+                let ty = Type::from_class_name(self.class_name(index).as_str());
+                self.new_array(Type::Array(Box::new(ty)), vec![count.expression]);
+            }
+            Instruction::MultiANewArray(index, dimensions) => {
+                let mut counts: Vec<Expression> =
+                    (0..dimensions).map(|_| self.pop().expression).collect();
+                counts.reverse();
+                let ty = Type::from_class_name(self.class_name(index).as_str());
+                self.new_array(ty, counts);
+            }
+            Instruction::ArrayLength => {
+                let array = self.pop();
+                self.push(
+                    Expression::ArrayLength(Box::new(array.expression)),
+                    Type::Int,
+                );
+            }
+
+            Instruction::CheckCast(index) => {
+                let value = self.pop();
+                let ty = Type::from_class_name(self.class_name(index).as_str());
+                // the type of the value is known to be the type already
+                if value.ty == ty {
+                    self.push_value(value);
+                } else {
+                    self.push(
+                        Expression::Cast {
+                            ty: ty.clone(),
+                            expression: Box::new(value.expression),
+                            widening: false,
+                        },
+                        ty,
+                    );
+                }
+            }
+            Instruction::InstanceOf(index) => {
+                let value = self.pop();
+                let ty = Type::from_class_name(self.class_name(index).as_str());
+                self.push(
+                    Expression::InstanceOf {
+                        expression: Box::new(value.expression),
+                        ty,
+                        binding: None,
+                    },
+                    Type::Boolean,
+                );
+            }
+
+            Instruction::MonitorEnter => {
+                let value = self.pop();
+                self.emit(Statement::MonitorEnter(value.expression, Pcs::default()));
+            }
+            Instruction::MonitorExit => {
+                let value = self.pop();
+                self.emit(Statement::MonitorExit(value.expression, Pcs::default()));
+            }
+        }
+
+        None
+    }
+
+    fn push(&mut self, expression: Expression, ty: Type) {
+        self.stack.push(StackValue { expression, ty });
+    }
+
+    fn push_value(&mut self, value: StackValue) {
+        self.stack.push(value);
+    }
+
+    fn push_values(&mut self, values: Vec<StackValue>) {
+        self.stack.extend(values);
+    }
+
+    fn push_int(&mut self, value: i32) {
+        self.push(Expression::Literal(Literal::Int(value)), Type::Int);
+    }
+
+    fn pop(&mut self) -> StackValue {
+        match self.stack.pop() {
+            Some(value) => value,
+            None => {
+                self.problems.push("Operand stack underflow".to_string());
+                StackValue {
+                    expression: Expression::local(u16::MAX, "$missing".to_string()),
+                    ty: Type::Unknown,
+                }
+            }
+        }
+    }
+
+    // Values that are popped without being used only matter for their side effects
+    fn discard(&mut self, value: StackValue) {
+        if value.expression.effects().calls {
+            self.emit(Statement::Expression(value.expression, Pcs::default()));
+        }
+    }
+
+    // Makes the value safe to use more than once
+    fn duplicate(&mut self, value: StackValue) -> StackValue {
+        if value.expression.is_trivial() {
+            value
+        } else {
+            self.materialize(value)
+        }
+    }
+
+    fn materialize(&mut self, value: StackValue) -> StackValue {
+        let local = self.new_temporary();
+        let statement = self.assign_temporary(&local, &value);
+        self.emit(statement);
+        StackValue {
+            expression: Expression::Local(local),
+            ty: value.ty,
+        }
+    }
+
+    // Adds a statement, values still on the stack that it could change or whose side
+    // effects it would be reordered with are assigned to temporaries first
+    fn emit(&mut self, mut statement: Statement) {
+        if let Statement::Expression(expression, _) = &mut statement {
+            self.inline_temporaries(expression);
+        }
+        self.spill_conflicts(&statement);
+        self.record(statement);
+    }
+
+    // Adds a statement compiled from the instructions since the last one
+    fn record(&mut self, mut statement: Statement) {
+        if let Some(pcs) = statement.pcs_mut() {
+            *pcs = pcs.join(Pcs::new(self.start.unwrap_or(self.pc), self.pc));
+        }
+        self.start = None;
+        self.statements.push(statement);
+    }
+
+    // The instructions of a statement that is taken back belong to the one built next
+    fn reopen(&mut self, statement: &Statement) {
+        if let Pcs(Some((first, _))) = statement.pcs() {
+            self.start = Some(self.start.map_or(first, |start| start.min(first)));
+        }
+    }
+
+    fn spill_conflicts(&mut self, statement: &Statement) {
+        let mut spill: Vec<bool> = self
+            .stack
+            .iter()
+            .map(|value| {
+                !matches!(value.expression, Expression::Uninitialized(_))
+                    && StatementAccess::of(statement).conflicts(&value.expression)
+            })
+            .collect();
+
+        // moving a value into a temporary evaluates it earlier, values below it that this
+        // would be reordered with have to move too
+        for i in (0..spill.len()).rev() {
+            if !spill[i] {
+                continue;
+            }
+            let access = StatementAccess::of(&Statement::Expression(
+                self.stack[i].expression.clone(),
+                Pcs::default(),
+            ));
+            for (value, spill) in self.stack.iter().zip(spill.iter_mut()).take(i) {
+                if !value.expression.is_trivial() && access.conflicts(&value.expression) {
+                    *spill = true;
+                }
+            }
+        }
+
+        for (i, spill) in spill.into_iter().enumerate() {
+            if !spill {
+                continue;
+            }
+
+            let value = self.stack[i].clone();
+            let local = self.new_temporary();
+            let statement = self.assign_temporary(&local, &value);
+            self.record(statement);
+            self.stack[i].expression = Expression::Local(local);
+        }
+    }
+
+    pub fn assign_temporary(&mut self, local: &Local, value: &StackValue) -> Statement {
+        self.constraints.assign(local.index, value, &value.ty);
+        Statement::Expression(
+            Expression::Assign {
+                target: Box::new(Expression::Local(local.clone())),
+                operator: None,
+                value: Box::new(value.expression.clone()),
+            },
+            Pcs::default(),
+        )
+    }
+
+    fn local(&mut self, pc: usize, slot: u16) -> Expression {
+        if slot == 0 && !self.method_information.is_static {
+            return Expression::This;
+        }
+        match self.variables.at(pc) {
+            Some(id) => Expression::local(id as u16, self.variables.variables[id].name.clone()),
+            None => {
+                self.problems
+                    .push(format!("Invalid local variable {}", slot));
+                Expression::local(u16::MAX, "$missing".to_string())
+            }
+        }
+    }
+
+    fn load(&mut self, pc: usize, slot: u16, ty: Type) {
+        let mut expression = self.local(pc, slot);
+        let incremented = matches!(
+            self.statements.last(),
+            Some(Statement::Expression(
+                Expression::Increment { target, .. } | Expression::Assign { target, .. },
+                _
+            )) if **target == expression
+        );
+        if incremented && self.increment == Some(self.statements.len()) {
+            let statement = self.statements.pop().unwrap();
+            self.reopen(&statement);
+            expression = match statement {
+                Statement::Expression(
+                    Expression::Increment {
+                        target, operator, ..
+                    },
+                    _,
+                ) => Expression::Increment {
+                    target,
+                    operator,
+                    prefix: true,
+                },
+                Statement::Expression(assignment, _) => assignment,
+                _ => unreachable!(),
+            };
+            self.increment = None;
+        }
+        let ty = match &expression {
+            Expression::This => Type::Class(self.method_information.class_name.replace('/', ".")),
+            Expression::Local(local) => {
+                self.constraints.load(local.index, &ty);
+                match self.types.get(local.index as usize) {
+                    Some(Type::Unknown) | None => ty,
+                    Some(known) => known.clone(),
+                }
+            }
+            _ => ty,
+        };
+        self.push(expression, ty);
+    }
+
+    fn store(&mut self, pc: usize, slot: u16, ty: Type) {
+        let value = self.pop();
+        let target = self.local(pc, slot);
+        let mut target_type = Type::Unknown;
+        if let Expression::Local(local) = &target {
+            self.constraints.assign(local.index, &value, &ty);
+            if let Some(ty) = self.types.get(local.index as usize) {
+                target_type = ty.clone();
+            }
+            self.constraints
+                .hint(&value.expression, target_type.clone());
+        }
+        self.assign(target, value, &target_type);
+    }
+
+    // Stores the value into the target. javac leaves the value of an assignment that is used
+    // on the stack with a dup before the store, or the old value for a postfix increment.
+    fn assign(&mut self, target: Expression, value: StackValue, ty: &Type) {
+        let expression = implicit(typed_value(value, ty), ty);
+        let top = self.stack.last().map(|top| top.expression.clone());
+
+        // `x = y = value`, the dup of a value that isn't trivial went through a temporary
+        if top.as_ref() == Some(&expression) {
+            let value = match &expression {
+                Expression::Local(temporary) => self.take_temporary(temporary),
+                _ => None,
+            };
+            let assignment = compound(target, value.unwrap_or(expression), ty);
+            self.stack.last_mut().unwrap().expression = assignment;
+            return;
+        }
+
+        // `x++`, where the old value is either the target itself or a temporary holding it
+        if let (Some(top), Some((operator, old))) = (&top, increment_of(&expression, ty)) {
+            let postfix = Expression::Increment {
+                target: Box::new(target.clone()),
+                operator,
+                prefix: false,
+            };
+            if *old == target && *top == target {
+                self.stack.last_mut().unwrap().expression = postfix;
+                return;
+            }
+            if let (Expression::Local(temporary), true) = (old, old == top) {
+                if self.temporary_value(temporary) == Some(&target) {
+                    self.statements.pop();
+                    self.stack.last_mut().unwrap().expression = postfix;
+                    return;
+                }
+            }
+        }
+
+        let statement = match compound(target, expression, ty) {
+            Expression::Increment {
+                target, operator, ..
+            } => Expression::Increment {
+                target,
+                operator,
+                prefix: false,
+            },
+            assignment => assignment,
+        };
+        self.emit(Statement::Expression(statement, Pcs::default()));
+    }
+
+    fn increment(&mut self, pc: usize, slot: u16, value: i32) {
+        let (operator, value) = if value < 0 {
+            (BinaryOperator::Sub, -value)
+        } else {
+            (BinaryOperator::Add, value)
+        };
+        let target = self.local(pc, slot);
+        if let Expression::Local(local) = &target {
+            let value = StackValue {
+                expression: target.clone(),
+                ty: Type::Int,
+            };
+            self.constraints.assign(local.index, &value, &Type::Int);
+        }
+
+        let top = self.stack.last().map(|top| &top.expression);
+        if value == 1 && top == Some(&target) {
+            self.stack.last_mut().unwrap().expression = Expression::Increment {
+                target: Box::new(target),
+                operator,
+                prefix: false,
+            };
+            return;
+        }
+
+        let statement = if value == 1 {
+            Expression::Increment {
+                target: Box::new(target),
+                operator,
+                prefix: false,
+            }
+        } else {
+            Expression::Assign {
+                target: Box::new(target),
+                operator: Some(operator),
+                value: Box::new(Expression::Literal(Literal::Int(value))),
+            }
+        };
+        let inside_expression = !self.stack.is_empty();
+        self.emit(Statement::Expression(statement, Pcs::default()));
+        if inside_expression {
+            self.increment = Some(self.statements.len());
+        }
+    }
+
+    // Moves the temporaries the last statements assign into the expression if it uses each
+    // once and they would be evaluated in the same order, like the array and index that
+    // `a[f()]++` duplicates
+    fn inline_temporaries(&mut self, expression: &mut Expression) {
+        let mut definitions: Vec<(u16, &Expression)> = Vec::new();
+        for statement in self.statements.iter().rev() {
+            match statement {
+                Statement::Expression(
+                    Expression::Assign {
+                        target,
+                        operator: None,
+                        value,
+                    },
+                    _,
+                ) => match target.as_ref() {
+                    Expression::Local(local)
+                        if local.index as usize >= self.variables.variables.len() =>
+                    {
+                        definitions.insert(0, (local.index, value))
+                    }
+                    _ => break,
+                },
+                _ => break,
+            }
+        }
+
+        let uses = |index: u16, expression: &Expression| {
+            let mut count = 0;
+            expression.visit(&mut |expression| {
+                if matches!(expression, Expression::Local(local) if local.index == index) {
+                    count += 1;
+                }
+            });
+            count
+        };
+        let inlined = (0..definitions.len()).find(|start| {
+            let definitions = &definitions[*start..];
+            if definitions.iter().any(|(index, _)| {
+                uses(*index, expression) != 1
+                    || self
+                        .stack
+                        .iter()
+                        .any(|value| uses(*index, &value.expression) > 0)
+            }) {
+                return false;
+            }
+
+            // nothing with side effects may move in front of the values
+            let (mut next, mut calls, mut reads, mut valid) = (0, false, false, true);
+            let (mut locals_read, mut locals_written) = (Vec::new(), Vec::new());
+            evaluation(
+                expression,
+                false,
+                &mut |expression, conditional| match expression {
+                    Expression::Local(local)
+                        if definitions.iter().any(|(index, _)| *index == local.index) =>
+                    {
+                        let (index, value) = definitions[next.min(definitions.len() - 1)];
+                        let effects = value.effects();
+                        // an array initializer of constants and locals is the same after calls
+                        let constant = matches!(value, Expression::NewArray { .. })
+                            && !effects.calls
+                            && !effects.reads_heap
+                            && !effects
+                                .locals_read
+                                .iter()
+                                .any(|local| locals_written.contains(local));
+                        // calls can't change the locals read before, only assignments can
+                        let reordered = conditional
+                            || (calls && !constant)
+                            || (reads && effects.calls)
+                            || effects
+                                .locals_written
+                                .iter()
+                                .any(|local| locals_read.contains(local));
+                        valid &= index == local.index && !reordered;
+                        next += 1;
+                    }
+                    Expression::Assign { target, .. } | Expression::Increment { target, .. } => {
+                        calls = true;
+                        if let Expression::Local(local) = target.as_ref() {
+                            locals_written.push(local.index);
+                        }
+                    }
+                    Expression::Invoke { .. } | Expression::New { .. } => calls = true,
+                    Expression::Local(local) => locals_read.push(local.index),
+                    Expression::Field { .. }
+                    | Expression::StaticField { .. }
+                    | Expression::ArrayElement { .. }
+                    | Expression::ArrayLength(_) => reads = true,
+                    _ => {}
+                },
+            );
+            valid
+        });
+        let start = match inlined {
+            Some(start) => start,
+            None => return,
+        };
+
+        let position = self.statements.len() - (definitions.len() - start);
+        let values: Vec<(u16, Expression)> = definitions[start..]
+            .iter()
+            .map(|(index, value)| (*index, (*value).clone()))
+            .collect();
+        for statement in self.statements.split_off(position) {
+            self.reopen(&statement);
+        }
+        expression.visit_mut(&mut |expression| {
+            let value = match expression {
+                Expression::Local(local) => values.iter().find(|(index, _)| *index == local.index),
+                _ => None,
+            };
+            if let Some((_, value)) = value {
+                *expression = value.clone();
+            }
+        });
+    }
+
+    // The value the last statement assigned to the temporary, if it did
+    fn temporary_value(&self, temporary: &Local) -> Option<&Expression> {
+        if (temporary.index as usize) < self.variables.variables.len() {
+            return None;
+        }
+        match self.statements.last() {
+            Some(Statement::Expression(
+                Expression::Assign {
+                    target,
+                    operator: None,
+                    value,
+                },
+                _,
+            )) if matches!(target.as_ref(), Expression::Local(local) if local == temporary) => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    // Removes the assignment of the temporary in the last statement, returns the value
+    fn take_temporary(&mut self, temporary: &Local) -> Option<Expression> {
+        self.temporary_value(temporary)?;
+        let statement = self.statements.pop()?;
+        self.reopen(&statement);
+        match statement {
+            Statement::Expression(Expression::Assign { value, .. }, _) => Some(*value),
+            _ => None,
+        }
+    }
+
+    // `ty` is the type of the array, its dimensions without a size are counted as extra
+    fn new_array(&mut self, ty: Type, dimensions: Vec<Expression>) {
+        let mut element = &ty;
+        let mut depth = 0;
+        while let Type::Array(inner) = element {
+            element = inner;
+            depth += 1;
+        }
+        self.push(
+            Expression::NewArray {
+                ty: element.clone(),
+                extra: depth - dimensions.len(),
+                dimensions,
+                elements: None,
+            },
+            ty.clone(),
+        );
+    }
+
+    // Adds the element to the array created in the definition of the temporary right before,
+    // if it is the next element of its initializer
+    fn initialize(&mut self, temporary: &Local, index: i32, value: &StackValue) -> bool {
+        let ty = match self.temporary_value(temporary) {
+            Some(Expression::NewArray {
+                ty,
+                dimensions,
+                extra,
+                elements,
+            }) if matches!(dimensions.as_slice(),
+                [Expression::Literal(Literal::Int(count))] if index < *count)
+                && elements.as_ref().map_or(0, Vec::len) == index as usize =>
+            {
+                (0..*extra).fold(ty.clone(), |ty, _| Type::Array(Box::new(ty)))
+            }
+            _ => return false,
+        };
+        self.constraints.hint(&value.expression, ty.clone());
+        // the element is evaluated after the values below the arrays being initialized, which
+        // may have to be assigned to temporaries before the arrays are created
+        let mut definitions = Vec::new();
+        while let Some(Statement::Expression(
+            Expression::Assign {
+                target,
+                operator: None,
+                value: array,
+            },
+            _,
+        )) = self.statements.last()
+        {
+            let initialized = match target.as_ref() {
+                Expression::Local(local) => {
+                    local == temporary
+                        || self
+                            .stack
+                            .iter()
+                            .any(|value| value.expression == Expression::Local(local.clone()))
+                }
+                _ => false,
+            };
+            if !initialized || !matches!(array.as_ref(), Expression::NewArray { .. }) {
+                break;
+            }
+            definitions.extend(self.statements.pop());
+        }
+        self.spill_conflicts(&Statement::Expression(
+            value.expression.clone(),
+            Pcs::default(),
+        ));
+        self.statements.extend(definitions.into_iter().rev());
+        if let Some(Statement::Expression(Expression::Assign { value: array, .. }, _)) =
+            self.statements.last_mut()
+        {
+            if let Expression::NewArray { elements, .. } = array.as_mut() {
+                elements
+                    .get_or_insert_with(Vec::new)
+                    .push(implicit(typed_value(value.clone(), &ty), &ty));
+            }
+        }
+        true
+    }
+
+    fn array_load(&mut self, ty: Type) {
+        let index = self.pop();
+        let array = self.pop();
+        let ty = match (&array.ty, ty) {
+            (Type::Array(element), Type::Unknown) => element.as_ref().clone(),
+            (Type::Array(element), Type::Byte) if **element == Type::Boolean => Type::Boolean,
+            (_, ty) => ty,
+        };
+        self.push(
+            Expression::ArrayElement {
+                array: Box::new(array.expression),
+                index: Box::new(index.expression),
+            },
+            ty,
+        );
+    }
+
+    fn binary(&mut self, operator: BinaryOperator, ty: Type) {
+        let right = self.pop();
+        let left = self.pop();
+        // the logical operators on booleans are compiled like the bitwise ones on ints
+        let ty = match operator {
+            BinaryOperator::And | BinaryOperator::Or | BinaryOperator::Xor
+                if left.ty == Type::Boolean && right.ty == Type::Boolean =>
+            {
+                Type::Boolean
+            }
+            _ => ty,
+        };
+        let (mut left, mut right) = (typed_value(left, &ty), typed_value(right, &ty));
+        // the other operand is converted implicitly, the shift distance isn't
+        if !matches!(
+            operator,
+            BinaryOperator::Shl | BinaryOperator::Shr | BinaryOperator::UShr
+        ) {
+            (left, right) = promoted(left, right, &ty);
+        }
+        self.push(
+            Expression::Binary {
+                operator,
+                left: Box::new(left),
+                right: Box::new(right),
+            },
+            ty,
+        );
+    }
+
+    fn compare(&mut self, kind: CompareKind) {
+        let right = self.pop();
+        let left = self.pop();
+        let (left, right) = promoted(left.expression, right.expression, &left.ty);
+        self.push(
+            Expression::Compare {
+                kind,
+                operator: None,
+                left: Box::new(left),
+                right: Box::new(right),
+            },
+            Type::Int,
+        );
+    }
+
+    fn cast(&mut self, ty: Type) {
+        let value = self.pop();
+        if value.ty == ty {
+            self.push_value(value);
+            return;
+        }
+        let widening = match ty {
+            Type::Long => is_int(&value.ty),
+            Type::Float => is_int(&value.ty) || value.ty == Type::Long,
+            Type::Double => value.ty != Type::Unknown,
+            _ => false,
+        };
+        self.push(
+            Expression::Cast {
+                ty: ty.clone(),
+                expression: Box::new(value.expression),
+                widening,
+            },
+            ty,
+        );
+    }
+
+    fn branch_zero(
+        &mut self,
+        operator: BinaryOperator,
+        decoded: &DecodedInstruction,
+        offset: i16,
+    ) -> Terminator {
+        let value = self.pop();
+        let condition = match (value.ty, value.expression) {
+            (Type::Boolean, expression) if operator == BinaryOperator::Eq => negate(expression),
+            (Type::Boolean, expression) => expression,
+            // lcmp and friends are always followed by a branch on their result
+            (
+                _,
+                Expression::Compare {
+                    kind,
+                    operator: None,
+                    left,
+                    right,
+                },
+            ) => compare(kind, operator, left, right),
+            (_, expression) => Expression::Binary {
+                operator,
+                left: Box::new(expression),
+                right: Box::new(Expression::Literal(Literal::Int(0))),
+            },
+        };
+        self.condition(condition, decoded.target(offset as i32))
+    }
+
+    fn branch_null(
+        &mut self,
+        operator: BinaryOperator,
+        decoded: &DecodedInstruction,
+        offset: i16,
+    ) -> Terminator {
+        let value = self.pop();
+        let condition = Expression::Binary {
+            operator,
+            left: Box::new(value.expression),
+            right: Box::new(Expression::Literal(Literal::Null)),
+        };
+        self.condition(condition, decoded.target(offset as i32))
+    }
+
+    fn branch(
+        &mut self,
+        operator: BinaryOperator,
+        decoded: &DecodedInstruction,
+        offset: i16,
+    ) -> Terminator {
+        let right = self.pop();
+        let left = self.pop();
+        // chars are compared with char literals
+        let condition = Expression::Binary {
+            operator,
+            left: Box::new(typed_value(left.clone(), &right.ty)),
+            right: Box::new(typed_value(right, &left.ty)),
+        };
+        self.condition(condition, decoded.target(offset as i32))
+    }
+
+    fn condition(&mut self, mut condition: Expression, target: usize) -> Terminator {
+        self.inline_temporaries(&mut condition);
+        self.spill_conflicts(&Statement::Expression(condition.clone(), Pcs::default()));
+        Terminator::If { condition, target }
+    }
+
+    fn switch(
+        &mut self,
+        mut value: StackValue,
+        cases: Vec<(i32, usize)>,
+        default: usize,
+    ) -> Terminator {
+        self.inline_temporaries(&mut value.expression);
+        self.spill_conflicts(&Statement::Expression(
+            value.expression.clone(),
+            Pcs::default(),
+        ));
+        Terminator::Switch {
+            value: value.expression,
+            ty: value.ty,
+            cases,
+            default,
+        }
+    }
+
+    // Where the call could resolve to another method the arguments keep the type of the
+    // parameters they are passed as, `f((Object) s)` doesn't call `f(String)`. Without the
+    // class and name of the method they are treated like the arguments of an overloaded one,
+    // `(int) c + " "` isn't a char.
+    fn pop_arguments(
+        &mut self,
+        method_descriptor: &str,
+        method: Option<(&str, &str)>,
+    ) -> Vec<StackValue> {
+        let class_name = self.method_information.class_name.as_str();
+        let (overloaded, known) = match method {
+            Some((class, name)) => (
+                sugar::overloaded(
+                    class,
+                    name,
+                    method_descriptor,
+                    class_name,
+                    self.method_information.methods,
+                ),
+                class == class_name,
+            ),
+            None => (true, false),
+        };
+        let (arguments, _) = descriptor::split_method_descriptor(method_descriptor);
+        let mut values: Vec<StackValue> = arguments.iter().map(|_| self.pop()).collect();
+        values.reverse();
+        for (value, argument) in values.iter_mut().zip(arguments.iter()) {
+            let ty = Type::from_descriptor(argument.as_str());
+            self.constraints.hint(&value.expression, ty.clone());
+            let converted = match (&value.expression, &value.ty, &ty) {
+                (Expression::Literal(Literal::Null), _, Type::Class(_) | Type::Array(_)) => true,
+                (_, Type::Char, Type::Int) => true,
+                (_, Type::Class(class), Type::Class(_) | Type::Array(_)) => {
+                    value.ty != ty && (known || sugar::OVERLOADED_TYPES.contains(&class.as_str()))
+                }
+                (_, Type::Array(element), Type::Class(_) | Type::Array(_)) => {
+                    value.ty != ty && (known || **element == Type::Char)
+                }
+                _ => false,
+            };
+            // unlike assignments calls don't narrow int constants
+            let narrowed = matches!(
+                (&value.expression, &ty),
+                (
+                    Expression::Literal(Literal::Int(_)),
+                    Type::Byte | Type::Short
+                )
+            );
+            value.expression = if overloaded && converted || narrowed {
+                Expression::Cast {
+                    ty,
+                    expression: Box::new(value.expression.clone()),
+                    widening: false,
+                }
+            } else {
+                typed_value(value.clone(), &ty)
+            };
+        }
+        values
+    }
+
+    fn invoke(&mut self, index: u16, has_receiver: bool, special: bool) {
+        let (class, name, method_descriptor) = self.member(index);
+        let arguments: Vec<Expression> = self
+            .pop_arguments(
+                method_descriptor.as_str(),
+                Some((class.as_str(), name.as_str())),
+            )
+            .into_iter()
+            .map(|value| value.expression)
+            .collect();
+        let receiver = if has_receiver {
+            let receiver = self.pop().expression;
+            self.constraints
+                .hint(&receiver, Type::from_class_name(class.as_str()));
+            Some(receiver)
+        } else {
+            None
+        };
+
+        if special && name == "<init>" {
+            if let Some(Expression::Uninitialized(id)) = receiver {
+                self.construct(id, method_descriptor, arguments);
+                return;
+            }
+        }
+
+        let target = receiver.map(|receiver| match receiver {
+            // calls of the super class' implementation and its constructors
+            Expression::This if special && class != self.method_information.class_name => {
+                Box::new(Expression::Super)
+            }
+            receiver => Box::new(receiver),
+        });
+
+        let call = Expression::Invoke {
+            target,
+            class: class.replace('/', "."),
+            name,
+            descriptor: method_descriptor.clone(),
+            arguments,
+        };
+        self.push_result(call, method_descriptor.as_str());
+    }
+
+    // Replaces the uninitialized object with the constructor call
+    fn construct(&mut self, id: usize, method_descriptor: String, arguments: Vec<Expression>) {
+        let class = self.uninitialized[id].clone();
+        let mut value = StackValue {
+            expression: Expression::New {
+                class: class.replace('/', "."),
+                descriptor: method_descriptor,
+                arguments,
+            },
+            ty: Type::Class(class.replace('/', ".")),
+        };
+
+        let uninitialized = Expression::Uninitialized(id);
+        let copies = self
+            .stack
+            .iter()
+            .filter(|value| value.expression == uninitialized)
+            .count();
+
+        match copies {
+            0 => self.emit(Statement::Expression(value.expression, Pcs::default())),
+            _ => {
+                if copies > 1 {
+                    value = self.materialize(value);
+                }
+                for stack_value in self.stack.iter_mut() {
+                    if stack_value.expression == uninitialized {
+                        stack_value.expression = value.expression.clone();
+                    }
+                }
+            }
+        }
+    }
+
+    fn push_result(&mut self, call: Expression, method_descriptor: &str) {
+        let (_, return_type) = descriptor::split_method_descriptor(method_descriptor);
+        match Type::from_descriptor(return_type.as_str()) {
+            Type::Void => self.emit(Statement::Expression(call, Pcs::default())),
+            ty => self.push(call, ty),
+        }
+    }
+
+    fn invoke_dynamic(&mut self, index: u16) {
+        let (bootstrap_index, name, method_descriptor) = match self.constant_pool.get_index(index) {
+            CPIndexType::InvokeDynamic {
+                boostrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                let (name, method_descriptor) = self.name_and_type(name_and_type_index);
+                (boostrap_method_attr_index, name, method_descriptor)
+            }
+            _ => panic!("Invalid Type in Constant Pool"),
+        };
+        let arguments: Vec<Expression> = self
+            .pop_arguments(method_descriptor.as_str(), None)
+            .into_iter()
+            .map(|value| value.expression)
+            .collect();
+
+        let bootstrap_method = match self
+            .method_information
+            .bootstrap_methods
+            .get(bootstrap_index as usize)
+        {
+            Some(bootstrap_method) => bootstrap_method,
+            None => {
+                self.problems
+                    .push(format!("Missing bootstrap method {}", bootstrap_index));
+                let call = Expression::Invoke {
+                    target: None,
+                    class: "$invokedynamic".to_string(),
+                    name,
+                    descriptor: method_descriptor.clone(),
+                    arguments,
+                };
+                self.push_result(call, method_descriptor.as_str());
+                return;
+            }
+        };
+
+        let (bootstrap_class, bootstrap_name, _) =
+            self.method_handle(bootstrap_method.method_ref).1;
+        let expression = match (bootstrap_class.as_str(), bootstrap_name.as_str()) {
+            ("java/lang/invoke/StringConcatFactory", "makeConcatWithConstants")
+                if matches!(
+                    self.bootstrap_argument(bootstrap_method, 0),
+                    Some(CPIndexType::String(_))
+                ) =>
+            {
+                let recipe = match self.constant(bootstrap_method.arguments[0]).0 {
+                    Expression::Literal(Literal::String(recipe)) => recipe,
+                    _ => String::new(),
+                };
+                let constants = bootstrap_method.arguments[1..]
+                    .iter()
+                    .map(|argument| self.constant(*argument).0)
+                    .collect();
+                concatenate(recipe.as_str(), arguments, constants)
+            }
+            ("java/lang/invoke/StringConcatFactory", "makeConcat") => {
+                let recipe = "\u{1}".repeat(arguments.len());
+                concatenate(recipe.as_str(), arguments, Vec::new())
+            }
+            ("java/lang/invoke/LambdaMetafactory", _)
+                if matches!(
+                    (
+                        self.bootstrap_argument(bootstrap_method, 0),
+                        self.bootstrap_argument(bootstrap_method, 1)
+                    ),
+                    (
+                        Some(CPIndexType::MethodType { .. }),
+                        Some(CPIndexType::MethodHandle { .. })
+                    )
+                ) =>
+            {
+                let interface_type =
+                    match self.constant_pool.get_index(bootstrap_method.arguments[0]) {
+                        CPIndexType::MethodType { descriptor_index } => self.utf8(descriptor_index),
+                        _ => String::new(),
+                    };
+                self.lambda(
+                    bootstrap_method.arguments[1],
+                    interface_type.as_str(),
+                    arguments,
+                )
+            }
+            // the labels of a pattern switch follow the selector and the case to restart at
+            ("java/lang/runtime/SwitchBootstraps", "typeSwitch" | "enumSwitch")
+                if bootstrap_method
+                    .arguments
+                    .iter()
+                    .all(|argument| self.is_label(*argument)) =>
+            {
+                let mut arguments = arguments;
+                arguments.extend(
+                    bootstrap_method
+                        .arguments
+                        .iter()
+                        .map(|argument| self.constant(*argument).0),
+                );
+                Expression::Invoke {
+                    target: None,
+                    class: "java.lang.runtime.SwitchBootstraps".to_string(),
+                    name,
+                    descriptor: method_descriptor.clone(),
+                    arguments,
+                }
+            }
+            (bootstrap_class, _) => Expression::Invoke {
+                target: None,
+                class: bootstrap_class.replace('/', "."),
+                name,
+                descriptor: method_descriptor.clone(),
+                arguments,
+            },
+        };
+
+        self.push_result(expression, method_descriptor.as_str());
+    }
+
+    // A lambda calling the implementation method with the captured values and its parameters
+    fn lambda(
+        &mut self,
+        implementation: u16,
+        interface_type: &str,
+        captured: Vec<Expression>,
+    ) -> Expression {
+        let (reference_kind, (class, name, method_descriptor)) = self.method_handle(implementation);
+        let (interface_arguments, _) = descriptor::split_method_descriptor(interface_type);
+
+        let parameters: Vec<String> = (0..interface_arguments.len())
+            .map(|i| format!("p{}", i))
+            .collect();
+        let mut arguments: Vec<Expression> = captured;
+        arguments.extend(
+            parameters
+                .iter()
+                .enumerate()
+                .map(|(i, parameter)| Expression::local(u16::MAX - i as u16, parameter.clone())),
+        );
+
+        let body = match reference_kind {
+            // REF_newInvokeSpecial
+            8 => Expression::New {
+                class: class.replace('/', "."),
+                descriptor: method_descriptor,
+                arguments,
+            },
+            // REF_invokeStatic
+            6 => Expression::Invoke {
+                target: None,
+                class: class.replace('/', "."),
+                name,
+                descriptor: method_descriptor,
+                arguments,
+            },
+            _ => {
+                let mut arguments = arguments.into_iter();
+                let target = arguments
+                    .next()
+                    .unwrap_or_else(|| Expression::local(u16::MAX, "$missing".to_string()));
+                Expression::Invoke {
+                    target: Some(Box::new(target)),
+                    class: class.replace('/', "."),
+                    name,
+                    descriptor: method_descriptor,
+                    arguments: arguments.collect(),
+                }
+            }
+        };
+
+        Expression::Lambda {
+            parameters,
+            body: Box::new(body),
+        }
+    }
+
+    fn push_constant(&mut self, index: u16) {
+        let (expression, ty) = self.constant(index);
+        self.push(expression, ty);
+    }
+
+    fn constant(&self, index: u16) -> (Expression, Type) {
+        match self.constant_pool.get_index(index) {
+            CPIndexType::Integer(value) => {
+                (Expression::Literal(Literal::Int(value as i32)), Type::Int)
+            }
+            CPIndexType::Float(value) => (Expression::Literal(Literal::Float(value)), Type::Float),
+            CPIndexType::Long(value) => {
+                (Expression::Literal(Literal::Long(value as i64)), Type::Long)
+            }
+            CPIndexType::Double(value) => {
+                (Expression::Literal(Literal::Double(value)), Type::Double)
+            }
+            CPIndexType::String(string_index) => (
+                Expression::Literal(Literal::String(self.utf8(string_index))),
+                Type::Class("java.lang.String".to_string()),
+            ),
+            CPIndexType::Class(name_index) => (
+                Expression::Literal(Literal::Class(Type::from_class_name(
+                    self.utf8(name_index).as_str(),
+                ))),
+                Type::Class("java.lang.Class".to_string()),
+            ),
+            CPIndexType::MethodType { descriptor_index } => (
+                Expression::Invoke {
+                    target: None,
+                    class: "java.lang.invoke.MethodType".to_string(),
+                    name: "fromMethodDescriptorString".to_string(),
+                    descriptor:
+                        "(Ljava/lang/String;Ljava/lang/ClassLoader;)Ljava/lang/invoke/MethodType;"
+                            .to_string(),
+                    arguments: vec![
+                        Expression::Literal(Literal::String(self.utf8(descriptor_index))),
+                        Expression::Literal(Literal::Null),
+                    ],
+                },
+                Type::Class("java.lang.invoke.MethodType".to_string()),
+            ),
+            CPIndexType::MethodHandle { .. } => (
+                Expression::Literal(Literal::String(super::super::printer::resolve(
+                    self.constant_pool,
+                    index,
+                ))),
+                Type::Class("java.lang.invoke.MethodHandle".to_string()),
+            ),
+            _ => panic!("Invalid Type in Constant Pool"),
+        }
+    }
+
+    // Bootstrap methods with other arguments than expected are left as calls to them
+    fn bootstrap_argument(
+        &self,
+        bootstrap_method: &BootstrapMethod,
+        index: usize,
+    ) -> Option<CPIndexType> {
+        bootstrap_method
+            .arguments
+            .get(index)
+            .map(|argument| self.constant_pool.get_index(*argument))
+    }
+
+    // Classes, strings and ints, enum constants described by dynamic constants are left out
+    fn is_label(&self, index: u16) -> bool {
+        matches!(
+            self.constant_pool.get_index(index),
+            CPIndexType::Class(_) | CPIndexType::String(_) | CPIndexType::Integer(_)
+        )
+    }
+
+    fn utf8(&self, index: u16) -> String {
+        match self.constant_pool.get_index(index) {
+            CPIndexType::Utf8(utf8) => utf8.string,
+            _ => panic!("Invalid Type in Constant Pool"),
+        }
+    }
+
+    fn class_name(&self, index: u16) -> String {
+        match self.constant_pool.get_index(index) {
+            CPIndexType::Class(name_index) => self.utf8(name_index),
+            _ => panic!("Invalid Type in Constant Pool"),
+        }
+    }
+
+    fn name_and_type(&self, index: u16) -> (String, String) {
+        match self.constant_pool.get_index(index) {
+            CPIndexType::NameAndType {
+                name_index,
+                descriptor_index,
+            } => (self.utf8(name_index), self.utf8(descriptor_index)),
+            _ => panic!("Invalid Type in Constant Pool"),
+        }
+    }
+
+    // The owner, name and descriptor of a field or method reference
+    fn member(&self, index: u16) -> (String, String, String) {
+        match self.constant_pool.get_index(index) {
+            CPIndexType::FieldRef {
+                class_index,
+                name_and_type_index,
+            }
+            | CPIndexType::MethodRef {
+                class_index,
+                name_and_type_index,
+            }
+            | CPIndexType::InterfaceMethodRef {
+                class_index,
+                name_and_type_index,
+            } => {
+                let (name, member_descriptor) = self.name_and_type(name_and_type_index);
+                (self.class_name(class_index), name, member_descriptor)
+            }
+            _ => panic!("Invalid Type in Constant Pool"),
+        }
+    }
+
+    fn method_handle(&self, index: u16) -> (u8, (String, String, String)) {
+        match self.constant_pool.get_index(index) {
+            CPIndexType::MethodHandle {
+                reference_type,
+                reference_index,
+            } => (reference_type, self.member(reference_index)),
+            _ => panic!("Invalid Type in Constant Pool"),
+        }
+    }
+}
+
+// int constants are used for booleans and chars too
+// The value without a widening cast to the type, where it is converted implicitly
+fn implicit(expression: Expression, ty: &Type) -> Expression {
+    match expression {
+        Expression::Cast {
+            ty: cast,
+            expression,
+            widening: true,
+        } if cast == *ty => *expression,
+        expression => expression,
+    }
+}
+
+// The operands of an operation on values of the type. Binary numeric promotion widens one
+// operand to the type of the other, so one of them has to keep its cast.
+fn promoted(left: Expression, right: Expression, ty: &Type) -> (Expression, Expression) {
+    match right {
+        Expression::Cast {
+            widening: true,
+            ty: ref cast,
+            ..
+        } if cast == ty => (left, implicit(right, ty)),
+        right => (implicit(left, ty), right),
+    }
+}
+
+// Calls `f` with every sub-expression after its operands, and whether it is only evaluated
+// depending on a condition
+fn evaluation(expression: &Expression, conditional: bool, f: &mut dyn FnMut(&Expression, bool)) {
+    let eager = match expression {
+        Expression::Conditional { .. }
+        | Expression::Binary {
+            operator: BinaryOperator::ConditionalAnd | BinaryOperator::ConditionalOr,
+            ..
+        } => 1,
+        Expression::Lambda { .. } => 0,
+        _ => usize::MAX,
+    };
+    for (i, child) in expression.children().into_iter().enumerate() {
+        // storing into a variable, field or element doesn't read it, only its operands
+        let stored = match expression {
+            Expression::Assign {
+                target,
+                operator: None,
+                ..
+            } => std::ptr::eq(child, target.as_ref()),
+            _ => false,
+        };
+        if stored {
+            for operand in child.children() {
+                evaluation(operand, conditional || i >= eager, f);
+            }
+        } else {
+            evaluation(child, conditional || i >= eager, f);
+        }
+    }
+    f(expression, conditional);
+}
+
+// `target = value` as a compound assignment or increment if the value is an operation on the
+// target, `(byte) (b + 1)` is `b += 1` since compound assignments narrow implicitly
+fn compound(target: Expression, value: Expression, ty: &Type) -> Expression {
+    if let Some((operator, old)) = increment_of(&value, ty) {
+        if *old == target {
+            return Expression::Increment {
+                target: Box::new(target),
+                operator,
+                prefix: true,
+            };
+        }
+    }
+    let operation = match &value {
+        Expression::Cast {
+            ty: cast,
+            expression,
+            ..
+        } if cast == ty => expression.as_ref(),
+        value => value,
+    };
+    match operation {
+        Expression::Binary {
+            operator,
+            left,
+            right,
+        } if **left == target && operator.is_compound() => Expression::Assign {
+            target: Box::new(target),
+            operator: Some(*operator),
+            value: right.clone(),
+        },
+        _ => Expression::Assign {
+            target: Box::new(target),
+            operator: None,
+            value: Box::new(value),
+        },
+    }
+}
+
+// `old + 1` or `old - 1` of a number, possibly narrowed back to the type
+fn increment_of<'e>(value: &'e Expression, ty: &Type) -> Option<(BinaryOperator, &'e Expression)> {
+    let value = match value {
+        Expression::Cast {
+            ty: cast,
+            expression,
+            ..
+        } if cast == ty => expression.as_ref(),
+        value => value,
+    };
+    match value {
+        Expression::Binary {
+            operator: operator @ (BinaryOperator::Add | BinaryOperator::Sub),
+            left,
+            right,
+        } if is_one(right) && is_number(ty) => Some((*operator, left.as_ref())),
+        _ => None,
+    }
+}
+
+fn is_number(ty: &Type) -> bool {
+    *ty != Type::Boolean && (is_int(ty) || matches!(ty, Type::Long | Type::Float | Type::Double))
+}
+
+fn is_one(expression: &Expression) -> bool {
+    match expression {
+        Expression::Literal(Literal::Int(value)) => *value == 1,
+        Expression::Literal(Literal::Long(value)) => *value == 1,
+        Expression::Literal(Literal::Float(value)) => *value == 1.0,
+        Expression::Literal(Literal::Double(value)) => *value == 1.0,
+        _ => false,
+    }
+}
+
+// `left <kind> right <operator> 0` as a comparison of the operands. fcmpl and dcmpl push -1
+// for NaN, fcmpg and dcmpg push 1, so the branch of an ordering that is taken for NaN is the
+// negation of the opposite ordering, `!(a >= b)` rather than `a < b`.
+fn compare(
+    kind: CompareKind,
+    operator: BinaryOperator,
+    left: Box<Expression>,
+    right: Box<Expression>,
+) -> Expression {
+    let nan = match kind {
+        CompareKind::LCmp => None,
+        CompareKind::FCmpL | CompareKind::DCmpL => Some(-1),
+        CompareKind::FCmpG | CompareKind::DCmpG => Some(1),
+    };
+    let taken_for_nan = |nan: i32| match operator {
+        BinaryOperator::Lt => nan < 0,
+        BinaryOperator::Ge => nan >= 0,
+        BinaryOperator::Gt => nan > 0,
+        BinaryOperator::Le => nan <= 0,
+        _ => false,
+    };
+    match nan {
+        // == and != mean the same for the values and the operands
+        Some(nan) if operator != BinaryOperator::Eq && operator != BinaryOperator::Ne => {
+            if taken_for_nan(nan) {
+                Expression::Unary {
+                    operator: UnaryOperator::Not,
+                    operand: Box::new(Expression::Compare {
+                        kind,
+                        operator: operator.negated(),
+                        left,
+                        right,
+                    }),
+                }
+            } else {
+                Expression::Compare {
+                    kind,
+                    operator: Some(operator),
+                    left,
+                    right,
+                }
+            }
+        }
+        _ => Expression::Binary {
+            operator,
+            left,
+            right,
+        },
+    }
+}
+
+// The value where a value of the type is expected. Int constants become constants of the type,
+// javac compiles both `c` and `c ? 1 : 0` to the same code, so a boolean used as a number
+// is the latter.
+pub fn typed_value(value: StackValue, ty: &Type) -> Expression {
+    match value.expression {
+        Expression::Literal(Literal::Int(literal)) => {
+            Expression::Literal(Literal::of_type(literal, ty))
+        }
+        expression if value.ty == Type::Boolean && is_number(ty) => Expression::Conditional {
+            condition: Box::new(expression),
+            then: Box::new(Expression::Literal(Literal::Int(1))),
+            otherwise: Box::new(Expression::Literal(Literal::Int(0))),
+        },
+        expression => expression,
+    }
+}
+
+// Builds the `+` chain for a StringConcatFactory recipe, \u0001 stands for the next argument
+// and \u0002 for the next constant
+fn concatenate(recipe: &str, arguments: Vec<Expression>, constants: Vec<Expression>) -> Expression {
+    let mut parts = Vec::new();
+    let mut arguments = arguments.into_iter();
+    let mut constants = constants.into_iter();
+    let mut text = String::new();
+
+    for char in recipe.chars() {
+        match char {
+            '\u{1}' | '\u{2}' => {
+                if !text.is_empty() {
+                    parts.push(Expression::Literal(Literal::String(std::mem::take(
+                        &mut text,
+                    ))));
+                }
+                let part = if char == '\u{1}' {
+                    arguments.next()
+                } else {
+                    constants.next()
+                };
+                if let Some(part) = part {
+                    parts.push(part);
+                }
+            }
+            char => text.push(char),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(Expression::Literal(Literal::String(text)));
+    }
+
+    // one of the first two operands has to be a String for `+` to concatenate
+    let is_string = |part: &Expression| matches!(part, Expression::Literal(Literal::String(_)));
+    if !parts.iter().take(2).any(is_string) {
+        parts.insert(0, Expression::Literal(Literal::String(String::new())));
+    }
+
+    let mut parts = parts.into_iter();
+    let first = parts.next().unwrap();
+    parts.fold(first, |left, right| Expression::Binary {
+        operator: BinaryOperator::Add,
+        left: Box::new(left),
+        right: Box::new(right),
+    })
+}
+
+// The locals a statement writes and what else it touches
+struct StatementAccess {
+    writes_local: Option<u16>,
+    writes_heap: bool,
+    effects: Effects,
+}
+
+impl StatementAccess {
+    fn of(statement: &Statement) -> StatementAccess {
+        match statement {
+            Statement::Declaration { local, value, .. } => StatementAccess {
+                writes_local: Some(local.index),
+                writes_heap: false,
+                effects: value
+                    .as_ref()
+                    .map_or_else(Effects::default, |value| value.effects()),
+            },
+            Statement::Expression(Expression::Assign { target, value, .. }, _) => {
+                StatementAccess::writing(target, value.effects())
+            }
+            Statement::Expression(Expression::Increment { target, .. }, _) => {
+                StatementAccess::writing(target, Effects::default())
+            }
+            Statement::MonitorEnter(value, _) | Statement::MonitorExit(value, _) => {
+                StatementAccess {
+                    writes_local: None,
+                    writes_heap: true,
+                    effects: value.effects(),
+                }
+            }
+            Statement::Expression(value, _)
+            | Statement::Return(Some(value), _)
+            | Statement::Throw(value, _) => StatementAccess {
+                writes_local: None,
+                writes_heap: false,
+                effects: value.effects(),
+            },
+            _ => StatementAccess {
+                writes_local: None,
+                writes_heap: false,
+                effects: Effects::default(),
+            },
+        }
+    }
+
+    // A statement that stores into `target` a value with the effects
+    fn writing(target: &Expression, mut effects: Effects) -> StatementAccess {
+        match target {
+            Expression::Local(local) => StatementAccess {
+                writes_local: Some(local.index),
+                writes_heap: false,
+                effects,
+            },
+            target => {
+                let target_effects = target.effects();
+                effects.locals_read.extend(target_effects.locals_read);
+                effects.calls |= target_effects.calls;
+                effects.reads_heap |= target_effects.reads_heap;
+                StatementAccess {
+                    writes_local: None,
+                    writes_heap: true,
+                    effects,
+                }
+            }
+        }
+    }
+
+    // Whether evaluating the expression after the statement could give a different result.
+    // Calls aren't assumed to change the fields and arrays a pending value reads, the
+    // order of calls and explicit stores is kept though.
+    fn conflicts(&self, expression: &Expression) -> bool {
+        let effects = expression.effects();
+
+        self.writes_local
+            .is_some_and(|local| effects.locals_read.contains(&local))
+            || effects.locals_written.iter().any(|local| {
+                self.writes_local == Some(*local) || self.effects.locals_read.contains(local)
+            })
+            || (effects.reads_heap && self.writes_heap)
+            || (effects.calls
+                && (self.writes_heap || self.effects.calls || self.effects.reads_heap))
+    }
+}
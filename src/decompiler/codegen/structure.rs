@@ -0,0 +1,1164 @@
+use super::super::ast::*;
+use super::cfg::BasicBlock;
+use super::stack::{BlockOutput, Terminator};
+use super::tries;
+
+use std::collections::HashSet;
+
+// An exception table entry with pcs, `ty` is None for `any`
+pub struct Handler {
+    pub start: usize,
+    pub end: usize,
+    pub handler: usize,
+    pub ty: Option<Type>,
+}
+
+// Exception table entries that share their handlers, one try statement
+struct TryGroup {
+    start: usize,
+    end: usize,
+    // handler blocks with the types they catch
+    handlers: Vec<(usize, Vec<Type>)>,
+}
+
+// A loop or switch that can be left with break or continue
+struct Frame {
+    header: Option<usize>,
+    is_loop: bool,
+    breaks: Vec<usize>,
+    continues: Vec<usize>,
+    label: String,
+    label_used: bool,
+}
+
+// Everything the structurer changes while it runs, so a loop can be structured again
+#[derive(Clone, Default)]
+struct State {
+    started_tries: HashSet<usize>,
+    structured_handlers: HashSet<usize>,
+    placed_labels: HashSet<usize>,
+    // labels referenced by comments on handlers that are left unstructured
+    referenced_labels: HashSet<usize>,
+}
+
+struct Structurer<'a> {
+    blocks: &'a [BasicBlock],
+    outputs: &'a [BlockOutput],
+    handlers: &'a [Handler],
+    tries: Vec<TryGroup>,
+    frames: Vec<Frame>,
+    // block pcs that still need a label for a goto
+    labels: HashSet<usize>,
+    state: State,
+}
+
+// Turns the blocks back into nested statements. Anything that doesn't fit into if, loops,
+// switch or try is left as labels and gotos.
+pub fn structure(
+    blocks: &[BasicBlock],
+    outputs: &[BlockOutput],
+    handlers: &[Handler],
+) -> Vec<Statement> {
+    let mut structurer = Structurer::new(blocks, outputs, handlers, HashSet::new());
+    let statements = structurer.range(0, blocks.len(), None);
+
+    let mut labels = structurer.state.referenced_labels;
+    for statement in &statements {
+        statement.walk(&mut |statement| {
+            if let Statement::Goto(target) = statement {
+                labels.insert(*target);
+            }
+        });
+    }
+    if labels.is_empty() {
+        return statements;
+    }
+
+    Structurer::new(blocks, outputs, handlers, labels).range(0, blocks.len(), None)
+}
+
+impl<'a> Structurer<'a> {
+    fn new(
+        blocks: &'a [BasicBlock],
+        outputs: &'a [BlockOutput],
+        handlers: &'a [Handler],
+        labels: HashSet<usize>,
+    ) -> Self {
+        let mut structurer = Structurer {
+            blocks,
+            outputs,
+            handlers,
+            tries: Vec::new(),
+            frames: Vec::new(),
+            labels,
+            state: State::default(),
+        };
+        structurer.tries = structurer.group_handlers();
+        structurer
+    }
+
+    // Groups the exception table by protected range, then merges the ranges javac splits
+    // around jumps out of the try
+    fn group_handlers(&self) -> Vec<TryGroup> {
+        let mut groups: Vec<TryGroup> = Vec::new();
+        // javac protects the start of the handlers for finally and synchronized with the
+        // handler itself, alone or at the end of the range of the catches before it
+        for handler in self
+            .handlers
+            .iter()
+            .filter(|handler| handler.start != handler.handler)
+        {
+            let end = match handler.handler > handler.start && handler.handler < handler.end {
+                true => handler.handler,
+                false => handler.end,
+            };
+            let ty = handler
+                .ty
+                .clone()
+                .unwrap_or_else(|| Type::Class("java.lang.Throwable".to_string()));
+            let block = self.index(handler.handler);
+
+            let group = match groups
+                .iter_mut()
+                .find(|group| group.start == handler.start && group.end == end)
+            {
+                Some(group) => group,
+                None => {
+                    groups.push(TryGroup {
+                        start: handler.start,
+                        end,
+                        handlers: Vec::new(),
+                    });
+                    groups.last_mut().unwrap()
+                }
+            };
+            match group.handlers.iter_mut().find(|(index, _)| *index == block) {
+                Some((_, types)) => types.push(ty),
+                None => group.handlers.push((block, vec![ty])),
+            }
+        }
+
+        let mut merged: Vec<TryGroup> = Vec::new();
+        for group in groups {
+            match merged
+                .iter_mut()
+                .find(|other| other.handlers == group.handlers)
+            {
+                Some(other) => {
+                    other.start = other.start.min(group.start);
+                    other.end = other.end.max(group.end);
+                }
+                None => merged.push(group),
+            }
+        }
+        merged
+    }
+
+    // The block starting at `pc`, or the one after it
+    fn index(&self, pc: usize) -> usize {
+        match self.blocks.binary_search_by_key(&pc, |block| block.pc) {
+            Ok(index) | Err(index) => index,
+        }
+    }
+
+    fn pc(&self, index: usize) -> Option<usize> {
+        self.blocks.get(index).map(|block| block.pc)
+    }
+
+    // Where control goes after block `next` is reached inside a range ending at `end`
+    fn follow_of(&self, next: usize, end: usize, follow: Option<usize>) -> Option<usize> {
+        if next >= end {
+            follow
+        } else {
+            self.pc(next)
+        }
+    }
+
+    // Whether the blocks `start..end` are only entered at `start`, and `start` only from
+    // inside or from `entry`
+    fn enclosed(&self, start: usize, end: usize, entry: Option<usize>) -> bool {
+        for index in start..end {
+            for predecessor in &self.blocks[index].predecessors {
+                let inside = *predecessor >= start && *predecessor < end;
+                if inside || (index == start && entry.is_none_or(|entry| entry == *predecessor)) {
+                    continue;
+                }
+                return false;
+            }
+        }
+        self.handlers_enclosed(start, end)
+    }
+
+    // Whether the handlers among the blocks `start..end` protect code in them
+    fn handlers_enclosed(&self, start: usize, end: usize) -> bool {
+        self.handlers.iter().all(|handler| {
+            let block = self.index(handler.handler);
+            block <= start || block >= end || {
+                let protected = self.index(handler.start);
+                protected >= start && protected < end
+            }
+        })
+    }
+
+    fn range(&mut self, start: usize, end: usize, follow: Option<usize>) -> Vec<Statement> {
+        let mut statements = Vec::new();
+        let mut index = start;
+        while index < end {
+            index = self.step(index, end, follow, &mut statements);
+        }
+        statements
+    }
+
+    // Structures whatever starts at block `index` and returns the block after it
+    fn step(
+        &mut self,
+        index: usize,
+        end: usize,
+        follow: Option<usize>,
+        statements: &mut Vec<Statement>,
+    ) -> usize {
+        self.place_label(index, statements);
+
+        let try_group = self.try_at(index, end);
+        // guards that fail jump back to a pattern switch, that isn't a loop
+        let latch = match self.restarted_switch(index) {
+            true => None,
+            false => self.latch(index, end),
+        };
+        let loop_first = match (try_group, latch) {
+            (Some(group), Some(latch)) => latch >= self.first_handler(group),
+            (None, Some(_)) => true,
+            _ => false,
+        };
+
+        if loop_first {
+            if let Some(next) = self.structure_loop(index, latch.unwrap(), end, follow, statements)
+            {
+                return next;
+            }
+        }
+        if let Some(group) = try_group {
+            if let Some(next) = self.structure_try(group, index, end, follow, statements) {
+                return next;
+            }
+        }
+        if let (false, Some(latch)) = (loop_first, latch) {
+            if let Some(next) = self.structure_loop(index, latch, end, follow, statements) {
+                return next;
+            }
+        }
+        if let Some(next) = self.top_tested_loop(index, end, follow, statements) {
+            return next;
+        }
+
+        self.block_statements(index, statements);
+        let next = index + 1;
+        let outputs = self.outputs;
+        match &outputs[index].terminator {
+            Terminator::Fallthrough if self.is_synchronized(index, end, statements) => {
+                self.synchronized(index, end, follow, statements)
+            }
+            Terminator::Fallthrough => {
+                if next == end && next < self.blocks.len() {
+                    let target = self.blocks[next].pc;
+                    statements.extend(self.jump(index, target, end, follow));
+                }
+                next
+            }
+            Terminator::Goto(target) => {
+                statements.extend(self.jump(index, *target, end, follow));
+                next
+            }
+            Terminator::If { condition, target } => {
+                self.conditional(index, condition.clone(), *target, end, follow, statements)
+            }
+            Terminator::Switch {
+                value,
+                ty,
+                cases,
+                default,
+            } => {
+                // the restarts are left as `continue`, for the guards of the cases
+                let restarted = self.restarted_switch(index);
+                if restarted {
+                    let header = self.blocks[index].pc;
+                    self.frames.push(Frame {
+                        header: Some(index),
+                        is_loop: true,
+                        breaks: Vec::new(),
+                        continues: vec![header],
+                        label: format!("label{}", header),
+                        label_used: false,
+                    });
+                }
+                let next = self.switch(
+                    index,
+                    value.clone(),
+                    &cases
+                        .iter()
+                        .map(|(key, target)| (Literal::of_type(*key, ty), *target))
+                        .collect::<Vec<_>>(),
+                    *default,
+                    end,
+                    follow,
+                    statements,
+                );
+                if restarted {
+                    self.frames.pop();
+                }
+                next
+            }
+            Terminator::Return(value) => {
+                statements.push(Statement::Return(value.clone(), outputs[index].pcs));
+                next
+            }
+            Terminator::Throw(value) => {
+                statements.push(Statement::Throw(value.clone(), outputs[index].pcs));
+                next
+            }
+            Terminator::Ret => {
+                statements.push(Statement::Comment("ret".to_string()));
+                next
+            }
+        }
+    }
+
+    fn place_label(&mut self, index: usize, statements: &mut Vec<Statement>) {
+        let pc = self.blocks[index].pc;
+        if self.labels.contains(&pc) && self.state.placed_labels.insert(pc) {
+            statements.push(Statement::Label(pc));
+        }
+    }
+
+    // The statements of a block without its terminator
+    fn block_statements(&mut self, index: usize, statements: &mut Vec<Statement>) {
+        self.place_label(index, statements);
+        let block = &self.blocks[index];
+        let mut body = self.outputs[index].statements.iter();
+
+        if block.is_handler {
+            if self.state.structured_handlers.contains(&index) {
+                // the catch parameter
+                body.next();
+            } else {
+                for handler in self.handlers {
+                    if handler.handler != block.pc {
+                        continue;
+                    }
+                    let ty = match &handler.ty {
+                        Some(ty) => ty.to_string(),
+                        None => "any".to_string(),
+                    };
+                    statements.push(Statement::Comment(format!(
+                        "catch {} in L{} - L{}",
+                        ty, handler.start, handler.end
+                    )));
+                    self.state.referenced_labels.insert(handler.start);
+                    self.state.referenced_labels.insert(handler.end);
+                }
+            }
+        }
+        statements.extend(body.cloned());
+    }
+
+    // What a jump from the end of block `from` to `target` turns into
+    fn jump(
+        &mut self,
+        from: usize,
+        target: usize,
+        end: usize,
+        follow: Option<usize>,
+    ) -> Vec<Statement> {
+        if Some(target) == self.follow_of(from + 1, end, follow) {
+            return Vec::new();
+        }
+        let pcs = self.outputs[from].pcs;
+        if let Some(statement) = self.frame_jump(target, pcs) {
+            return vec![statement];
+        }
+        if let Some(statement) = self.return_at(target, pcs) {
+            return vec![statement];
+        }
+        vec![Statement::Goto(target)]
+    }
+
+    // break or continue for a jump to the end or the start of an enclosing loop or switch
+    fn frame_jump(&mut self, target: usize, pcs: Pcs) -> Option<Statement> {
+        let mut innermost_loop = true;
+        let mut innermost = true;
+        for frame in self.frames.iter_mut().rev() {
+            if frame.is_loop && frame.continues.contains(&target) {
+                if innermost_loop {
+                    return Some(Statement::Continue(None, pcs));
+                }
+                frame.label_used = true;
+                return Some(Statement::Continue(Some(frame.label.clone()), pcs));
+            }
+            if frame.breaks.contains(&target) {
+                if innermost {
+                    return Some(Statement::Break(None, pcs));
+                }
+                frame.label_used = true;
+                return Some(Statement::Break(Some(frame.label.clone()), pcs));
+            }
+            innermost_loop &= !frame.is_loop;
+            innermost = false;
+        }
+        None
+    }
+
+    // A jump to a block that only returns is the return itself, at the pcs of the jump
+    fn return_at(&self, target: usize, pcs: Pcs) -> Option<Statement> {
+        let index = self.index(target);
+        let output = self.outputs.get(index)?;
+        if !output.statements.is_empty() || self.blocks[index].pc != target {
+            return None;
+        }
+        match &output.terminator {
+            Terminator::Return(None) => Some(Statement::Return(None, pcs)),
+            Terminator::Return(Some(value)) if value.is_trivial() => {
+                Some(Statement::Return(Some(value.clone()), pcs))
+            }
+            _ => None,
+        }
+    }
+
+    fn conditional(
+        &mut self,
+        index: usize,
+        condition: Expression,
+        target: usize,
+        end: usize,
+        follow: Option<usize>,
+        statements: &mut Vec<Statement>,
+    ) -> usize {
+        let next = index + 1;
+        let target_index = self.index(target);
+        let pcs = self.outputs[index].pcs;
+
+        if target_index <= index || target_index >= end {
+            if Some(target) == follow && self.enclosed(next, end, Some(index)) {
+                // the rest of the range only runs if the condition is false
+                let then = self.range(next, end, follow);
+                statements.push(if_statement(negate(condition), then, None, pcs));
+                return end;
+            }
+            let jump = match self
+                .frame_jump(target, pcs)
+                .or_else(|| self.return_at(target, pcs))
+            {
+                Some(statement) => statement,
+                None => Statement::Goto(target),
+            };
+            statements.push(if_statement(condition, vec![jump], None, pcs));
+            return next;
+        }
+
+        if !self.enclosed(next, target_index, Some(index)) {
+            statements.push(if_statement(
+                condition,
+                vec![Statement::Goto(target)],
+                None,
+                pcs,
+            ));
+            return next;
+        }
+
+        // an else branch is jumped over at the end of the then branch
+        if target_index > next {
+            if let Terminator::Goto(after) = self.outputs[target_index - 1].terminator {
+                let after_index = self.index(after);
+                if after > target
+                    && (after_index < end || Some(after) == follow)
+                    && self.enclosed(target_index, after_index.min(end), Some(index))
+                {
+                    let else_end = after_index.min(end);
+                    let then = self.range(next, target_index, Some(after));
+                    let otherwise = self.range(target_index, else_end, Some(after));
+                    statements.push(if_statement(negate(condition), then, Some(otherwise), pcs));
+                    return else_end;
+                }
+            }
+        }
+
+        let then = self.range(next, target_index, Some(target));
+        statements.push(if_statement(negate(condition), then, None, pcs));
+        target_index
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn switch(
+        &mut self,
+        index: usize,
+        value: Expression,
+        cases: &[(Literal, usize)],
+        default: usize,
+        end: usize,
+        follow: Option<usize>,
+        statements: &mut Vec<Statement>,
+    ) -> usize {
+        let next = index + 1;
+        let default_index = self.index(default);
+        let targets: Vec<usize> = cases
+            .iter()
+            .map(|(_, target)| self.index(*target))
+            .chain(std::iter::once(default_index))
+            .collect();
+        let last_target = *targets.iter().max().unwrap();
+        let in_range = targets.iter().all(|target| *target > index)
+            && (last_target < end || (last_target == end && self.pc(end) == follow));
+
+        // the end of the switch is where the breaks go
+        let mut switch_end = None;
+        if in_range {
+            for block in next..end {
+                // code after the switch may jump further
+                if switch_end.is_some_and(|switch_end| block >= switch_end) {
+                    break;
+                }
+                let jump = match &self.outputs[block].terminator {
+                    Terminator::Goto(target) | Terminator::If { target, .. } => *target,
+                    _ => continue,
+                };
+                let jump_index = self.index(jump);
+                if jump_index >= last_target && (jump_index < end || Some(jump) == follow) {
+                    switch_end = Some(switch_end.map_or(jump_index.min(end), |other: usize| {
+                        other.max(jump_index.min(end))
+                    }));
+                }
+            }
+        }
+        let switch_end = match switch_end {
+            Some(switch_end) => switch_end,
+            // a default that is fallen into from the case before it is the end
+            None if default_index == last_target
+                && default_index > next
+                && self.blocks[default_index]
+                    .predecessors
+                    .contains(&(default_index - 1)) =>
+            {
+                default_index
+            }
+            None => end,
+        };
+
+        // every case may be jumped to by the switch
+        let enclosed = (next..switch_end).all(|block| {
+            self.blocks[block].predecessors.iter().all(|predecessor| {
+                *predecessor == index || (next..switch_end).contains(predecessor)
+            })
+        }) && self.handlers_enclosed(next, switch_end);
+
+        if !in_range || !enclosed {
+            let mut fallback = Vec::new();
+            for (key, target) in cases {
+                fallback.push(SwitchCase {
+                    labels: vec![Expression::Literal(key.clone())],
+                    default: false,
+                    pattern: None,
+                    body: vec![Statement::Goto(*target)],
+                });
+            }
+            fallback.push(SwitchCase {
+                labels: Vec::new(),
+                default: true,
+                pattern: None,
+                body: vec![Statement::Goto(default)],
+            });
+            statements.push(Statement::Switch {
+                value,
+                cases: fallback,
+                pcs: self.outputs[index].pcs,
+            });
+            return next;
+        }
+
+        let switch_follow = self.follow_of(switch_end, end, follow);
+        self.frames.push(Frame {
+            header: None,
+            is_loop: false,
+            breaks: switch_follow.into_iter().collect(),
+            continues: Vec::new(),
+            label: format!("label{}", self.blocks[index].pc),
+            label_used: false,
+        });
+
+        let mut starts: Vec<usize> = targets
+            .iter()
+            .copied()
+            .filter(|target| *target < switch_end)
+            .collect();
+        starts.sort_unstable();
+        starts.dedup();
+
+        let mut switch_cases = Vec::new();
+        for (i, start) in starts.iter().enumerate() {
+            let case_end = starts.get(i + 1).copied().unwrap_or(switch_end);
+            let case_follow = self.follow_of(case_end, end, follow);
+            let body = self.range(*start, case_end, case_follow);
+            switch_cases.push(SwitchCase {
+                labels: self.case_labels(cases, *start),
+                default: default_index == *start,
+                pattern: None,
+                body,
+            });
+        }
+
+        // cases that only break are the same as a missing default
+        let empty = self.case_labels(cases, switch_end);
+        if !empty.is_empty() && default_index < switch_end {
+            switch_cases.push(SwitchCase {
+                labels: empty,
+                default: false,
+                pattern: None,
+                body: vec![Statement::Break(None, Pcs::default())],
+            });
+        }
+
+        let frame = self.frames.pop().unwrap();
+        statements.push(labeled(
+            frame,
+            Statement::Switch {
+                value,
+                cases: switch_cases,
+                pcs: self.outputs[index].pcs,
+            },
+        ));
+
+        // blocks between the switch and its first case can't be reached
+        let first_case = starts.first().copied().unwrap_or(switch_end);
+        let unreachable = self.range(next, first_case, self.pc(first_case));
+        statements.extend(unreachable);
+        switch_end
+    }
+
+    fn case_labels(&self, cases: &[(Literal, usize)], block: usize) -> Vec<Expression> {
+        cases
+            .iter()
+            .filter(|(_, target)| self.index(*target) == block)
+            .map(|(key, _)| Expression::Literal(key.clone()))
+            .collect()
+    }
+
+    // A switch over `SwitchBootstraps.typeSwitch(value, restart)`, which jumps back to it
+    // with the case to continue matching at
+    fn restarted_switch(&self, index: usize) -> bool {
+        match &self.outputs[index].terminator {
+            Terminator::Switch {
+                value:
+                    Expression::Invoke {
+                        class, arguments, ..
+                    },
+                ..
+            } => {
+                class == "java.lang.runtime.SwitchBootstraps"
+                    && matches!(arguments.get(1), Some(Expression::Local(_)))
+                    && self.blocks[index]
+                        .predecessors
+                        .iter()
+                        .any(|predecessor| *predecessor > index)
+            }
+            _ => false,
+        }
+    }
+
+    // The last block in the range that jumps back to `index`
+    fn latch(&self, index: usize, end: usize) -> Option<usize> {
+        if self.frames.iter().any(|frame| frame.header == Some(index)) {
+            return None;
+        }
+        self.blocks[index]
+            .predecessors
+            .iter()
+            .copied()
+            .filter(|predecessor| *predecessor >= index && *predecessor < end)
+            .max()
+    }
+
+    fn structure_loop(
+        &mut self,
+        index: usize,
+        latch: usize,
+        end: usize,
+        follow: Option<usize>,
+        statements: &mut Vec<Statement>,
+    ) -> Option<usize> {
+        if !self.enclosed(index, latch + 1, None) {
+            return None;
+        }
+
+        let header = self.blocks[index].pc;
+        let latch_pc = self.blocks[latch].pc;
+        let loop_follow = self.follow_of(latch + 1, end, follow);
+        let mut frame = Frame {
+            header: Some(index),
+            is_loop: true,
+            breaks: loop_follow.into_iter().collect(),
+            continues: vec![header],
+            label: format!("label{}", header),
+            label_used: false,
+        };
+
+        let outputs = self.outputs;
+        let statement = match &outputs[latch].terminator {
+            Terminator::If { condition, target } if *target == header => {
+                frame.continues = vec![latch_pc];
+                self.frames.push(frame);
+                let mut body = self.range(index, latch, Some(latch_pc));
+                self.block_statements(latch, &mut body);
+                let frame = self.frames.pop().unwrap();
+                labeled(
+                    frame,
+                    Statement::DoWhile {
+                        body,
+                        condition: condition.clone(),
+                        pcs: outputs[latch].pcs,
+                    },
+                )
+            }
+            _ => {
+                let snapshot = self.state.clone();
+                self.frames.push(frame);
+                let body = self.range(index, latch + 1, Some(header));
+                let frame = self.frames.pop().unwrap();
+
+                let update = self.update(index, latch);
+                let jumps_to_update = body.iter().any(|statement| {
+                    let mut found = false;
+                    statement.walk(&mut |statement| {
+                        found |= *statement == Statement::Goto(latch_pc);
+                    });
+                    found
+                });
+
+                match update {
+                    // a continue has to run the update, so it is a for loop
+                    Some(update) if jumps_to_update => {
+                        self.state = snapshot;
+                        self.frames.push(Frame {
+                            continues: vec![latch_pc],
+                            label_used: false,
+                            ..frame
+                        });
+                        let body = self.range(index, latch, Some(latch_pc));
+                        let frame = self.frames.pop().unwrap();
+                        let (condition, pcs, body) = loop_condition(body);
+                        // the update and the jump back are compiled from the latch
+                        let pcs = outputs[latch]
+                            .statements
+                            .iter()
+                            .fold(pcs, |pcs, statement| pcs.join(statement.pcs()))
+                            .join(outputs[latch].pcs);
+                        labeled(
+                            frame,
+                            Statement::For {
+                                condition,
+                                update,
+                                body,
+                                pcs,
+                            },
+                        )
+                    }
+                    _ => {
+                        let (condition, pcs, body) = loop_condition(body);
+                        let condition =
+                            condition.unwrap_or(Expression::Literal(Literal::Boolean(true)));
+                        let pcs = pcs.join(outputs[latch].pcs);
+                        labeled(
+                            frame,
+                            Statement::While {
+                                condition,
+                                body,
+                                pcs,
+                            },
+                        )
+                    }
+                }
+            }
+        };
+
+        statements.push(statement);
+        Some(latch + 1)
+    }
+
+    // The expressions of a latch block that only updates and jumps back
+    fn update(&self, index: usize, latch: usize) -> Option<Vec<Expression>> {
+        let output = &self.outputs[latch];
+        if latch == index
+            || output.statements.is_empty()
+            || !matches!(output.terminator, Terminator::Goto(_))
+        {
+            return None;
+        }
+        output
+            .statements
+            .iter()
+            .map(|statement| match statement {
+                Statement::Expression(expression, _) => Some(expression.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // A loop that jumps to its condition at the bottom before the first iteration
+    fn top_tested_loop(
+        &mut self,
+        index: usize,
+        end: usize,
+        follow: Option<usize>,
+        statements: &mut Vec<Statement>,
+    ) -> Option<usize> {
+        let target = match self.outputs[index].terminator {
+            Terminator::Goto(target) => target,
+            _ => return None,
+        };
+        let latch = self.index(target);
+        let body_start = index + 1;
+        if latch <= body_start || latch >= end || self.blocks[latch].pc != target {
+            return None;
+        }
+        let outputs = self.outputs;
+        let (condition, pcs) = match &outputs[latch].terminator {
+            Terminator::If { condition, target }
+                if Some(*target) == self.pc(body_start)
+                    && self.outputs[latch].statements.is_empty() =>
+            {
+                (condition.clone(), outputs[latch].pcs)
+            }
+            _ => return None,
+        };
+        if !self.enclosed(body_start, latch, None)
+            || !self.blocks[latch]
+                .predecessors
+                .iter()
+                .all(|predecessor| *predecessor >= index && *predecessor < latch)
+        {
+            return None;
+        }
+
+        self.block_statements(index, statements);
+        let header = self.blocks[body_start].pc;
+        self.frames.push(Frame {
+            header: Some(body_start),
+            is_loop: true,
+            breaks: self.follow_of(latch + 1, end, follow).into_iter().collect(),
+            continues: vec![target],
+            label: format!("label{}", header),
+            label_used: false,
+        });
+        let body = self.range(body_start, latch, Some(target));
+        let frame = self.frames.pop().unwrap();
+        statements.push(labeled(
+            frame,
+            Statement::While {
+                condition,
+                body,
+                pcs,
+            },
+        ));
+        Some(latch + 1)
+    }
+
+    // Whether the block ends by entering a monitor that the blocks after it release before
+    // every exit and in a handler that rethrows, which is how javac compiles `synchronized`
+    fn is_synchronized(&self, index: usize, end: usize, statements: &[Statement]) -> bool {
+        self.monitor(index, statements)
+            .is_some_and(|(_, _, handler, release_end)| {
+                handler > index + 1
+                    && release_end <= end
+                    && self.enclosed(index + 1, handler, Some(index))
+            })
+    }
+
+    // The try group, the handler block and the block after the handler of the monitor
+    // entered at the end of block `index`
+    fn monitor(
+        &self,
+        index: usize,
+        statements: &[Statement],
+    ) -> Option<(Local, usize, usize, usize)> {
+        let lock = match statements.last() {
+            Some(Statement::MonitorEnter(
+                Expression::Assign {
+                    target,
+                    operator: None,
+                    ..
+                },
+                _,
+            )) => match target.as_ref() {
+                Expression::Local(local) => local,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let start = self.pc(index + 1)?;
+        (0..self.tries.len())
+            .filter(|group| {
+                !self.state.started_tries.contains(group)
+                    && self.tries[*group].start == start
+                    && self.tries[*group].handlers.len() == 1
+            })
+            .find_map(|group| {
+                let handler = self.tries[group].handlers[0].0;
+                let release_end = self.release_end(handler, lock)?;
+                Some((lock.clone(), group, handler, release_end))
+            })
+    }
+
+    // The block after a handler that releases the monitor of `lock` and rethrows
+    fn release_end(&self, handler: usize, lock: &Local) -> Option<usize> {
+        let output = &self.outputs[handler];
+        let exception = output.statements.first().and_then(super::catch_parameter)?;
+        let thrown = Expression::Local(exception.clone());
+        if output.statements[1..]
+            != [Statement::MonitorExit(
+                Expression::Local(lock.clone()),
+                Pcs::default(),
+            )]
+        {
+            return None;
+        }
+        match &output.terminator {
+            Terminator::Throw(value) if *value == thrown => Some(handler + 1),
+            Terminator::Fallthrough => match self.outputs.get(handler + 1) {
+                Some(BlockOutput {
+                    statements,
+                    terminator: Terminator::Throw(value),
+                    ..
+                }) if statements.is_empty() && *value == thrown => Some(handler + 2),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn synchronized(
+        &mut self,
+        index: usize,
+        end: usize,
+        follow: Option<usize>,
+        statements: &mut Vec<Statement>,
+    ) -> usize {
+        let (lock, group, handler, release_end) = self.monitor(index, statements).unwrap();
+        let (value, pcs) = match statements.pop() {
+            Some(Statement::MonitorEnter(Expression::Assign { value, .. }, pcs)) => (*value, pcs),
+            _ => unreachable!(),
+        };
+        self.state.started_tries.insert(group);
+        self.state.structured_handlers.insert(handler);
+
+        let body_follow = self.follow_of(release_end, end, follow);
+        let mut body = self.range(index + 1, handler, body_follow);
+        // the releases in front of the exits and the handler that releases on exceptions
+        let mut pcs = pcs;
+        for statement in &body {
+            statement.walk(&mut |statement| pcs = pcs.join(statement.pcs()));
+        }
+        for output in &self.outputs[handler..release_end] {
+            pcs = output
+                .statements
+                .iter()
+                .fold(pcs.join(output.pcs), |pcs, statement| {
+                    pcs.join(statement.pcs())
+                });
+        }
+        release_monitor(&mut body, &lock);
+        statements.push(Statement::Synchronized {
+            lock: value,
+            body,
+            pcs,
+        });
+        release_end
+    }
+
+    // The group of the outermost try statement starting at block `index`
+    fn try_at(&self, index: usize, end: usize) -> Option<usize> {
+        (0..self.tries.len())
+            .filter(|group| !self.state.started_tries.contains(group))
+            .filter(|group| {
+                let group_info = &self.tries[*group];
+                let protected_end = self.index(group_info.end);
+                self.index(group_info.start) == index
+                    && group_info
+                        .handlers
+                        .iter()
+                        .all(|(handler, _)| *handler >= protected_end && *handler < end)
+            })
+            .max_by_key(|group| self.tries[*group].end)
+    }
+
+    fn first_handler(&self, group: usize) -> usize {
+        self.tries[group]
+            .handlers
+            .iter()
+            .map(|(handler, _)| *handler)
+            .min()
+            .unwrap()
+    }
+
+    fn structure_try(
+        &mut self,
+        group: usize,
+        index: usize,
+        end: usize,
+        follow: Option<usize>,
+        statements: &mut Vec<Statement>,
+    ) -> Option<usize> {
+        self.state.started_tries.insert(group);
+
+        let mut handlers = self.tries[group].handlers.clone();
+        handlers.sort_by_key(|(handler, _)| *handler);
+        let first_handler = handlers[0].0;
+        let last_handler = handlers[handlers.len() - 1].0;
+
+        // the try body jumps over the handlers to the end of the statement
+        let qualifies = |this: &Self, block: usize| match this.outputs[block].terminator {
+            Terminator::Goto(target) => {
+                let target_index = this.index(target);
+                if target_index > last_handler && (target_index < end || Some(target) == follow) {
+                    Some(target_index.min(end))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        let try_end = qualifies(self, first_handler - 1)
+            .or_else(|| {
+                // or a catch does, the last catch is left open
+                handlers[1..]
+                    .iter()
+                    .filter_map(|(handler, _)| qualifies(self, handler - 1))
+                    .min()
+            })
+            .unwrap_or(end);
+
+        let mut regions = Vec::new();
+        for (i, (handler, types)) in handlers.iter().enumerate() {
+            let region_end = handlers.get(i + 1).map_or(try_end, |(next, _)| *next);
+            let local = match self.outputs[*handler]
+                .statements
+                .first()
+                .and_then(super::catch_parameter)
+            {
+                Some(local) => local.clone(),
+                None => return None,
+            };
+            if !self.enclosed(*handler, region_end, Some(*handler)) {
+                return None;
+            }
+            regions.push((*handler, region_end, types.clone(), local));
+        }
+        if !self.enclosed(index, first_handler, None) {
+            return None;
+        }
+
+        let try_follow = self.follow_of(try_end, end, follow);
+        for (handler, ..) in &regions {
+            self.state.structured_handlers.insert(*handler);
+        }
+        let body = self.range(index, first_handler, try_follow);
+        let catches = regions
+            .into_iter()
+            .map(|(handler, region_end, types, local)| Catch {
+                types,
+                local,
+                body: self.range(handler, region_end, try_follow),
+            })
+            .collect();
+
+        let statement = tries::try_statement(statements, body, catches);
+        statements.push(statement);
+        Some(try_end)
+    }
+}
+
+fn if_statement(
+    condition: Expression,
+    then: Vec<Statement>,
+    otherwise: Option<Vec<Statement>>,
+    pcs: Pcs,
+) -> Statement {
+    match otherwise.filter(|otherwise| !otherwise.is_empty()) {
+        Some(otherwise) if then.is_empty() => Statement::If {
+            condition: negate(condition),
+            then: otherwise,
+            otherwise: None,
+            pcs,
+        },
+        otherwise => Statement::If {
+            condition,
+            then,
+            otherwise,
+            pcs,
+        },
+    }
+}
+
+// Removes the releases of the monitor in front of every exit of a synchronized body
+fn release_monitor(statements: &mut Vec<Statement>, lock: &Local) {
+    let release = Statement::MonitorExit(Expression::Local(lock.clone()), Pcs::default());
+    while let Some(position) = statements
+        .iter()
+        .position(|statement| *statement == release)
+    {
+        statements.remove(position);
+        // the returned value is kept in a variable while the monitor is released
+        if let (
+            Some(Statement::Expression(
+                Expression::Assign {
+                    target,
+                    operator: None,
+                    value,
+                },
+                _,
+            )),
+            Some(Statement::Return(Some(returned), _)),
+        ) = (
+            position.checked_sub(1).and_then(|i| statements.get(i)),
+            statements.get(position),
+        ) {
+            if matches!(target.as_ref(), Expression::Local(_)) && **target == *returned {
+                let value = value.as_ref().clone();
+                let pcs = statements[position - 1]
+                    .pcs()
+                    .join(statements[position].pcs());
+                statements.splice(
+                    position - 1..=position,
+                    [Statement::Return(Some(value), pcs)],
+                );
+            }
+        }
+    }
+    for statement in statements.iter_mut() {
+        for child in statement.children_mut() {
+            release_monitor(child, lock);
+        }
+    }
+}
+
+fn labeled(frame: Frame, statement: Statement) -> Statement {
+    if frame.label_used {
+        Statement::Labeled {
+            label: frame.label,
+            body: Box::new(statement),
+        }
+    } else {
+        statement
+    }
+}
+
+// A loop body starting with `if (c) break;` loops while `!c`
+fn loop_condition(mut body: Vec<Statement>) -> (Option<Expression>, Pcs, Vec<Statement>) {
+    match body.first() {
+        Some(Statement::If {
+            then,
+            otherwise: None,
+            ..
+        }) if then.as_slice() == [Statement::Break(None, Pcs::default())] => match body.remove(0) {
+            Statement::If { condition, pcs, .. } => (Some(negate(condition)), pcs, body),
+            _ => unreachable!(),
+        },
+        _ => (None, Pcs::default(), body),
+    }
+}
@@ -0,0 +1,419 @@
+use super::super::ast::*;
+use super::{MethodInformation, MethodSignature};
+use crate::disassembler::descriptor;
+
+// The wrapper classes with the descriptor of their primitive type and its unboxing method
+const BOXES: [(&str, &str, &str); 8] = [
+    ("java.lang.Boolean", "Z", "booleanValue"),
+    ("java.lang.Byte", "B", "byteValue"),
+    ("java.lang.Character", "C", "charValue"),
+    ("java.lang.Short", "S", "shortValue"),
+    ("java.lang.Integer", "I", "intValue"),
+    ("java.lang.Long", "J", "longValue"),
+    ("java.lang.Float", "F", "floatValue"),
+    ("java.lang.Double", "D", "doubleValue"),
+];
+
+// Methods of other classes can't be checked for overloads, these are the ones of the JDK that
+// also take primitive values where they take objects
+const OVERLOADED: [&str; 6] = ["remove", "append", "insert", "print", "println", "valueOf"];
+
+// The classes these methods take besides Object, and char arrays. References of other types
+// call the same method whether they are cast to Object or not.
+pub const OVERLOADED_TYPES: [&str; 4] = [
+    "java.lang.String",
+    "java.lang.CharSequence",
+    "java.lang.StringBuffer",
+    "java.lang.StringBuilder",
+];
+
+// javac boxes and unboxes values with calls of the wrapper classes and passes variable
+// arguments in an array. The calls are removed where the type they convert to is implied
+// and the arrays are spread out where the called method is known to take variable arguments.
+pub fn resugar(
+    statements: &mut [Statement],
+    types: &[Type],
+    method_information: &MethodInformation,
+) {
+    let class_name = method_information.class_name.replace('/', ".");
+    for statement in statements.iter_mut() {
+        statement.walk_mut(&mut |statement| {
+            match statement {
+                Statement::Return(Some(value), _) => implicit(value, types),
+                Statement::If { condition, .. }
+                | Statement::While { condition, .. }
+                | Statement::DoWhile { condition, .. }
+                | Statement::For {
+                    condition: Some(condition),
+                    ..
+                }
+                | Statement::Switch {
+                    value: condition, ..
+                }
+                | Statement::Assert { condition, .. } => unboxed(condition),
+                _ => {}
+            }
+            statement.expressions_mut(&mut |expression| {
+                expression.visit_mut(&mut |expression| {
+                    conversions(expression, types);
+                    boxed_arguments(expression, types, &class_name, method_information.methods);
+                    spread(expression, types, &class_name, method_information.methods);
+                })
+            });
+        });
+    }
+}
+
+// Removes the conversions of the operands of the expression where the operation implies them
+fn conversions(expression: &mut Expression, types: &[Type]) {
+    match expression {
+        Expression::Assign {
+            target,
+            operator: None,
+            value,
+        } => {
+            // temporaries only get their types once all their values are known
+            if let Expression::Local(local) = target.as_ref() {
+                if let Some(ty) = types.get(local.index as usize) {
+                    typed_literals(value, ty);
+                }
+            }
+            implicit(value, types)
+        }
+        Expression::Assign { value, .. }
+        | Expression::Unary { operand: value, .. }
+        | Expression::ArrayElement { index: value, .. }
+        | Expression::Conditional {
+            condition: value, ..
+        } => unboxed(value),
+        Expression::Binary {
+            operator: BinaryOperator::Eq | BinaryOperator::Ne,
+            left,
+            right,
+        } => {
+            // two wrapped values would be compared by reference
+            if primitive(right, types).is_some() {
+                unboxed(left);
+            } else if primitive(left, types).is_some() {
+                unboxed(right);
+            }
+        }
+        Expression::Binary { left, right, .. } => {
+            unboxed(left);
+            unboxed(right);
+        }
+        Expression::NewArray {
+            dimensions,
+            elements,
+            ..
+        } => {
+            dimensions.iter_mut().for_each(unboxed);
+            for element in elements.iter_mut().flatten() {
+                implicit(element, types);
+            }
+        }
+        _ => {}
+    }
+}
+
+// `tmp = 1` is `tmp = true` if tmp is a boolean, the arms of conditionals too
+fn typed_literals(expression: &mut Expression, ty: &Type) {
+    match expression {
+        Expression::Literal(Literal::Int(literal)) => {
+            *expression = Expression::Literal(Literal::of_type(*literal, ty))
+        }
+        Expression::Conditional {
+            then, otherwise, ..
+        } => {
+            typed_literals(then, ty);
+            typed_literals(otherwise, ty);
+        }
+        _ => {}
+    }
+}
+
+// A value assigned to a variable of the type it is boxed or unboxed to
+fn implicit(expression: &mut Expression, types: &[Type]) {
+    // `Integer.valueOf(x.intValue())` makes a new object
+    if !boxed(expression, types) {
+        unboxed(expression);
+    }
+}
+
+// `Integer.valueOf(x)` is `x` if it is an int, other types would box to other classes
+fn boxed(expression: &mut Expression, types: &[Type]) -> bool {
+    let value = match expression {
+        Expression::Invoke {
+            target: None,
+            class,
+            name,
+            descriptor,
+            arguments,
+        } if name == "valueOf" && arguments.len() == 1 => {
+            match BOXES.iter().find(|(box_class, _, _)| class == box_class) {
+                Some((_, primitive_descriptor, _))
+                    if *descriptor
+                        == format!("({})L{};", primitive_descriptor, class.replace('.', "/"))
+                        && primitive(&arguments[0], types)
+                            == Some(Type::from_descriptor(primitive_descriptor)) =>
+                {
+                    arguments.pop().unwrap()
+                }
+                _ => return false,
+            }
+        }
+        _ => return false,
+    };
+    *expression = value;
+    true
+}
+
+// `x.intValue()` is `x` if x is an Integer
+fn unboxed(expression: &mut Expression) {
+    let value = match expression {
+        Expression::Invoke {
+            target: Some(target),
+            class,
+            name,
+            descriptor,
+            arguments,
+        } if arguments.is_empty()
+            && BOXES
+                .iter()
+                .any(|(box_class, primitive_descriptor, method)| {
+                    class == box_class
+                        && name == method
+                        && *descriptor == format!("(){}", primitive_descriptor)
+                }) =>
+        {
+            target.as_ref().clone()
+        }
+        _ => return,
+    };
+    *expression = value;
+}
+
+// The primitive type of the expression, if it is known
+fn primitive(expression: &Expression, types: &[Type]) -> Option<Type> {
+    let ty = match expression {
+        Expression::Literal(literal) => match literal {
+            Literal::Boolean(_) => Type::Boolean,
+            Literal::Char(_) => Type::Char,
+            Literal::Int(_) => Type::Int,
+            Literal::Long(_) => Type::Long,
+            Literal::Float(_) => Type::Float,
+            Literal::Double(_) => Type::Double,
+            _ => return None,
+        },
+        Expression::Local(local) => types.get(local.index as usize)?.clone(),
+        Expression::ArrayElement { array, .. } => match array.as_ref() {
+            Expression::Local(local) => match types.get(local.index as usize)? {
+                Type::Array(element) => element.as_ref().clone(),
+                _ => return None,
+            },
+            _ => return None,
+        },
+        Expression::ArrayLength(_) => Type::Int,
+        Expression::Cast { ty, .. } => ty.clone(),
+        Expression::Invoke { descriptor, .. } => {
+            let (_, return_type) = descriptor::split_method_descriptor(descriptor.as_str());
+            Type::from_descriptor(return_type.as_str())
+        }
+        Expression::InstanceOf { .. }
+        | Expression::Unary {
+            operator: UnaryOperator::Not,
+            ..
+        } => Type::Boolean,
+        Expression::Unary { operand, .. } => promoted(&primitive(operand, types)?, &Type::Int),
+        Expression::Binary {
+            operator:
+                BinaryOperator::ConditionalAnd
+                | BinaryOperator::ConditionalOr
+                | BinaryOperator::Eq
+                | BinaryOperator::Ne
+                | BinaryOperator::Lt
+                | BinaryOperator::Ge
+                | BinaryOperator::Gt
+                | BinaryOperator::Le,
+            ..
+        } => Type::Boolean,
+        Expression::Binary {
+            operator: BinaryOperator::Shl | BinaryOperator::Shr | BinaryOperator::UShr,
+            left,
+            ..
+        } => promoted(&primitive(left, types)?, &Type::Int),
+        Expression::Binary { left, right, .. } => {
+            let (left, right) = (primitive(left, types)?, primitive(right, types)?);
+            if left == Type::Boolean && right == Type::Boolean {
+                Type::Boolean
+            } else {
+                promoted(&left, &right)
+            }
+        }
+        _ => return None,
+    };
+    match ty {
+        Type::Class(_) | Type::Array(_) | Type::Void | Type::Unknown => None,
+        ty => Some(ty),
+    }
+}
+
+// The type binary numeric promotion converts the operands to
+fn promoted(left: &Type, right: &Type) -> Type {
+    [Type::Double, Type::Float, Type::Long]
+        .iter()
+        .find(|ty| *ty == left || *ty == right)
+        .cloned()
+        .unwrap_or(Type::Int)
+}
+
+// `f(Integer.valueOf(x))` is `f(x)` if the parameter isn't primitive, unless another method
+// with as many parameters could be called with x instead
+fn boxed_arguments(
+    expression: &mut Expression,
+    types: &[Type],
+    class_name: &str,
+    methods: &[MethodSignature],
+) {
+    let (class, name, descriptor, arguments) = match called(expression) {
+        Some(call) => call,
+        None => return,
+    };
+    if overloaded(class, name, descriptor, class_name, methods) {
+        return;
+    }
+    let (parameters, _) = descriptor::split_method_descriptor(descriptor.as_str());
+    for (argument, parameter) in arguments.iter_mut().zip(parameters) {
+        if parameter.starts_with('L') {
+            boxed(argument, types);
+        }
+    }
+}
+
+// Whether another method of the class with as many parameters could be called with the
+// arguments of a call, the classes have to be named the same way
+pub fn overloaded(
+    class: &str,
+    name: &str,
+    descriptor: &str,
+    class_name: &str,
+    methods: &[MethodSignature],
+) -> bool {
+    if class != class_name {
+        return OVERLOADED.contains(&name);
+    }
+    let count = descriptor::split_method_descriptor(descriptor).0.len();
+    methods.iter().any(|method| {
+        method.name == name
+            && !method.synthetic
+            && method.descriptor != descriptor
+            && (method.varargs
+                || descriptor::split_method_descriptor(method.descriptor.as_str())
+                    .0
+                    .len()
+                    == count)
+    })
+}
+
+// The class, name, descriptor and arguments of a method or constructor call
+fn called(expression: &mut Expression) -> Option<(&String, &str, &String, &mut Vec<Expression>)> {
+    match expression {
+        Expression::Invoke {
+            class,
+            name,
+            descriptor,
+            arguments,
+            ..
+        } => Some((class, name.as_str(), descriptor, arguments)),
+        Expression::New {
+            class,
+            descriptor,
+            arguments,
+        } => Some((class, "<init>", descriptor, arguments)),
+        _ => None,
+    }
+}
+
+// `f(new T[]{a, b})` is `f(a, b)` if `f` takes variable arguments and no other method of the
+// class could be called with them
+fn spread(
+    expression: &mut Expression,
+    types: &[Type],
+    class_name: &str,
+    methods: &[MethodSignature],
+) {
+    let (class, name, descriptor, arguments) = match called(expression) {
+        Some(call) => call,
+        None => return,
+    };
+    if class != class_name
+        || !methods
+            .iter()
+            .any(|method| method.varargs && method.name == name && method.descriptor == *descriptor)
+    {
+        return;
+    }
+
+    let (parameters, _) = descriptor::split_method_descriptor(descriptor.as_str());
+    let parameter = match parameters.last() {
+        Some(parameter) => Type::from_descriptor(parameter.as_str()),
+        None => return,
+    };
+    let mut elements = match arguments.last() {
+        Some(Expression::NewArray {
+            ty,
+            dimensions,
+            extra,
+            elements,
+        }) if parameter == (0..=*extra).fold(ty.clone(), |ty, _| Type::Array(Box::new(ty))) => {
+            match (dimensions.as_slice(), elements) {
+                ([Expression::Literal(Literal::Int(0))], None) => Vec::new(),
+                ([Expression::Literal(Literal::Int(count))], Some(elements))
+                    if elements.len() == *count as usize =>
+                {
+                    elements.clone()
+                }
+                _ => return,
+            }
+        }
+        _ => return,
+    };
+    elements
+        .iter_mut()
+        .for_each(|element| implicit(element, types));
+
+    // a single value that could be an array of the parameter type is passed as the array
+    if let [element] = elements.as_slice() {
+        let may_be_array = match &parameter {
+            Type::Array(element) => matches!(element.as_ref(), Type::Class(name)
+                if name == "java.lang.Object"
+                    || name == "java.lang.Cloneable"
+                    || name == "java.io.Serializable"),
+            _ => false,
+        };
+        let certain = match element {
+            Expression::Literal(Literal::Null) => false,
+            Expression::Literal(_) | Expression::New { .. } => true,
+            _ => !may_be_array,
+        };
+        if !certain {
+            return;
+        }
+    }
+
+    let arity = parameters.len() - 1 + elements.len();
+    let ambiguous = methods.iter().any(|method| {
+        method.name == name
+            && method.descriptor != *descriptor
+            && (method.varargs
+                || descriptor::split_method_descriptor(method.descriptor.as_str())
+                    .0
+                    .len()
+                    == arity)
+    });
+    if ambiguous {
+        return;
+    }
+    arguments.pop();
+    arguments.extend(elements);
+}
@@ -0,0 +1,591 @@
+use super::super::ast::*;
+use super::super::switch_maps::SwitchMaps;
+use super::foreach::count;
+use super::records;
+
+use std::collections::HashMap;
+
+// Java 14 added switch expressions
+const SWITCH_EXPRESSIONS: u16 = 58;
+// Java 21 added record patterns
+const RECORD_PATTERNS: u16 = 65;
+
+// javac compiles pattern switches to a switch over `SwitchBootstraps.typeSwitch`, which is
+// restarted at the next case when a guard fails, and switch expressions to switch statements
+// that leave the value in a temporary. Both run after the declarations, the binding of a
+// pattern is the variable its case starts with.
+pub fn modern_switches(statements: &mut Vec<Statement>, temporaries: usize, major: u16) {
+    let mut uses = HashMap::new();
+    for statement in statements.iter_mut() {
+        count(statement, &mut uses);
+    }
+    rewrite(statements, &uses, temporaries, major);
+}
+
+// Before Java 21 javac switches on enums through an array in a synthetic class that maps the
+// ordinal of each constant to a case, `switch (Outer$1.$SwitchMap$Enum[e.ordinal()])`. The
+// expressions also get a default throwing `IncompatibleClassChangeError`.
+pub fn enum_switches(statements: &mut [Statement], switch_maps: &SwitchMaps) {
+    for statement in statements {
+        statement.walk_mut(&mut |statement| {
+            let switch = match &mut *statement {
+                Statement::Labeled { body, .. } => body.as_mut(),
+                statement => statement,
+            };
+            if let Statement::Switch { value, cases, .. } = switch {
+                enum_switch(value, cases, switch_maps);
+            }
+            statement.expressions_mut(&mut |expression| {
+                expression.visit_mut(&mut |expression| {
+                    if let Expression::Switch { value, cases } = expression {
+                        enum_switch(value, cases, switch_maps);
+                        for case in cases.iter_mut() {
+                            enum_switches(&mut case.body, switch_maps);
+                        }
+                    }
+                })
+            });
+        });
+    }
+}
+
+fn enum_switch(value: &mut Expression, cases: &mut Vec<SwitchCase>, switch_maps: &SwitchMaps) {
+    let (map, selector) = match value {
+        Expression::ArrayElement { array, index } => match (array.as_ref(), index.as_ref()) {
+            (
+                Expression::StaticField { class, name },
+                Expression::Invoke {
+                    target: Some(target),
+                    name: ordinal,
+                    arguments,
+                    ..
+                },
+            ) if ordinal == "ordinal" && arguments.is_empty() => {
+                match switch_maps.get(&(class.clone(), name.clone())) {
+                    Some(map) => (map, target.as_ref().clone()),
+                    None => return,
+                }
+            }
+            _ => return,
+        },
+        _ => return,
+    };
+    let labels: Option<Vec<Vec<Expression>>> = cases
+        .iter()
+        .map(|case| {
+            case.labels
+                .iter()
+                .map(|label| match label {
+                    Expression::Literal(Literal::Int(value)) => {
+                        map.get(value).map(|(class, name)| Expression::StaticField {
+                            class: class.clone(),
+                            name: name.clone(),
+                        })
+                    }
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+    let labels = match labels {
+        Some(labels) => labels,
+        None => return,
+    };
+    for (case, labels) in cases.iter_mut().zip(labels) {
+        case.labels = labels;
+    }
+    cases.retain(|case| !(case.default && case.labels.is_empty() && is_unreachable(&case.body)));
+    *value = selector;
+}
+
+// `throw new IncompatibleClassChangeError()`, for constants added after the switch was compiled
+fn is_unreachable(body: &[Statement]) -> bool {
+    matches!(
+        body,
+        [Statement::Throw(Expression::New { class, arguments, .. }, _)]
+            if class == "java.lang.IncompatibleClassChangeError" && arguments.is_empty()
+    )
+}
+
+fn rewrite(
+    statements: &mut Vec<Statement>,
+    uses: &HashMap<u16, usize>,
+    temporaries: usize,
+    major: u16,
+) {
+    for statement in statements.iter_mut() {
+        for child in statement.children_mut() {
+            rewrite(child, uses, temporaries, major);
+        }
+    }
+    let mut position = 0;
+    while position < statements.len() {
+        position = pattern_switch(statements, position, uses, major) + 1;
+    }
+    if major >= SWITCH_EXPRESSIONS {
+        for position in 0..statements.len() {
+            if position < statements.len() {
+                switch_expression(statements, position, uses, temporaries);
+            }
+        }
+    }
+}
+
+// `[Objects.requireNonNull(x);] selector = x; restart = 0; switch (typeSwitch(selector,
+// restart, labels...)) { ... }`, returns the new position of the switch
+fn pattern_switch(
+    statements: &mut Vec<Statement>,
+    position: usize,
+    uses: &HashMap<u16, usize>,
+    major: u16,
+) -> usize {
+    if position < 2 {
+        return position;
+    }
+    let (value, cases) = match &statements[position] {
+        Statement::Switch { value, cases, .. } => (value, cases),
+        _ => return position,
+    };
+    let (name, selector, restart, labels) = match value {
+        Expression::Invoke {
+            target: None,
+            class,
+            name,
+            arguments,
+            ..
+        } if class == "java.lang.runtime.SwitchBootstraps" && arguments.len() >= 2 => {
+            match (&arguments[0], &arguments[1]) {
+                (Expression::Local(selector), Expression::Local(restart)) => {
+                    (name, selector, restart, &arguments[2..])
+                }
+                _ => return position,
+            }
+        }
+        _ => return position,
+    };
+    // declarations without a value, like the one of a temporary, may come in between
+    let mut restart_position = position - 1;
+    while restart_position > 1
+        && matches!(
+            statements[restart_position],
+            Statement::Declaration { value: None, .. }
+        )
+    {
+        restart_position -= 1;
+    }
+    let (selector_type, value) = match &statements[restart_position - 1] {
+        Statement::Declaration {
+            ty,
+            local,
+            value: Some(value),
+            ..
+        } if local == selector => (ty, value),
+        _ => return position,
+    };
+    match &statements[restart_position] {
+        Statement::Declaration {
+            local,
+            value: Some(Expression::Literal(Literal::Int(0))),
+            ..
+        } if local == restart => {}
+        _ => return position,
+    }
+
+    let mut patterns = Vec::new();
+    let mut casts = 0;
+    let mut restarts = 0;
+    for original in cases {
+        let mut case = SwitchCase {
+            labels: Vec::new(),
+            default: original.default,
+            pattern: None,
+            body: original.body.clone(),
+        };
+        for label in &original.labels {
+            let index = match label {
+                Expression::Literal(Literal::Int(index)) => *index,
+                _ => return position,
+            };
+            match labels.get(index as usize) {
+                _ if index == -1 => case.labels.push(Expression::Literal(Literal::Null)),
+                // no label matched
+                _ if index as usize == labels.len() => case.default = true,
+                Some(Expression::Literal(Literal::Class(ty))) => {
+                    let binding = match case.body.first() {
+                        Some(Statement::Declaration {
+                            ty: binding_type,
+                            local,
+                            value:
+                                Some(Expression::Cast {
+                                    ty: cast,
+                                    expression,
+                                    ..
+                                }),
+                            ..
+                        }) if binding_type == ty
+                            && cast == ty
+                            && **expression == Expression::Local(selector.clone()) =>
+                        {
+                            local.clone()
+                        }
+                        _ => return position,
+                    };
+                    if case.pattern.is_some() {
+                        return position;
+                    }
+                    case.body.remove(0);
+                    casts += 1;
+                    let mut binding = Binding::Variable(binding);
+                    if let (Binding::Variable(record), true) = (&binding, major >= RECORD_PATTERNS)
+                    {
+                        // components that don't match restart the switch like failed guards
+                        let mut failed = 0;
+                        let components = records::components(
+                            record,
+                            &mut case.body,
+                            uses,
+                            false,
+                            &mut |statement| {
+                                let condition = failed_guard(statement, restart, index + 1)?;
+                                failed += 1;
+                                Some(condition.clone())
+                            },
+                        );
+                        if let Some(components) = components {
+                            binding = Binding::Record(components);
+                            restarts += failed;
+                        }
+                    }
+                    let mut guards = Vec::new();
+                    while let Some(guard) = case
+                        .body
+                        .first()
+                        .and_then(|statement| failed_guard(statement, restart, index + 1))
+                    {
+                        guards.push(negate(guard.clone()));
+                        case.body.remove(0);
+                        restarts += 1;
+                    }
+                    case.pattern = Some(Pattern {
+                        ty: ty.clone(),
+                        binding,
+                        guard: guards.into_iter().reduce(|left, right| Expression::Binary {
+                            operator: BinaryOperator::ConditionalAnd,
+                            left: Box::new(left),
+                            right: Box::new(right),
+                        }),
+                    });
+                }
+                // the names of enum constants
+                Some(Expression::Literal(Literal::String(constant))) if name == "enumSwitch" => {
+                    match selector_type {
+                        Type::Class(class) => case.labels.push(Expression::StaticField {
+                            class: class.clone(),
+                            name: constant.clone(),
+                        }),
+                        _ => return position,
+                    }
+                }
+                Some(constant @ Expression::Literal(_)) => case.labels.push(constant.clone()),
+                _ => return position,
+            }
+        }
+        // a pattern can't share its case with other labels
+        if case.pattern.is_some() && (!case.labels.is_empty() || case.default) {
+            return position;
+        }
+        patterns.push(case);
+    }
+    if uses.get(&selector.index) != Some(&(casts + 1))
+        || uses.get(&restart.index) != Some(&(restarts + 1))
+    {
+        return position;
+    }
+
+    let value = value.clone();
+    let mut start = restart_position - 1;
+    if start > 0 && is_null_check(&statements[start - 1], &value) {
+        start -= 1;
+    }
+    let pcs = statements[start..=position]
+        .iter()
+        .fold(Pcs::default(), |pcs, statement| pcs.join(statement.pcs()));
+    statements[position] = Statement::Switch {
+        value,
+        cases: patterns,
+        pcs,
+    };
+    statements.drain(start..=restart_position);
+    position - (restart_position + 1 - start)
+}
+
+// `if (!guard) { restart = next; continue; }`
+fn failed_guard<'a>(
+    statement: &'a Statement,
+    restart: &Local,
+    next: i32,
+) -> Option<&'a Expression> {
+    match statement {
+        Statement::If {
+            condition,
+            then,
+            otherwise: None,
+            ..
+        } => match then.as_slice() {
+            [Statement::Expression(
+                Expression::Assign {
+                    target,
+                    operator: None,
+                    value,
+                },
+                _,
+            ), Statement::Continue(None, _)]
+                if **target == Expression::Local(restart.clone())
+                    && **value == Expression::Literal(Literal::Int(next)) =>
+            {
+                Some(condition)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// javac checks the selector before switches without `case null`
+fn is_null_check(statement: &Statement, value: &Expression) -> bool {
+    matches!(statement, Statement::Expression(Expression::Invoke {
+        target: None,
+        class,
+        name,
+        arguments,
+        ..
+    }, _) if class == "java.util.Objects"
+        && name == "requireNonNull"
+        && arguments.as_slice() == std::slice::from_ref(value))
+}
+
+// `T tmp; switch (x) { case 1: tmp = a; break; default: tmp = b; } use(tmp);` with the
+// temporary used once, right after the switch
+fn switch_expression(
+    statements: &mut Vec<Statement>,
+    position: usize,
+    uses: &HashMap<u16, usize>,
+    temporaries: usize,
+) {
+    if position + 2 >= statements.len() {
+        return;
+    }
+    let (ty, temporary) = match &statements[position] {
+        Statement::Declaration {
+            ty,
+            local,
+            value: None,
+            ..
+        } if local.index as usize >= temporaries => (ty.clone(), local.clone()),
+        _ => return,
+    };
+    // a switch nested in a case assigns the same temporary and breaks out of both
+    let (label, value, cases) = match &statements[position + 1] {
+        Statement::Labeled { label, body } => match body.as_ref() {
+            Statement::Switch { value, cases, .. } => (Some(label.as_str()), value, cases),
+            _ => return,
+        },
+        Statement::Switch { value, cases, .. } => (None, value, cases),
+        _ => return,
+    };
+    if !cases.iter().any(|case| case.default) {
+        return;
+    }
+
+    let mut yielding = cases.clone();
+    let mut assignments = 0;
+    let last = yielding.len() - 1;
+    for case in yielding.iter_mut() {
+        assignments += yields(&mut case.body, &temporary, &ty, label, false);
+    }
+    // the last case leaves the switch at its end
+    if let Some(statement) = yielding[last].body.last_mut() {
+        if let Some(value) = assigned(statement, &temporary) {
+            *statement = Statement::Yield(yielded(value.clone(), &ty), statement.pcs());
+            assignments += 1;
+        }
+    }
+    let completes = yielding.iter_mut().all(|case| {
+        case.body
+            .iter_mut()
+            .all(|statement| stays_inside(statement, &temporary, &mut Vec::new(), 0))
+    });
+    let ends = matches!(
+        yielding[last].body.last(),
+        Some(Statement::Yield(_, _)) | Some(Statement::Throw(_, _))
+    );
+    if !completes || !ends || uses.get(&temporary.index) != Some(&(assignments + 1)) {
+        return;
+    }
+
+    let mut switch = Some(Expression::Switch {
+        value: Box::new(value.clone()),
+        cases: yielding,
+    });
+    let read = Expression::Local(temporary);
+    let use_statement = &mut statements[position + 2];
+    if !matches!(
+        use_statement,
+        Statement::Expression(_, _)
+            | Statement::Declaration { .. }
+            | Statement::Return(_, _)
+            | Statement::Yield(_, _)
+            | Statement::Throw(_, _)
+            | Statement::If { .. }
+            | Statement::Switch { .. }
+            | Statement::Synchronized { .. }
+    ) || !evaluated_once(use_statement, &read)
+    {
+        return;
+    }
+    use_statement.expressions_mut(&mut |expression| {
+        expression.visit_mut(&mut |expression| {
+            if *expression == read {
+                *expression = switch.take().unwrap();
+            }
+        })
+    });
+    statements.drain(position..position + 2);
+}
+
+// Whether the statement reads the value once, and not only under a condition
+fn evaluated_once(statement: &mut Statement, value: &Expression) -> bool {
+    let mut reads = 0;
+    let mut conditional = false;
+    statement.expressions_mut(&mut |expression| {
+        expression.visit(&mut |expression| match expression {
+            _ if expression == value => reads += 1,
+            Expression::Conditional {
+                then, otherwise, ..
+            } => conditional |= contains(then, value) || contains(otherwise, value),
+            Expression::Binary {
+                operator: BinaryOperator::ConditionalAnd | BinaryOperator::ConditionalOr,
+                right,
+                ..
+            } => conditional |= contains(right, value),
+            Expression::Lambda { body, .. } => conditional |= contains(body, value),
+            _ => {}
+        })
+    });
+    reads == 1 && !conditional
+}
+
+fn contains(expression: &Expression, value: &Expression) -> bool {
+    let mut found = false;
+    expression.visit(&mut |expression| found |= expression == value);
+    found
+}
+
+// Replaces `tmp = value; break;` with `yield value;` and returns how many it replaced, the
+// breaks of loops and switches nested in the case only leave them
+fn yields(
+    statements: &mut Vec<Statement>,
+    temporary: &Local,
+    ty: &Type,
+    label: Option<&str>,
+    nested: bool,
+) -> usize {
+    let mut replaced = 0;
+    let mut i = 0;
+    while i + 1 < statements.len() {
+        let leaves = match &statements[i + 1] {
+            Statement::Break(None, _) => !nested,
+            Statement::Break(Some(target), _) => Some(target.as_str()) == label,
+            _ => false,
+        };
+        if let Some(value) = assigned(&statements[i], temporary).filter(|_| leaves) {
+            let pcs = statements[i].pcs();
+            statements[i] = Statement::Yield(yielded(value.clone(), ty), pcs);
+            statements.remove(i + 1);
+            replaced += 1;
+        }
+        i += 1;
+    }
+    for statement in statements.iter_mut() {
+        let mut inner = &*statement;
+        while let Statement::Labeled { body, .. } = inner {
+            inner = body;
+        }
+        let nested = nested
+            || matches!(
+                inner,
+                Statement::While { .. }
+                    | Statement::DoWhile { .. }
+                    | Statement::For { .. }
+                    | Statement::ForEach { .. }
+                    | Statement::Switch { .. }
+            );
+        for child in statement.children_mut() {
+            replaced += yields(child, temporary, ty, label, nested);
+        }
+    }
+    replaced
+}
+
+fn assigned<'a>(statement: &'a Statement, temporary: &Local) -> Option<&'a Expression> {
+    match statement {
+        Statement::Expression(
+            Expression::Assign {
+                target,
+                operator: None,
+                value,
+            },
+            _,
+        ) if **target == Expression::Local(temporary.clone()) => Some(value),
+        _ => None,
+    }
+}
+
+// Booleans and chars are ints in the bytecode
+fn yielded(value: Expression, ty: &Type) -> Expression {
+    match value {
+        Expression::Literal(Literal::Int(value)) => {
+            Expression::Literal(Literal::of_type(value, ty))
+        }
+        value => value,
+    }
+}
+
+// Whether the statement neither uses the temporary nor leaves the switch other than by
+// `yield` or `throw`, `labels` are the ones of statements around it inside the switch and
+// `depth` counts the loops and switches
+fn stays_inside(
+    statement: &mut Statement,
+    temporary: &Local,
+    labels: &mut Vec<String>,
+    depth: usize,
+) -> bool {
+    let read = Expression::Local(temporary.clone());
+    let mut inside = true;
+    statement.expressions_mut(&mut |expression| inside &= !contains(expression, &read));
+    let depth = match statement {
+        Statement::While { .. }
+        | Statement::DoWhile { .. }
+        | Statement::For { .. }
+        | Statement::ForEach { .. }
+        | Statement::Switch { .. } => depth + 1,
+        Statement::Labeled { label, body } => {
+            labels.push(label.clone());
+            let inside = inside && stays_inside(body, temporary, labels, depth);
+            labels.pop();
+            return inside;
+        }
+        Statement::Break(None, _) | Statement::Continue(None, _) => return inside && depth > 0,
+        Statement::Break(Some(label), _) | Statement::Continue(Some(label), _) => {
+            return labels.contains(label)
+        }
+        Statement::Return(_, _) | Statement::Label(_) | Statement::Goto(_) => return false,
+        _ => depth,
+    };
+    inside
+        && statement
+            .children_mut()
+            .into_iter()
+            .flatten()
+            .all(|statement| stays_inside(statement, temporary, labels, depth))
+}
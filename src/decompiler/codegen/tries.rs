@@ -0,0 +1,547 @@
+use super::super::ast::*;
+
+// Builds a try statement from the body and the catches the structurer found, with the
+// finally block and the resources javac expands into plain handlers recovered
+pub fn try_statement(
+    statements: &mut Vec<Statement>,
+    body: Vec<Statement>,
+    catches: Vec<Catch>,
+) -> Statement {
+    let (mut body, catches, finally) = finally(body, catches);
+    // expansions with exits are only recognized once the finally block is taken out of them
+    nested_resources(&mut body);
+    let mut statement = Statement::Try {
+        resources: Vec::new(),
+        body,
+        catches,
+        finally,
+    };
+    if !resources(statements, &mut statement) {
+        surround_resources(&mut statement);
+    }
+    statement
+}
+
+// The catches and finally of a try-with-resources surround its expansion
+fn surround_resources(statement: &mut Statement) {
+    if let Statement::Try {
+        resources, body, ..
+    } = statement
+    {
+        let expanded = matches!(body.as_slice(), [Statement::Try {
+            resources: inner,
+            catches,
+            finally: None,
+            ..
+        }] if !inner.is_empty() && catches.is_empty());
+        if expanded && resources.is_empty() {
+            if let Some(Statement::Try {
+                resources: inner,
+                body: inner_body,
+                ..
+            }) = body.pop()
+            {
+                *resources = inner;
+                *body = inner_body;
+            }
+        }
+    }
+}
+
+// A last catch of any exception that runs some code and rethrows is a finally block, if
+// the code is also copied to the end of the body and of the other catches and in front of
+// every return in them
+fn finally(
+    body: Vec<Statement>,
+    catches: Vec<Catch>,
+) -> (Vec<Statement>, Vec<Catch>, Option<Vec<Statement>>) {
+    let code = match catches.last() {
+        Some(Catch {
+            types,
+            local,
+            body: handler,
+        }) if is_throwable(types) => match handler.split_last() {
+            Some((Statement::Throw(Expression::Local(thrown), _), code))
+                if thrown == local && !code.is_empty() && !mentions(code, local) =>
+            {
+                code.to_vec()
+            }
+            _ => return (body, catches, None),
+        },
+        _ => return (body, catches, None),
+    };
+
+    let mut stripped = body.clone();
+    let mut others: Vec<Catch> = catches[..catches.len() - 1].to_vec();
+    if remove_copies(&mut stripped, &code)
+        && others
+            .iter_mut()
+            .all(|catch| remove_copies(&mut catch.body, &code))
+    {
+        return (stripped, others, Some(code));
+    }
+    (body, catches, None)
+}
+
+// Collapses the expansion of a try-with-resources statement for the variable assigned right
+// before the try, returns whether it is one
+fn resources(statements: &mut Vec<Statement>, statement: &mut Statement) -> bool {
+    let close = match (statements.last(), &*statement) {
+        (
+            Some(Statement::Expression(
+                Expression::Assign {
+                    target,
+                    operator: None,
+                    ..
+                },
+                _,
+            )),
+            Statement::Try {
+                resources,
+                catches,
+                finally,
+                ..
+            },
+        ) if resources.is_empty() => match (target.as_ref(), catches.as_slice(), finally) {
+            // javac 11 and later close the resource in front of every exit and in a handler
+            // that adds the exception of close to the suppressed ones
+            (Expression::Local(resource), [catch], None) => closed(&catch.body, catch, resource),
+            // earlier versions keep the primary exception in a variable and close in finally
+            (Expression::Local(_), [catch], Some(finally)) => {
+                closed_finally(finally, catch, statements)
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    let copies = match close {
+        Some(copies) => copies,
+        None => return false,
+    };
+
+    let (mut body, primary) = match &*statement {
+        Statement::Try { body, finally, .. } => (body.clone(), finally.is_some()),
+        _ => unreachable!(),
+    };
+    if !copies.is_empty() && !remove_copies(&mut body, &copies) {
+        return false;
+    }
+    // the inner resources are closed in front of the exits of the outer ones too
+    nested_resources(&mut body);
+    if primary {
+        // the variable for the primary exception
+        statements.pop();
+    }
+    let resource = match statements.pop() {
+        Some(Statement::Expression(Expression::Assign { target, value, .. }, pcs)) => match *target
+        {
+            Expression::Local(local) => Statement::Declaration {
+                ty: Type::Unknown,
+                local,
+                value: Some(*value),
+                pcs,
+            },
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+
+    // resources declared together are nested, the inner ones are closed first
+    let mut resources = vec![resource];
+    if let [Statement::Try {
+        resources: inner,
+        catches,
+        finally: None,
+        ..
+    }] = body.as_slice()
+    {
+        if !inner.is_empty() && catches.is_empty() {
+            if let Some(Statement::Try {
+                resources: inner,
+                body: inner_body,
+                ..
+            }) = body.pop()
+            {
+                resources.extend(inner);
+                body = inner_body;
+            }
+        }
+    }
+    *statement = Statement::Try {
+        resources,
+        body,
+        catches: Vec::new(),
+        finally: None,
+    };
+    true
+}
+
+// Collapses the expansions of try-with-resources statements among the statements that weren't
+// recognized when they were built
+fn nested_resources(statements: &mut Vec<Statement>) {
+    let mut position = 0;
+    while position < statements.len() {
+        if matches!(&statements[position], Statement::Try { resources, .. } if resources.is_empty())
+        {
+            let mut rest = statements.split_off(position);
+            let mut statement = rest.remove(0);
+            resources(statements, &mut statement);
+            position = statements.len();
+            statements.push(statement);
+            statements.extend(rest);
+        }
+        position += 1;
+    }
+}
+
+// The close copied in front of the exits of the body, if the handler closes the resource
+// and rethrows, with the null check for resources that may be null
+fn closed(handler: &[Statement], catch: &Catch, resource: &Local) -> Option<Vec<Statement>> {
+    if !is_throwable(&catch.types) {
+        return None;
+    }
+    let (close, checked) = match handler {
+        [Statement::If {
+            condition,
+            then,
+            otherwise: None,
+            ..
+        }, Statement::Throw(Expression::Local(thrown), _)]
+            if thrown == &catch.local && is_not_null(condition, resource) =>
+        {
+            (then.as_slice(), Some(condition))
+        }
+        [close, Statement::Throw(Expression::Local(thrown), _)] if thrown == &catch.local => {
+            (std::slice::from_ref(close), None)
+        }
+        _ => return None,
+    };
+    let close = match close {
+        [Statement::Try {
+            resources,
+            body,
+            catches,
+            finally: None,
+        }] if resources.is_empty() => match (body.as_slice(), catches.as_slice()) {
+            ([close], [suppressed])
+                if is_close(close, resource) && is_suppressed(suppressed, &catch.local) =>
+            {
+                close.clone()
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(match checked {
+        Some(condition) => vec![Statement::If {
+            condition: condition.clone(),
+            then: vec![close],
+            otherwise: None,
+            pcs: Pcs::default(),
+        }],
+        None => vec![close],
+    })
+}
+
+// The finally block javac 7 to 10 close the resource in, the resource and the variable for
+// the primary exception are assigned right before the try, the primary one in the only catch
+fn closed_finally(
+    finally: &[Statement],
+    catch: &Catch,
+    statements: &[Statement],
+) -> Option<Vec<Statement>> {
+    let (resource, primary) = match statements {
+        [.., Statement::Expression(
+            Expression::Assign {
+                target: resource,
+                operator: None,
+                ..
+            },
+            _,
+        ), Statement::Expression(
+            Expression::Assign {
+                target: primary,
+                operator: None,
+                value,
+            },
+            _,
+        )] if **value == Expression::Literal(Literal::Null) => {
+            match (resource.as_ref(), primary.as_ref()) {
+                (Expression::Local(resource), Expression::Local(primary)) => (resource, primary),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    let rethrown = [
+        Statement::Expression(
+            Expression::Assign {
+                target: Box::new(Expression::Local(primary.clone())),
+                operator: None,
+                value: Box::new(Expression::Local(catch.local.clone())),
+            },
+            Pcs::default(),
+        ),
+        Statement::Throw(Expression::Local(catch.local.clone()), Pcs::default()),
+    ];
+    if !is_throwable(&catch.types) || catch.body != rethrown {
+        return None;
+    }
+
+    let close = match finally {
+        [Statement::If {
+            condition,
+            then,
+            otherwise: None,
+            ..
+        }] if is_not_null(condition, resource) => then.as_slice(),
+        close => close,
+    };
+    let closes = match close {
+        // javac 9 and 10 call a helper method
+        [Statement::Expression(
+            Expression::Invoke {
+                target: None,
+                name,
+                arguments,
+                ..
+            },
+            _,
+        )] => {
+            name == "$closeResource"
+                && *arguments
+                    == [
+                        Expression::Local(primary.clone()),
+                        Expression::Local(resource.clone()),
+                    ]
+        }
+        [Statement::If {
+            condition,
+            then,
+            otherwise: Some(otherwise),
+            ..
+        }] => {
+            let (suppressing, plain) = match null_comparison(condition, primary) {
+                Some(BinaryOperator::Ne) => (then, otherwise),
+                Some(_) => (otherwise, then),
+                None => return None,
+            };
+            let suppressing = match suppressing.as_slice() {
+                [Statement::Try {
+                    resources,
+                    body,
+                    catches,
+                    finally: None,
+                }] if resources.is_empty() => match (body.as_slice(), catches.as_slice()) {
+                    ([close], [suppressed]) => {
+                        is_close(close, resource) && is_suppressed(suppressed, primary)
+                    }
+                    _ => false,
+                },
+                _ => false,
+            };
+            suppressing && matches!(plain.as_slice(), [close] if is_close(close, resource))
+        }
+        _ => false,
+    };
+    // the finally block was already taken out of the body
+    if closes {
+        Some(Vec::new())
+    } else {
+        None
+    }
+}
+
+// Removes the copies of `code` javac puts in front of every return, break and continue and
+// at the end of the statements. Returns whether a copy was found in front of every return
+// and at the end, if the statements can complete normally.
+fn remove_copies(statements: &mut Vec<Statement>, code: &[Statement]) -> bool {
+    let mut complete = remove_before_exits(statements, code);
+    if statements.len() >= code.len() && is_copy(&statements[statements.len() - code.len()..], code)
+    {
+        statements.truncate(statements.len() - code.len());
+    } else {
+        complete &= !completes(statements);
+    }
+    complete
+}
+
+// Whether the statements may complete normally, as far as their last statement tells
+fn completes(statements: &[Statement]) -> bool {
+    match statements.last() {
+        Some(
+            Statement::Return(_, _)
+            | Statement::Throw(_, _)
+            | Statement::Break(_, _)
+            | Statement::Continue(_, _),
+        ) => false,
+        Some(Statement::If {
+            then,
+            otherwise: Some(otherwise),
+            ..
+        }) => completes(then) || completes(otherwise),
+        Some(Statement::Try {
+            body,
+            catches,
+            finally,
+            ..
+        }) => {
+            (completes(body) || catches.iter().any(|catch| completes(&catch.body)))
+                && finally.as_ref().is_none_or(|finally| completes(finally))
+        }
+        _ => true,
+    }
+}
+
+fn remove_before_exits(statements: &mut Vec<Statement>, code: &[Statement]) -> bool {
+    let mut complete = true;
+    let mut position = 0;
+    while position < statements.len() {
+        let exit = matches!(
+            statements[position],
+            Statement::Return(_, _) | Statement::Break(_, _) | Statement::Continue(_, _)
+        );
+        if exit
+            && position >= code.len()
+            && is_copy(&statements[position - code.len()..position], code)
+        {
+            statements.drain(position - code.len()..position);
+            position -= code.len();
+            position = returned_directly(statements, position);
+        } else if matches!(statements[position], Statement::Return(_, _)) {
+            complete = false;
+        }
+        for child in statements[position].children_mut() {
+            complete &= remove_before_exits(child, code);
+        }
+        position += 1;
+    }
+    complete
+}
+
+// The copies of a finally block catch exceptions in different variables
+fn is_copy(statements: &[Statement], code: &[Statement]) -> bool {
+    statements == code || normalized(statements) == normalized(code)
+}
+
+// The statements with the variables of their catches numbered in order
+fn normalized(statements: &[Statement]) -> Vec<Statement> {
+    let mut statements = statements.to_vec();
+    let mut caught: Vec<u16> = Vec::new();
+    let number = |local: &mut Local, caught: &Vec<u16>| {
+        if let Some(position) = caught.iter().position(|index| *index == local.index) {
+            *local = Local {
+                index: u16::MAX - position as u16,
+                name: String::new(),
+            };
+        }
+    };
+    for statement in &mut statements {
+        statement.walk_mut(&mut |statement| {
+            if let Statement::Try { catches, .. } = statement {
+                for catch in catches.iter_mut() {
+                    caught.push(catch.local.index);
+                    number(&mut catch.local, &caught);
+                }
+            }
+            statement.expressions_mut(&mut |expression| {
+                expression.visit_mut(&mut |expression| {
+                    if let Expression::Local(local) = expression {
+                        number(local, &caught);
+                    }
+                })
+            });
+        });
+    }
+    statements
+}
+
+// A value kept in a variable while code runs before the return is returned directly,
+// returns the new position of the return
+fn returned_directly(statements: &mut Vec<Statement>, position: usize) -> usize {
+    if position == 0 {
+        return position;
+    }
+    if let [Statement::Expression(
+        Expression::Assign {
+            target,
+            operator: None,
+            value,
+        },
+        _,
+    ), Statement::Return(Some(returned), _)] = &statements[position - 1..=position]
+    {
+        if matches!(target.as_ref(), Expression::Local(_)) && **target == *returned {
+            let value = value.as_ref().clone();
+            let pcs = statements[position - 1]
+                .pcs()
+                .join(statements[position].pcs());
+            statements.splice(
+                position - 1..=position,
+                [Statement::Return(Some(value), pcs)],
+            );
+            return position - 1;
+        }
+    }
+    position
+}
+
+fn is_throwable(types: &[Type]) -> bool {
+    types == [Type::Class("java.lang.Throwable".to_string())]
+}
+
+fn is_close(statement: &Statement, resource: &Local) -> bool {
+    matches!(statement, Statement::Expression(Expression::Invoke {
+        target: Some(target),
+        name,
+        arguments,
+        ..
+    }, _) if name == "close" && arguments.is_empty() && **target == Expression::Local(resource.clone()))
+}
+
+// `catch (Throwable t) { primary.addSuppressed(t); }`
+fn is_suppressed(catch: &Catch, primary: &Local) -> bool {
+    is_throwable(&catch.types)
+        && matches!(catch.body.as_slice(), [Statement::Expression(Expression::Invoke {
+            target: Some(target),
+            name,
+            arguments,
+            ..
+        }, _)] if name == "addSuppressed"
+            && **target == Expression::Local(primary.clone())
+            && *arguments == [Expression::Local(catch.local.clone())])
+}
+
+fn is_not_null(condition: &Expression, local: &Local) -> bool {
+    null_comparison(condition, local) == Some(BinaryOperator::Ne)
+}
+
+fn null_comparison(condition: &Expression, local: &Local) -> Option<BinaryOperator> {
+    match condition {
+        Expression::Binary {
+            operator: operator @ (BinaryOperator::Eq | BinaryOperator::Ne),
+            left,
+            right,
+        } if **left == Expression::Local(local.clone())
+            && **right == Expression::Literal(Literal::Null) =>
+        {
+            Some(*operator)
+        }
+        _ => None,
+    }
+}
+
+// Whether the statements use the variable
+fn mentions(statements: &[Statement], local: &Local) -> bool {
+    let mut found = false;
+    for statement in statements {
+        statement.clone().walk_mut(&mut |statement| {
+            statement.expressions_mut(&mut |expression| {
+                expression.visit(&mut |expression| {
+                    found |= *expression == Expression::Local(local.clone());
+                })
+            })
+        });
+    }
+    found
+}
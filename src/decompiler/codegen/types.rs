@@ -0,0 +1,158 @@
+use super::super::ast::{Expression, Literal, Type};
+use super::stack::StackValue;
+
+use std::collections::HashMap;
+
+const OBJECT: &str = "java.lang.Object";
+
+enum Value {
+    Typed(Type),
+    // int constants fit into any of the int types, they don't decide the type alone
+    Int(i32),
+    Null,
+}
+
+// What is known about the values of every variable, collected while simulating the method
+#[derive(Default)]
+pub struct Constraints {
+    values: HashMap<u16, Vec<Value>>,
+    // the types variables are used as, for variables whose values don't tell
+    hints: HashMap<u16, Vec<Type>>,
+    // the type of the load instructions, for variables that are never stored
+    loads: HashMap<u16, Type>,
+}
+
+impl Constraints {
+    // Records a value stored into the variable by an instruction of the given type
+    pub fn assign(&mut self, local: u16, value: &StackValue, store_type: &Type) {
+        // `c ? 'x' : 'y'` is as much a char as its constants
+        let mut literals = Vec::new();
+        if int_literals(&value.expression, &mut literals) {
+            let values = self.values.entry(local).or_default();
+            values.extend(literals.into_iter().map(Value::Int));
+            return;
+        }
+        let value = match (&value.expression, &value.ty) {
+            (Expression::Literal(Literal::Int(literal)), _) => Value::Int(*literal),
+            (Expression::Literal(Literal::Null), _) => Value::Null,
+            (_, Type::Unknown) => Value::Typed(store_type.clone()),
+            (_, ty) => Value::Typed(ty.clone()),
+        };
+        self.values.entry(local).or_default().push(value);
+    }
+
+    // Records that the expression is used where a value of the type is expected
+    pub fn hint(&mut self, expression: &Expression, ty: Type) {
+        if let (Expression::Local(local), false) = (expression, ty == Type::Unknown) {
+            self.hints.entry(local.index).or_default().push(ty);
+        }
+    }
+
+    pub fn load(&mut self, local: u16, ty: &Type) {
+        if *ty != Type::Unknown {
+            self.loads.insert(local, ty.clone());
+        }
+    }
+
+    pub fn resolve(&self, local: u16) -> Type {
+        let values: &[Value] = self.values.get(&local).map_or(&[], |values| values);
+        let hints: &[Type] = self.hints.get(&local).map_or(&[], |hints| hints);
+
+        let mut joined: Option<Type> = None;
+        let mut literals = Vec::new();
+        for value in values {
+            match value {
+                Value::Typed(Type::Unknown) | Value::Null => {}
+                Value::Typed(ty) => {
+                    joined = Some(match joined {
+                        Some(joined) => common_supertype(&joined, ty),
+                        None => ty.clone(),
+                    })
+                }
+                Value::Int(literal) => literals.push(*literal),
+            }
+        }
+
+        if !literals.is_empty() {
+            let ty = joined.unwrap_or_else(|| {
+                hints
+                    .iter()
+                    .find(|hint| is_int(hint))
+                    .cloned()
+                    .unwrap_or(Type::Int)
+            });
+            return if literals.iter().all(|literal| fits(*literal, &ty)) {
+                ty
+            } else {
+                Type::Int
+            };
+        }
+
+        match joined {
+            Some(ty) => ty,
+            None => hints
+                .iter()
+                .find(|hint| !is_object(hint))
+                .or_else(|| hints.first())
+                .cloned()
+                .or_else(|| self.loads.get(&local).cloned())
+                .unwrap_or_else(|| Type::Class(OBJECT.to_string())),
+        }
+    }
+}
+
+// The constants a conditional of int constants evaluates to
+fn int_literals(expression: &Expression, literals: &mut Vec<i32>) -> bool {
+    match expression {
+        Expression::Conditional {
+            then, otherwise, ..
+        } => int_literals(then, literals) && int_literals(otherwise, literals),
+        Expression::Literal(Literal::Int(literal)) => {
+            literals.push(*literal);
+            true
+        }
+        _ => false,
+    }
+}
+
+// boolean, byte, char and short are ints on the operand stack
+pub fn is_int(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Boolean | Type::Byte | Type::Char | Type::Short | Type::Int
+    )
+}
+
+fn is_reference(ty: &Type) -> bool {
+    matches!(ty, Type::Class(_) | Type::Array(_))
+}
+
+fn is_object(ty: &Type) -> bool {
+    matches!(ty, Type::Class(name) if name == OBJECT)
+}
+
+pub fn fits(literal: i32, ty: &Type) -> bool {
+    match ty {
+        Type::Boolean => literal == 0 || literal == 1,
+        Type::Byte => literal as i8 as i32 == literal,
+        Type::Short => literal as i16 as i32 == literal,
+        Type::Char => literal as u16 as i32 == literal,
+        _ => true,
+    }
+}
+
+// The most specific type both types can be assigned to. The class hierarchy isn't known,
+// so different classes only have Object in common.
+pub fn common_supertype(a: &Type, b: &Type) -> Type {
+    match (a, b) {
+        _ if a == b => a.clone(),
+        (Type::Unknown, ty) | (ty, Type::Unknown) => ty.clone(),
+        (Type::Byte, Type::Short) | (Type::Short, Type::Byte) => Type::Short,
+        (a, b) if is_int(a) && is_int(b) => Type::Int,
+        (Type::Array(a), Type::Array(b)) if is_reference(a) && is_reference(b) => {
+            Type::Array(Box::new(common_supertype(a, b)))
+        }
+        (a, b) if is_reference(a) && is_reference(b) => Type::Class(OBJECT.to_string()),
+        (a, _) => a.clone(),
+    }
+}
@@ -1,12 +0,0 @@
-pub struct Variable {
-    pub ty: super::value::Type,
-    pub var_num: u16,
-}
-
-pub fn name_from_number(
-    num: u16,
-    variable_table: Vec<Variable>,
-    method_information: super::MethodInformation,
-) {
-    
-}
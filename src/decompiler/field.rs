@@ -1,3 +1,5 @@
+use super::ast::{Expression, FieldDeclaration, Literal, Member, Statement, Type};
+
 const PUBLIC: u16 = 0x0001;
 const PRIVATE: u16 = 0x0002;
 const PROTECTED: u16 = 0x0004;
@@ -9,75 +11,233 @@ const SYNTHETIC: u16 = 0x1000;
 // idk how to use it but it doesn't look really important
 // const ENUM: u16 = 0x4000;
 
-pub fn decompile_fields(class_file: &mut super::ClassFile) -> String {
+pub fn decompile_fields(
+    class_file: &super::ClassFile,
+    options: &super::DecompilerOptions,
+) -> Vec<Member> {
     let field_count = class_file.fields.field_count;
+    let interface = super::class::is_interface(class_file);
     let fields = &class_file.fields.fields;
 
-    let mut fields_string = String::new();
+    let mut members = Vec::new();
 
     for i in 0..field_count {
         let field = &fields[i as usize];
-        fields_string.push('\t');
-        fields_string
-            .push_str(generate_field_signature(field, &mut class_file.constant_pool).as_str());
-        fields_string.push_str(";\n");
+        // javac adds it for assert statements
+        if field.access_flags & SYNTHETIC != 0
+            && (options.hide_synthetic
+                || get_constant_value(field.name_index, &class_file.constant_pool)
+                    == super::codegen::ASSERTIONS_DISABLED)
+        {
+            continue;
+        }
+        members.push(Member::Field(generate_field_declaration(
+            field,
+            interface,
+            &class_file.constant_pool,
+        )));
     }
 
-    fields_string
+    members
 }
 
-fn generate_field_signature(
+fn generate_field_declaration(
     field: &super::FieldInfo,
-    constant_pool: &mut super::ConstantPool,
-) -> String {
-    let mut field_signature = String::new();
+    interface: bool,
+    constant_pool: &super::ConstantPool,
+) -> FieldDeclaration {
+    let mut modifiers = Vec::new();
 
-    let access_flags = field.access_flags;
+    // fields of interfaces are always public static final
+    let access_flags = match interface {
+        true => field.access_flags & !(PUBLIC | STATIC | FINAL),
+        false => field.access_flags,
+    };
 
     if access_flags & PUBLIC != 0 {
-        field_signature.push_str("public ");
+        modifiers.push("public");
     } else if access_flags & PRIVATE != 0 {
-        field_signature.push_str("private ");
+        modifiers.push("private");
     } else if access_flags & PROTECTED != 0 {
-        field_signature.push_str("protected ");
+        modifiers.push("protected");
     }
 
     if access_flags & SYNTHETIC != 0 {
-        field_signature.push_str("/* synthetic */ ");
+        modifiers.push("/* synthetic */");
     }
 
     if access_flags & STATIC != 0 {
-        field_signature.push_str("static ");
+        modifiers.push("static");
     }
 
     if access_flags & FINAL != 0 {
-        field_signature.push_str("final ");
+        modifiers.push("final");
     }
 
     if access_flags & VOLATILE != 0 {
-        field_signature.push_str("volatile ");
+        modifiers.push("volatile");
     }
 
     if access_flags & TRANSIENT != 0 {
-        field_signature.push_str("transient ");
+        modifiers.push("transient");
     }
 
-    field_signature.push_str(
-        format!(
-            "{} ",
-            super::value::get_type(get_constant_value(field.descriptor_index, constant_pool))
-        )
-        .as_str(),
-    );
+    let ty =
+        Type::from_descriptor(get_constant_value(field.descriptor_index, constant_pool).as_str());
+    // constants are initialized by the virtual machine
+    let value = field
+        .attributes
+        .iter()
+        .find(|attribute| attribute.get_name() == "ConstantValue")
+        .map(|attribute| {
+            let index = u16::from_be_bytes([attribute.info[0], attribute.info[1]]);
+            Expression::Literal(match constant_pool.get_index(index) {
+                super::CPIndexType::Integer(value) => Literal::of_type(value as i32, &ty),
+                super::CPIndexType::Float(value) => Literal::Float(value),
+                super::CPIndexType::Long(value) => Literal::Long(value as i64),
+                super::CPIndexType::Double(value) => Literal::Double(value),
+                super::CPIndexType::String(index) => {
+                    Literal::String(get_constant_value(index, constant_pool))
+                }
+                _ => panic!("Invalid Type in Constant Pool"),
+            })
+        });
+
+    FieldDeclaration {
+        modifiers: modifiers.into_iter().map(String::from).collect(),
+        ty,
+        name: get_constant_value(field.name_index, constant_pool),
+        value,
+    }
+}
+
+// Moves the assignments the static initializer and every constructor start with into the
+// declarations of the fields, if they assign the fields in the order they are declared in and
+// don't depend on the parameters of the constructors. The fields of interfaces are all static.
+pub fn move_initializers(members: &mut Vec<Member>, class_name: &str, interface: bool) {
+    let class_name = class_name.replace('/', ".");
+    let fields: Vec<(String, bool)> = members
+        .iter()
+        .filter_map(|member| match member {
+            Member::Field(field) => Some((
+                field.name.clone(),
+                interface || field.modifiers.iter().any(|modifier| modifier == "static"),
+            )),
+            _ => None,
+        })
+        .collect();
+
+    let mut moved = Vec::new();
+    for member in members.iter_mut() {
+        if let Member::Initializer(body, _) = member {
+            let initializers = initializers(body, 0, &fields, Some(class_name.as_str()));
+            body.drain(..initializers.len());
+            moved.extend(initializers);
+        }
+    }
 
-    field_signature.push_str(get_constant_value(field.name_index, constant_pool).as_str());
+    // constructors that call another constructor of the class don't run the initializers
+    let mut constructors: Vec<(&mut Vec<Statement>, usize)> = members
+        .iter_mut()
+        .filter_map(|member| match member {
+            Member::Method(method) if method.return_type.is_none() => method.body.as_mut(),
+            _ => None,
+        })
+        .filter_map(|body| match body.first() {
+            Some(Statement::Expression(
+                Expression::Invoke {
+                    target: Some(target),
+                    name,
+                    ..
+                },
+                _,
+            )) if name == "<init>" => match target.as_ref() {
+                Expression::Super => Some((body, 1)),
+                _ => None,
+            },
+            _ => Some((body, 0)),
+        })
+        .collect();
+    let mut common: Option<Vec<(usize, Expression)>> = None;
+    for (body, start) in constructors.iter() {
+        let initializers = initializers(body, *start, &fields, None);
+        common = Some(match common {
+            None => initializers,
+            Some(common) => common
+                .into_iter()
+                .zip(initializers)
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+    let common = common.unwrap_or_default();
+    for (body, start) in constructors.iter_mut() {
+        body.drain(*start..*start + common.len());
+    }
+    moved.extend(common);
+
+    for (index, value) in moved {
+        if let Some(Member::Field(field)) = members
+            .iter_mut()
+            .filter(|member| matches!(member, Member::Field(_)))
+            .nth(index)
+        {
+            field.value = Some(value);
+        }
+    }
+    // a static initializer left empty is omitted
+    members.retain(|member| !matches!(member, Member::Initializer(body, _) if body.is_empty()));
+}
 
-    field_signature
+// The field indices and values of the leading assignments of static fields of the class, or
+// of instance fields without a class
+fn initializers(
+    body: &[Statement],
+    start: usize,
+    fields: &[(String, bool)],
+    class_name: Option<&str>,
+) -> Vec<(usize, Expression)> {
+    let mut initializers: Vec<(usize, Expression)> = Vec::new();
+    for statement in body.iter().skip(start) {
+        let (target, value) = match statement {
+            Statement::Expression(
+                Expression::Assign {
+                    target,
+                    operator: None,
+                    value,
+                },
+                _,
+            ) => (target.as_ref(), value.as_ref()),
+            _ => break,
+        };
+        let name = match (target, class_name) {
+            (Expression::StaticField { class, name }, Some(class_name)) if class == class_name => {
+                name
+            }
+            (Expression::Field { target, name }, None) if **target == Expression::This => name,
+            _ => break,
+        };
+        let index = match fields
+            .iter()
+            .position(|(field, is_static)| field == name && *is_static == class_name.is_some())
+        {
+            Some(index) => index,
+            None => break,
+        };
+        let mut local = false;
+        value.visit(&mut |expression| local |= matches!(expression, Expression::Local(_)));
+        if local || initializers.last().is_some_and(|(last, _)| *last >= index) {
+            break;
+        }
+        initializers.push((index, value.clone()));
+    }
+    initializers
 }
 
-fn get_constant_value(index: u16, constant_pool: &mut super::ConstantPool) -> String {
+fn get_constant_value(index: u16, constant_pool: &super::ConstantPool) -> String {
     match constant_pool.get_index(index) {
-        super::CPIndexType::Utf8(string) => string,
+        super::CPIndexType::Utf8(utf8) => utf8.string,
         _ => panic!("Invalid Type in Constant Pool"),
     }
 }
@@ -0,0 +1,263 @@
+// Shortens the class names of a compilation unit to their simple names where that is unambiguous
+use super::ast::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+// `nested` has the source names of member classes by their binary names
+pub fn import_types(unit: &mut CompilationUnit, nested: &HashMap<String, String>) {
+    let package = unit.package.clone().unwrap_or_default();
+    // the top level class of a nested class file is named in front of the `$`
+    let own = unit.types[0].name.split('$').next().unwrap().to_string();
+
+    let mut names = BTreeSet::new();
+    for declaration in &mut unit.types {
+        type_declaration(declaration, &mut |name| {
+            names.insert(name.clone());
+        });
+    }
+
+    // member types of the class shadow top level types of the same name
+    let mut members = BTreeSet::new();
+    // the top level classes by their simple name
+    let mut candidates: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    candidates
+        .entry(own.clone())
+        .or_default()
+        .insert(qualify(package.as_str(), own.as_str()));
+    for name in &names {
+        let (name_package, rest) = split(name);
+        let mut parts = rest.split('$');
+        let top = parts.next().unwrap();
+        if name_package == package && top == own {
+            members.extend(parts.next().map(String::from));
+        }
+        candidates
+            .entry(top.to_string())
+            .or_default()
+            .insert(qualify(name_package, top));
+    }
+
+    let mut simple = BTreeSet::new();
+    for (name, candidates) in &candidates {
+        if members.contains(name) {
+            continue;
+        }
+        // the class itself and the classes of its package come before imported ones
+        let own_class = qualify(package.as_str(), own.as_str());
+        let chosen = if candidates.contains(&own_class) {
+            own_class
+        } else if let Some(local) = candidates.iter().find(|c| split(c).0 == package) {
+            local.clone()
+        } else if candidates.len() == 1 {
+            candidates.iter().next().unwrap().clone()
+        } else {
+            continue;
+        };
+        simple.insert(chosen);
+    }
+
+    unit.imports = simple
+        .iter()
+        .filter(|name| {
+            let name_package = split(name).0;
+            !name_package.is_empty() && name_package != package && name_package != "java.lang"
+        })
+        .cloned()
+        .collect();
+
+    for declaration in &mut unit.types {
+        type_declaration(declaration, &mut |name| {
+            let (name_package, rest) = split(name);
+            let top = rest.split('$').next().unwrap();
+            let source = nested.get(name.as_str()).unwrap_or(name);
+            *name = if simple.contains(&qualify(name_package, top)) && !name_package.is_empty() {
+                source[name_package.len() + 1..].to_string()
+            } else {
+                source.clone()
+            };
+        });
+    }
+    declared_names(unit, nested);
+}
+
+// Writes the member classes the unit refers to with their source names, without imports
+pub fn nest_types(unit: &mut CompilationUnit, nested: &HashMap<String, String>) {
+    for declaration in &mut unit.types {
+        type_declaration(declaration, &mut |name| {
+            if let Some(source) = nested.get(name.as_str()) {
+                *name = source.clone();
+            }
+        });
+    }
+    declared_names(unit, nested);
+}
+
+// A member class is declared with its own name, so are its constructors
+fn declared_names(unit: &mut CompilationUnit, nested: &HashMap<String, String>) {
+    let package = unit.package.clone().unwrap_or_default();
+    for declaration in &mut unit.types {
+        let source = match nested.get(&qualify(package.as_str(), declaration.name.as_str())) {
+            Some(source) => source,
+            None => continue,
+        };
+        let name = split(source).1.to_string();
+        for member in &mut declaration.members {
+            if let Member::Method(method) = member {
+                if method.return_type.is_none() && method.name == declaration.name {
+                    method.name = name.clone();
+                }
+            }
+        }
+        declaration.name = name;
+    }
+}
+
+// The package and the name of a class in it
+fn split(name: &str) -> (&str, &str) {
+    name.rsplit_once('.').unwrap_or(("", name))
+}
+
+fn qualify(package: &str, name: &str) -> String {
+    match package.is_empty() {
+        true => name.to_string(),
+        false => format!("{}.{}", package, name),
+    }
+}
+
+// Calls `f` on every class name the printer writes out
+fn type_declaration(declaration: &mut TypeDeclaration, f: &mut dyn FnMut(&mut String)) {
+    for ty in declaration
+        .extends
+        .iter_mut()
+        .chain(declaration.implements.iter_mut())
+    {
+        class_type(ty, f);
+    }
+    for member in &mut declaration.members {
+        match member {
+            Member::Field(field) => {
+                class_type(&mut field.ty, f);
+                if let Some(value) = &mut field.value {
+                    expression(value, f);
+                }
+            }
+            Member::Method(method) => {
+                if let Some(return_type) = &mut method.return_type {
+                    class_type(return_type, f);
+                }
+                for parameter in &mut method.parameters {
+                    class_type(&mut parameter.ty, f);
+                }
+                for ty in &mut method.throws {
+                    class_type(ty, f);
+                }
+                statements(method.body.iter_mut().flatten(), f);
+            }
+            Member::Initializer(body, _) => statements(body.iter_mut(), f),
+        }
+    }
+}
+
+fn class_type(ty: &mut Type, f: &mut dyn FnMut(&mut String)) {
+    match ty {
+        Type::Class(name) => f(name),
+        Type::Array(ty) => class_type(ty, f),
+        _ => {}
+    }
+}
+
+fn statements<'a>(
+    statements: impl Iterator<Item = &'a mut Statement>,
+    f: &mut dyn FnMut(&mut String),
+) {
+    for statement in statements {
+        statement.walk_mut(&mut |statement| {
+            match statement {
+                Statement::Declaration { ty, .. } | Statement::ForEach { ty, .. } => {
+                    class_type(ty, f)
+                }
+                Statement::Try {
+                    resources, catches, ..
+                } => {
+                    for resource in resources {
+                        if let Statement::Declaration { ty, .. } = resource {
+                            class_type(ty, f);
+                        }
+                    }
+                    for catch in catches {
+                        catch.types.iter_mut().for_each(|ty| class_type(ty, f));
+                    }
+                }
+                Statement::Switch { cases, .. } => {
+                    cases.iter_mut().for_each(|case| case_labels(case, f))
+                }
+                _ => {}
+            }
+            statement.expressions_mut(&mut |value| expression(value, f));
+        });
+    }
+}
+
+fn case_labels(case: &mut SwitchCase, f: &mut dyn FnMut(&mut String)) {
+    for label in &mut case.labels {
+        expression(label, f);
+    }
+    if let Some(pattern) = &mut case.pattern {
+        class_type(&mut pattern.ty, f);
+        pattern.binding.types_mut(&mut |ty| class_type(ty, f));
+        if let Some(guard) = &mut pattern.guard {
+            expression(guard, f);
+        }
+    }
+}
+
+fn expression(value: &mut Expression, f: &mut dyn FnMut(&mut String)) {
+    value.visit_mut(&mut |value| match value {
+        // the printer spells an unfused lcmp as a call of Long.compare
+        Expression::Compare {
+            kind: CompareKind::LCmp,
+            operator: None,
+            left,
+            right,
+        } => {
+            let arguments = vec![left.as_ref().clone(), right.as_ref().clone()];
+            *value = Expression::Invoke {
+                target: None,
+                class: "java.lang.Long".to_string(),
+                name: "compare".to_string(),
+                descriptor: "(JJ)I".to_string(),
+                arguments,
+            };
+            if let Expression::Invoke { class, .. } = value {
+                f(class);
+            }
+        }
+        // the class of instance calls isn't written
+        Expression::Invoke {
+            target: None,
+            class,
+            ..
+        }
+        | Expression::StaticField { class, .. }
+        | Expression::New { class, .. } => f(class),
+        Expression::InstanceOf {
+            ty,
+            binding: Some(binding),
+            ..
+        } => {
+            class_type(ty, f);
+            binding.types_mut(&mut |ty| class_type(ty, f));
+        }
+        Expression::Cast { ty, .. }
+        | Expression::InstanceOf { ty, .. }
+        | Expression::NewArray { ty, .. }
+        | Expression::Literal(Literal::Class(ty)) => class_type(ty, f),
+        Expression::Switch { cases, .. } => {
+            for case in cases {
+                case_labels(case, f);
+                statements(case.body.iter_mut(), f);
+            }
+        }
+        Expression::LambdaBlock { body, .. } => statements(body.iter_mut(), f),
+        _ => {}
+    });
+}
@@ -1,31 +1,29 @@
-pub fn get_interfaces(class_file: &mut super::ClassFile) -> String {
-    let mut interfaces_string = String::new();
+use super::ast::Type;
+
+pub fn get_interfaces(class_file: &super::ClassFile) -> Vec<Type> {
+    let mut interfaces = Vec::new();
 
     for i in 0..class_file.interfaces.interface_count {
-        interfaces_string.push_str(
-            format!(
-                "{} ",
-                get_interface_name(
-                    class_file.interfaces.interfaces[i as usize],
-                    &mut class_file.constant_pool
-                )
-                .replace("/", ".")
+        interfaces.push(Type::from_class_name(
+            get_interface_name(
+                class_file.interfaces.interfaces[i as usize],
+                &class_file.constant_pool,
             )
             .as_str(),
-        );
+        ));
     }
 
-    interfaces_string
+    interfaces
 }
 
-fn get_interface_name(index: u16, constant_pool: &mut super::ConstantPool) -> String {
+fn get_interface_name(index: u16, constant_pool: &super::ConstantPool) -> String {
     let class_name_index = match constant_pool.get_index(index) {
         super::CPIndexType::Class(a) => a,
         _ => panic!("Invalid Type in Constant Pool"),
     };
 
     match constant_pool.get_index(class_name_index) {
-        super::CPIndexType::Utf8(a) => a,
+        super::CPIndexType::Utf8(utf8) => utf8.string,
         _ => panic!("Invalid Type in Constant Pool"),
     }
 }
@@ -0,0 +1,160 @@
+// Finds the source lines of the instructions the statements were compiled from
+use super::ast::{Expression, LineSite, Literal, Pcs, Statement};
+use super::instruction::DecodedInstruction;
+
+pub fn line_sites(
+    instructions: &[DecodedInstruction],
+    code_attribute: &super::CodeAttribute,
+) -> Vec<LineSite> {
+    let line_numbers = code_attribute.line_numbers();
+    let mut sites = Vec::new();
+    for decoded in instructions {
+        // the entries start lines, the last one before the instruction covers it
+        let line = line_numbers
+            .iter()
+            .filter(|line_number| line_number.start_pc as usize <= decoded.pc)
+            .max_by_key(|line_number| line_number.start_pc)
+            .map(|line_number| line_number.line_number);
+        sites.push(LineSite {
+            pc: decoded.pc,
+            line,
+        });
+    }
+    sites
+}
+
+// The first original line of a statement and the pcs of the instructions it was compiled from
+pub struct Origin {
+    pub line: Option<u16>,
+    pub pcs: (usize, usize),
+}
+
+// The lines of the instructions of a method
+pub struct SourceLines {
+    sites: Vec<LineSite>,
+}
+
+impl SourceLines {
+    pub fn new(sites: Vec<LineSite>) -> Self {
+        SourceLines { sites }
+    }
+
+    // Where the statement and the statements nested in it were compiled from. The line is the
+    // one of the instructions in front of the nested statements, a `try` has none.
+    pub fn statement_origin(&self, statement: &Statement) -> Option<Origin> {
+        // the labeled statement is asked for itself
+        if let Statement::Labeled { .. } = statement {
+            return None;
+        }
+        let mut pcs = Pcs::default();
+        statement.walk(&mut |statement| pcs = pcs.join(statement.pcs()));
+        let line = match statement {
+            // the condition comes after the body, `while (true)` only has the jump back
+            Statement::DoWhile { .. }
+            | Statement::While {
+                condition: Expression::Literal(Literal::Boolean(true)),
+                ..
+            } => None,
+            statement => statement.pcs().0.and_then(|(first, _)| self.line(first)),
+        };
+        Some(Origin { line, pcs: pcs.0? })
+    }
+
+    // The line of the instruction at `pc`
+    fn line(&self, pc: usize) -> Option<u16> {
+        self.sites
+            .iter()
+            .rev()
+            .find(|site| site.pc <= pc)
+            .and_then(|site| site.line)
+    }
+}
+
+// Which output lines belong to which instructions, lines are counted from 1
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SourceMap {
+    pub methods: Vec<MethodMap>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodMap {
+    pub name: String,
+    pub descriptor: String,
+    pub lines: (usize, usize),
+    pub pcs: Option<(usize, usize)>,
+    pub statements: Vec<StatementMap>,
+}
+
+// A statement with the statements nested in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementMap {
+    pub lines: (usize, usize),
+    pub pcs: (usize, usize),
+    // the line in the LineNumberTable
+    pub line: Option<u16>,
+}
+
+impl SourceMap {
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"methods\": [");
+        for (i, method) in self.methods.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            json.push_str(
+                format!(
+                    "\n  {{\"name\": {}, \"descriptor\": {}, \"lines\": {}, \"pcs\": {}, \
+                     \"statements\": [",
+                    json_string(method.name.as_str()),
+                    json_string(method.descriptor.as_str()),
+                    json_range(method.lines),
+                    method.pcs.map_or("null".to_string(), json_range),
+                )
+                .as_str(),
+            );
+            for (j, statement) in method.statements.iter().enumerate() {
+                if j > 0 {
+                    json.push(',');
+                }
+                json.push_str(
+                    format!(
+                        "\n    {{\"lines\": {}, \"pcs\": {}, \"line\": {}}}",
+                        json_range(statement.lines),
+                        json_range(statement.pcs),
+                        statement
+                            .line
+                            .map_or("null".to_string(), |line| line.to_string()),
+                    )
+                    .as_str(),
+                );
+            }
+            if !method.statements.is_empty() {
+                json.push_str("\n  ");
+            }
+            json.push_str("]}");
+        }
+        if !self.methods.is_empty() {
+            json.push('\n');
+        }
+        json.push_str("]}\n");
+        json
+    }
+}
+
+fn json_range((start, end): (usize, usize)) -> String {
+    format!("[{}, {}]", start, end)
+}
+
+fn json_string(string: &str) -> String {
+    let mut json = String::from("\"");
+    for c in string.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => json.push_str(format!("\\u{:04x}", c as u32).as_str()),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
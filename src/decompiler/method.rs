@@ -1,5 +1,10 @@
-use super::attribute;
-use std::io::{BufReader, Read};
+use super::ast::{Expression, LineSite, Member, MethodDeclaration, Parameter, Statement, Type};
+use super::codegen::{MethodBody, MethodSignature};
+use super::instruction::DecodedInstruction;
+use crate::disassembler::attribute::{read_bootstrap_methods, BootstrapMethod};
+use crate::disassembler::descriptor;
+use std::io;
+use std::io::BufReader;
 
 const PUBLIC: u16 = 0x0001;
 const PRIVATE: u16 = 0x0002;
@@ -14,517 +19,514 @@ const ABSTRACT: u16 = 0x0400;
 const STRICT: u16 = 0x0800;
 const SYNTHETIC: u16 = 0x1000;
 
-pub fn decompile_methods(class_file: &mut super::ClassFile) -> String {
+pub fn decompile_methods(
+    class_file: &super::ClassFile,
+    options: &super::DecompilerOptions,
+    switch_maps: &super::switch_maps::SwitchMaps,
+    diagnostics: &mut Vec<super::Diagnostic>,
+) -> Vec<Member> {
     let method_count = class_file.methods.method_count;
+    let interface = super::class::is_interface(class_file);
     let methods = &class_file.methods.methods;
+    let bootstrap_methods =
+        read_bootstrap_methods(&class_file.attributes.attributes).unwrap_or_default();
+    let signatures: Vec<MethodSignature> = methods
+        .iter()
+        .map(|method_info| MethodSignature {
+            name: get_string(method_info.name_index, &class_file.constant_pool),
+            descriptor: get_string(method_info.descriptor_index, &class_file.constant_pool),
+            varargs: method_info.access_flags & VARARGS != 0,
+            synthetic: method_info.access_flags & SYNTHETIC != 0,
+        })
+        .collect();
 
-    let mut methods_string = String::new();
+    let mut members = Vec::new();
 
     for i in 0..method_count {
         let method_info = &methods[i as usize];
+        // lambda bodies are needed to inline them
+        if options.hide_synthetic
+            && method_info.access_flags & SYNTHETIC != 0
+            && !signatures[i as usize].name.starts_with("lambda$")
+        {
+            continue;
+        }
 
         let mut method = Method::new(
             class_file.this_class,
+            class_file.major,
+            interface,
             method_info,
-            &mut class_file.constant_pool,
+            &bootstrap_methods,
+            &signatures,
+            &class_file.constant_pool,
         );
 
-        methods_string.push_str(method.decompile_method().as_str());
+        match method.decompile_method(options, switch_maps, diagnostics) {
+            // a static initializer that only set up what the declarations leave out
+            Member::Initializer(body, _) if body.is_empty() => {}
+            member => members.push(member),
+        }
+    }
+
+    if options.hide_synthetic {
+        let class_name =
+            super::class::get_class_name(class_file.this_class, &class_file.constant_pool);
+        inline_lambdas(&mut members, class_name.replace('/', ".").as_str());
+    }
+    members
+}
+
+// Puts the bodies of the methods javac compiles lambdas to back into the lambdas. The methods
+// are left out unless a lambda still calls them.
+fn inline_lambdas(members: &mut Vec<Member>, class_name: &str) {
+    // lambdas in lambdas are inlined into the outer ones first
+    for _ in 0..members.len() {
+        let bodies = lambda_bodies(members);
+        let mut changed = false;
+        for member in members.iter_mut() {
+            visit_expressions(member, &mut |expression| {
+                let inlined = match expression {
+                    Expression::Lambda {
+                        parameters: lambda_parameters,
+                        body,
+                    } => match body.as_ref() {
+                        Expression::Invoke {
+                            class,
+                            name,
+                            descriptor,
+                            arguments,
+                            ..
+                        } if class == class_name => bodies
+                            .iter()
+                            .find(|body| body.0 == *name && body.1 == *descriptor)
+                            .filter(|body| {
+                                body.2.len() == arguments.len()
+                                    && lambda_parameters.len() <= arguments.len()
+                            })
+                            .map(|(_, _, parameters, body)| {
+                                // the parameters of the lambda follow the captured values
+                                let captured = arguments.len() - lambda_parameters.len();
+                                inlined(parameters, captured, &arguments[..captured], body)
+                            }),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if let Some(lambda) = inlined {
+                    *expression = lambda;
+                    changed = true;
+                }
+            });
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut called = Vec::new();
+    for member in members.iter_mut() {
+        visit_expressions(member, &mut |expression| {
+            if let Expression::Invoke { class, name, .. } = expression {
+                if class == class_name && name.starts_with("lambda$") {
+                    called.push(name.clone());
+                }
+            }
+        });
+    }
+    members.retain(|member| match member {
+        Member::Method(method) if method.name.starts_with("lambda$") => {
+            called.contains(&method.name)
+        }
+        _ => true,
+    });
+}
+
+// The name, descriptor, parameter names and body of the lambda methods that can be inlined
+fn lambda_bodies(members: &[Member]) -> Vec<(String, String, Vec<String>, Expression)> {
+    members
+        .iter()
+        .filter_map(|member| match member {
+            Member::Method(method) if method.name.starts_with("lambda$") => {
+                let body = match method.body.as_deref()? {
+                    [Statement::Return(Some(value), _)] | [Statement::Expression(value, _)] => {
+                        value.clone()
+                    }
+                    // the statements go on one line, which comments would end
+                    body if !has_comments(body) => Expression::LambdaBlock {
+                        parameters: Vec::new(),
+                        body: body.to_vec(),
+                    },
+                    _ => return None,
+                };
+                let parameters = method
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.name.clone())
+                    .collect();
+                Some((
+                    method.name.clone(),
+                    method.descriptor.clone(),
+                    parameters,
+                    body,
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+// The lambda with the body of its method, the parameters the captured values are passed in
+// replaced with them
+fn inlined(
+    parameters: &[String],
+    captured: usize,
+    values: &[Expression],
+    body: &Expression,
+) -> Expression {
+    let mut replace = |expression: &mut Expression| {
+        if let Expression::Local(local) = expression {
+            if let Some(i) = parameters[..captured]
+                .iter()
+                .position(|parameter| *parameter == local.name)
+            {
+                *expression = values[i].clone();
+            }
+        }
+    };
+    let parameters = parameters[captured..].to_vec();
+    match body.clone() {
+        Expression::LambdaBlock { mut body, .. } => {
+            for statement in body.iter_mut() {
+                statement.walk_mut(&mut |statement| {
+                    statement.expressions_mut(&mut |expression| expression.visit_mut(&mut replace))
+                });
+            }
+            Expression::LambdaBlock { parameters, body }
+        }
+        mut body => {
+            body.visit_mut(&mut replace);
+            Expression::Lambda {
+                parameters,
+                body: Box::new(body),
+            }
+        }
     }
+}
 
-    methods_string
+fn has_comments(statements: &[Statement]) -> bool {
+    let mut comments = false;
+    for statement in statements.to_vec().iter_mut() {
+        statement.walk_mut(&mut |statement| {
+            comments |= matches!(
+                statement,
+                Statement::Comment(_) | Statement::MonitorEnter(..) | Statement::MonitorExit(..)
+            )
+        });
+    }
+    comments
+}
+
+fn visit_expressions(member: &mut Member, f: &mut dyn FnMut(&mut Expression)) {
+    match member {
+        Member::Method(MethodDeclaration {
+            body: Some(body), ..
+        })
+        | Member::Initializer(body, _) => visit_statements(body, f),
+        _ => {}
+    }
+}
+
+// Calls `f` on the expressions of the statements and of the lambdas and switch expressions in
+// them
+fn visit_statements(statements: &mut [Statement], f: &mut dyn FnMut(&mut Expression)) {
+    for statement in statements.iter_mut() {
+        statement.walk_mut(&mut |statement| {
+            statement.expressions_mut(&mut |expression| {
+                expression.visit_mut(&mut |expression| {
+                    f(expression);
+                    match expression {
+                        Expression::LambdaBlock { body, .. } => visit_statements(body, f),
+                        Expression::Switch { cases, .. } => cases
+                            .iter_mut()
+                            .for_each(|case| visit_statements(&mut case.body, f)),
+                        _ => {}
+                    }
+                })
+            })
+        });
+    }
 }
 
 struct Method<'a> {
     decompile: bool,
-    varargs: bool,
     is_static: bool,
-    arg_count: u8,
     this_class: u16,
+    major: u16,
+    interface: bool,
     method_info: &'a super::MethodInfo,
-    method_signature: String,
-    constant_pool: &'a mut super::ConstantPool,
+    bootstrap_methods: &'a [BootstrapMethod],
+    signatures: &'a [MethodSignature],
+    constant_pool: &'a super::ConstantPool,
 }
 
 impl<'a> Method<'a> {
     pub fn new(
         this_class: u16,
+        major: u16,
+        interface: bool,
         method_info: &'a super::MethodInfo,
-        constant_pool: &'a mut super::ConstantPool,
+        bootstrap_methods: &'a [BootstrapMethod],
+        signatures: &'a [MethodSignature],
+        constant_pool: &'a super::ConstantPool,
     ) -> Self {
         Method {
             decompile: true,
-            varargs: false,
             is_static: false,
-            arg_count: 0,
             this_class,
+            major,
+            interface,
             method_info,
-            method_signature: String::new(),
+            bootstrap_methods,
+            signatures,
             constant_pool,
         }
     }
 
-    pub fn decompile_method(&mut self) -> String {
-        let mut method_code = String::new();
-
-        method_code.push('\t');
-        method_code.push_str(self.get_method_signature());
-
-        if !self.decompile {
-            method_code.push_str(";\n");
-            return method_code;
-        }
-
-        method_code.push_str(" {\n");
-
-        let code = self.decompile_bytecode();
-
-        let code = code.split('\n');
-        for string in code {
-            method_code.push_str("\t\t");
-            method_code.push_str(string);
-            method_code.push('\n');
-        }
-
-        method_code.push_str("\t}");
+    pub fn decompile_method(
+        &mut self,
+        options: &super::DecompilerOptions,
+        switch_maps: &super::switch_maps::SwitchMaps,
+        diagnostics: &mut Vec<super::Diagnostic>,
+    ) -> Member {
+        let mut declaration = self.generate_method_declaration();
+
+        if self.decompile {
+            let (method_body, lines) = self.decompile_bytecode(options, switch_maps);
+            let name = self.get_string(self.method_info.name_index);
+            for message in method_body.problems {
+                diagnostics.push(super::Diagnostic {
+                    method: name.clone(),
+                    descriptor: declaration.descriptor.clone(),
+                    message,
+                });
+            }
+            for (parameter, name) in declaration
+                .parameters
+                .iter_mut()
+                .zip(method_body.parameter_names)
+            {
+                parameter.name = name;
+            }
+            let mut body = method_body.statements;
 
-        method_code.push('\n');
-        method_code
-    }
+            // javac adds these when they aren't written out
+            if let Some(Statement::Return(None, _)) = body.last() {
+                body.pop();
+            }
+            if declaration.return_type.is_none() {
+                if let Some(Statement::Expression(
+                    Expression::Invoke {
+                        target: Some(target),
+                        name,
+                        arguments,
+                        ..
+                    },
+                    _,
+                )) = body.first()
+                {
+                    if **target == Expression::Super && name == "<init>" && arguments.is_empty() {
+                        body.remove(0);
+                    }
+                }
+            }
 
-    fn get_method_signature(&mut self) -> &String {
-        if self.method_signature.is_empty() {
-            self.generate_method_signature();
+            if declaration.name == "<clinit>" {
+                let class_name = super::class::get_class_name(self.this_class, self.constant_pool);
+                super::codegen::remove_flag(&mut body, class_name.as_str());
+                return Member::Initializer(body, lines);
+            }
+            declaration.body = Some(body);
+            declaration.lines = lines;
         }
 
-        &self.method_signature
+        Member::Method(declaration)
     }
 
-    fn generate_method_signature(&mut self) {
-        let mut method_signature = String::new();
+    fn generate_method_declaration(&mut self) -> MethodDeclaration {
+        let mut modifiers = Vec::new();
 
         let access_flags = self.method_info.access_flags;
 
-        if access_flags & PUBLIC != 0 {
-            method_signature.push_str("public ");
+        // methods of interfaces are public unless they are private
+        if access_flags & PUBLIC != 0 && !self.interface {
+            modifiers.push("public");
         } else if access_flags & PRIVATE != 0 {
-            method_signature.push_str("private ");
+            modifiers.push("private");
         } else if access_flags & PROTECTED != 0 {
-            method_signature.push_str("protected ");
+            modifiers.push("protected");
         }
 
         if access_flags & SYNTHETIC != 0 {
-            method_signature.push_str("/* synthetic */ ");
+            modifiers.push("/* synthetic */");
         }
 
         if access_flags & STATIC != 0 {
-            method_signature.push_str("static ");
+            modifiers.push("static");
             self.is_static = true;
         }
 
         if access_flags & ABSTRACT != 0 {
-            method_signature.push_str("abstract ");
+            if !self.interface {
+                modifiers.push("abstract");
+            }
             self.decompile = false;
+        } else if self.interface && access_flags & (STATIC | PRIVATE) == 0 {
+            modifiers.push("default");
         }
 
         if access_flags & FINAL != 0 {
-            method_signature.push_str("final ");
+            modifiers.push("final");
         }
 
         if access_flags & SYNCHRONIZED != 0 {
-            method_signature.push_str("synchronized ");
+            modifiers.push("synchronized");
         }
 
         if access_flags & BRIDGE != 0 {
-            method_signature.push_str("/* brigde */ ");
-        }
-
-        if access_flags & VARARGS != 0 {
-            self.varargs = true;
+            modifiers.push("/* bridge */");
         }
 
         if access_flags & STRICT != 0 {
-            method_signature.push_str("/* strict */ ");
+            modifiers.push("/* strict */");
         }
 
         if access_flags & NATIVE != 0 {
-            method_signature.push_str("native ");
+            modifiers.push("native");
             self.decompile = false;
         }
 
-        let descriptor = match self
-            .constant_pool
-            .get_index(self.method_info.descriptor_index)
-        {
-            super::CPIndexType::Utf8(return_type) => return_type,
-            _ => panic!("Invalid Type in Constant Pool"),
-        };
-
-        let (args, return_value) =
-            descriptor.split_at(descriptor.find(")").expect("Invalid descriptor String") + 1);
-
-        method_signature
-            .push_str(format!("{} ", super::value::get_type(return_value.to_string())).as_str());
-        method_signature.push_str(self.get_string(self.method_info.name_index).as_str());
+        let descriptor = self.get_string(self.method_info.descriptor_index);
+        let (arguments, return_value) = descriptor::split_method_descriptor(descriptor.as_str());
 
-        method_signature = method_signature.replace(
-            "void <init>",
-            super::class::get_class_name(self.this_class, self.constant_pool)
+        let mut name = self.get_string(self.method_info.name_index);
+        let mut return_type = Some(Type::from_descriptor(return_value.as_str()));
+        if name == "<init>" {
+            name = super::class::get_class_name(self.this_class, self.constant_pool)
                 .split('/')
-                .last()
-                .unwrap(),
-        );
-
-        let mut args = args.to_string();
-        args.retain(|a| a != '(' && a != ')');
-
-        let mut fmt_args = String::new();
-
-        let mut arg_offset = 1;
-        let mut index = 0;
-
-        if self.is_static {
-            arg_offset = 0;
+                .next_back()
+                .unwrap()
+                .to_string();
+            return_type = None;
         }
 
-        for string in self.parse_args(args) {
-            if !string.is_empty() {
-                if index > 0 {
-                    fmt_args.push_str(", ");
-                }
-
-                fmt_args.push_str(format!("{} arg{}", string, index + arg_offset).as_str());
-                index += 1;
-            }
-        }
-
-        self.arg_count = index + arg_offset;
-
-        let mut args = String::new();
-
-        if self.varargs {
-            let last_array_index = fmt_args
-                .rfind("[]")
-                .expect("Invalid arguments: Method with varargs must contain an Array")
-                .clone();
-
-            let (first_args, last_arg) = fmt_args.split_at(last_array_index);
+        // arguments are named after their local variable slot until the code tells better
+        let mut slot = if self.is_static { 0 } else { 1 };
 
-            let last_arg = last_arg.replace("[]", "...");
-
-            args.push_str(first_args);
-            args.push_str(last_arg.as_str());
-        } else {
-            args = fmt_args;
+        let mut parameters = Vec::new();
+        for argument in arguments {
+            parameters.push(Parameter {
+                ty: Type::from_descriptor(argument.as_str()),
+                name: format!("arg{}", slot),
+            });
+            slot += descriptor::slot_size(argument.as_str());
         }
 
-        method_signature.push_str(format!("({})", args).as_str());
+        let mut throws = Vec::new();
 
-        method_signature = method_signature.replace("static void <clinit>()", "static");
+        let exceptions = self
+            .method_info
+            .attributes
+            .iter()
+            .find(|attribute| attribute.get_name() == "Exceptions");
 
-        let mut exceptions: Option<&Vec<u8>> = None;
+        if let Some(exceptions) = exceptions {
+            let exceptions = &exceptions.info;
+            let exception_count = u16::from_be_bytes([exceptions[0], exceptions[1]]);
 
-        for attribute in &self.method_info.attributes {
-            if exceptions == None {
-                let attribute_name = match &attribute.attribute_name_index.value {
-                    super::CPIndexType::Utf8(name) => name,
-                    _ => panic!("Invalid Type in Constant Pool"),
-                };
+            for i in 0..exception_count as usize {
+                let exception_index =
+                    u16::from_be_bytes([exceptions[2 * (i + 1)], exceptions[2 * (i + 1) + 1]]);
 
-                exceptions = match attribute_name.as_str() {
-                    "Exceptions" => Some(&attribute.info),
-                    _ => None,
-                };
+                throws.push(Type::from_class_name(
+                    super::class::get_class_name(exception_index, self.constant_pool).as_str(),
+                ));
             }
         }
 
-        match exceptions {
-            Some(exceptions) => {
-                method_signature.push_str(" throws ");
-                let exception_count: [u8; 2] = [exceptions[0], exceptions[1]];
-                let exception_count = u16::from_be_bytes(exception_count);
-
-                for i in 0..exception_count as usize {
-                    let mut class_name = String::new();
-
-                    if i > 0 {
-                        method_signature.push_str(", ");
-                    }
-
-                    let exception_index =
-                        u16::from_be_bytes([exceptions[2 * (i + 1)], exceptions[2 * (i + 1) + 1]]);
-
-                    class_name.push('L');
-                    class_name.push_str(
-                        super::class::get_class_name(exception_index, self.constant_pool).as_str(),
-                    );
-
-                    method_signature.push_str(super::value::get_type(class_name).as_str());
-                }
-            }
-            None => {}
-        };
-
-        self.method_signature = method_signature;
-    }
-
-    fn parse_args(&self, args: String) -> Vec<String> {
-        let mut types = Vec::<String>::new();
-
-        let mut chars = args.chars();
-
-        while chars.clone().count() != 0 {
-            let char = chars.next().unwrap();
-
-            match char {
-                'B' | 'C' | 'D' | 'F' | 'I' | 'J' | 'S' | 'V' | 'Z' => {
-                    types.push(super::value::get_type(char.to_string()));
-                }
-                'L' => {
-                    let mut type_string = String::new();
-                    let mut char = chars.next().unwrap();
-                    while char != ';' {
-                        type_string.push(char);
-                        if chars.clone().count() == 0 {
-                            break;
-                        }
-                        char = chars.next().unwrap();
-                    }
-                    types.push(type_string.replace('/', "."));
-                }
-                '[' => {
-                    let mut type_string = String::new();
-                    let mut char = chars.next().unwrap();
-                    while char != ';' {
-                        type_string.push(char);
-                        if chars.clone().count() == 0 {
-                            break;
-                        }
-                        char = chars.next().unwrap();
-                    }
-                    let type_string = self.parse_args(type_string);
-                    let mut type_string = type_string.get(0).unwrap().to_string();
-                    type_string.push_str("[]");
-                    types.push(type_string);
-                }
-                type_string => panic!("Invalid Type: {}", type_string),
-            }
+        MethodDeclaration {
+            modifiers: modifiers.into_iter().map(String::from).collect(),
+            return_type,
+            name,
+            descriptor,
+            parameters,
+            varargs: access_flags & VARARGS != 0,
+            throws,
+            body: None,
+            lines: Vec::new(),
         }
-        types
     }
 
-    fn decompile_bytecode(&mut self) -> String {
-        let mut bytecode: Option<&Vec<u8>> = None;
-
-        for attribute in &self.method_info.attributes {
-            if bytecode == None {
-                let attribute_name = match &attribute.attribute_name_index.value {
-                    super::CPIndexType::Utf8(name) => name,
-                    _ => panic!("Invalid Type in Constant Pool"),
-                };
-
-                bytecode = match attribute_name.as_str() {
-                    "Code" => Some(&attribute.info),
-                    _ => None,
+    fn decompile_bytecode(
+        &mut self,
+        options: &super::DecompilerOptions,
+        switch_maps: &super::switch_maps::SwitchMaps,
+    ) -> (MethodBody, Vec<LineSite>) {
+        let (code, instructions) = match self.read_code() {
+            Ok(code) => code,
+            // the body is left empty, parsed class files have been checked for this
+            Err(error) => {
+                let method_body = MethodBody {
+                    parameter_names: Vec::new(),
+                    statements: Vec::new(),
+                    problems: vec![error.to_string()],
                 };
+                return (method_body, Vec::new());
             }
-        }
-
-        let mut bytecode = match bytecode {
-            Some(bytecode) => bytecode,
-            None => panic!("No Method Bytecode found"),
-        }
-        .take(100);
-
-        let mut _max_stack = [0u8; 2];
-        let mut _max_locales = [0u8; 2];
-
-        bytecode
-            .read_exact(&mut _max_stack)
-            .expect("Invalid Method Bytecode");
-        bytecode
-            .read_exact(&mut _max_locales)
-            .expect("Invalid Method Bytecode");
-
-        let code_length_bytes: &mut [u8; 4] = &mut [0; 4];
-        bytecode
-            .read_exact(code_length_bytes)
-            .expect("Invalid Method Bytecode");
-        let code_length = u32::from_be_bytes(*code_length_bytes) as usize;
-
-        bytecode.set_limit(code_length as u64);
-
-        let mut code_stream = Vec::<u8>::with_capacity(code_length);
-
-        for _i in 0..code_length {
-            code_stream.push(0);
-        }
-
-        bytecode
-            .read_exact(code_stream.as_mut_slice())
-            .expect("Invalid Method Bytecode");
-
-        let code_stream = BufReader::with_capacity(code_length, code_stream.as_slice());
-
-        bytecode.set_limit(2);
-
-        let exception_table_length_bytes: &mut [u8; 2] = &mut [0; 2];
-
-        bytecode
-            .read_exact(exception_table_length_bytes)
-            .expect("Invalid Method Bytecode");
-
-        let exception_table_length = u16::from_be_bytes(*exception_table_length_bytes);
-
-        let mut exception_table_bytes = Vec::<u8>::with_capacity(code_length);
-
-        for _i in 0..exception_table_length * 8 {
-            exception_table_bytes.push(0);
-        }
-
-        bytecode.set_limit(exception_table_length as u64 * 8);
-
-        bytecode
-            .read_exact(exception_table_bytes.as_mut_slice())
-            .expect("Invalid Method Bytecode");
-
-        bytecode.set_limit(2);
-
-        let attributes_count_bytes: &mut [u8; 2] = &mut [0; 2];
-
-        bytecode
-            .read_exact(attributes_count_bytes)
-            .expect("Invalid Method Bytecode");
-
-        let attributes_count = u16::from_be_bytes(*attributes_count_bytes);
-
-        let mut attributes: Vec<attribute::Attribute> = Vec::new();
-
-        // let mut variable_table: Option<super::variable::VariableTable> = None;
-
-
-        for _ in 0..attributes_count {
-            bytecode.set_limit(6);
-
-            let attribute_name_index_bytes: &mut [u8; 2] = &mut [0; 2];
-
-            bytecode
-                .read_exact(attribute_name_index_bytes)
-                .expect("Invalid Method Bytecode");
-
-            let attribute_length_bytes: &mut [u8; 4] = &mut [0; 4];
-
-            bytecode
-                .read_exact(attribute_length_bytes)
-                .expect("Invalid Method Bytecode");
-
-            let attribute_length = u32::from_be_bytes(*attribute_length_bytes);
-
-            bytecode.set_limit(attribute_length as u64);
-
-            let mut attribute_bytes: Vec<u8> = Vec::new();
-
-            for _i in 0..attribute_length {
-                attribute_bytes.push(0);
-            }
-
-            bytecode
-                .read_exact(attribute_bytes.as_mut_slice())
-                .expect("Invalid Method Bytecode");
-            let name_index = u16::from_be_bytes(*attribute_name_index_bytes);
-            let attribute =
-                attribute::Attribute::new(name_index, attribute_length, attribute_bytes);
-
-            if self.get_string(attribute.get_name_index()) == "LocalVariableTable" {
-                bytecode.set_limit(2);
-
-                let length_bytes: &mut [u8; 2] = &mut [0, 0];
-
-                bytecode
-                    .read_exact(length_bytes)
-                    .expect("Invalid Method Bytecode");
-
-                let length = u16::from_be_bytes(*length_bytes);
-
-                // let mut variables: Vec<super::codegen::variable::Variable> = Vec::new();
-
-                for _i in 0..length {
-                    let start_pc_bytes: &mut [u8; 2] = &mut [0, 0];
-
-                    bytecode
-                        .read_exact(start_pc_bytes)
-                        .expect("Invalid Method Bytecode");
-
-                    let start_pc = u16::from_be_bytes(*start_pc_bytes);
-
-                    let length_bytes: &mut [u8; 2] = &mut [0, 0];
-
-                    bytecode
-                        .read_exact(length_bytes)
-                        .expect("Invalid Method Bytecode");
-
-                    let length = u16::from_be_bytes(*length_bytes);
-
-                    let name_index_bytes: &mut [u8; 2] = &mut [0, 0];
-
-                    bytecode
-                        .read_exact(name_index_bytes)
-                        .expect("Invalid Method Bytecode");
-
-                    let name_index = u16::from_be_bytes(*name_index_bytes);
-
-                    let descriptor_index_bytes: &mut [u8; 2] = &mut [0, 0];
-
-                    bytecode
-                        .read_exact(descriptor_index_bytes)
-                        .expect("Invalid Method Bytecode");
-
-                    let descriptor_index = u16::from_be_bytes(*descriptor_index_bytes);
-
-                    let index_bytes: &mut [u8; 2] = &mut [0, 0];
-
-                    bytecode
-                        .read_exact(index_bytes)
-                        .expect("Invalid Method Bytecode");
-
-                    let index = u16::from_be_bytes(*index_bytes);
-
-                    // let variable = super::variable::Variable {
-                    //     start_pc,
-                    //     length,
-                    //     name_index,
-                    //     descriptor_index,
-                    //     index,
-                    // };
-                    // variables.push(variable);
-                }
-                // variable_table = Some(super::variable::VariableTable {
-                //     variable_count: length,
-                //     variables: variables.clone(),
-                // })
-            }
-
-            attributes.push(attribute);
-        }
-
-        // println!("{:#?}", attributes);
-
-        let instructions = super::instruction::parse(code_stream, code_length);
-        // println!("{:?}", instructions);
-        super::codegen::generate_code(instructions, self.constant_pool, &super::codegen::MethodInformation {
-            is_static: self.is_static,
-            arg_count: self.arg_count
-        })
+        };
+        let descriptor = self.get_string(self.method_info.descriptor_index);
+        let lines = super::lines::line_sites(&instructions, &code);
+
+        let method_body = super::codegen::generate_code(
+            instructions,
+            &code,
+            self.constant_pool,
+            &super::codegen::MethodInformation {
+                is_static: self.is_static,
+                class_name: super::class::get_class_name(self.this_class, self.constant_pool),
+                descriptor,
+                major: self.major,
+                bootstrap_methods: self.bootstrap_methods,
+                methods: self.signatures,
+                options,
+                switch_maps,
+            },
+        );
+        (method_body, lines)
+    }
 
-        // TODO: Local Variables
+    fn read_code(&self) -> io::Result<(super::CodeAttribute, Vec<DecodedInstruction>)> {
+        let bytecode = self
+            .method_info
+            .attributes
+            .iter()
+            .find(|attribute| attribute.get_name() == "Code")
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no Code attribute"))?;
+
+        let code = super::CodeAttribute::new(&bytecode.info, self.constant_pool)?;
+        let code_stream = BufReader::new(code.code.as_slice());
+        let instructions = super::instruction::parse(code_stream, code.code.len())?;
+        Ok((code, instructions))
     }
 
     fn get_string(&mut self, index: u16) -> String {
-        let method_name = self.constant_pool.get_index(index);
+        get_string(index, self.constant_pool)
+    }
+}
 
-        match method_name {
-            super::CPIndexType::Utf8(a) => a,
-            _ => panic!("Invalid Type in Constant Pool"),
-        }
+fn get_string(index: u16, constant_pool: &super::ConstantPool) -> String {
+    match constant_pool.get_index(index) {
+        super::CPIndexType::Utf8(utf8) => utf8.string,
+        _ => panic!("Invalid Type in Constant Pool"),
     }
 }
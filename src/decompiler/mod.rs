@@ -1,32 +1,148 @@
-mod attribute;
+mod ast;
 mod class;
 mod codegen;
 mod field;
-mod instruction;
+mod imports;
 mod interface;
+mod lines;
 mod method;
-mod value;
+mod module;
+mod pretty;
+mod switch_maps;
 mod variable;
 
+pub use super::disassembler::code::CodeAttribute;
 pub use super::disassembler::constant_pool::*;
 pub use super::disassembler::field::FieldInfo;
+pub use super::disassembler::instruction;
 pub use super::disassembler::method::*;
+pub use super::disassembler::printer;
 pub use super::disassembler::ClassFile;
+pub use lines::{MethodMap, SourceMap, StatementMap};
+pub use pretty::LineNumbers;
 
-pub fn decompile_class_file(class_file: &mut ClassFile) -> String {
-    let mut code = String::new();
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecompilerOptions {
+    pub line_numbers: LineNumbers,
+    // class names are shortened with imports, else they are written out in full
+    pub imports: bool,
+    // leave out the fields and methods the compiler generated, like bridges and lambda bodies
+    pub hide_synthetic: bool,
+    // leave out the comments on methods that couldn't be decompiled completely, they are still
+    // reported as diagnostics
+    pub hide_problems: bool,
+    pub naming: Naming,
+}
+
+impl Default for DecompilerOptions {
+    fn default() -> Self {
+        DecompilerOptions {
+            line_numbers: LineNumbers::Ignore,
+            imports: true,
+            hide_synthetic: false,
+            hide_problems: false,
+            naming: Naming::DebugInfo,
+        }
+    }
+}
+
+// How local variables are named
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Naming {
+    // the names of the LocalVariableTable, variables without one are named after their slot
+    DebugInfo,
+    // `argN` and `varN` after the slots, even if there are names
+    Slots,
+}
+
+// A problem with the code of a method, which is printed as far as it was recovered
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub method: String,
+    pub descriptor: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decompilation {
+    pub source: String,
+    pub source_map: SourceMap,
+    pub diagnostics: Vec<Diagnostic>,
+}
 
-    code.push_str(class::generate_signature_code(class_file).as_str());
+pub fn decompile_class_file(class_file: &ClassFile, options: &DecompilerOptions) -> String {
+    let (unit, _) = compilation_unit(class_file, &[], options);
+    pretty::print_compilation_unit(&unit, options.line_numbers)
+}
 
-    code.push_str("{\n");
+// The source together with where the code of each method ended up and what went wrong
+pub fn decompile(class_file: &ClassFile, options: &DecompilerOptions) -> Decompilation {
+    decompile_with(class_file, &[], options)
+}
 
-    code.push_str(field::decompile_fields(class_file).as_str());
+// Like `decompile`, with the other classes of the program, like the synthetic ones javac adds
+// for switches on enums, to recover what they stand for
+pub fn decompile_with(
+    class_file: &ClassFile,
+    classes: &[ClassFile],
+    options: &DecompilerOptions,
+) -> Decompilation {
+    let (unit, diagnostics) = compilation_unit(class_file, classes, options);
+    let (source, source_map) = pretty::print_with_source_map(&unit, options.line_numbers);
+    Decompilation {
+        source,
+        source_map,
+        diagnostics,
+    }
+}
 
-    code.push('\n');
+fn compilation_unit(
+    class_file: &ClassFile,
+    classes: &[ClassFile],
+    options: &DecompilerOptions,
+) -> (ast::CompilationUnit, Vec<Diagnostic>) {
+    if class::is_module(class_file) {
+        let unit = ast::CompilationUnit {
+            package: None,
+            imports: Vec::new(),
+            module: module::decompile_module(class_file),
+            types: Vec::new(),
+        };
+        return (unit, Vec::new());
+    }
 
-    code.push_str(method::decompile_methods(class_file).as_str());
+    let mut declaration = class::generate_type_declaration(class_file);
+    let mut diagnostics = Vec::new();
 
-    code.push_str("}\n");
+    declaration
+        .members
+        .extend(field::decompile_fields(class_file, options));
+    declaration.members.extend(method::decompile_methods(
+        class_file,
+        options,
+        &switch_maps::read_switch_maps(classes),
+        &mut diagnostics,
+    ));
+    let class_name = class::get_class_name(class_file.this_class, &class_file.constant_pool);
+    field::move_initializers(
+        &mut declaration.members,
+        class_name.as_str(),
+        class::is_interface(class_file),
+    );
 
-    code
+    let mut unit = ast::CompilationUnit {
+        package: class_name
+            .rsplit_once('/')
+            .map(|(package, _)| package.replace('/', ".")),
+        imports: Vec::new(),
+        module: None,
+        types: vec![declaration],
+    };
+    let nested = class::nested_names(class_file);
+    if options.imports {
+        imports::import_types(&mut unit, &nested);
+    } else {
+        imports::nest_types(&mut unit, &nested);
+    }
+    (unit, diagnostics)
 }
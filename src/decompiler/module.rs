@@ -0,0 +1,114 @@
+use super::ast::{ModuleDeclaration, PackageDirective, Requires, Type};
+use crate::disassembler::attribute::{read_module, ModuleDirective};
+
+const OPEN: u16 = 0x0020;
+const TRANSITIVE: u16 = 0x0020;
+const STATIC_PHASE: u16 = 0x0040;
+const SYNTHETIC: u16 = 0x1000;
+const MANDATED: u16 = 0x8000;
+
+// None without a Module attribute, parsed class files have been checked for one
+pub fn decompile_module(class_file: &super::ClassFile) -> Option<ModuleDeclaration> {
+    let constant_pool = &class_file.constant_pool;
+    let attributes = &class_file.attributes.attributes;
+    let module = read_module(attributes).ok().flatten()?;
+
+    // the dependency on java.base is implicit
+    let requires = module
+        .requires
+        .iter()
+        .filter(|directive| directive.flags & MANDATED == 0)
+        .map(|directive| {
+            let mut modifiers = Vec::new();
+            if directive.flags & SYNTHETIC != 0 {
+                modifiers.push("/* synthetic */");
+            }
+            if directive.flags & TRANSITIVE != 0 {
+                modifiers.push("transitive");
+            }
+            if directive.flags & STATIC_PHASE != 0 {
+                modifiers.push("static");
+            }
+            Requires {
+                modifiers: modifiers.into_iter().map(String::from).collect(),
+                name: get_name(directive.index, constant_pool),
+                version: get_version(directive.targets[0], constant_pool),
+            }
+        })
+        .collect();
+    let packages = |directives: &[ModuleDirective]| {
+        directives
+            .iter()
+            .map(|directive| PackageDirective {
+                name: get_name(directive.index, constant_pool),
+                to: directive
+                    .targets
+                    .iter()
+                    .map(|target| get_name(*target, constant_pool))
+                    .collect(),
+            })
+            .collect()
+    };
+    let class = |index: &u16| {
+        Type::from_class_name(super::class::get_class_name(*index, constant_pool).as_str())
+    };
+
+    let mut declaration = ModuleDeclaration {
+        open: module.flags & OPEN != 0,
+        name: get_name(module.name, constant_pool),
+        version: get_version(module.version, constant_pool),
+        requires,
+        exports: packages(&module.exports),
+        opens: packages(&module.opens),
+        uses: module.uses.iter().map(class).collect(),
+        provides: module
+            .provides
+            .iter()
+            .map(|(service, implementations)| {
+                (class(service), implementations.iter().map(class).collect())
+            })
+            .collect(),
+        packages: Vec::new(),
+        main_class: None,
+    };
+
+    for attribute in attributes {
+        let info = &attribute.info;
+        let read_u16 = |offset: usize| u16::from_be_bytes([info[offset], info[offset + 1]]);
+        match attribute.get_name() {
+            "ModulePackages" => {
+                declaration.packages = (0..read_u16(0) as usize)
+                    .map(|i| get_name(read_u16(2 + 2 * i), constant_pool))
+                    .collect()
+            }
+            "ModuleMainClass" => declaration.main_class = Some(class(&read_u16(0))),
+            _ => {}
+        }
+    }
+
+    Some(declaration)
+}
+
+// The name of a module or package, packages are written with slashes
+fn get_name(index: u16, constant_pool: &super::ConstantPool) -> String {
+    let name_index = match constant_pool.get_index(index) {
+        super::CPIndexType::Module(a) | super::CPIndexType::Package(a) => a,
+        _ => panic!("Invalid Type in Constant Pool"),
+    };
+
+    match constant_pool.get_index(name_index) {
+        super::CPIndexType::Utf8(utf8) => utf8.string.replace('/', "."),
+        _ => panic!("Invalid Type in Constant Pool"),
+    }
+}
+
+fn get_version(index: u16, constant_pool: &super::ConstantPool) -> Option<String> {
+    if index == 0 {
+        return None;
+    }
+
+    match constant_pool.get_index(index) {
+        super::CPIndexType::Utf8(utf8) => Some(utf8.string),
+        _ => panic!("Invalid Type in Constant Pool"),
+    }
+}
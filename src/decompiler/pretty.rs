@@ -0,0 +1,1312 @@
+// Prints the syntax tree as Java source
+use super::ast::*;
+use super::lines::{MethodMap, SourceLines, SourceMap, StatementMap};
+
+const INDENT: &str = "\t";
+// how wide a tab counts when deciding whether a line is too long
+const TAB_WIDTH: usize = 4;
+const LINE_WIDTH: usize = 100;
+
+// Whether statements are moved down to the lines of the LineNumberTable
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LineNumbers {
+    #[default]
+    Ignore,
+    Align,
+    // statements that are already past their line are marked with it
+    AlignOrComment,
+}
+
+pub fn print_compilation_unit(unit: &CompilationUnit, line_numbers: LineNumbers) -> String {
+    let printer = Printer {
+        line_numbers,
+        ..Printer::default()
+    };
+    print(unit, printer).output
+}
+
+// Also tells where the code of the methods ended up
+pub fn print_with_source_map(
+    unit: &CompilationUnit,
+    line_numbers: LineNumbers,
+) -> (String, SourceMap) {
+    let printer = Printer {
+        line_numbers,
+        source_map: Some(SourceMap::default()),
+        ..Printer::default()
+    };
+    let printer = print(unit, printer);
+    (printer.output, printer.source_map.unwrap())
+}
+
+fn print(unit: &CompilationUnit, mut printer: Printer) -> Printer {
+    let mut preamble: Vec<String> = unit
+        .package
+        .iter()
+        .map(|package| format!("package {};", package))
+        .collect();
+    preamble.extend(
+        unit.imports
+            .iter()
+            .map(|import| format!("import {};", import)),
+    );
+    // the package and the imports go on one line when the code of the class starts before
+    // the line they would push the class to
+    let loose = preamble.len() + unit.package.iter().count() + (!unit.imports.is_empty()) as usize;
+    let first = unit
+        .types
+        .first()
+        .and_then(|declaration| printer.aligned_line(&declaration.members));
+    if matches!(first, Some(first) if loose + 2 > first) && !preamble.is_empty() {
+        printer.line(preamble.join(" ").as_str());
+    } else {
+        if let Some(package) = &unit.package {
+            printer.line(format!("package {};", package).as_str());
+            printer.output.push('\n');
+        }
+        for import in &unit.imports {
+            printer.line(format!("import {};", import).as_str());
+        }
+        if !unit.imports.is_empty() {
+            printer.output.push('\n');
+        }
+    }
+    if let Some(module) = &unit.module {
+        printer.module_declaration(module);
+    }
+    for (i, declaration) in unit.types.iter().enumerate() {
+        if i > 0 {
+            printer.output.push('\n');
+        }
+        printer.type_declaration(declaration);
+    }
+    printer
+}
+
+#[derive(Default)]
+struct Printer {
+    output: String,
+    depth: usize,
+    // a statement label that goes in front of the next line
+    label: Option<String>,
+    line_numbers: LineNumbers,
+    // the lines of the method that is printed
+    source_lines: Option<SourceLines>,
+    // the original line of a statement that couldn't be moved to it
+    line_comment: Option<String>,
+    // the next line goes on the end of the last one
+    pack: bool,
+    source_map: Option<SourceMap>,
+    // the statements of the method that is printed
+    statement_maps: Vec<StatementMap>,
+    // the line breaks in the output up to `counted`
+    newlines: usize,
+    counted: usize,
+}
+
+impl Printer {
+    fn line(&mut self, text: &str) {
+        if self.pack {
+            self.pack = false;
+            // the counted line break is taken back
+            self.current_line();
+            self.newlines -= 1;
+            self.output.pop();
+            self.counted = self.output.len();
+            self.output.push(' ');
+        } else {
+            for _ in 0..self.depth {
+                self.output.push_str(INDENT);
+            }
+        }
+        if let Some(comment) = self.line_comment.take() {
+            self.output.push_str(comment.as_str());
+        }
+        if let Some(label) = self.label.take() {
+            self.output.push_str(format!("{}: ", label).as_str());
+        }
+        self.output.push_str(text);
+        self.output.push('\n');
+    }
+
+    // A line ending in an expression, which is wrapped if the line gets too long
+    fn expression_line(&mut self, prefix: &str, expression: &Expression, suffix: &str) {
+        match expression {
+            Expression::Switch { value, cases } => {
+                return self.switch_expression(prefix, value, cases, suffix)
+            }
+            Expression::Assign {
+                target,
+                operator,
+                value,
+            } if matches!(value.as_ref(), Expression::Switch { .. }) => {
+                let prefix = format!(
+                    "{}{} {}= ",
+                    prefix,
+                    expression_at(target, PRIMARY),
+                    operator.map(|operator| operator.symbol()).unwrap_or("")
+                );
+                return self.expression_line(prefix.as_str(), value, suffix);
+            }
+            _ => {}
+        }
+        let used = self.depth * TAB_WIDTH + prefix.len() + suffix.len();
+        let text = wrap(expression, self.depth + 2, used);
+        self.line(format!("{}{}{}", prefix, text, suffix).as_str());
+    }
+
+    // The declaration of a variable or field with a value
+    fn initialized(&mut self, prefix: &str, ty: &Type, value: &Expression) {
+        match (ty, value) {
+            // declarations of arrays may leave out the type of the initializer
+            (
+                Type::Array(_),
+                Expression::NewArray {
+                    elements: Some(elements),
+                    extra,
+                    ..
+                },
+            ) => {
+                let used = self.depth * TAB_WIDTH + prefix.len() + 1;
+                let text = wrap_initializer(elements, *extra > 0, self.depth + 2, used);
+                self.line(format!("{}{};", prefix, text).as_str());
+            }
+            _ => self.expression_line(prefix, value, ";"),
+        }
+    }
+
+    fn module_declaration(&mut self, module: &ModuleDeclaration) {
+        // what the module descriptor records beyond the source
+        if let Some(version) = &module.version {
+            self.line(format!("// version {}", version).as_str());
+        }
+        if !module.packages.is_empty() {
+            self.line(format!("// packages {}", module.packages.join(", ")).as_str());
+        }
+        if let Some(main_class) = &module.main_class {
+            self.line(format!("// main class {}", main_class).as_str());
+        }
+        let open = if module.open { "open " } else { "" };
+        self.line(format!("{}module {} {{", open, module.name).as_str());
+
+        self.depth += 1;
+        let targets = |to: &[String]| match to.is_empty() {
+            true => String::new(),
+            false => format!(" to {}", to.join(", ")),
+        };
+        let mut groups: Vec<Vec<String>> = Vec::new();
+        groups.push(
+            module
+                .requires
+                .iter()
+                .map(|requires| {
+                    let mut line = String::from("requires ");
+                    for modifier in &requires.modifiers {
+                        line.push_str(format!("{} ", modifier).as_str());
+                    }
+                    line.push_str(format!("{};", requires.name).as_str());
+                    if let Some(version) = &requires.version {
+                        line.push_str(format!(" // {}", version).as_str());
+                    }
+                    line
+                })
+                .collect(),
+        );
+        for (keyword, directives) in [("exports", &module.exports), ("opens", &module.opens)] {
+            groups.push(
+                directives
+                    .iter()
+                    .map(|directive| {
+                        format!("{} {}{};", keyword, directive.name, targets(&directive.to))
+                    })
+                    .collect(),
+            );
+        }
+        groups.push(
+            module
+                .uses
+                .iter()
+                .map(|ty| format!("uses {};", ty))
+                .collect(),
+        );
+        groups.push(
+            module
+                .provides
+                .iter()
+                .map(|(service, with)| format!("provides {} with {};", service, types(with)))
+                .collect(),
+        );
+        for (i, group) in groups.iter().filter(|group| !group.is_empty()).enumerate() {
+            if i > 0 {
+                self.output.push('\n');
+            }
+            for line in group {
+                self.line(line.as_str());
+            }
+        }
+        self.depth -= 1;
+        self.line("}");
+    }
+
+    fn type_declaration(&mut self, declaration: &TypeDeclaration) {
+        let mut header = modifiers(&declaration.modifiers);
+        header.push_str(match declaration.kind {
+            TypeKind::Class => "class ",
+            TypeKind::Interface => "interface ",
+            TypeKind::Annotation => "@interface ",
+            TypeKind::Enum => "enum ",
+        });
+        header.push_str(declaration.name.as_str());
+        if !declaration.extends.is_empty() {
+            header.push_str(format!(" extends {}", types(&declaration.extends)).as_str());
+        }
+        if !declaration.implements.is_empty() {
+            header.push_str(format!(" implements {}", types(&declaration.implements)).as_str());
+        }
+        header.push_str(" {");
+        if let Some(first) = self.aligned_line(&declaration.members) {
+            self.pack = self.current_line() >= first && self.output.ends_with(";\n");
+        }
+        self.line(header.as_str());
+
+        self.depth += 1;
+        for (i, member) in declaration.members.iter().enumerate() {
+            // fields are kept together, everything else is separated by an empty line
+            let both_fields = i > 0
+                && matches!(member, Member::Field(_))
+                && matches!(declaration.members[i - 1], Member::Field(_));
+            match self.first_line(member) {
+                Some(line) => self.pad_to(line),
+                None => match self.aligned_line(&declaration.members[i..]) {
+                    // members are packed together when they don't fit in between
+                    Some(line) => {
+                        let room = line.saturating_sub(self.current_line());
+                        let rest = declaration.members[i..]
+                            .iter()
+                            .take_while(|member| self.first_line(member).is_none())
+                            .count();
+                        let packed =
+                            i > 0 && self.first_line(&declaration.members[i - 1]).is_none();
+                        if rest > room && (packed || room == 0) {
+                            self.pack = true;
+                        } else if rest < room && i > 0 && !both_fields {
+                            self.output.push('\n');
+                        }
+                    }
+                    None if i > 0 && !both_fields => self.output.push('\n'),
+                    None => {}
+                },
+            }
+            self.member(member);
+        }
+        self.depth -= 1;
+        self.line("}");
+    }
+
+    fn member(&mut self, member: &Member) {
+        match member {
+            Member::Field(field) => {
+                let declaration =
+                    format!("{}{} {}", modifiers(&field.modifiers), field.ty, field.name);
+                match &field.value {
+                    Some(value) => {
+                        let prefix = format!("{} = ", declaration);
+                        self.initialized(prefix.as_str(), &field.ty, value);
+                    }
+                    None => self.line(format!("{};", declaration).as_str()),
+                }
+            }
+            Member::Method(method) => {
+                // a packed header goes on the last line
+                let start = self.current_line() - self.pack as usize;
+                self.method(method);
+                let name = match method.return_type {
+                    Some(_) => method.name.as_str(),
+                    None => "<init>",
+                };
+                self.map_method(name, method.descriptor.as_str(), start, &method.lines);
+            }
+            Member::Initializer(body, lines) => {
+                let start = self.current_line() - self.pack as usize;
+                self.line("static {");
+                self.body(body, lines);
+                self.line("}");
+                self.map_method("<clinit>", "()V", start, lines);
+            }
+        }
+    }
+
+    // Adds a method that was printed from the line `start` on to the source map
+    fn map_method(&mut self, name: &str, descriptor: &str, start: usize, lines: &[LineSite]) {
+        let end = self.current_line() - 1;
+        let statements = std::mem::take(&mut self.statement_maps);
+        if let Some(source_map) = &mut self.source_map {
+            source_map.methods.push(MethodMap {
+                name: name.to_string(),
+                descriptor: descriptor.to_string(),
+                lines: (start, end),
+                pcs: lines.first().zip(lines.last()).map(|(a, b)| (a.pc, b.pc)),
+                statements,
+            });
+        }
+    }
+
+    // The line the header of a member goes on to have its code on the original lines
+    fn first_line(&self, member: &Member) -> Option<usize> {
+        if self.line_numbers == LineNumbers::Ignore {
+            return None;
+        }
+        let (lines, offset) = match member {
+            // the implicit call of the super constructor is on the line of the constructor
+            Member::Method(method) if method.return_type.is_none() => {
+                (&method.lines, calls_constructor(method) as usize)
+            }
+            Member::Method(method) => (&method.lines, 1),
+            Member::Initializer(_, lines) => (lines, 1),
+            Member::Field(_) => return None,
+        };
+        let first = lines.first()?.line? as usize;
+        Some(first.saturating_sub(offset))
+    }
+
+    // The line the header of the first member with code goes on
+    fn aligned_line(&self, members: &[Member]) -> Option<usize> {
+        members.iter().find_map(|member| self.first_line(member))
+    }
+
+    // The line the next line of output goes on
+    fn current_line(&mut self) -> usize {
+        self.newlines += self.output[self.counted..].matches('\n').count();
+        self.counted = self.output.len();
+        self.newlines + 1
+    }
+
+    // Starts the next line at `line` if the output hasn't gone past it yet
+    fn pad_to(&mut self, line: usize) {
+        for _ in self.current_line()..line {
+            self.output.push('\n');
+        }
+    }
+
+    fn body(&mut self, body: &[Statement], lines: &[LineSite]) {
+        if self.line_numbers != LineNumbers::Ignore || self.source_map.is_some() {
+            self.source_lines = Some(SourceLines::new(lines.to_vec()));
+        }
+        self.block(body);
+        self.source_lines = None;
+    }
+
+    fn method(&mut self, method: &MethodDeclaration) {
+        let mut header = modifiers(&method.modifiers);
+        if let Some(return_type) = &method.return_type {
+            header.push_str(format!("{} ", return_type).as_str());
+        }
+        header.push_str(method.name.as_str());
+
+        let parameters = method
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(i, parameter)| match &parameter.ty {
+                Type::Array(ty) if method.varargs && i + 1 == method.parameters.len() => {
+                    format!("{}... {}", ty, parameter.name)
+                }
+                ty => format!("{} {}", ty, parameter.name),
+            })
+            .collect::<Vec<String>>();
+        let throws = match method.throws.is_empty() {
+            true => String::new(),
+            false => format!(" throws {}", types(&method.throws)),
+        };
+
+        let flat = format!("{}({}){} {{", header, parameters.join(", "), throws);
+        if self.depth * TAB_WIDTH + flat.len() > LINE_WIDTH && !parameters.is_empty() {
+            // one parameter per line
+            let indent = INDENT.repeat(self.depth + 2);
+            let separator = format!(",\n{}", indent);
+            header.push_str(format!("(\n{}{})", indent, parameters.join(&separator)).as_str());
+        } else {
+            header.push_str(format!("({})", parameters.join(", ")).as_str());
+        }
+        header.push_str(throws.as_str());
+
+        match &method.body {
+            None => self.line(format!("{};", header).as_str()),
+            Some(body) => {
+                self.line(format!("{} {{", header).as_str());
+                self.body(body, &method.lines);
+                self.line("}");
+            }
+        }
+    }
+
+    // Cases that each end in `yield` or `throw` are written with arrows
+    fn switch_expression(
+        &mut self,
+        prefix: &str,
+        value: &Expression,
+        cases: &[SwitchCase],
+        suffix: &str,
+    ) {
+        let prefix = format!("{}switch (", prefix);
+        self.expression_line(prefix.as_str(), value, ") {");
+        self.depth += 1;
+        let arrows = cases.iter().all(|case| {
+            matches!(
+                case.body.last(),
+                Some(Statement::Yield(_, _)) | Some(Statement::Throw(_, _))
+            )
+        });
+        for case in cases {
+            if !arrows {
+                self.case(case);
+                continue;
+            }
+            let labels = case_labels(case);
+            let label = match (case.default, labels.as_slice()) {
+                // only null may share a label with default
+                (true, [null]) if null == "null" => "case null, default ->".to_string(),
+                (true, _) => "default ->".to_string(),
+                (false, labels) => format!("case {} ->", labels.join(", ")),
+            };
+            match case.body.as_slice() {
+                [Statement::Yield(value, _)] => {
+                    self.expression_line(format!("{} ", label).as_str(), value, ";")
+                }
+                [Statement::Throw(value, _)] => {
+                    self.expression_line(format!("{} throw ", label).as_str(), value, ";")
+                }
+                body => {
+                    self.line(format!("{} {{", label).as_str());
+                    self.block(body);
+                    self.line("}");
+                }
+            }
+        }
+        self.depth -= 1;
+        self.line(format!("}}{}", suffix).as_str());
+    }
+
+    fn case(&mut self, case: &SwitchCase) {
+        let labels = case_labels(case);
+        match (case.default, labels.as_slice()) {
+            (true, [null]) if null == "null" => self.line("case null, default:"),
+            (default, labels) => {
+                for label in labels {
+                    self.line(format!("case {}:", label).as_str());
+                }
+                if default {
+                    self.line("default:");
+                }
+            }
+        }
+        self.block(&case.body);
+    }
+
+    fn block(&mut self, statements: &[Statement]) {
+        let start = self.current_line();
+        self.depth += 1;
+        for statement in statements {
+            self.statement(statement);
+        }
+        self.depth -= 1;
+        // a block that went on the line of its header is closed there too
+        if self.line_numbers != LineNumbers::Ignore
+            && !statements.is_empty()
+            && self.current_line() == start
+        {
+            self.pack = true;
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        let origin = self
+            .source_lines
+            .as_ref()
+            .and_then(|source_lines| source_lines.statement_origin(statement));
+        let line = origin.as_ref().and_then(|origin| origin.line);
+        if let (Some(line), true) = (line, self.line_numbers != LineNumbers::Ignore) {
+            self.pad_to(line as usize);
+            // statements on the line of the last one or the header of their block follow it
+            if self.current_line() == line as usize + 1
+                && (self.output.ends_with(";\n") || self.output.ends_with("{\n"))
+            {
+                self.pack = true;
+            } else if self.current_line() > line as usize
+                && self.line_numbers == LineNumbers::AlignOrComment
+            {
+                self.line_comment = Some(format!("/* {} */ ", line));
+            }
+        }
+        // nested statements come after the ones they are in
+        let start = self.current_line() - self.pack as usize;
+        let index = self.statement_maps.len();
+        if let (Some(origin), true) = (&origin, self.source_map.is_some()) {
+            self.statement_maps.push(StatementMap {
+                lines: (start, start),
+                pcs: origin.pcs,
+                line,
+            });
+        }
+        match statement {
+            Statement::Expression(expression, _) => self.expression_line("", expression, ";"),
+            Statement::Declaration {
+                ty,
+                local,
+                value: Some(value),
+                ..
+            } => self.initialized(format!("{} {} = ", ty, local.name).as_str(), ty, value),
+            Statement::Declaration {
+                ty,
+                local,
+                value: None,
+                ..
+            } => self.line(format!("{} {};", ty, local.name).as_str()),
+            Statement::Return(None, _) => self.line("return;"),
+            Statement::Return(Some(value), _) => self.expression_line("return ", value, ";"),
+            Statement::Yield(value, _) => self.expression_line("yield ", value, ";"),
+            Statement::Throw(value, _) => self.expression_line("throw ", value, ";"),
+            Statement::Assert {
+                condition,
+                message: None,
+                ..
+            } => self.expression_line("assert ", condition, ";"),
+            Statement::Assert {
+                condition,
+                message: Some(message),
+                ..
+            } => {
+                let prefix = format!("assert {} : ", expression(condition));
+                self.expression_line(prefix.as_str(), message, ";");
+            }
+            Statement::MonitorEnter(value, _) => {
+                self.expression_line("// monitorenter(", value, ");")
+            }
+            Statement::MonitorExit(value, _) => {
+                self.expression_line("// monitorexit(", value, ");")
+            }
+            Statement::Comment(comment) => self.line(format!("// {}", comment).as_str()),
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+                ..
+            } => {
+                self.expression_line("if (", condition, ") {");
+                self.block(then);
+                let mut otherwise = otherwise;
+                // else-if chains stay flat
+                while let Some(statements) = otherwise {
+                    match statements.as_slice() {
+                        [Statement::If {
+                            condition,
+                            then,
+                            otherwise: next,
+                            ..
+                        }] => {
+                            self.expression_line("} else if (", condition, ") {");
+                            self.block(then);
+                            otherwise = next;
+                        }
+                        _ => {
+                            self.line("} else {");
+                            self.block(statements);
+                            break;
+                        }
+                    }
+                }
+                self.line("}");
+            }
+            Statement::While {
+                condition, body, ..
+            } => {
+                self.expression_line("while (", condition, ") {");
+                self.block(body);
+                self.line("}");
+            }
+            Statement::DoWhile {
+                body, condition, ..
+            } => {
+                self.line("do {");
+                self.block(body);
+                self.expression_line("} while (", condition, ");");
+            }
+            Statement::For {
+                condition,
+                update,
+                body,
+                ..
+            } => {
+                let update = update
+                    .iter()
+                    .map(expression)
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                let header = match condition {
+                    Some(condition) => format!("for (; {}; {}) {{", expression(condition), update),
+                    None => format!("for (;; {}) {{", update),
+                };
+                self.line(header.as_str());
+                self.block(body);
+                self.line("}");
+            }
+            Statement::ForEach {
+                ty,
+                local,
+                iterable,
+                body,
+                ..
+            } => {
+                let prefix = format!("for ({} {} : ", ty, local.name);
+                self.expression_line(prefix.as_str(), iterable, ") {");
+                self.block(body);
+                self.line("}");
+            }
+            Statement::Switch { value, cases, .. } => {
+                self.expression_line("switch (", value, ") {");
+                self.depth += 1;
+                for case in cases {
+                    self.case(case);
+                }
+                self.depth -= 1;
+                self.line("}");
+            }
+            Statement::Try {
+                resources,
+                body,
+                catches,
+                finally,
+            } => {
+                if resources.is_empty() {
+                    self.line("try {");
+                } else {
+                    let resources: Vec<String> = resources
+                        .iter()
+                        .map(|resource| match resource {
+                            Statement::Declaration {
+                                ty,
+                                local,
+                                value: Some(value),
+                                ..
+                            } => format!("{} {} = {}", ty, local.name, expression(value)),
+                            _ => String::new(),
+                        })
+                        .collect();
+                    let mut text = resources.join("; ");
+                    if self.depth * TAB_WIDTH + text.len() + 9 > LINE_WIDTH {
+                        // one resource per line
+                        let separator = format!(";\n{}", INDENT.repeat(self.depth + 2));
+                        text = resources.join(separator.as_str());
+                    }
+                    self.line(format!("try ({}) {{", text).as_str());
+                }
+                self.block(body);
+                for catch in catches {
+                    let types = catch
+                        .types
+                        .iter()
+                        .map(|ty| ty.to_string())
+                        .collect::<Vec<String>>()
+                        .join(" | ");
+                    self.line(format!("}} catch ({} {}) {{", types, catch.local.name).as_str());
+                    self.block(&catch.body);
+                }
+                if let Some(finally) = finally {
+                    self.line("} finally {");
+                    self.block(finally);
+                }
+                self.line("}");
+            }
+            Statement::Synchronized { lock, body, .. } => {
+                self.expression_line("synchronized (", lock, ") {");
+                self.block(body);
+                self.line("}");
+            }
+            Statement::Labeled { label, body } => {
+                self.label = Some(label.clone());
+                self.statement(body);
+            }
+            Statement::Break(None, _) => self.line("break;"),
+            Statement::Break(Some(label), _) => self.line(format!("break {};", label).as_str()),
+            Statement::Continue(None, _) => self.line("continue;"),
+            Statement::Continue(Some(label), _) => {
+                self.line(format!("continue {};", label).as_str())
+            }
+            Statement::Label(pc) => self.line(format!("L{}:", pc).as_str()),
+            Statement::Goto(pc) => self.line(format!("goto L{};", pc).as_str()),
+        }
+        if let (Some(_), true) = (&origin, self.source_map.is_some()) {
+            self.statement_maps[index].lines.1 = self.current_line() - 1;
+        }
+    }
+}
+
+// The constants and the pattern of a case, without `default`
+fn case_labels(case: &SwitchCase) -> Vec<String> {
+    let mut labels: Vec<String> = case
+        .labels
+        .iter()
+        .map(|label| match label {
+            // enum constants are named without their class
+            Expression::StaticField { name, .. } => name.clone(),
+            label => expression(label),
+        })
+        .collect();
+    if let Some(pattern) = &case.pattern {
+        let mut label = pattern_text(&pattern.ty, &pattern.binding);
+        if let Some(guard) = &pattern.guard {
+            label.push_str(format!(" when {}", expression(guard)).as_str());
+        }
+        labels.push(label);
+    }
+    labels
+}
+
+// `T name` or `R(int a, Q(String s))`
+fn pattern_text(ty: &Type, binding: &Binding) -> String {
+    match binding {
+        Binding::Variable(local) => format!("{} {}", ty, local.name),
+        Binding::Record(components) => format!(
+            "{}({})",
+            ty,
+            components
+                .iter()
+                .map(|(ty, binding)| pattern_text(ty, binding))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    }
+}
+
+fn modifiers(modifiers: &[String]) -> String {
+    modifiers
+        .iter()
+        .map(|modifier| format!("{} ", modifier))
+        .collect()
+}
+
+fn types(types: &[Type]) -> String {
+    types
+        .iter()
+        .map(|ty| ty.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+const PRIMARY: u8 = 15;
+const POSTFIX: u8 = 14;
+const UNARY: u8 = 13;
+const RELATIONAL: u8 = 9;
+const CONDITIONAL: u8 = 2;
+const ASSIGNMENT: u8 = 1;
+
+// Higher binds tighter, as in the Java grammar
+fn precedence(expression: &Expression) -> u8 {
+    match expression {
+        Expression::Assign { .. } | Expression::Lambda { .. } | Expression::LambdaBlock { .. } => {
+            ASSIGNMENT
+        }
+        Expression::Binary { operator, .. }
+        | Expression::Compare {
+            operator: Some(operator),
+            ..
+        } => binary_precedence(*operator),
+        Expression::InstanceOf { .. } => RELATIONAL,
+        Expression::Unary { .. }
+        | Expression::Cast { .. }
+        | Expression::Increment { prefix: true, .. } => UNARY,
+        Expression::Increment { prefix: false, .. } => POSTFIX,
+        Expression::Conditional { .. } => CONDITIONAL,
+        _ => PRIMARY,
+    }
+}
+
+fn binary_precedence(operator: BinaryOperator) -> u8 {
+    match operator {
+        BinaryOperator::ConditionalOr => 3,
+        BinaryOperator::ConditionalAnd => 4,
+        BinaryOperator::Or => 5,
+        BinaryOperator::Xor => 6,
+        BinaryOperator::And => 7,
+        BinaryOperator::Eq | BinaryOperator::Ne => 8,
+        BinaryOperator::Lt | BinaryOperator::Ge | BinaryOperator::Gt | BinaryOperator::Le => {
+            RELATIONAL
+        }
+        BinaryOperator::Shl | BinaryOperator::Shr | BinaryOperator::UShr => 10,
+        BinaryOperator::Add | BinaryOperator::Sub => 11,
+        BinaryOperator::Mul | BinaryOperator::Div | BinaryOperator::Rem => 12,
+    }
+}
+
+fn is_primitive(ty: &Type) -> bool {
+    !matches!(ty, Type::Class(_) | Type::Array(_) | Type::Unknown)
+}
+
+pub fn expression(expression: &Expression) -> String {
+    expression_at(expression, 0)
+}
+
+// Prints the expression where at least `precedence` is needed without parentheses
+fn expression_at(expression: &Expression, minimum: u8) -> String {
+    let text = unparenthesized(expression);
+    if precedence(expression) < minimum {
+        format!("({})", text)
+    } else {
+        text
+    }
+}
+
+// `new int[]` in front of an array initializer
+fn array_type(array: &Expression) -> String {
+    match array {
+        Expression::NewArray { ty, extra, .. } => format!("new {}{}", ty, "[]".repeat(extra + 1)),
+        _ => String::new(),
+    }
+}
+
+// The elements of an array of arrays don't repeat their type
+fn initializer(elements: &[Expression], nested: bool) -> String {
+    let elements: Vec<String> = elements
+        .iter()
+        .map(|element| match element {
+            Expression::NewArray {
+                elements: Some(elements),
+                extra,
+                ..
+            } if nested => initializer(elements, *extra > 0),
+            element => expression(element),
+        })
+        .collect();
+    format!("{{{}}}", elements.join(", "))
+}
+
+fn arguments(arguments: &[Expression]) -> String {
+    arguments
+        .iter()
+        .map(expression)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// Everything in front of the argument list of a call
+fn callee(call: &Expression) -> String {
+    match call {
+        Expression::Invoke {
+            target,
+            class,
+            name,
+            ..
+        } => match target.as_deref() {
+            Some(target @ Expression::This) | Some(target @ Expression::Super)
+                if name == "<init>" =>
+            {
+                unparenthesized(target)
+            }
+            Some(target) => format!("{}.{}", expression_at(target, PRIMARY), name),
+            None => format!("{}.{}", class, name),
+        },
+        Expression::New { class, .. } => format!("new {}", class),
+        _ => unreachable!(),
+    }
+}
+
+fn unparenthesized(value: &Expression) -> String {
+    match value {
+        Expression::Literal(literal) => literal.to_string(),
+        Expression::Local(local) => local.name.clone(),
+        Expression::This => "this".to_string(),
+        Expression::Super => "super".to_string(),
+        Expression::CaughtException => "$exception".to_string(),
+        Expression::Uninitialized(_) => "$uninitialized".to_string(),
+        Expression::Field { target, name } => {
+            format!("{}.{}", expression_at(target, PRIMARY), name)
+        }
+        Expression::StaticField { class, name } => format!("{}.{}", class, name),
+        Expression::ArrayElement { array, index } => {
+            // `new int[1][0]` would create a two dimensional array
+            let array = match array.as_ref() {
+                Expression::NewArray { .. } => format!("({})", unparenthesized(array)),
+                array => expression_at(array, PRIMARY),
+            };
+            format!("{}[{}]", array, expression(index))
+        }
+        Expression::ArrayLength(array) => format!("{}.length", expression_at(array, PRIMARY)),
+        Expression::Unary { operator, operand } => {
+            let mut operand = expression_at(operand, UNARY);
+            // `- -x` must not turn into `--x`
+            if operand.starts_with(operator.symbol()) {
+                operand = format!("({})", operand);
+            }
+            format!("{}{}", operator.symbol(), operand)
+        }
+        Expression::Binary {
+            operator,
+            left,
+            right,
+        }
+        | Expression::Compare {
+            operator: Some(operator),
+            left,
+            right,
+            ..
+        } => {
+            let precedence = binary_precedence(*operator);
+            format!(
+                "{} {} {}",
+                expression_at(left, precedence),
+                operator.symbol(),
+                expression_at(right, precedence + 1)
+            )
+        }
+        Expression::Compare {
+            kind: CompareKind::LCmp,
+            left,
+            right,
+            ..
+        } => format!(
+            "java.lang.Long.compare({}, {})",
+            expression(left),
+            expression(right)
+        ),
+        Expression::Compare {
+            kind, left, right, ..
+        } => format!(
+            "{}({}, {})",
+            kind.name(),
+            expression(left),
+            expression(right)
+        ),
+        Expression::Cast {
+            ty,
+            expression: value,
+            ..
+        } => {
+            let mut value = expression_at(value, UNARY);
+            // `(Integer) -x` would be read as a subtraction
+            if !is_primitive(ty) && (value.starts_with('-') || value.starts_with('+')) {
+                value = format!("({})", value);
+            }
+            format!("({}) {}", ty, value)
+        }
+        Expression::InstanceOf {
+            expression: value,
+            ty,
+            binding,
+        } => {
+            let pattern = match binding {
+                Some(binding) => pattern_text(ty, binding),
+                None => ty.to_string(),
+            };
+            format!(
+                "{} instanceof {}",
+                expression_at(value, RELATIONAL),
+                pattern
+            )
+        }
+        Expression::Invoke {
+            arguments: values, ..
+        }
+        | Expression::New {
+            arguments: values, ..
+        } => {
+            format!("{}({})", callee(value), arguments(values))
+        }
+        Expression::NewArray {
+            elements: Some(elements),
+            extra,
+            ..
+        } => format!("{}{}", array_type(value), initializer(elements, *extra > 0)),
+        Expression::NewArray {
+            ty,
+            dimensions,
+            extra,
+            elements: None,
+        } => {
+            let mut text = format!("new {}", ty);
+            for dimension in dimensions {
+                text.push_str(format!("[{}]", expression(dimension)).as_str());
+            }
+            text.push_str("[]".repeat(*extra).as_str());
+            text
+        }
+        Expression::Assign {
+            target,
+            operator,
+            value,
+        } => format!(
+            "{} {}= {}",
+            expression_at(target, PRIMARY),
+            operator.map(|operator| operator.symbol()).unwrap_or(""),
+            expression_at(value, ASSIGNMENT)
+        ),
+        Expression::Increment {
+            target,
+            operator,
+            prefix,
+        } => {
+            let target = expression_at(target, PRIMARY);
+            let symbol = operator.symbol().repeat(2);
+            if *prefix {
+                format!("{}{}", symbol, target)
+            } else {
+                format!("{}{}", target, symbol)
+            }
+        }
+        Expression::Conditional {
+            condition,
+            then,
+            otherwise,
+        } => format!(
+            "{} ? {} : {}",
+            expression_at(condition, CONDITIONAL + 1),
+            expression_at(then, CONDITIONAL),
+            expression_at(otherwise, CONDITIONAL)
+        ),
+        Expression::Lambda { parameters, body } => format!(
+            "({}) -> {}",
+            parameters.join(", "),
+            expression_at(body, ASSIGNMENT)
+        ),
+        Expression::LambdaBlock { parameters, body } => {
+            let mut printer = Printer::default();
+            printer.block(body);
+            let lines: Vec<&str> = printer.output.lines().map(str::trim).collect();
+            match lines.is_empty() {
+                true => format!("({}) -> {{}}", parameters.join(", ")),
+                false => format!("({}) -> {{ {} }}", parameters.join(", "), lines.join(" ")),
+            }
+        }
+        // statements print switch expressions over several lines, anywhere else it's one
+        Expression::Switch { value, cases } => {
+            let mut printer = Printer::default();
+            printer.switch_expression("", value, cases, "");
+            let lines: Vec<&str> = printer.output.lines().map(str::trim).collect();
+            lines.join(" ")
+        }
+    }
+}
+
+// Prints the expression, breaking it over several lines if it doesn't fit into what is
+// left of the line. Continuation lines are indented by `depth` tabs.
+fn wrap(value: &Expression, depth: usize, used: usize) -> String {
+    let flat = expression(value);
+    let continuation = depth * TAB_WIDTH;
+    // deeply nested code has no room left to wrap into
+    if used + flat.len() <= LINE_WIDTH || continuation > LINE_WIDTH / 2 {
+        return flat;
+    }
+
+    let indent = INDENT.repeat(depth);
+    match value {
+        Expression::Assign {
+            target,
+            operator,
+            value,
+        } => {
+            let prefix = format!(
+                "{} {}= ",
+                expression_at(target, PRIMARY),
+                operator.map(|operator| operator.symbol()).unwrap_or("")
+            );
+            let value = match value.as_ref() {
+                Expression::NewArray {
+                    elements: Some(_), ..
+                } => wrap(value, depth, used + prefix.len()),
+                value => wrap_at(value, ASSIGNMENT, depth, used + prefix.len()),
+            };
+            format!("{}{}", prefix, value)
+        }
+        Expression::Binary { operator, .. } => {
+            // one operand of a chain like `a + b + c` per line
+            let precedence = binary_precedence(*operator);
+            let mut operands = Vec::new();
+            let mut current = value;
+            while let Expression::Binary {
+                operator: next,
+                left,
+                right,
+            } = current
+            {
+                if next != operator {
+                    break;
+                }
+                operands.push((right.as_ref(), precedence + 1));
+                current = left;
+            }
+            operands.push((current, precedence));
+            operands.reverse();
+
+            let symbol = format!("{} ", operator.symbol());
+            let mut text = wrap_at(operands[0].0, operands[0].1, depth, used);
+            for (operand, minimum) in &operands[1..] {
+                let operand = wrap_at(operand, *minimum, depth + 1, continuation + symbol.len());
+                text.push_str(format!("\n{}{}{}", indent, symbol, operand).as_str());
+            }
+            text
+        }
+        Expression::Conditional {
+            condition,
+            then,
+            otherwise,
+        } => format!(
+            "{}\n{}? {}\n{}: {}",
+            wrap_at(condition, CONDITIONAL + 1, depth, used),
+            indent,
+            wrap_at(then, CONDITIONAL, depth + 1, continuation + 2),
+            indent,
+            wrap_at(otherwise, CONDITIONAL, depth + 1, continuation + 2)
+        ),
+        Expression::Cast {
+            ty,
+            expression: value,
+            ..
+        } if !flat.starts_with(format!("({}) (", ty).as_str()) => {
+            let prefix = format!("({}) ", ty);
+            let value = wrap_at(value, UNARY, depth, used + prefix.len());
+            format!("{}{}", prefix, value)
+        }
+        Expression::NewArray {
+            elements: Some(elements),
+            extra,
+            ..
+        } => {
+            let prefix = array_type(value);
+            let used = used + prefix.len();
+            let text = wrap_initializer(elements, *extra > 0, depth, used);
+            format!("{}{}", prefix, text)
+        }
+        Expression::Invoke {
+            target: Some(target),
+            name,
+            arguments,
+            ..
+        } if is_chained(target) => {
+            // one call of a chain per line
+            let mut calls = vec![(name, arguments)];
+            let mut base = target.as_ref();
+            while let Expression::Invoke {
+                target: Some(target),
+                name,
+                arguments,
+                ..
+            } = base
+            {
+                if name == "<init>" {
+                    break;
+                }
+                calls.push((name, arguments));
+                base = target;
+            }
+            calls.reverse();
+
+            let mut text = match precedence(base) >= PRIMARY {
+                true => wrap(base, depth, used),
+                false => format!("({})", expression(base)),
+            };
+            for (name, arguments) in calls {
+                let callee = format!(".{}", name);
+                let call = wrap_arguments(callee, arguments, depth + 1, continuation);
+                text.push_str(format!("\n{}{}", indent, call).as_str());
+            }
+            text
+        }
+        Expression::Invoke { arguments, .. } | Expression::New { arguments, .. } => {
+            wrap_arguments(callee(value), arguments, depth, used)
+        }
+        _ => flat,
+    }
+}
+
+// Like `wrap`, but parenthesized where at least `minimum` precedence is needed
+fn wrap_at(value: &Expression, minimum: u8, depth: usize, used: usize) -> String {
+    if precedence(value) < minimum {
+        format!("({})", wrap(value, depth, used + 2))
+    } else {
+        wrap(value, depth, used)
+    }
+}
+
+// Whether the constructor starts with an explicit `this(...)` or `super(...)`
+fn calls_constructor(method: &MethodDeclaration) -> bool {
+    matches!(
+        method.body.as_deref(),
+        Some([Statement::Expression(Expression::Invoke { target: Some(target), name, .. }, _), ..])
+            if name == "<init>" && matches!(target.as_ref(), Expression::This | Expression::Super)
+    )
+}
+
+// Whether a call on `target` continues a chain of calls
+fn is_chained(target: &Expression) -> bool {
+    match target {
+        Expression::Invoke {
+            target: Some(target),
+            name,
+            ..
+        } => name != "<init>" || !matches!(target.as_ref(), Expression::This | Expression::Super),
+        Expression::Invoke { .. } | Expression::New { .. } => true,
+        _ => false,
+    }
+}
+
+// A call with its arguments on their own lines if it doesn't fit
+fn wrap_arguments(callee: String, values: &[Expression], depth: usize, used: usize) -> String {
+    let flat = format!("{}({})", callee, arguments(values));
+    if used + flat.len() <= LINE_WIDTH || values.is_empty() {
+        return flat;
+    }
+
+    let indent = INDENT.repeat(depth);
+    let continuation = depth * TAB_WIDTH;
+    let mut text = format!("{}(", callee);
+    for (i, argument) in values.iter().enumerate() {
+        let separator = if i + 1 == values.len() { ")" } else { "," };
+        text.push_str(
+            format!(
+                "\n{}{}{}",
+                indent,
+                wrap(argument, depth + 1, continuation + separator.len()),
+                separator
+            )
+            .as_str(),
+        );
+    }
+    text
+}
+
+// As many elements per line as fit, nested initializers that don't fit are wrapped themselves
+fn wrap_initializer(elements: &[Expression], nested: bool, depth: usize, used: usize) -> String {
+    let flat = initializer(elements, nested);
+    if used + flat.len() <= LINE_WIDTH {
+        return flat;
+    }
+
+    let indent = INDENT.repeat(depth);
+    let continuation = depth * TAB_WIDTH;
+    let mut text = "{".to_string();
+    let mut width = LINE_WIDTH;
+    for (i, element) in elements.iter().enumerate() {
+        let separator = if i + 1 == elements.len() { "}" } else { "," };
+        let element = match element {
+            Expression::NewArray {
+                elements: Some(elements),
+                extra,
+                ..
+            } if nested => {
+                let used = continuation + separator.len();
+                wrap_initializer(elements, *extra > 0, depth + 1, used)
+            }
+            element => wrap(element, depth + 1, continuation + separator.len()),
+        };
+        let length = element.len() + separator.len();
+        if width + 1 + length <= LINE_WIDTH && !element.contains('\n') {
+            text.push(' ');
+            width += 1 + length;
+        } else {
+            text.push_str(format!("\n{}", indent).as_str());
+            width = continuation + length;
+        }
+        text.push_str(element.as_str());
+        text.push_str(separator);
+    }
+    text
+}
@@ -0,0 +1,125 @@
+use super::instruction::{DecodedInstruction, Instruction};
+use super::{CPIndexType, ClassFile, CodeAttribute, ConstantPool};
+use std::collections::HashMap;
+use std::io::BufReader;
+
+// The arrays javac switches on instead of the ordinals of an enum, by the class and name of their
+// field, with the constant of each case value
+pub type SwitchMaps = HashMap<Reference, HashMap<i32, Reference>>;
+
+// The class, in source form, and name of a field or method
+pub type Reference = (String, String);
+
+// javac fills each `$SwitchMap$` array in the static initializer of a synthetic class, one
+// `map[Enum.CONSTANT.ordinal()] = value` per constant
+pub fn read_switch_maps(classes: &[ClassFile]) -> SwitchMaps {
+    let mut maps = SwitchMaps::new();
+    for class_file in classes {
+        let constant_pool = &class_file.constant_pool;
+        for method_info in &class_file.methods.methods {
+            if string(method_info.name_index, constant_pool).as_deref() != Some("<clinit>") {
+                continue;
+            }
+            let instructions = match code(&method_info.attributes, constant_pool) {
+                Some(instructions) => instructions,
+                None => continue,
+            };
+            for window in instructions.windows(5) {
+                if let Some((map, value, constant)) = assignment(window, constant_pool) {
+                    maps.entry(map).or_default().insert(value, constant);
+                }
+            }
+        }
+    }
+    maps
+}
+
+fn assignment(
+    window: &[DecodedInstruction],
+    constant_pool: &ConstantPool,
+) -> Option<(Reference, i32, Reference)> {
+    match [
+        &window[0].instruction,
+        &window[1].instruction,
+        &window[2].instruction,
+        &window[3].instruction,
+        &window[4].instruction,
+    ] {
+        [Instruction::GetStatic(map), Instruction::GetStatic(constant), Instruction::InvokeVirtual(ordinal), value, Instruction::IAStore] =>
+        {
+            let map = member(*map, constant_pool)?;
+            let constant = member(*constant, constant_pool)?;
+            let ordinal = member(*ordinal, constant_pool)?;
+            if !map.1.starts_with("$SwitchMap$") || ordinal.1 != "ordinal" {
+                return None;
+            }
+            Some((map, int(value, constant_pool)?, constant))
+        }
+        _ => None,
+    }
+}
+
+fn code(
+    attributes: &[crate::disassembler::attribute::AttributeInfo],
+    constant_pool: &ConstantPool,
+) -> Option<Vec<DecodedInstruction>> {
+    let attribute = attributes
+        .iter()
+        .find(|attribute| attribute.get_name() == "Code")?;
+    let code = CodeAttribute::new(&attribute.info, constant_pool).ok()?;
+    super::instruction::parse(BufReader::new(code.code.as_slice()), code.code.len()).ok()
+}
+
+fn int(instruction: &Instruction, constant_pool: &ConstantPool) -> Option<i32> {
+    match instruction {
+        Instruction::IConstM1 => Some(-1),
+        Instruction::IConst0 => Some(0),
+        Instruction::IConst1 => Some(1),
+        Instruction::IConst2 => Some(2),
+        Instruction::IConst3 => Some(3),
+        Instruction::IConst4 => Some(4),
+        Instruction::IConst5 => Some(5),
+        Instruction::BiPush(value) => Some(*value as i8 as i32),
+        Instruction::SiPush(value) => Some(*value as i16 as i32),
+        Instruction::Ldc(index) => int_constant(*index as u16, constant_pool),
+        Instruction::LdcW(index) => int_constant(*index, constant_pool),
+        _ => None,
+    }
+}
+
+fn int_constant(index: u16, constant_pool: &ConstantPool) -> Option<i32> {
+    match constant_pool.get(index)? {
+        CPIndexType::Integer(value) => Some(*value as i32),
+        _ => None,
+    }
+}
+
+fn member(index: u16, constant_pool: &ConstantPool) -> Option<Reference> {
+    let (class_index, name_and_type_index) = match constant_pool.get(index)? {
+        CPIndexType::FieldRef {
+            class_index,
+            name_and_type_index,
+        }
+        | CPIndexType::MethodRef {
+            class_index,
+            name_and_type_index,
+        } => (*class_index, *name_and_type_index),
+        _ => return None,
+    };
+    let class = match constant_pool.get(class_index)? {
+        CPIndexType::Class(name_index) => string(*name_index, constant_pool)?,
+        _ => return None,
+    };
+    let name = match constant_pool.get(name_and_type_index)? {
+        CPIndexType::NameAndType { name_index, .. } => string(*name_index, constant_pool)?,
+        _ => return None,
+    };
+    Some((class.replace('/', "."), name))
+}
+
+fn string(index: u16, constant_pool: &ConstantPool) -> Option<String> {
+    match constant_pool.get(index)? {
+        CPIndexType::Utf8(utf8) => Some(utf8.string.clone()),
+        _ => None,
+    }
+}
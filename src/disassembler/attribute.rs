@@ -1,3 +1,7 @@
+use super::code::ByteReader;
+
+use std::io;
+
 #[derive(Debug)]
 pub struct AttributePool {
     pub attribute_count: u16,
@@ -16,3 +20,168 @@ pub struct AttributeNameIndex {
     pub value: super::constant_pool::CPIndexType,
     pub index: u16,
 }
+
+impl AttributeInfo {
+    pub fn get_name(&self) -> &str {
+        match &self.attribute_name_index.value {
+            super::constant_pool::CPIndexType::Utf8(name) => name.string.as_str(),
+            _ => panic!("Invalid Type in Constant Pool"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BootstrapMethod {
+    pub method_ref: u16,
+    pub arguments: Vec<u16>,
+}
+
+// Reads the entries of the class' BootstrapMethods attribute, if there is one
+pub fn read_bootstrap_methods(attributes: &[AttributeInfo]) -> io::Result<Vec<BootstrapMethod>> {
+    let attribute = match attributes
+        .iter()
+        .find(|attribute| attribute.get_name() == "BootstrapMethods")
+    {
+        Some(attribute) => attribute,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut reader = ByteReader {
+        buf: &attribute.info,
+        index: 0,
+    };
+
+    let mut bootstrap_methods = Vec::new();
+    for _i in 0..reader.read_u16()? {
+        let method_ref = reader.read_u16()?;
+        let mut arguments = Vec::new();
+        for _j in 0..reader.read_u16()? {
+            arguments.push(reader.read_u16()?);
+        }
+
+        bootstrap_methods.push(BootstrapMethod {
+            method_ref,
+            arguments,
+        });
+    }
+
+    Ok(bootstrap_methods)
+}
+
+// An entry of the InnerClasses attribute, the outer class and the name are 0 for local and
+// anonymous classes
+#[derive(Debug, Clone)]
+pub struct InnerClass {
+    pub inner_class: u16,
+    pub outer_class: u16,
+    pub inner_name: u16,
+    pub flags: u16,
+}
+
+// Reads the entries of the class' InnerClasses attribute, if there is one
+pub fn read_inner_classes(attributes: &[AttributeInfo]) -> io::Result<Vec<InnerClass>> {
+    let attribute = match attributes
+        .iter()
+        .find(|attribute| attribute.get_name() == "InnerClasses")
+    {
+        Some(attribute) => attribute,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut reader = ByteReader {
+        buf: &attribute.info,
+        index: 0,
+    };
+
+    let mut inner_classes = Vec::new();
+    for _i in 0..reader.read_u16()? {
+        inner_classes.push(InnerClass {
+            inner_class: reader.read_u16()?,
+            outer_class: reader.read_u16()?,
+            inner_name: reader.read_u16()?,
+            flags: reader.read_u16()?,
+        });
+    }
+
+    Ok(inner_classes)
+}
+
+// A `requires`, `exports` or `opens` directive, `targets` are the `to` modules or the version
+// of a required module
+#[derive(Debug, Clone)]
+pub struct ModuleDirective {
+    pub index: u16,
+    pub flags: u16,
+    pub targets: Vec<u16>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModuleAttribute {
+    pub name: u16,
+    pub flags: u16,
+    pub version: u16,
+    pub requires: Vec<ModuleDirective>,
+    pub exports: Vec<ModuleDirective>,
+    pub opens: Vec<ModuleDirective>,
+    pub uses: Vec<u16>,
+    // the services with their implementations
+    pub provides: Vec<(u16, Vec<u16>)>,
+}
+
+// Reads the Module attribute of a module-info class
+pub fn read_module(attributes: &[AttributeInfo]) -> io::Result<Option<ModuleAttribute>> {
+    let attribute = match attributes
+        .iter()
+        .find(|attribute| attribute.get_name() == "Module")
+    {
+        Some(attribute) => attribute,
+        None => return Ok(None),
+    };
+
+    let reader = &mut ByteReader {
+        buf: &attribute.info,
+        index: 0,
+    };
+    let indexes = |reader: &mut ByteReader| -> io::Result<Vec<u16>> {
+        (0..reader.read_u16()?).map(|_| reader.read_u16()).collect()
+    };
+    let packages = |reader: &mut ByteReader| -> io::Result<Vec<ModuleDirective>> {
+        (0..reader.read_u16()?)
+            .map(|_| {
+                Ok(ModuleDirective {
+                    index: reader.read_u16()?,
+                    flags: reader.read_u16()?,
+                    targets: indexes(reader)?,
+                })
+            })
+            .collect()
+    };
+
+    let (name, flags, version) = (reader.read_u16()?, reader.read_u16()?, reader.read_u16()?);
+    let requires = (0..reader.read_u16()?)
+        .map(|_| {
+            Ok(ModuleDirective {
+                index: reader.read_u16()?,
+                flags: reader.read_u16()?,
+                targets: vec![reader.read_u16()?],
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    let exports = packages(reader)?;
+    let opens = packages(reader)?;
+    let uses = indexes(reader)?;
+    let provides = (0..reader.read_u16()?)
+        .map(|_| Ok((reader.read_u16()?, indexes(reader)?)))
+        .collect::<io::Result<_>>()?;
+
+    Ok(Some(ModuleAttribute {
+        name,
+        flags,
+        version,
+        requires,
+        exports,
+        opens,
+        uses,
+        provides,
+    }))
+}
@@ -6,6 +6,7 @@ use super::method::*;
 
 use super::ClassFile;
 
+use std::io;
 use std::io::Read;
 pub struct ClassFileBuilder<'a> {
     pub buf: &'a [u8],
@@ -13,83 +14,90 @@ pub struct ClassFileBuilder<'a> {
 }
 
 impl<'a> ClassFileBuilder<'a> {
-    pub fn parse(mut self) -> ClassFile {
-        let header = (self.read_u32(), self.read_u16(), self.read_u16());
-        self.constant_pool = self.read_constant_pool();
-        ClassFile {
+    pub fn parse(mut self) -> io::Result<ClassFile> {
+        let header = (self.read_u32()?, self.read_u16()?, self.read_u16()?);
+        self.constant_pool = self.read_constant_pool()?;
+        Ok(ClassFile {
             magic: header.0,
             minor: header.1,
             major: header.2,
-            access_flags: self.read_u16(),
-            this_class: self.read_u16(),
-            super_class: self.read_u16(),
-            interfaces: self.read_interface_pool(),
-            fields: self.read_field_pool(),
-            methods: self.read_method_pool(),
-            attributes: self.read_attribute_pool(),
+            access_flags: self.read_u16()?,
+            this_class: self.read_u16()?,
+            super_class: self.read_u16()?,
+            interfaces: self.read_interface_pool()?,
+            fields: self.read_field_pool()?,
+            methods: self.read_method_pool()?,
+            attributes: self.read_attribute_pool()?,
             constant_pool: self.constant_pool,
-        }
+        })
     }
 }
 
 // value read methods
 impl<'a> ClassFileBuilder<'a> {
-    pub fn read_constant_pool(&mut self) -> ConstantPool {
-        let index_count = self.read_u16();
+    pub fn read_constant_pool(&mut self) -> io::Result<ConstantPool> {
+        let index_count = self.read_u16()?;
         let mut indexes = Vec::new();
         let mut push_twice = false;
         let mut i = 1;
         while i < index_count {
-            indexes.push(match self.read_u8() {
+            indexes.push(match self.read_u8()? {
                 1 => {
-                    let mut buffer = vec![0_u8; self.read_u16() as usize];
-                    self.buf.read_exact(&mut buffer).unwrap();
-                    CPIndexType::Utf8(String::from_utf8(buffer).expect("Invalid UTF8 String"))
+                    let mut buffer = vec![0_u8; self.read_u16()? as usize];
+                    self.buf.read_exact(&mut buffer)?;
+                    CPIndexType::Utf8(Utf8String::from_bytes(buffer)?)
                 }
-                3 => CPIndexType::Integer(self.read_u32()),
-                4 => CPIndexType::Float(self.read_u32() as f32),
+                3 => CPIndexType::Integer(self.read_u32()?),
+                4 => CPIndexType::Float(f32::from_bits(self.read_u32()?)),
                 5 => {
                     push_twice = true;
                     CPIndexType::Long({
                         let mut buffer = [0; 8];
-                        self.buf.read_exact(&mut buffer).unwrap();
+                        self.buf.read_exact(&mut buffer)?;
                         u64::from_be_bytes(buffer)
                     })
                 }
                 6 => {
                     push_twice = true;
-                    CPIndexType::Double(self.read_f64())
+                    CPIndexType::Double(self.read_f64()?)
                 }
-                7 => CPIndexType::Class(self.read_u16()),
-                8 => CPIndexType::String(self.read_u16()),
+                7 => CPIndexType::Class(self.read_u16()?),
+                8 => CPIndexType::String(self.read_u16()?),
                 9 => CPIndexType::FieldRef {
-                    class_index: self.read_u16(),
-                    name_and_type_index: self.read_u16(),
+                    class_index: self.read_u16()?,
+                    name_and_type_index: self.read_u16()?,
                 },
                 10 => CPIndexType::MethodRef {
-                    class_index: self.read_u16(),
-                    name_and_type_index: self.read_u16(),
+                    class_index: self.read_u16()?,
+                    name_and_type_index: self.read_u16()?,
                 },
                 11 => CPIndexType::InterfaceMethodRef {
-                    class_index: self.read_u16(),
-                    name_and_type_index: self.read_u16(),
+                    class_index: self.read_u16()?,
+                    name_and_type_index: self.read_u16()?,
                 },
                 12 => CPIndexType::NameAndType {
-                    name_index: self.read_u16(),
-                    descriptor_index: self.read_u16(),
+                    name_index: self.read_u16()?,
+                    descriptor_index: self.read_u16()?,
                 },
                 15 => CPIndexType::MethodHandle {
-                    reference_type: self.read_u8(),
-                    reference_index: self.read_u16(),
+                    reference_type: self.read_u8()?,
+                    reference_index: self.read_u16()?,
                 },
                 16 => CPIndexType::MethodType {
-                    descriptor_index: self.read_u16(),
+                    descriptor_index: self.read_u16()?,
                 },
                 18 => CPIndexType::InvokeDynamic {
-                    boostrap_method_attr_index: self.read_u16(),
-                    name_and_type_index: self.read_u16(),
+                    boostrap_method_attr_index: self.read_u16()?,
+                    name_and_type_index: self.read_u16()?,
                 },
-                _ => unimplemented!("Unknown tag"),
+                19 => CPIndexType::Module(self.read_u16()?),
+                20 => CPIndexType::Package(self.read_u16()?),
+                tag => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unknown constant pool tag {}", tag),
+                    ))
+                }
             });
             if push_twice {
                 indexes.push(CPIndexType::Long(0));
@@ -99,39 +107,39 @@ impl<'a> ClassFileBuilder<'a> {
             i += 1;
         }
 
-        ConstantPool {
+        Ok(ConstantPool {
             index_count,
             indexes,
-        }
+        })
     }
 
-    pub fn read_interface_pool(&mut self) -> InterfacePool {
-        let interface_count = self.read_u16();
+    pub fn read_interface_pool(&mut self) -> io::Result<InterfacePool> {
+        let interface_count = self.read_u16()?;
         let mut interfaces = Vec::new();
 
         for _i in 0..interface_count {
-            interfaces.push(self.read_u16())
+            interfaces.push(self.read_u16()?)
         }
 
-        InterfacePool {
+        Ok(InterfacePool {
             interface_count,
             interfaces,
-        }
+        })
     }
 
-    pub fn read_field_pool(&mut self) -> FieldPool {
-        let field_count = self.read_u16();
+    pub fn read_field_pool(&mut self) -> io::Result<FieldPool> {
+        let field_count = self.read_u16()?;
         let mut fields: Vec<FieldInfo> = Vec::new();
 
         for _i in 0..field_count {
-            let access_flags = self.read_u16();
-            let name_index = self.read_u16();
-            let descriptor_index = self.read_u16();
-            let attributes_count = self.read_u16();
+            let access_flags = self.read_u16()?;
+            let name_index = self.read_u16()?;
+            let descriptor_index = self.read_u16()?;
+            let attributes_count = self.read_u16()?;
             let mut attributes: Vec<AttributeInfo> = Vec::new();
 
             for _j in 0..attributes_count {
-                attributes.push(self.read_attribute());
+                attributes.push(self.read_attribute()?);
             }
 
             fields.push(FieldInfo {
@@ -143,25 +151,25 @@ impl<'a> ClassFileBuilder<'a> {
             });
         }
 
-        FieldPool {
+        Ok(FieldPool {
             field_count,
             fields,
-        }
+        })
     }
 
-    pub fn read_method_pool(&mut self) -> MethodPool {
-        let method_count = self.read_u16();
+    pub fn read_method_pool(&mut self) -> io::Result<MethodPool> {
+        let method_count = self.read_u16()?;
         let mut methods: Vec<MethodInfo> = Vec::new();
 
         for _i in 0..method_count {
-            let access_flags = self.read_u16();
-            let name_index = self.read_u16();
-            let descriptor_index = self.read_u16();
-            let attributes_count = self.read_u16();
+            let access_flags = self.read_u16()?;
+            let name_index = self.read_u16()?;
+            let descriptor_index = self.read_u16()?;
+            let attributes_count = self.read_u16()?;
             let mut attributes: Vec<AttributeInfo> = Vec::new();
 
             for _j in 0..attributes_count {
-                attributes.push(self.read_attribute())
+                attributes.push(self.read_attribute()?)
             }
 
             methods.push(MethodInfo {
@@ -173,70 +181,78 @@ impl<'a> ClassFileBuilder<'a> {
             });
         }
 
-        MethodPool {
+        Ok(MethodPool {
             method_count,
             methods,
-        }
+        })
     }
 
-    pub fn read_attribute_pool(&mut self) -> AttributePool {
-        let attribute_count = self.read_u16();
+    pub fn read_attribute_pool(&mut self) -> io::Result<AttributePool> {
+        let attribute_count = self.read_u16()?;
         let mut attributes = Vec::new();
 
         for _i in 0..attribute_count {
-            attributes.push(self.read_attribute());
+            attributes.push(self.read_attribute()?);
         }
-        AttributePool {
+        Ok(AttributePool {
             attribute_count,
             attributes,
-        }
+        })
     }
 
-    fn read_attribute(&mut self) -> AttributeInfo {
-        let attribute_name_index = self.read_u16();
-        let attribute_length = self.read_u32();
+    fn read_attribute(&mut self) -> io::Result<AttributeInfo> {
+        let attribute_name_index = self.read_u16()?;
+        let attribute_length = self.read_u32()?;
         let mut info: Vec<u8> = Vec::new();
 
         for _j in 0..attribute_length {
-            info.push(self.read_u8());
+            info.push(self.read_u8()?);
         }
 
-        let value = self.constant_pool.get_index(attribute_name_index);
+        let value = match self.constant_pool.get(attribute_name_index) {
+            Some(value @ CPIndexType::Utf8(_)) => value.clone(),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid attribute name index {}", attribute_name_index),
+                ))
+            }
+        };
 
-        AttributeInfo {
+        Ok(AttributeInfo {
             attribute_name_index: AttributeNameIndex {
-                value: value,
+                value,
                 index: attribute_name_index,
             },
             attribute_length,
             info,
-        }
+        })
     }
 }
 
 // byte read methods
 impl<'a> ClassFileBuilder<'a> {
-    fn read_u8(&mut self) -> u8 {
+    fn read_u8(&mut self) -> io::Result<u8> {
         let mut buffer = [0u8];
-        self.buf.read_exact(&mut buffer).unwrap();
-        u8::from_be_bytes(buffer)
+        self.buf.read_exact(&mut buffer)?;
+        Ok(u8::from_be_bytes(buffer))
     }
 
-    fn read_u16(&mut self) -> u16 {
+    fn read_u16(&mut self) -> io::Result<u16> {
         let mut buffer = [0; 2];
-        self.buf.read_exact(&mut buffer).unwrap();
-        u16::from_be_bytes(buffer)
+        self.buf.read_exact(&mut buffer)?;
+        Ok(u16::from_be_bytes(buffer))
     }
 
-    fn read_u32(&mut self) -> u32 {
+    fn read_u32(&mut self) -> io::Result<u32> {
         let mut buffer = [0; 4];
-        self.buf.read_exact(&mut buffer).unwrap();
-        u32::from_be_bytes(buffer)
+        self.buf.read_exact(&mut buffer)?;
+        Ok(u32::from_be_bytes(buffer))
     }
 
-    fn read_f64(&mut self) -> f64 {
+    fn read_f64(&mut self) -> io::Result<f64> {
         let mut buffer = [0; 8];
-        self.buf.read_exact(&mut buffer).unwrap();
-        f64::from_be_bytes(buffer)
+        self.buf.read_exact(&mut buffer)?;
+        Ok(f64::from_be_bytes(buffer))
     }
 }
@@ -0,0 +1,192 @@
+use super::attribute::*;
+use super::constant_pool::*;
+use super::exception::*;
+use super::invalid;
+
+use std::io;
+
+#[derive(Debug)]
+pub struct CodeAttribute {
+    pub max_stack: u16,
+    pub max_locals: u16,
+    pub code: Vec<u8>,
+    pub exception_table: Vec<ExceptionTableIndex>,
+    pub attributes: Vec<AttributeInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LineNumber {
+    pub start_pc: u16,
+    pub line_number: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct LocalVariable {
+    pub start_pc: u16,
+    pub length: u16,
+    pub name_index: u16,
+    pub descriptor_index: u16,
+    pub index: u16,
+}
+
+impl CodeAttribute {
+    // Fails on a cut off attribute or one whose tables don't fit in it
+    pub fn new(info: &[u8], constant_pool: &ConstantPool) -> io::Result<Self> {
+        let mut reader = ByteReader {
+            buf: info,
+            index: 0,
+        };
+
+        let max_stack = reader.read_u16()?;
+        let max_locals = reader.read_u16()?;
+        let code_length = reader.read_u32()?;
+        let code = reader.read_bytes(code_length as usize)?.to_vec();
+
+        let exception_table_length = reader.read_u16()?;
+        let mut exception_table = Vec::new();
+
+        for _i in 0..exception_table_length {
+            let mut index = [0u8; 8];
+            index.copy_from_slice(reader.read_bytes(8)?);
+            exception_table.push(read_exception_table_index(&index));
+        }
+
+        let attributes_count = reader.read_u16()?;
+        let mut attributes = Vec::new();
+
+        for _i in 0..attributes_count {
+            let attribute_name_index = reader.read_u16()?;
+            let attribute_length = reader.read_u32()?;
+            let info = reader.read_bytes(attribute_length as usize)?.to_vec();
+
+            let value = match constant_pool.get(attribute_name_index) {
+                Some(value @ CPIndexType::Utf8(_)) => value.clone(),
+                _ => {
+                    return Err(invalid(format!(
+                        "invalid attribute name index {}",
+                        attribute_name_index
+                    )))
+                }
+            };
+            attributes.push(AttributeInfo {
+                attribute_name_index: AttributeNameIndex {
+                    value,
+                    index: attribute_name_index,
+                },
+                attribute_length,
+                info,
+            });
+        }
+
+        let code = CodeAttribute {
+            max_stack,
+            max_locals,
+            code,
+            exception_table,
+            attributes,
+        };
+        code.read_line_numbers()?;
+        code.read_local_variable_table("LocalVariableTable")?;
+        code.read_local_variable_table("LocalVariableTypeTable")?;
+        Ok(code)
+    }
+
+    pub fn line_numbers(&self) -> Vec<LineNumber> {
+        self.read_line_numbers().unwrap_or_default()
+    }
+
+    pub fn local_variables(&self) -> Vec<LocalVariable> {
+        self.read_local_variable_table("LocalVariableTable")
+            .unwrap_or_default()
+    }
+
+    pub fn local_variable_types(&self) -> Vec<LocalVariable> {
+        self.read_local_variable_table("LocalVariableTypeTable")
+            .unwrap_or_default()
+    }
+
+    fn read_line_numbers(&self) -> io::Result<Vec<LineNumber>> {
+        let mut line_numbers = Vec::new();
+
+        // there may be more than one LineNumberTable per method
+        for attribute in &self.attributes {
+            if attribute.get_name() != "LineNumberTable" {
+                continue;
+            }
+
+            let mut reader = ByteReader {
+                buf: &attribute.info,
+                index: 0,
+            };
+
+            for _i in 0..reader.read_u16()? {
+                line_numbers.push(LineNumber {
+                    start_pc: reader.read_u16()?,
+                    line_number: reader.read_u16()?,
+                });
+            }
+        }
+
+        Ok(line_numbers)
+    }
+
+    fn read_local_variable_table(&self, name: &str) -> io::Result<Vec<LocalVariable>> {
+        let mut local_variables = Vec::new();
+
+        for attribute in &self.attributes {
+            if attribute.get_name() != name {
+                continue;
+            }
+
+            let mut reader = ByteReader {
+                buf: &attribute.info,
+                index: 0,
+            };
+
+            for _i in 0..reader.read_u16()? {
+                local_variables.push(LocalVariable {
+                    start_pc: reader.read_u16()?,
+                    length: reader.read_u16()?,
+                    name_index: reader.read_u16()?,
+                    descriptor_index: reader.read_u16()?,
+                    index: reader.read_u16()?,
+                });
+            }
+        }
+
+        Ok(local_variables)
+    }
+}
+
+pub struct ByteReader<'a> {
+    pub buf: &'a [u8],
+    pub index: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn read_u8(&mut self) -> io::Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_be_bytes([self.read_u8()?, self.read_u8()?]))
+    }
+
+    pub fn read_u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_be_bytes([
+            self.read_u8()?,
+            self.read_u8()?,
+            self.read_u8()?,
+            self.read_u8()?,
+        ]))
+    }
+
+    pub fn read_bytes(&mut self, length: usize) -> io::Result<&'a [u8]> {
+        let bytes = self
+            .buf
+            .get(self.index..self.index.saturating_add(length))
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+        self.index += length;
+        Ok(bytes)
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Debug)]
+use std::io;
+
+#[derive(Debug, Default)]
 pub struct ConstantPool {
     pub index_count: u16,
     pub indexes: Vec<CPIndexType>,
@@ -15,9 +17,59 @@ impl ConstantPool {
     pub fn get_index(&self, index: u16) -> CPIndexType {
         self.indexes[(index - 1) as usize].clone()
     }
+
+    // None for 0 and indexes past the end of the pool
+    pub fn get(&self, index: u16) -> Option<&CPIndexType> {
+        (index as usize)
+            .checked_sub(1)
+            .and_then(|index| self.indexes.get(index))
+    }
+
+    // Returns the index of an equal entry, or appends the entry if there is none
+    pub fn add(&mut self, entry: CPIndexType) -> u16 {
+        let mut index = 0;
+        while index < self.indexes.len() {
+            if self.indexes[index] == entry {
+                return index as u16 + 1;
+            }
+
+            // skip the placeholder after a Long or Double
+            match self.indexes[index] {
+                CPIndexType::Long(_) | CPIndexType::Double(_) => index += 2,
+                _ => index += 1,
+            }
+        }
+
+        let wide = matches!(entry, CPIndexType::Long(_) | CPIndexType::Double(_));
+        self.indexes.push(entry);
+        if wide {
+            self.indexes.push(CPIndexType::Long(0));
+        }
+        self.index_count = self.indexes.len() as u16 + 1;
+
+        index as u16 + 1
+    }
+
+    pub fn add_utf8(&mut self, string: &str) -> u16 {
+        self.add(CPIndexType::Utf8(Utf8String::from(string)))
+    }
+
+    pub fn add_class(&mut self, name: &str) -> u16 {
+        let name_index = self.add_utf8(name);
+        self.add(CPIndexType::Class(name_index))
+    }
+
+    pub fn add_name_and_type(&mut self, name: &str, descriptor: &str) -> u16 {
+        let name_index = self.add_utf8(name);
+        let descriptor_index = self.add_utf8(descriptor);
+        self.add(CPIndexType::NameAndType {
+            name_index,
+            descriptor_index,
+        })
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum CPIndexType {
     Class(u16),
     FieldRef {
@@ -41,7 +93,7 @@ pub enum CPIndexType {
         name_index: u16,
         descriptor_index: u16,
     },
-    Utf8(String),
+    Utf8(Utf8String),
     MethodHandle {
         reference_type: u8,
         reference_index: u16,
@@ -53,65 +105,90 @@ pub enum CPIndexType {
         boostrap_method_attr_index: u16,
         name_and_type_index: u16,
     },
+    // the names of modules and packages in module-info classes
+    Module(u16),
+    Package(u16),
+}
+
+// The text of a `CONSTANT_Utf8` entry and the bytes it was read from. Unpaired surrogates can't be
+// represented in a `String`, so the bytes are what is written back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Utf8String {
+    pub string: String,
+    pub bytes: Vec<u8>,
 }
 
-impl Clone for CPIndexType {
-    fn clone(&self) -> Self {
-        match self {
-            CPIndexType::Class(a) => CPIndexType::Class(a.clone()),
-            CPIndexType::FieldRef {
-                class_index,
-                name_and_type_index,
-            } => CPIndexType::FieldRef {
-                class_index: class_index.clone(),
-                name_and_type_index: name_and_type_index.clone(),
-            },
-            CPIndexType::MethodRef {
-                class_index,
-                name_and_type_index,
-            } => CPIndexType::MethodRef {
-                class_index: class_index.clone(),
-                name_and_type_index: name_and_type_index.clone(),
-            },
-            CPIndexType::InterfaceMethodRef {
-                class_index,
-                name_and_type_index,
-            } => CPIndexType::InterfaceMethodRef {
-                class_index: class_index.clone(),
-                name_and_type_index: name_and_type_index.clone(),
-            },
-            CPIndexType::String(value) => CPIndexType::String(value.clone()),
-            CPIndexType::Integer(value) => CPIndexType::Integer(value.clone()),
-            CPIndexType::Float(value) => CPIndexType::Float(value.clone()),
-            CPIndexType::Long(value) => CPIndexType::Long(*value),
-            CPIndexType::Double(value) => CPIndexType::Double(*value),
-            CPIndexType::NameAndType {
-                name_index,
-                descriptor_index,
-            } => CPIndexType::NameAndType {
-                name_index: name_index.clone(),
-                descriptor_index: descriptor_index.clone(),
-            },
-            CPIndexType::Utf8(a) => CPIndexType::Utf8(a.to_string()),
-            CPIndexType::MethodHandle {
-                reference_type,
-                reference_index,
-            } => CPIndexType::MethodHandle {
-                reference_type: reference_type.clone(),
-                reference_index: reference_index.clone(),
-            },
-            CPIndexType::MethodType {
-                descriptor_index,
-            } => CPIndexType::MethodType {
-                descriptor_index: descriptor_index.clone(),
-            },
-            CPIndexType::InvokeDynamic {
-                boostrap_method_attr_index,
-                name_and_type_index,
-            } => CPIndexType::InvokeDynamic {
-                boostrap_method_attr_index: boostrap_method_attr_index.clone(),
-                name_and_type_index: name_and_type_index.clone(),
-            },
+impl Utf8String {
+    pub fn from_bytes(bytes: Vec<u8>) -> io::Result<Self> {
+        Ok(Utf8String {
+            string: decode_modified_utf8(&bytes)?,
+            bytes,
+        })
+    }
+}
+
+impl From<&str> for Utf8String {
+    fn from(string: &str) -> Self {
+        Utf8String {
+            string: string.to_string(),
+            bytes: encode_modified_utf8(string),
+        }
+    }
+}
+
+/// Decodes the modified UTF-8 used by `CONSTANT_Utf8` entries: `U+0000` is
+/// encoded as `C0 80` and supplementary characters as surrogate pairs.
+/// Unpaired surrogates can't be represented in a `String` and become `U+FFFD`.
+pub fn decode_modified_utf8(bytes: &[u8]) -> io::Result<String> {
+    let mut units: Vec<u16> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let byte = bytes[i] as u16;
+        if byte & 0x80 == 0 {
+            units.push(byte);
+            i += 1;
+        } else if byte & 0xe0 == 0xc0 && i + 1 < bytes.len() {
+            units.push(((byte & 0x1f) << 6) | (bytes[i + 1] as u16 & 0x3f));
+            i += 2;
+        } else if byte & 0xf0 == 0xe0 && i + 2 < bytes.len() {
+            units.push(
+                ((byte & 0x0f) << 12)
+                    | ((bytes[i + 1] as u16 & 0x3f) << 6)
+                    | (bytes[i + 2] as u16 & 0x3f),
+            );
+            i += 3;
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid modified UTF-8 string",
+            ));
         }
     }
-}
\ No newline at end of file
+
+    Ok(std::char::decode_utf16(units)
+        .map(|c| c.unwrap_or(std::char::REPLACEMENT_CHARACTER))
+        .collect())
+}
+
+/// Inverse of [`decode_modified_utf8`].
+pub fn encode_modified_utf8(string: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(string.len());
+
+    for unit in string.encode_utf16() {
+        match unit {
+            0x0001..=0x007f => bytes.push(unit as u8),
+            0x0000 | 0x0080..=0x07ff => {
+                bytes.push(0xc0 | (unit >> 6) as u8);
+                bytes.push(0x80 | (unit & 0x3f) as u8);
+            }
+            _ => {
+                bytes.push(0xe0 | (unit >> 12) as u8);
+                bytes.push(0x80 | ((unit >> 6) & 0x3f) as u8);
+                bytes.push(0x80 | (unit & 0x3f) as u8);
+            }
+        }
+    }
+
+    bytes
+}
@@ -0,0 +1,82 @@
+// Helpers for field and method descriptors (JVMS 4.3)
+
+pub fn split_method_descriptor(descriptor: &str) -> (Vec<String>, String) {
+    let end = descriptor.find(')').unwrap_or(descriptor.len());
+    let mut args = Vec::new();
+    let mut rest = descriptor.get(1..end).unwrap_or("");
+
+    while !rest.is_empty() {
+        let length = field_descriptor_length(rest);
+        args.push(rest[..length].to_string());
+        rest = &rest[length..];
+    }
+
+    (args, descriptor.get(end + 1..).unwrap_or("").to_string())
+}
+
+// Malformed descriptors are cut at the end of the string
+pub fn field_descriptor_length(descriptor: &str) -> usize {
+    match descriptor.as_bytes().first() {
+        Some(b'L') => descriptor.find(';').map_or(descriptor.len(), |end| end + 1),
+        Some(b'[') => 1 + field_descriptor_length(&descriptor[1..]),
+        Some(_) => 1,
+        None => 0,
+    }
+}
+
+pub fn is_field_descriptor(descriptor: &str) -> bool {
+    let element = descriptor.trim_start_matches('[');
+    descriptor.len() - element.len() <= 255
+        && match element.as_bytes() {
+            [b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z'] => true,
+            [b'L', name @ .., b';'] => {
+                !name.is_empty() && !name.iter().any(|c| matches!(c, b'.' | b';' | b'['))
+            }
+            _ => false,
+        }
+}
+
+pub fn is_method_descriptor(descriptor: &str) -> bool {
+    let (args, return_value) = split_method_descriptor(descriptor);
+    descriptor.starts_with('(')
+        && descriptor.contains(')')
+        && args.iter().all(|arg| is_field_descriptor(arg))
+        && (return_value == "V" || is_field_descriptor(return_value.as_str()))
+}
+
+// long and double take up two local variable and operand stack slots
+pub fn slot_size(descriptor: &str) -> u16 {
+    match descriptor.as_bytes().first() {
+        Some(b'J') | Some(b'D') => 2,
+        Some(b'V') | None => 0,
+        _ => 1,
+    }
+}
+
+pub fn argument_slots(descriptor: &str) -> u16 {
+    split_method_descriptor(descriptor)
+        .0
+        .iter()
+        .map(|arg| slot_size(arg))
+        .sum()
+}
+
+pub fn to_java_type(descriptor: &str) -> String {
+    match descriptor.as_bytes().first().copied().unwrap_or_default() {
+        b'B' => "byte".to_string(),
+        b'C' => "char".to_string(),
+        b'D' => "double".to_string(),
+        b'F' => "float".to_string(),
+        b'I' => "int".to_string(),
+        b'J' => "long".to_string(),
+        b'S' => "short".to_string(),
+        b'V' => "void".to_string(),
+        b'Z' => "boolean".to_string(),
+        b'[' => format!("{}[]", to_java_type(&descriptor[1..])),
+        _ => descriptor
+            .strip_prefix('L')
+            .and_then(|name| name.strip_suffix(';'))
+            .unwrap_or(descriptor)
+            .replace('/', "."),
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ExceptionTableIndex {
     pub start_pc: u16,
     pub end_pc: u16,
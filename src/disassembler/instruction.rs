@@ -0,0 +1,1178 @@
+use super::invalid;
+
+use std::io;
+use std::io::{BufReader, Read};
+
+#[derive(Debug, Clone)]
+pub enum Instruction {
+    AALoad,
+    AAStore,
+    AConstNull,
+    ALoad(u16),
+    ALoad0,
+    ALoad1,
+    ALoad2,
+    ALoad3,
+    ANewArray(u16),
+    AReturn,
+    ArrayLength,
+    AStore(u16),
+    AStore0,
+    AStore1,
+    AStore2,
+    AStore3,
+    AThrow,
+    BALoad,
+    BAStore,
+    BiPush(u8),
+    Breakpoint,
+    CALoad,
+    CAStore,
+    CheckCast(u16),
+    D2F,
+    D2I,
+    D2L,
+    DAdd,
+    DALoad,
+    DAStore,
+    DCmpG,
+    DCmpL,
+    DConst0,
+    DConst1,
+    DDiv,
+    DLoad(u16),
+    DLoad0,
+    DLoad1,
+    DLoad2,
+    DLoad3,
+    DMul,
+    DNeg,
+    DRem,
+    DReturn,
+    DStore(u16),
+    DStore0,
+    DStore1,
+    DStore2,
+    DStore3,
+    DSub,
+    Dup,
+    DupX1,
+    DupX2,
+    Dup2,
+    Dup2X1,
+    Dup2X2,
+    F2D,
+    F2I,
+    F2L,
+    FAdd,
+    FALoad,
+    FAStore,
+    FCmpG,
+    FCmpL,
+    FConst0,
+    FConst1,
+    FConst2,
+    FDiv,
+    FLoad(u16),
+    FLoad0,
+    FLoad1,
+    FLoad2,
+    FLoad3,
+    FMul,
+    FNeg,
+    FRem,
+    FReturn,
+    FStore(u16),
+    FStore0,
+    FStore1,
+    FStore2,
+    FStore3,
+    FSub,
+    GetField(u16),
+    GetStatic(u16),
+    GoTo(i16),
+    GoToW(i32),
+    I2C,
+    I2B,
+    I2D,
+    I2F,
+    I2L,
+    I2S,
+    IAdd,
+    IALoad,
+    IAnd,
+    IAStore,
+    IConstM1,
+    IConst0,
+    IConst1,
+    IConst2,
+    IConst3,
+    IConst4,
+    IConst5,
+    IDiv,
+    IfACmpEq(i16),
+    IfACmpNe(i16),
+    IfICmpEq(i16),
+    IfICmpGe(i16),
+    IfICmpGt(i16),
+    IfICmpLe(i16),
+    IfICmpLt(i16),
+    IfICmpNe(i16),
+    IfEq(i16),
+    IfGe(i16),
+    IfGt(i16),
+    IfLe(i16),
+    IfLt(i16),
+    IfNe(i16),
+    IfNonNull(i16),
+    IfNull(i16),
+    IInc(u16, i16),
+    ILoad(u16),
+    ILoad0,
+    ILoad1,
+    ILoad2,
+    ILoad3,
+    ImpDep1,
+    ImpDep2,
+    IMul,
+    INeg,
+    InstanceOf(u16),
+    InvokeDynamic(u16),
+    InvokeInterface(u16, u8),
+    InvokeSpecial(u16),
+    InvokeStatic(u16),
+    InvokeVirtual(u16),
+    IOr,
+    IRem,
+    IReturn,
+    IShl,
+    IShr,
+    IStore(u16),
+    IStore0,
+    IStore1,
+    IStore2,
+    IStore3,
+    ISub,
+    IUShr,
+    IXor,
+    Jsr(i16),
+    JsrW(i32),
+    L2D,
+    L2F,
+    L2I,
+    LAdd,
+    LALoad,
+    LAnd,
+    LAStore,
+    LCmp,
+    LConst0,
+    LConst1,
+    Ldc(u8),
+    LdcW(u16),
+    Ldc2W(u16),
+    LDiv,
+    LLoad(u16),
+    LLoad0,
+    LLoad1,
+    LLoad2,
+    LLoad3,
+    LMul,
+    LNeg,
+    LookUpSwitch {
+        default: i32,
+        pairs: Vec<(i32, i32)>,
+    },
+    LOr,
+    LRem,
+    LReturn,
+    LShl,
+    LShr,
+    LStore(u16),
+    LStore0,
+    LStore1,
+    LStore2,
+    LStore3,
+    LSub,
+    LUShr,
+    LXor,
+    MonitorEnter,
+    MonitorExit,
+    MultiANewArray(u16, u8),
+    New(u16),
+    NewArray(u8),
+    Nop,
+    Pop,
+    Pop2,
+    PutField(u16),
+    PutStatic(u16),
+    Ret(u16),
+    Return,
+    SALoad,
+    SAStore,
+    SiPush(u16),
+    Swap,
+    TableSwitch {
+        default: i32,
+        low: i32,
+        high: i32,
+        offsets: Vec<i32>,
+    },
+}
+
+impl Instruction {
+    pub fn opcode(&self) -> u8 {
+        match self {
+            Instruction::Nop => 0x00,
+            Instruction::AConstNull => 0x01,
+            Instruction::IConstM1 => 0x02,
+            Instruction::IConst0 => 0x03,
+            Instruction::IConst1 => 0x04,
+            Instruction::IConst2 => 0x05,
+            Instruction::IConst3 => 0x06,
+            Instruction::IConst4 => 0x07,
+            Instruction::IConst5 => 0x08,
+            Instruction::LConst0 => 0x09,
+            Instruction::LConst1 => 0x0a,
+            Instruction::FConst0 => 0x0b,
+            Instruction::FConst1 => 0x0c,
+            Instruction::FConst2 => 0x0d,
+            Instruction::DConst0 => 0x0e,
+            Instruction::DConst1 => 0x0f,
+            Instruction::BiPush(..) => 0x10,
+            Instruction::SiPush(..) => 0x11,
+            Instruction::Ldc(..) => 0x12,
+            Instruction::LdcW(..) => 0x13,
+            Instruction::Ldc2W(..) => 0x14,
+            Instruction::ILoad(..) => 0x15,
+            Instruction::LLoad(..) => 0x16,
+            Instruction::FLoad(..) => 0x17,
+            Instruction::DLoad(..) => 0x18,
+            Instruction::ALoad(..) => 0x19,
+            Instruction::ILoad0 => 0x1a,
+            Instruction::ILoad1 => 0x1b,
+            Instruction::ILoad2 => 0x1c,
+            Instruction::ILoad3 => 0x1d,
+            Instruction::LLoad0 => 0x1e,
+            Instruction::LLoad1 => 0x1f,
+            Instruction::LLoad2 => 0x20,
+            Instruction::LLoad3 => 0x21,
+            Instruction::FLoad0 => 0x22,
+            Instruction::FLoad1 => 0x23,
+            Instruction::FLoad2 => 0x24,
+            Instruction::FLoad3 => 0x25,
+            Instruction::DLoad0 => 0x26,
+            Instruction::DLoad1 => 0x27,
+            Instruction::DLoad2 => 0x28,
+            Instruction::DLoad3 => 0x29,
+            Instruction::ALoad0 => 0x2a,
+            Instruction::ALoad1 => 0x2b,
+            Instruction::ALoad2 => 0x2c,
+            Instruction::ALoad3 => 0x2d,
+            Instruction::IALoad => 0x2e,
+            Instruction::LALoad => 0x2f,
+            Instruction::FALoad => 0x30,
+            Instruction::DALoad => 0x31,
+            Instruction::AALoad => 0x32,
+            Instruction::BALoad => 0x33,
+            Instruction::CALoad => 0x34,
+            Instruction::SALoad => 0x35,
+            Instruction::IStore(..) => 0x36,
+            Instruction::LStore(..) => 0x37,
+            Instruction::FStore(..) => 0x38,
+            Instruction::DStore(..) => 0x39,
+            Instruction::AStore(..) => 0x3a,
+            Instruction::IStore0 => 0x3b,
+            Instruction::IStore1 => 0x3c,
+            Instruction::IStore2 => 0x3d,
+            Instruction::IStore3 => 0x3e,
+            Instruction::LStore0 => 0x3f,
+            Instruction::LStore1 => 0x40,
+            Instruction::LStore2 => 0x41,
+            Instruction::LStore3 => 0x42,
+            Instruction::FStore0 => 0x43,
+            Instruction::FStore1 => 0x44,
+            Instruction::FStore2 => 0x45,
+            Instruction::FStore3 => 0x46,
+            Instruction::DStore0 => 0x47,
+            Instruction::DStore1 => 0x48,
+            Instruction::DStore2 => 0x49,
+            Instruction::DStore3 => 0x4a,
+            Instruction::AStore0 => 0x4b,
+            Instruction::AStore1 => 0x4c,
+            Instruction::AStore2 => 0x4d,
+            Instruction::AStore3 => 0x4e,
+            Instruction::IAStore => 0x4f,
+            Instruction::LAStore => 0x50,
+            Instruction::FAStore => 0x51,
+            Instruction::DAStore => 0x52,
+            Instruction::AAStore => 0x53,
+            Instruction::BAStore => 0x54,
+            Instruction::CAStore => 0x55,
+            Instruction::SAStore => 0x56,
+            Instruction::Pop => 0x57,
+            Instruction::Pop2 => 0x58,
+            Instruction::Dup => 0x59,
+            Instruction::DupX1 => 0x5a,
+            Instruction::DupX2 => 0x5b,
+            Instruction::Dup2 => 0x5c,
+            Instruction::Dup2X1 => 0x5d,
+            Instruction::Dup2X2 => 0x5e,
+            Instruction::Swap => 0x5f,
+            Instruction::IAdd => 0x60,
+            Instruction::LAdd => 0x61,
+            Instruction::FAdd => 0x62,
+            Instruction::DAdd => 0x63,
+            Instruction::ISub => 0x64,
+            Instruction::LSub => 0x65,
+            Instruction::FSub => 0x66,
+            Instruction::DSub => 0x67,
+            Instruction::IMul => 0x68,
+            Instruction::LMul => 0x69,
+            Instruction::FMul => 0x6a,
+            Instruction::DMul => 0x6b,
+            Instruction::IDiv => 0x6c,
+            Instruction::LDiv => 0x6d,
+            Instruction::FDiv => 0x6e,
+            Instruction::DDiv => 0x6f,
+            Instruction::IRem => 0x70,
+            Instruction::LRem => 0x71,
+            Instruction::FRem => 0x72,
+            Instruction::DRem => 0x73,
+            Instruction::INeg => 0x74,
+            Instruction::LNeg => 0x75,
+            Instruction::FNeg => 0x76,
+            Instruction::DNeg => 0x77,
+            Instruction::IShl => 0x78,
+            Instruction::LShl => 0x79,
+            Instruction::IShr => 0x7a,
+            Instruction::LShr => 0x7b,
+            Instruction::IUShr => 0x7c,
+            Instruction::LUShr => 0x7d,
+            Instruction::IAnd => 0x7e,
+            Instruction::LAnd => 0x7f,
+            Instruction::IOr => 0x80,
+            Instruction::LOr => 0x81,
+            Instruction::IXor => 0x82,
+            Instruction::LXor => 0x83,
+            Instruction::IInc(..) => 0x84,
+            Instruction::I2L => 0x85,
+            Instruction::I2F => 0x86,
+            Instruction::I2D => 0x87,
+            Instruction::L2I => 0x88,
+            Instruction::L2F => 0x89,
+            Instruction::L2D => 0x8a,
+            Instruction::F2I => 0x8b,
+            Instruction::F2L => 0x8c,
+            Instruction::F2D => 0x8d,
+            Instruction::D2I => 0x8e,
+            Instruction::D2L => 0x8f,
+            Instruction::D2F => 0x90,
+            Instruction::I2B => 0x91,
+            Instruction::I2C => 0x92,
+            Instruction::I2S => 0x93,
+            Instruction::LCmp => 0x94,
+            Instruction::FCmpL => 0x95,
+            Instruction::FCmpG => 0x96,
+            Instruction::DCmpL => 0x97,
+            Instruction::DCmpG => 0x98,
+            Instruction::IfEq(..) => 0x99,
+            Instruction::IfNe(..) => 0x9a,
+            Instruction::IfLt(..) => 0x9b,
+            Instruction::IfGe(..) => 0x9c,
+            Instruction::IfGt(..) => 0x9d,
+            Instruction::IfLe(..) => 0x9e,
+            Instruction::IfICmpEq(..) => 0x9f,
+            Instruction::IfICmpNe(..) => 0xa0,
+            Instruction::IfICmpLt(..) => 0xa1,
+            Instruction::IfICmpGe(..) => 0xa2,
+            Instruction::IfICmpGt(..) => 0xa3,
+            Instruction::IfICmpLe(..) => 0xa4,
+            Instruction::IfACmpEq(..) => 0xa5,
+            Instruction::IfACmpNe(..) => 0xa6,
+            Instruction::GoTo(..) => 0xa7,
+            Instruction::Jsr(..) => 0xa8,
+            Instruction::Ret(..) => 0xa9,
+            Instruction::TableSwitch { .. } => 0xaa,
+            Instruction::LookUpSwitch { .. } => 0xab,
+            Instruction::IReturn => 0xac,
+            Instruction::LReturn => 0xad,
+            Instruction::FReturn => 0xae,
+            Instruction::DReturn => 0xaf,
+            Instruction::AReturn => 0xb0,
+            Instruction::Return => 0xb1,
+            Instruction::GetStatic(..) => 0xb2,
+            Instruction::PutStatic(..) => 0xb3,
+            Instruction::GetField(..) => 0xb4,
+            Instruction::PutField(..) => 0xb5,
+            Instruction::InvokeVirtual(..) => 0xb6,
+            Instruction::InvokeSpecial(..) => 0xb7,
+            Instruction::InvokeStatic(..) => 0xb8,
+            Instruction::InvokeInterface(..) => 0xb9,
+            Instruction::InvokeDynamic(..) => 0xba,
+            Instruction::New(..) => 0xbb,
+            Instruction::NewArray(..) => 0xbc,
+            Instruction::ANewArray(..) => 0xbd,
+            Instruction::ArrayLength => 0xbe,
+            Instruction::AThrow => 0xbf,
+            Instruction::CheckCast(..) => 0xc0,
+            Instruction::InstanceOf(..) => 0xc1,
+            Instruction::MonitorEnter => 0xc2,
+            Instruction::MonitorExit => 0xc3,
+            Instruction::MultiANewArray(..) => 0xc5,
+            Instruction::IfNull(..) => 0xc6,
+            Instruction::IfNonNull(..) => 0xc7,
+            Instruction::GoToW(..) => 0xc8,
+            Instruction::JsrW(..) => 0xc9,
+            Instruction::Breakpoint => 0xca,
+            Instruction::ImpDep1 => 0xfe,
+            Instruction::ImpDep2 => 0xff,
+        }
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        mnemonic(self.opcode()).expect("Unknown opcode")
+    }
+
+    // Encodes the instruction as it would appear at `pc`, the position matters for the
+    // padding of the switch instructions. Local indexes that don't fit in a byte use wide.
+    pub fn write(&self, pc: usize, buf: &mut Vec<u8>) {
+        let opcode = self.opcode();
+
+        match *self {
+            Instruction::ALoad(index)
+            | Instruction::AStore(index)
+            | Instruction::DLoad(index)
+            | Instruction::DStore(index)
+            | Instruction::FLoad(index)
+            | Instruction::FStore(index)
+            | Instruction::ILoad(index)
+            | Instruction::IStore(index)
+            | Instruction::LLoad(index)
+            | Instruction::LStore(index)
+            | Instruction::Ret(index) => {
+                if index > u8::MAX as u16 {
+                    buf.push(0xc4);
+                    buf.push(opcode);
+                    buf.extend_from_slice(&index.to_be_bytes());
+                } else {
+                    buf.push(opcode);
+                    buf.push(index as u8);
+                }
+            }
+            Instruction::IInc(index, value) => {
+                if index > u8::MAX as u16 || value != value as i8 as i16 {
+                    buf.push(0xc4);
+                    buf.push(opcode);
+                    buf.extend_from_slice(&index.to_be_bytes());
+                    buf.extend_from_slice(&value.to_be_bytes());
+                } else {
+                    buf.push(opcode);
+                    buf.push(index as u8);
+                    buf.push(value as u8);
+                }
+            }
+
+            Instruction::BiPush(value) | Instruction::Ldc(value) | Instruction::NewArray(value) => {
+                buf.push(opcode);
+                buf.push(value);
+            }
+
+            Instruction::SiPush(value)
+            | Instruction::LdcW(value)
+            | Instruction::Ldc2W(value)
+            | Instruction::ANewArray(value)
+            | Instruction::CheckCast(value)
+            | Instruction::GetField(value)
+            | Instruction::GetStatic(value)
+            | Instruction::InstanceOf(value)
+            | Instruction::InvokeSpecial(value)
+            | Instruction::InvokeStatic(value)
+            | Instruction::InvokeVirtual(value)
+            | Instruction::New(value)
+            | Instruction::PutField(value)
+            | Instruction::PutStatic(value) => {
+                buf.push(opcode);
+                buf.extend_from_slice(&value.to_be_bytes());
+            }
+            Instruction::GoTo(offset)
+            | Instruction::IfACmpEq(offset)
+            | Instruction::IfACmpNe(offset)
+            | Instruction::IfICmpEq(offset)
+            | Instruction::IfICmpGe(offset)
+            | Instruction::IfICmpGt(offset)
+            | Instruction::IfICmpLe(offset)
+            | Instruction::IfICmpLt(offset)
+            | Instruction::IfICmpNe(offset)
+            | Instruction::IfEq(offset)
+            | Instruction::IfGe(offset)
+            | Instruction::IfGt(offset)
+            | Instruction::IfLe(offset)
+            | Instruction::IfLt(offset)
+            | Instruction::IfNe(offset)
+            | Instruction::IfNonNull(offset)
+            | Instruction::IfNull(offset)
+            | Instruction::Jsr(offset) => {
+                buf.push(opcode);
+                buf.extend_from_slice(&offset.to_be_bytes());
+            }
+            Instruction::GoToW(offset) | Instruction::JsrW(offset) => {
+                buf.push(opcode);
+                buf.extend_from_slice(&offset.to_be_bytes());
+            }
+
+            Instruction::InvokeInterface(index, count) => {
+                buf.push(opcode);
+                buf.extend_from_slice(&index.to_be_bytes());
+                buf.push(count);
+                buf.push(0);
+            }
+            Instruction::InvokeDynamic(index) => {
+                buf.push(opcode);
+                buf.extend_from_slice(&index.to_be_bytes());
+                buf.extend_from_slice(&[0, 0]);
+            }
+            Instruction::MultiANewArray(index, dimensions) => {
+                buf.push(opcode);
+                buf.extend_from_slice(&index.to_be_bytes());
+                buf.push(dimensions);
+            }
+
+            Instruction::TableSwitch {
+                default,
+                low,
+                high,
+                ref offsets,
+            } => {
+                buf.push(opcode);
+                write_padding(pc, buf);
+                buf.extend_from_slice(&default.to_be_bytes());
+                buf.extend_from_slice(&low.to_be_bytes());
+                buf.extend_from_slice(&high.to_be_bytes());
+                for offset in offsets {
+                    buf.extend_from_slice(&offset.to_be_bytes());
+                }
+            }
+            Instruction::LookUpSwitch { default, ref pairs } => {
+                buf.push(opcode);
+                write_padding(pc, buf);
+                buf.extend_from_slice(&default.to_be_bytes());
+                buf.extend_from_slice(&(pairs.len() as i32).to_be_bytes());
+                for (key, offset) in pairs {
+                    buf.extend_from_slice(&key.to_be_bytes());
+                    buf.extend_from_slice(&offset.to_be_bytes());
+                }
+            }
+
+            _ => buf.push(opcode),
+        }
+    }
+
+    pub fn length(&self, pc: usize) -> usize {
+        let mut buf = Vec::new();
+        self.write(pc, &mut buf);
+        buf.len()
+    }
+}
+
+// the padding after a switch opcode depends on where the instruction starts
+fn write_padding(pc: usize, buf: &mut Vec<u8>) {
+    for _i in 0..(3 - pc % 4) {
+        buf.push(0);
+    }
+}
+
+pub fn mnemonic(opcode: u8) -> Option<&'static str> {
+    Some(match opcode {
+        0x00 => "nop",
+        0x01 => "aconst_null",
+        0x02 => "iconst_m1",
+        0x03 => "iconst_0",
+        0x04 => "iconst_1",
+        0x05 => "iconst_2",
+        0x06 => "iconst_3",
+        0x07 => "iconst_4",
+        0x08 => "iconst_5",
+        0x09 => "lconst_0",
+        0x0a => "lconst_1",
+        0x0b => "fconst_0",
+        0x0c => "fconst_1",
+        0x0d => "fconst_2",
+        0x0e => "dconst_0",
+        0x0f => "dconst_1",
+        0x10 => "bipush",
+        0x11 => "sipush",
+        0x12 => "ldc",
+        0x13 => "ldc_w",
+        0x14 => "ldc2_w",
+        0x15 => "iload",
+        0x16 => "lload",
+        0x17 => "fload",
+        0x18 => "dload",
+        0x19 => "aload",
+        0x1a => "iload_0",
+        0x1b => "iload_1",
+        0x1c => "iload_2",
+        0x1d => "iload_3",
+        0x1e => "lload_0",
+        0x1f => "lload_1",
+        0x20 => "lload_2",
+        0x21 => "lload_3",
+        0x22 => "fload_0",
+        0x23 => "fload_1",
+        0x24 => "fload_2",
+        0x25 => "fload_3",
+        0x26 => "dload_0",
+        0x27 => "dload_1",
+        0x28 => "dload_2",
+        0x29 => "dload_3",
+        0x2a => "aload_0",
+        0x2b => "aload_1",
+        0x2c => "aload_2",
+        0x2d => "aload_3",
+        0x2e => "iaload",
+        0x2f => "laload",
+        0x30 => "faload",
+        0x31 => "daload",
+        0x32 => "aaload",
+        0x33 => "baload",
+        0x34 => "caload",
+        0x35 => "saload",
+        0x36 => "istore",
+        0x37 => "lstore",
+        0x38 => "fstore",
+        0x39 => "dstore",
+        0x3a => "astore",
+        0x3b => "istore_0",
+        0x3c => "istore_1",
+        0x3d => "istore_2",
+        0x3e => "istore_3",
+        0x3f => "lstore_0",
+        0x40 => "lstore_1",
+        0x41 => "lstore_2",
+        0x42 => "lstore_3",
+        0x43 => "fstore_0",
+        0x44 => "fstore_1",
+        0x45 => "fstore_2",
+        0x46 => "fstore_3",
+        0x47 => "dstore_0",
+        0x48 => "dstore_1",
+        0x49 => "dstore_2",
+        0x4a => "dstore_3",
+        0x4b => "astore_0",
+        0x4c => "astore_1",
+        0x4d => "astore_2",
+        0x4e => "astore_3",
+        0x4f => "iastore",
+        0x50 => "lastore",
+        0x51 => "fastore",
+        0x52 => "dastore",
+        0x53 => "aastore",
+        0x54 => "bastore",
+        0x55 => "castore",
+        0x56 => "sastore",
+        0x57 => "pop",
+        0x58 => "pop2",
+        0x59 => "dup",
+        0x5a => "dup_x1",
+        0x5b => "dup_x2",
+        0x5c => "dup2",
+        0x5d => "dup2_x1",
+        0x5e => "dup2_x2",
+        0x5f => "swap",
+        0x60 => "iadd",
+        0x61 => "ladd",
+        0x62 => "fadd",
+        0x63 => "dadd",
+        0x64 => "isub",
+        0x65 => "lsub",
+        0x66 => "fsub",
+        0x67 => "dsub",
+        0x68 => "imul",
+        0x69 => "lmul",
+        0x6a => "fmul",
+        0x6b => "dmul",
+        0x6c => "idiv",
+        0x6d => "ldiv",
+        0x6e => "fdiv",
+        0x6f => "ddiv",
+        0x70 => "irem",
+        0x71 => "lrem",
+        0x72 => "frem",
+        0x73 => "drem",
+        0x74 => "ineg",
+        0x75 => "lneg",
+        0x76 => "fneg",
+        0x77 => "dneg",
+        0x78 => "ishl",
+        0x79 => "lshl",
+        0x7a => "ishr",
+        0x7b => "lshr",
+        0x7c => "iushr",
+        0x7d => "lushr",
+        0x7e => "iand",
+        0x7f => "land",
+        0x80 => "ior",
+        0x81 => "lor",
+        0x82 => "ixor",
+        0x83 => "lxor",
+        0x84 => "iinc",
+        0x85 => "i2l",
+        0x86 => "i2f",
+        0x87 => "i2d",
+        0x88 => "l2i",
+        0x89 => "l2f",
+        0x8a => "l2d",
+        0x8b => "f2i",
+        0x8c => "f2l",
+        0x8d => "f2d",
+        0x8e => "d2i",
+        0x8f => "d2l",
+        0x90 => "d2f",
+        0x91 => "i2b",
+        0x92 => "i2c",
+        0x93 => "i2s",
+        0x94 => "lcmp",
+        0x95 => "fcmpl",
+        0x96 => "fcmpg",
+        0x97 => "dcmpl",
+        0x98 => "dcmpg",
+        0x99 => "ifeq",
+        0x9a => "ifne",
+        0x9b => "iflt",
+        0x9c => "ifge",
+        0x9d => "ifgt",
+        0x9e => "ifle",
+        0x9f => "if_icmpeq",
+        0xa0 => "if_icmpne",
+        0xa1 => "if_icmplt",
+        0xa2 => "if_icmpge",
+        0xa3 => "if_icmpgt",
+        0xa4 => "if_icmple",
+        0xa5 => "if_acmpeq",
+        0xa6 => "if_acmpne",
+        0xa7 => "goto",
+        0xa8 => "jsr",
+        0xa9 => "ret",
+        0xaa => "tableswitch",
+        0xab => "lookupswitch",
+        0xac => "ireturn",
+        0xad => "lreturn",
+        0xae => "freturn",
+        0xaf => "dreturn",
+        0xb0 => "areturn",
+        0xb1 => "return",
+        0xb2 => "getstatic",
+        0xb3 => "putstatic",
+        0xb4 => "getfield",
+        0xb5 => "putfield",
+        0xb6 => "invokevirtual",
+        0xb7 => "invokespecial",
+        0xb8 => "invokestatic",
+        0xb9 => "invokeinterface",
+        0xba => "invokedynamic",
+        0xbb => "new",
+        0xbc => "newarray",
+        0xbd => "anewarray",
+        0xbe => "arraylength",
+        0xbf => "athrow",
+        0xc0 => "checkcast",
+        0xc1 => "instanceof",
+        0xc2 => "monitorenter",
+        0xc3 => "monitorexit",
+        0xc4 => "wide",
+        0xc5 => "multianewarray",
+        0xc6 => "ifnull",
+        0xc7 => "ifnonnull",
+        0xc8 => "goto_w",
+        0xc9 => "jsr_w",
+        0xca => "breakpoint",
+        0xfe => "impdep1",
+        0xff => "impdep2",
+        _ => return None,
+    })
+}
+
+struct InstructionStream<'a> {
+    pub inner: BufReader<&'a [u8]>,
+    pub index: usize,
+}
+
+// An instruction together with where it is located in the code
+#[derive(Debug, Clone)]
+pub struct DecodedInstruction {
+    pub pc: usize,
+    pub length: usize,
+    pub instruction: Instruction,
+}
+
+impl DecodedInstruction {
+    // Resolves a branch offset relative to this instruction
+    pub fn target(&self, offset: i32) -> usize {
+        (self.pc as i64 + offset as i64) as usize
+    }
+
+    // The pcs the instruction can jump to, switch cases come in order with the default last.
+    // Falling through to the next instruction isn't included.
+    pub fn branch_targets(&self) -> Vec<usize> {
+        match self.instruction {
+            Instruction::GoTo(offset)
+            | Instruction::IfACmpEq(offset)
+            | Instruction::IfACmpNe(offset)
+            | Instruction::IfICmpEq(offset)
+            | Instruction::IfICmpGe(offset)
+            | Instruction::IfICmpGt(offset)
+            | Instruction::IfICmpLe(offset)
+            | Instruction::IfICmpLt(offset)
+            | Instruction::IfICmpNe(offset)
+            | Instruction::IfEq(offset)
+            | Instruction::IfGe(offset)
+            | Instruction::IfGt(offset)
+            | Instruction::IfLe(offset)
+            | Instruction::IfLt(offset)
+            | Instruction::IfNe(offset)
+            | Instruction::IfNonNull(offset)
+            | Instruction::IfNull(offset)
+            | Instruction::Jsr(offset) => vec![self.target(offset as i32)],
+            Instruction::GoToW(offset) | Instruction::JsrW(offset) => vec![self.target(offset)],
+            Instruction::TableSwitch {
+                default,
+                ref offsets,
+                ..
+            } => offsets
+                .iter()
+                .chain(std::iter::once(&default))
+                .map(|offset| self.target(*offset))
+                .collect(),
+            Instruction::LookUpSwitch { default, ref pairs } => pairs
+                .iter()
+                .map(|(_, offset)| offset)
+                .chain(std::iter::once(&default))
+                .map(|offset| self.target(*offset))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+// Finds the instruction starting at `pc`, the instructions have to be in code order
+pub fn index_of(instructions: &[DecodedInstruction], pc: usize) -> Option<usize> {
+    instructions
+        .binary_search_by_key(&pc, |instruction| instruction.pc)
+        .ok()
+}
+
+// Fails on unknown opcodes and on code that ends inside an instruction
+pub fn parse(
+    code_stream: BufReader<&[u8]>,
+    code_length: usize,
+) -> io::Result<Vec<DecodedInstruction>> {
+    let mut instructions = Vec::new();
+
+    let stream = &mut InstructionStream {
+        inner: code_stream,
+        index: 0,
+    };
+
+    while stream.index < code_length {
+        let pc = stream.index;
+        let instruction = match read(stream)? {
+            0x00 => Instruction::Nop,
+            0x01 => Instruction::AConstNull,
+            0x02 => Instruction::IConstM1,
+            0x03 => Instruction::IConst0,
+            0x04 => Instruction::IConst1,
+            0x05 => Instruction::IConst2,
+            0x06 => Instruction::IConst3,
+            0x07 => Instruction::IConst4,
+            0x08 => Instruction::IConst5,
+            0x09 => Instruction::LConst0,
+            0x0a => Instruction::LConst1,
+            0x0b => Instruction::FConst0,
+            0x0c => Instruction::FConst1,
+            0x0d => Instruction::FConst2,
+            0x0e => Instruction::DConst0,
+            0x0f => Instruction::DConst1,
+            0x10 => Instruction::BiPush(read(stream)?),
+            0x11 => Instruction::SiPush(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0x12 => Instruction::Ldc(read(stream)?),
+            0x13 => Instruction::LdcW(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0x14 => Instruction::Ldc2W(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0x15 => Instruction::ILoad(read(stream)? as u16),
+            0x16 => Instruction::LLoad(read(stream)? as u16),
+            0x17 => Instruction::FLoad(read(stream)? as u16),
+            0x18 => Instruction::DLoad(read(stream)? as u16),
+            0x19 => Instruction::ALoad(read(stream)? as u16),
+            0x1a => Instruction::ILoad0,
+            0x1b => Instruction::ILoad1,
+            0x1c => Instruction::ILoad2,
+            0x1d => Instruction::ILoad3,
+            0x1e => Instruction::LLoad0,
+            0x1f => Instruction::LLoad1,
+            0x20 => Instruction::LLoad2,
+            0x21 => Instruction::LLoad3,
+            0x22 => Instruction::FLoad0,
+            0x23 => Instruction::FLoad1,
+            0x24 => Instruction::FLoad2,
+            0x25 => Instruction::FLoad3,
+            0x26 => Instruction::DLoad0,
+            0x27 => Instruction::DLoad1,
+            0x28 => Instruction::DLoad2,
+            0x29 => Instruction::DLoad3,
+            0x2a => Instruction::ALoad0,
+            0x2b => Instruction::ALoad1,
+            0x2c => Instruction::ALoad2,
+            0x2d => Instruction::ALoad3,
+            0x2e => Instruction::IALoad,
+            0x2f => Instruction::LALoad,
+            0x30 => Instruction::FALoad,
+            0x31 => Instruction::DALoad,
+            0x32 => Instruction::AALoad,
+            0x33 => Instruction::BALoad,
+            0x34 => Instruction::CALoad,
+            0x35 => Instruction::SALoad,
+            0x36 => Instruction::IStore(read(stream)? as u16),
+            0x37 => Instruction::LStore(read(stream)? as u16),
+            0x38 => Instruction::FStore(read(stream)? as u16),
+            0x39 => Instruction::DStore(read(stream)? as u16),
+            0x3a => Instruction::AStore(read(stream)? as u16),
+            0x3b => Instruction::IStore0,
+            0x3c => Instruction::IStore1,
+            0x3d => Instruction::IStore2,
+            0x3e => Instruction::IStore3,
+            0x3f => Instruction::LStore0,
+            0x40 => Instruction::LStore1,
+            0x41 => Instruction::LStore2,
+            0x42 => Instruction::LStore3,
+            0x43 => Instruction::FStore0,
+            0x44 => Instruction::FStore1,
+            0x45 => Instruction::FStore2,
+            0x46 => Instruction::FStore3,
+            0x47 => Instruction::DStore0,
+            0x48 => Instruction::DStore1,
+            0x49 => Instruction::DStore2,
+            0x4a => Instruction::DStore3,
+            0x4b => Instruction::AStore0,
+            0x4c => Instruction::AStore1,
+            0x4d => Instruction::AStore2,
+            0x4e => Instruction::AStore3,
+            0x4f => Instruction::IAStore,
+            0x50 => Instruction::LAStore,
+            0x51 => Instruction::FAStore,
+            0x52 => Instruction::DAStore,
+            0x53 => Instruction::AAStore,
+            0x54 => Instruction::BAStore,
+            0x55 => Instruction::CAStore,
+            0x56 => Instruction::SAStore,
+            0x57 => Instruction::Pop,
+            0x58 => Instruction::Pop2,
+            0x59 => Instruction::Dup,
+            0x5a => Instruction::DupX1,
+            0x5b => Instruction::DupX2,
+            0x5c => Instruction::Dup2,
+            0x5d => Instruction::Dup2X1,
+            0x5e => Instruction::Dup2X2,
+            0x5f => Instruction::Swap,
+            0x60 => Instruction::IAdd,
+            0x61 => Instruction::LAdd,
+            0x62 => Instruction::FAdd,
+            0x63 => Instruction::DAdd,
+            0x64 => Instruction::ISub,
+            0x65 => Instruction::LSub,
+            0x66 => Instruction::FSub,
+            0x67 => Instruction::DSub,
+            0x68 => Instruction::IMul,
+            0x69 => Instruction::LMul,
+            0x6a => Instruction::FMul,
+            0x6b => Instruction::DMul,
+            0x6c => Instruction::IDiv,
+            0x6d => Instruction::LDiv,
+            0x6e => Instruction::FDiv,
+            0x6f => Instruction::DDiv,
+            0x70 => Instruction::IRem,
+            0x71 => Instruction::LRem,
+            0x72 => Instruction::FRem,
+            0x73 => Instruction::DRem,
+            0x74 => Instruction::INeg,
+            0x75 => Instruction::LNeg,
+            0x76 => Instruction::FNeg,
+            0x77 => Instruction::DNeg,
+            0x78 => Instruction::IShl,
+            0x79 => Instruction::LShl,
+            0x7a => Instruction::IShr,
+            0x7b => Instruction::LShr,
+            0x7c => Instruction::IUShr,
+            0x7d => Instruction::LUShr,
+            0x7e => Instruction::IAnd,
+            0x7f => Instruction::LAnd,
+            0x80 => Instruction::IOr,
+            0x81 => Instruction::LOr,
+            0x82 => Instruction::IXor,
+            0x83 => Instruction::LXor,
+            0x84 => Instruction::IInc(read(stream)? as u16, read(stream)? as i8 as i16),
+            0x85 => Instruction::I2L,
+            0x86 => Instruction::I2F,
+            0x87 => Instruction::I2D,
+            0x88 => Instruction::L2I,
+            0x89 => Instruction::L2F,
+            0x8a => Instruction::L2D,
+            0x8b => Instruction::F2I,
+            0x8c => Instruction::F2L,
+            0x8d => Instruction::F2D,
+            0x8e => Instruction::D2I,
+            0x8f => Instruction::D2L,
+            0x90 => Instruction::D2F,
+            0x91 => Instruction::I2B,
+            0x92 => Instruction::I2C,
+            0x93 => Instruction::I2S,
+            0x94 => Instruction::LCmp,
+            0x95 => Instruction::FCmpL,
+            0x96 => Instruction::FCmpG,
+            0x97 => Instruction::DCmpL,
+            0x98 => Instruction::DCmpG,
+            0x99 => Instruction::IfEq(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0x9a => Instruction::IfNe(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0x9b => Instruction::IfLt(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0x9c => Instruction::IfGe(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0x9d => Instruction::IfGt(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0x9e => Instruction::IfLe(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0x9f => Instruction::IfICmpEq(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xa0 => Instruction::IfICmpNe(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xa1 => Instruction::IfICmpLt(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xa2 => Instruction::IfICmpGe(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xa3 => Instruction::IfICmpGt(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xa4 => Instruction::IfICmpLe(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xa5 => Instruction::IfACmpEq(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xa6 => Instruction::IfACmpNe(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xa7 => Instruction::GoTo(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xa8 => Instruction::Jsr(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xa9 => Instruction::Ret(read(stream)? as u16),
+            0xaa => {
+                skip_padding(stream)?;
+                let default = read_i32(stream)?;
+                let low = read_i32(stream)?;
+                let high = read_i32(stream)?;
+                let mut offsets = Vec::new();
+                for _i in low..=high {
+                    offsets.push(read_i32(stream)?);
+                }
+                Instruction::TableSwitch {
+                    default,
+                    low,
+                    high,
+                    offsets,
+                }
+            }
+            0xab => {
+                skip_padding(stream)?;
+                let default = read_i32(stream)?;
+                let mut pairs = Vec::new();
+                for _i in 0..read_i32(stream)? {
+                    pairs.push((read_i32(stream)?, read_i32(stream)?));
+                }
+                Instruction::LookUpSwitch { default, pairs }
+            }
+            0xac => Instruction::IReturn,
+            0xad => Instruction::LReturn,
+            0xae => Instruction::FReturn,
+            0xaf => Instruction::DReturn,
+            0xb0 => Instruction::AReturn,
+            0xb1 => Instruction::Return,
+            0xb2 => Instruction::GetStatic(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xb3 => Instruction::PutStatic(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xb4 => Instruction::GetField(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xb5 => Instruction::PutField(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xb6 => Instruction::InvokeVirtual(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xb7 => Instruction::InvokeSpecial(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xb8 => Instruction::InvokeStatic(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xb9 => {
+                let instruction = Instruction::InvokeInterface(
+                    u16::from_be_bytes([read(stream)?, read(stream)?]),
+                    read(stream)?,
+                );
+                // the last operand byte is always zero
+                read(stream)?;
+                instruction
+            }
+            0xba => {
+                let instruction =
+                    Instruction::InvokeDynamic(u16::from_be_bytes([read(stream)?, read(stream)?]));
+                // followed by two zero bytes
+                read(stream)?;
+                read(stream)?;
+                instruction
+            }
+            0xbb => Instruction::New(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xbc => Instruction::NewArray(read(stream)?),
+            0xbd => Instruction::ANewArray(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xbe => Instruction::ArrayLength,
+            0xbf => Instruction::AThrow,
+            0xc0 => Instruction::CheckCast(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xc1 => Instruction::InstanceOf(u16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xc2 => Instruction::MonitorEnter,
+            0xc3 => Instruction::MonitorExit,
+            0xc4 => {
+                let opcode = read(stream)?;
+                let index = u16::from_be_bytes([read(stream)?, read(stream)?]);
+                match opcode {
+                    0x15 => Instruction::ILoad(index),
+                    0x16 => Instruction::LLoad(index),
+                    0x17 => Instruction::FLoad(index),
+                    0x18 => Instruction::DLoad(index),
+                    0x19 => Instruction::ALoad(index),
+                    0x36 => Instruction::IStore(index),
+                    0x37 => Instruction::LStore(index),
+                    0x38 => Instruction::FStore(index),
+                    0x39 => Instruction::DStore(index),
+                    0x3a => Instruction::AStore(index),
+                    0xa9 => Instruction::Ret(index),
+                    0x84 => {
+                        Instruction::IInc(index, i16::from_be_bytes([read(stream)?, read(stream)?]))
+                    }
+                    opcode => return Err(invalid(format!("invalid wide instruction: {}", opcode))),
+                }
+            }
+            0xc5 => Instruction::MultiANewArray(
+                u16::from_be_bytes([read(stream)?, read(stream)?]),
+                read(stream)?,
+            ),
+            0xc6 => Instruction::IfNull(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xc7 => Instruction::IfNonNull(i16::from_be_bytes([read(stream)?, read(stream)?])),
+            0xc8 => Instruction::GoToW(read_i32(stream)?),
+            0xc9 => Instruction::JsrW(read_i32(stream)?),
+            0xca => Instruction::Breakpoint,
+            0xfe => Instruction::ImpDep1,
+            0xff => Instruction::ImpDep2,
+            opcode => return Err(invalid(format!("unknown instruction: {}", opcode))),
+        };
+        instructions.push(DecodedInstruction {
+            pc,
+            length: stream.index - pc,
+            instruction,
+        });
+    }
+    Ok(instructions)
+}
+
+fn read_i32(stream: &mut InstructionStream) -> io::Result<i32> {
+    Ok(i32::from_be_bytes([
+        read(stream)?,
+        read(stream)?,
+        read(stream)?,
+        read(stream)?,
+    ]))
+}
+
+// switch operands are aligned to a multiple of four bytes from the start of the code
+fn skip_padding(stream: &mut InstructionStream) -> io::Result<()> {
+    while !stream.index.is_multiple_of(4) {
+        read(stream)?;
+    }
+    Ok(())
+}
+
+fn read(stream: &mut InstructionStream) -> io::Result<u8> {
+    let buf: &mut [u8; 1] = &mut [0u8];
+    stream.inner.read_exact(buf)?;
+    stream.index += 1;
+    Ok(buf[0])
+}
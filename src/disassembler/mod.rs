@@ -1,15 +1,25 @@
 pub mod attribute;
 pub mod builder;
+pub mod code;
 pub mod constant_pool;
+pub mod descriptor;
+pub mod exception;
 pub mod field;
+pub mod instruction;
 pub mod interface;
 pub mod method;
+pub mod printer;
+pub mod validate;
+pub mod writer;
+
+use std::io;
+use std::io::Write;
 
 #[derive(Debug)]
 pub struct ClassFile {
-    magic: u32,
-    minor: u16,
-    major: u16,
+    pub magic: u32,
+    pub minor: u16,
+    pub major: u16,
     pub constant_pool: constant_pool::ConstantPool,
     pub access_flags: u16,
     pub this_class: u16,
@@ -21,14 +31,32 @@ pub struct ClassFile {
 }
 
 impl ClassFile {
-    pub fn new(buf: &[u8]) -> ClassFile {
-        builder::ClassFileBuilder {
+    pub fn new(buf: &[u8]) -> io::Result<ClassFile> {
+        let class_file = builder::ClassFileBuilder {
             buf,
             constant_pool: constant_pool::ConstantPool::new(
                 0,
                 Vec::<constant_pool::CPIndexType>::new(),
             ),
         }
-        .parse()
+        .parse()?;
+        validate::validate(&class_file)?;
+        Ok(class_file)
+    }
+
+    pub fn disassemble(&self) -> String {
+        printer::print_class_file(self)
+    }
+
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        writer::ClassFileWriter { buf: Vec::new() }.write(self)
     }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_bytes()?.as_slice())
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
 }
@@ -0,0 +1,946 @@
+use super::attribute::{read_bootstrap_methods, read_inner_classes, AttributeInfo, InnerClass};
+use super::code::*;
+use super::constant_pool::*;
+use super::descriptor;
+use super::instruction::{self, DecodedInstruction, Instruction};
+use super::ClassFile;
+
+use std::io::BufReader;
+
+const CLASS_FLAGS: [(u16, &str); 9] = [
+    (0x0001, "ACC_PUBLIC"),
+    (0x0010, "ACC_FINAL"),
+    (0x0020, "ACC_SUPER"),
+    (0x0200, "ACC_INTERFACE"),
+    (0x0400, "ACC_ABSTRACT"),
+    (0x1000, "ACC_SYNTHETIC"),
+    (0x2000, "ACC_ANNOTATION"),
+    (0x4000, "ACC_ENUM"),
+    (0x8000, "ACC_MODULE"),
+];
+
+const FIELD_FLAGS: [(u16, &str); 9] = [
+    (0x0001, "ACC_PUBLIC"),
+    (0x0002, "ACC_PRIVATE"),
+    (0x0004, "ACC_PROTECTED"),
+    (0x0008, "ACC_STATIC"),
+    (0x0010, "ACC_FINAL"),
+    (0x0040, "ACC_VOLATILE"),
+    (0x0080, "ACC_TRANSIENT"),
+    (0x1000, "ACC_SYNTHETIC"),
+    (0x4000, "ACC_ENUM"),
+];
+
+const METHOD_FLAGS: [(u16, &str); 12] = [
+    (0x0001, "ACC_PUBLIC"),
+    (0x0002, "ACC_PRIVATE"),
+    (0x0004, "ACC_PROTECTED"),
+    (0x0008, "ACC_STATIC"),
+    (0x0010, "ACC_FINAL"),
+    (0x0020, "ACC_SYNCHRONIZED"),
+    (0x0040, "ACC_BRIDGE"),
+    (0x0080, "ACC_VARARGS"),
+    (0x0100, "ACC_NATIVE"),
+    (0x0400, "ACC_ABSTRACT"),
+    (0x0800, "ACC_STRICT"),
+    (0x1000, "ACC_SYNTHETIC"),
+];
+
+// flags that show up as java keywords in the member declaration lines
+const MODIFIERS: [(u16, &str); 8] = [
+    (0x0001, "public"),
+    (0x0002, "private"),
+    (0x0004, "protected"),
+    (0x0008, "static"),
+    (0x0010, "final"),
+    (0x0020, "synchronized"),
+    (0x0100, "native"),
+    (0x0400, "abstract"),
+];
+
+pub fn print_class_file(class_file: &ClassFile) -> String {
+    let constant_pool = &class_file.constant_pool;
+    let mut output = String::new();
+
+    if let Some(source_file) = find_attribute(&class_file.attributes.attributes, "SourceFile") {
+        output.push_str(
+            format!(
+                "Compiled from \"{}\"\n",
+                get_utf8(constant_pool, read_u16(&source_file.info, 0))
+            )
+            .as_str(),
+        );
+    }
+
+    output.push_str(class_declaration(class_file).as_str());
+    output.push('\n');
+    output.push_str(format!("  minor version: {}\n", class_file.minor).as_str());
+    output.push_str(format!("  major version: {}\n", class_file.major).as_str());
+    output.push_str(
+        format!(
+            "  flags: {}\n",
+            format_flags(class_file.access_flags, &CLASS_FLAGS)
+        )
+        .as_str(),
+    );
+    output.push_str(
+        with_comment(
+            format!("  this_class: #{}", class_file.this_class),
+            resolve(constant_pool, class_file.this_class),
+        )
+        .as_str(),
+    );
+    if class_file.super_class != 0 {
+        output.push_str(
+            with_comment(
+                format!("  super_class: #{}", class_file.super_class),
+                resolve(constant_pool, class_file.super_class),
+            )
+            .as_str(),
+        );
+    } else {
+        output.push_str("  super_class: #0\n");
+    }
+    output.push_str(
+        format!(
+            "  interfaces: {}, fields: {}, methods: {}, attributes: {}\n",
+            class_file.interfaces.interface_count,
+            class_file.fields.field_count,
+            class_file.methods.method_count,
+            class_file.attributes.attribute_count
+        )
+        .as_str(),
+    );
+
+    output.push_str(print_constant_pool(constant_pool).as_str());
+
+    output.push_str("{\n");
+
+    for field in &class_file.fields.fields {
+        let field_descriptor = get_utf8(constant_pool, field.descriptor_index);
+
+        output.push_str(
+            format!(
+                "  {}{} {};\n",
+                modifiers(field.access_flags),
+                descriptor::to_java_type(field_descriptor.as_str()),
+                get_utf8(constant_pool, field.name_index)
+            )
+            .as_str(),
+        );
+        output.push_str(format!("    descriptor: {}\n", field_descriptor).as_str());
+        output.push_str(
+            format!(
+                "    flags: {}\n",
+                format_flags(field.access_flags, &FIELD_FLAGS)
+            )
+            .as_str(),
+        );
+        output.push_str(print_attributes(&field.attributes, constant_pool, "    ").as_str());
+        output.push('\n');
+    }
+
+    for (i, method) in class_file.methods.methods.iter().enumerate() {
+        if i > 0 {
+            output.push('\n');
+        }
+
+        let name = get_utf8(constant_pool, method.name_index);
+        let method_descriptor = get_utf8(constant_pool, method.descriptor_index);
+        let (args, return_type) = descriptor::split_method_descriptor(method_descriptor.as_str());
+        let args = args
+            .iter()
+            .map(|arg| descriptor::to_java_type(arg))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        output.push_str("  ");
+        output.push_str(modifiers(method.access_flags).as_str());
+        match name.as_str() {
+            "<clinit>" => output.push_str("{}"),
+            "<init>" => output.push_str(
+                format!(
+                    "{}({})",
+                    resolve(constant_pool, class_file.this_class).replace('/', "."),
+                    args
+                )
+                .as_str(),
+            ),
+            _ => output.push_str(
+                format!(
+                    "{} {}({})",
+                    descriptor::to_java_type(return_type.as_str()),
+                    name,
+                    args
+                )
+                .as_str(),
+            ),
+        }
+        output.push_str(";\n");
+        output.push_str(format!("    descriptor: {}\n", method_descriptor).as_str());
+        output.push_str(
+            format!(
+                "    flags: {}\n",
+                format_flags(method.access_flags, &METHOD_FLAGS)
+            )
+            .as_str(),
+        );
+
+        for attribute in &method.attributes {
+            if attribute.get_name() == "Code" {
+                let code = match CodeAttribute::new(&attribute.info, constant_pool) {
+                    Ok(code) => code,
+                    Err(error) => {
+                        output.push_str(format!("    Code: // {}\n", error).as_str());
+                        continue;
+                    }
+                };
+                let args_size = descriptor::argument_slots(method_descriptor.as_str())
+                    + (method.access_flags & 0x0008 == 0) as u16;
+
+                output.push_str("    Code:\n");
+                output.push_str(
+                    format!(
+                        "      stack={}, locals={}, args_size={}\n",
+                        code.max_stack, code.max_locals, args_size
+                    )
+                    .as_str(),
+                );
+                output.push_str(print_code(&code, constant_pool).as_str());
+            } else {
+                output.push_str(
+                    print_attributes(std::slice::from_ref(attribute), constant_pool, "    ")
+                        .as_str(),
+                );
+            }
+        }
+    }
+
+    output.push_str("}\n");
+    output
+        .push_str(print_attributes(&class_file.attributes.attributes, constant_pool, "").as_str());
+
+    output
+}
+
+pub fn print_constant_pool(constant_pool: &ConstantPool) -> String {
+    let mut output = String::from("Constant pool:\n");
+    let width = format!("#{}", constant_pool.indexes.len()).len() + 2;
+
+    let mut index = 1;
+    while index as usize <= constant_pool.indexes.len() {
+        let entry = constant_pool.get_index(index);
+
+        let (kind, args) = match &entry {
+            CPIndexType::Class(name_index) => ("Class", format!("#{}", name_index)),
+            CPIndexType::FieldRef {
+                class_index,
+                name_and_type_index,
+            } => (
+                "Fieldref",
+                format!("#{}.#{}", class_index, name_and_type_index),
+            ),
+            CPIndexType::MethodRef {
+                class_index,
+                name_and_type_index,
+            } => (
+                "Methodref",
+                format!("#{}.#{}", class_index, name_and_type_index),
+            ),
+            CPIndexType::InterfaceMethodRef {
+                class_index,
+                name_and_type_index,
+            } => (
+                "InterfaceMethodref",
+                format!("#{}.#{}", class_index, name_and_type_index),
+            ),
+            CPIndexType::String(string_index) => ("String", format!("#{}", string_index)),
+            CPIndexType::Integer(_) => ("Integer", resolve(constant_pool, index)),
+            CPIndexType::Float(_) => ("Float", resolve(constant_pool, index)),
+            CPIndexType::Long(_) => ("Long", resolve(constant_pool, index)),
+            CPIndexType::Double(_) => ("Double", resolve(constant_pool, index)),
+            CPIndexType::NameAndType {
+                name_index,
+                descriptor_index,
+            } => (
+                "NameAndType",
+                format!("#{}:#{}", name_index, descriptor_index),
+            ),
+            CPIndexType::Utf8(_) => ("Utf8", resolve(constant_pool, index)),
+            CPIndexType::MethodHandle {
+                reference_type,
+                reference_index,
+            } => (
+                "MethodHandle",
+                format!("{}:#{}", reference_type, reference_index),
+            ),
+            CPIndexType::MethodType { descriptor_index } => {
+                ("MethodType", format!("#{}", descriptor_index))
+            }
+            CPIndexType::InvokeDynamic {
+                boostrap_method_attr_index,
+                name_and_type_index,
+            } => (
+                "InvokeDynamic",
+                format!("#{}:#{}", boostrap_method_attr_index, name_and_type_index),
+            ),
+            CPIndexType::Module(name_index) => ("Module", format!("#{}", name_index)),
+            CPIndexType::Package(name_index) => ("Package", format!("#{}", name_index)),
+        };
+
+        let line = format!(
+            "{:>width$} = {:<18} {}",
+            format!("#{}", index),
+            kind,
+            args,
+            width = width
+        );
+
+        match entry {
+            CPIndexType::Integer(_)
+            | CPIndexType::Float(_)
+            | CPIndexType::Long(_)
+            | CPIndexType::Double(_)
+            | CPIndexType::Utf8(_) => {
+                output.push_str(line.as_str());
+                output.push('\n');
+            }
+            _ => output.push_str(
+                format!(
+                    "{:<width$}// {}\n",
+                    line,
+                    resolve(constant_pool, index),
+                    width = width + 36
+                )
+                .as_str(),
+            ),
+        }
+
+        // the slot after a Long or Double is unusable
+        match entry {
+            CPIndexType::Long(_) | CPIndexType::Double(_) => index += 2,
+            _ => index += 1,
+        }
+    }
+
+    output
+}
+
+pub fn print_code(code: &CodeAttribute, constant_pool: &ConstantPool) -> String {
+    let mut output = String::new();
+
+    let code_stream = BufReader::new(code.code.as_slice());
+    let instructions = match instruction::parse(code_stream, code.code.len()) {
+        Ok(instructions) => instructions,
+        Err(error) => return format!("      // {}\n", error),
+    };
+
+    for decoded in &instructions {
+        let mut line = print_instruction(decoded, constant_pool);
+
+        // assembled code may jump into the middle of an instruction or past the end
+        if decoded
+            .branch_targets()
+            .iter()
+            .any(|target| instruction::index_of(&instructions, *target).is_none())
+        {
+            line.push_str("  // invalid branch target");
+        }
+
+        output.push_str(format!("{:>10}: {}\n", decoded.pc, line).as_str());
+    }
+
+    if !code.exception_table.is_empty() {
+        output.push_str("      Exception table:\n");
+        output.push_str("         from    to  target type\n");
+
+        for exception in &code.exception_table {
+            let catch_type = match exception.catch_type {
+                0 => "any".to_string(),
+                catch_type => format!("Class {}", resolve(constant_pool, catch_type)),
+            };
+
+            output.push_str(
+                format!(
+                    "{:>14}{:>6}{:>6}   {}\n",
+                    exception.start_pc, exception.end_pc, exception.handler_pc, catch_type
+                )
+                .as_str(),
+            );
+        }
+    }
+
+    for attribute in &code.attributes {
+        match attribute.get_name() {
+            "LineNumberTable" => {
+                output.push_str("      LineNumberTable:\n");
+                for line_number in code.line_numbers() {
+                    output.push_str(
+                        format!(
+                            "        line {}: {}\n",
+                            line_number.line_number, line_number.start_pc
+                        )
+                        .as_str(),
+                    );
+                }
+            }
+            name @ "LocalVariableTable" | name @ "LocalVariableTypeTable" => {
+                let local_variables = if name == "LocalVariableTable" {
+                    code.local_variables()
+                } else {
+                    code.local_variable_types()
+                };
+
+                output.push_str(format!("      {}:\n", name).as_str());
+                output.push_str("        Start  Length  Slot  Name   Signature\n");
+                for local_variable in local_variables {
+                    output.push_str(
+                        format!(
+                            "{:>13}{:>8}{:>6}{:>6}   {}\n",
+                            local_variable.start_pc,
+                            local_variable.length,
+                            local_variable.index,
+                            get_utf8(constant_pool, local_variable.name_index),
+                            get_utf8(constant_pool, local_variable.descriptor_index)
+                        )
+                        .as_str(),
+                    );
+                }
+            }
+            _ => output.push_str(
+                print_attributes(std::slice::from_ref(attribute), constant_pool, "      ").as_str(),
+            ),
+        }
+    }
+
+    output
+}
+
+pub fn print_instruction(decoded: &DecodedInstruction, constant_pool: &ConstantPool) -> String {
+    let instruction = &decoded.instruction;
+    let targets = decoded.branch_targets();
+    let mut mnemonic = instruction.mnemonic().to_string();
+
+    let (operands, comment) = match *instruction {
+        Instruction::ALoad(index)
+        | Instruction::AStore(index)
+        | Instruction::DLoad(index)
+        | Instruction::DStore(index)
+        | Instruction::FLoad(index)
+        | Instruction::FStore(index)
+        | Instruction::ILoad(index)
+        | Instruction::IStore(index)
+        | Instruction::LLoad(index)
+        | Instruction::LStore(index)
+        | Instruction::Ret(index) => {
+            if index > u8::MAX as u16 {
+                mnemonic.push_str("_w");
+            }
+            (index.to_string(), None)
+        }
+
+        Instruction::BiPush(value) => ((value as i8).to_string(), None),
+        Instruction::SiPush(value) => ((value as i16).to_string(), None),
+        Instruction::IInc(index, value) => {
+            if index > u8::MAX as u16 || value != value as i8 as i16 {
+                mnemonic.push_str("_w");
+            }
+            (format!("{}, {}", index, value), None)
+        }
+        Instruction::NewArray(atype) => (array_type_name(atype).to_string(), None),
+
+        Instruction::Ldc(index) => constant_operand(constant_pool, index as u16),
+        Instruction::LdcW(index)
+        | Instruction::Ldc2W(index)
+        | Instruction::ANewArray(index)
+        | Instruction::CheckCast(index)
+        | Instruction::GetField(index)
+        | Instruction::GetStatic(index)
+        | Instruction::InstanceOf(index)
+        | Instruction::InvokeSpecial(index)
+        | Instruction::InvokeStatic(index)
+        | Instruction::InvokeVirtual(index)
+        | Instruction::New(index)
+        | Instruction::PutField(index)
+        | Instruction::PutStatic(index) => constant_operand(constant_pool, index),
+
+        Instruction::InvokeDynamic(index) => (
+            format!("#{},  0", index),
+            constant_operand(constant_pool, index).1,
+        ),
+        Instruction::InvokeInterface(index, count) => (
+            format!("#{},  {}", index, count),
+            constant_operand(constant_pool, index).1,
+        ),
+        Instruction::MultiANewArray(index, dimensions) => (
+            format!("#{},  {}", index, dimensions),
+            constant_operand(constant_pool, index).1,
+        ),
+
+        Instruction::GoTo(_)
+        | Instruction::IfACmpEq(_)
+        | Instruction::IfACmpNe(_)
+        | Instruction::IfICmpEq(_)
+        | Instruction::IfICmpGe(_)
+        | Instruction::IfICmpGt(_)
+        | Instruction::IfICmpLe(_)
+        | Instruction::IfICmpLt(_)
+        | Instruction::IfICmpNe(_)
+        | Instruction::IfEq(_)
+        | Instruction::IfGe(_)
+        | Instruction::IfGt(_)
+        | Instruction::IfLe(_)
+        | Instruction::IfLt(_)
+        | Instruction::IfNe(_)
+        | Instruction::IfNonNull(_)
+        | Instruction::IfNull(_)
+        | Instruction::Jsr(_)
+        | Instruction::GoToW(_)
+        | Instruction::JsrW(_) => (targets[0].to_string(), None),
+
+        Instruction::TableSwitch { low, high, .. } => (
+            print_switch(
+                format!("{{ // {} to {}", low, high),
+                (low..=high).collect(),
+                &targets,
+            ),
+            None,
+        ),
+        Instruction::LookUpSwitch { ref pairs, .. } => (
+            print_switch(
+                format!("{{ // {}", pairs.len()),
+                pairs.iter().map(|(key, _)| *key).collect(),
+                &targets,
+            ),
+            None,
+        ),
+
+        _ => return mnemonic,
+    };
+
+    match comment {
+        Some(comment) => format!(
+            "{:<34}// {}",
+            format!("{:<13} {}", mnemonic, operands),
+            comment
+        ),
+        None => format!("{:<13} {}", mnemonic, operands),
+    }
+}
+
+// the default target comes after the targets of the keys
+fn print_switch(header: String, keys: Vec<i32>, targets: &[usize]) -> String {
+    let mut operands = header;
+    operands.push('\n');
+
+    for (key, target) in keys.iter().zip(targets) {
+        operands.push_str(format!("{:>24}: {}\n", key, target).as_str());
+    }
+    operands.push_str(format!("{:>24}: {}\n", "default", targets[keys.len()]).as_str());
+    operands.push_str("            }");
+
+    operands
+}
+
+fn constant_operand(constant_pool: &ConstantPool, index: u16) -> (String, Option<String>) {
+    let kind = match constant_pool.get_index(index) {
+        CPIndexType::Class(_) => "class",
+        CPIndexType::FieldRef { .. } => "Field",
+        CPIndexType::MethodRef { .. } => "Method",
+        CPIndexType::InterfaceMethodRef { .. } => "InterfaceMethod",
+        CPIndexType::String(_) => "String",
+        CPIndexType::Integer(_) => "int",
+        CPIndexType::Float(_) => "float",
+        CPIndexType::Long(_) => "long",
+        CPIndexType::Double(_) => "double",
+        CPIndexType::MethodHandle { .. } => "MethodHandle",
+        CPIndexType::MethodType { .. } => "MethodType",
+        CPIndexType::InvokeDynamic { .. } => "InvokeDynamic",
+        CPIndexType::Module(_) => "Module",
+        CPIndexType::Package(_) => "Package",
+        CPIndexType::NameAndType { .. } | CPIndexType::Utf8(_) => "",
+    };
+
+    (
+        format!("#{}", index),
+        Some(format!("{} {}", kind, resolve(constant_pool, index))),
+    )
+}
+
+// Renders a constant pool entry the way javap does in its comments
+pub fn resolve(constant_pool: &ConstantPool, index: u16) -> String {
+    match constant_pool.get_index(index) {
+        CPIndexType::Class(name_index) => {
+            let name = get_utf8(constant_pool, name_index);
+            if name.starts_with('[') {
+                format!("\"{}\"", name)
+            } else {
+                name
+            }
+        }
+        CPIndexType::FieldRef {
+            class_index,
+            name_and_type_index,
+        }
+        | CPIndexType::MethodRef {
+            class_index,
+            name_and_type_index,
+        }
+        | CPIndexType::InterfaceMethodRef {
+            class_index,
+            name_and_type_index,
+        } => format!(
+            "{}.{}",
+            resolve(constant_pool, class_index),
+            resolve(constant_pool, name_and_type_index)
+        ),
+        CPIndexType::String(string_index) => resolve(constant_pool, string_index),
+        CPIndexType::Integer(value) => (value as i32).to_string(),
+        CPIndexType::Float(value) => match special_float(value as f64) {
+            Some(value) => format!("{}f", value),
+            None => format!("{:?}f", value),
+        },
+        CPIndexType::Long(value) => format!("{}l", value as i64),
+        CPIndexType::Double(value) => match special_float(value) {
+            Some(value) => format!("{}d", value),
+            None => format!("{:?}d", value),
+        },
+        CPIndexType::NameAndType {
+            name_index,
+            descriptor_index,
+        } => {
+            let name = get_utf8(constant_pool, name_index);
+            let name = if name.starts_with('<') {
+                format!("\"{}\"", name)
+            } else {
+                name
+            };
+            format!("{}:{}", name, get_utf8(constant_pool, descriptor_index))
+        }
+        CPIndexType::Utf8(utf8) => escape(utf8.string.as_str()),
+        CPIndexType::MethodHandle {
+            reference_type,
+            reference_index,
+        } => format!(
+            "{} {}",
+            reference_kind_name(reference_type),
+            resolve(constant_pool, reference_index)
+        ),
+        CPIndexType::MethodType { descriptor_index } => {
+            format!(" {}", get_utf8(constant_pool, descriptor_index))
+        }
+        CPIndexType::InvokeDynamic {
+            boostrap_method_attr_index,
+            name_and_type_index,
+        } => format!(
+            "#{}:{}",
+            boostrap_method_attr_index,
+            resolve(constant_pool, name_and_type_index)
+        ),
+        CPIndexType::Module(name_index) => format!("\"{}\"", get_utf8(constant_pool, name_index)),
+        CPIndexType::Package(name_index) => get_utf8(constant_pool, name_index),
+    }
+}
+
+fn print_attributes(
+    attributes: &[AttributeInfo],
+    constant_pool: &ConstantPool,
+    indent: &str,
+) -> String {
+    let mut output = String::new();
+
+    for attribute in attributes {
+        let info = &attribute.info;
+
+        match attribute.get_name() {
+            "SourceFile" => output.push_str(
+                format!(
+                    "{}SourceFile: \"{}\"\n",
+                    indent,
+                    get_utf8(constant_pool, read_u16(info, 0))
+                )
+                .as_str(),
+            ),
+            "Signature" => output.push_str(
+                with_comment(
+                    format!("{}Signature: #{}", indent, read_u16(info, 0)),
+                    resolve(constant_pool, read_u16(info, 0)),
+                )
+                .as_str(),
+            ),
+            "ConstantValue" => {
+                let index = read_u16(info, 0);
+                output.push_str(
+                    format!(
+                        "{}ConstantValue: {}\n",
+                        indent,
+                        constant_operand(constant_pool, index).1.unwrap()
+                    )
+                    .as_str(),
+                );
+            }
+            "Exceptions" => {
+                let exceptions = (0..read_u16(info, 0) as usize)
+                    .map(|i| resolve(constant_pool, read_u16(info, 2 + i * 2)).replace('/', "."))
+                    .collect::<Vec<String>>();
+                output.push_str(format!("{}Exceptions:\n", indent).as_str());
+                output.push_str(format!("{}  throws {}\n", indent, exceptions.join(", ")).as_str());
+            }
+            "StackMapTable" => output.push_str(
+                format!(
+                    "{}StackMapTable: number_of_entries = {}\n",
+                    indent,
+                    read_u16(info, 0)
+                )
+                .as_str(),
+            ),
+            "InnerClasses" => {
+                output.push_str(format!("{}InnerClasses:\n", indent).as_str());
+                for InnerClass {
+                    inner_class,
+                    outer_class,
+                    inner_name,
+                    flags,
+                } in read_inner_classes(attributes).unwrap_or_default()
+                {
+                    let mut line = format!("{}  {}", indent, modifiers(flags));
+                    if inner_name != 0 {
+                        line.push_str(format!("#{}= ", inner_name).as_str());
+                    }
+                    line.push_str(format!("#{}", inner_class).as_str());
+                    if outer_class != 0 {
+                        line.push_str(format!(" of #{}", outer_class).as_str());
+                    }
+                    line.push(';');
+
+                    let mut comment = String::new();
+                    if inner_name != 0 {
+                        comment
+                            .push_str(format!("{}=", get_utf8(constant_pool, inner_name)).as_str());
+                    }
+                    comment.push_str(
+                        format!("class {}", resolve(constant_pool, inner_class)).as_str(),
+                    );
+                    if outer_class != 0 {
+                        comment.push_str(
+                            format!(" of class {}", resolve(constant_pool, outer_class)).as_str(),
+                        );
+                    }
+                    output.push_str(with_comment(line, comment).as_str());
+                }
+            }
+            "BootstrapMethods" => {
+                output.push_str(format!("{}BootstrapMethods:\n", indent).as_str());
+                for (i, bootstrap_method) in read_bootstrap_methods(attributes)
+                    .unwrap_or_default()
+                    .iter()
+                    .enumerate()
+                {
+                    output.push_str(
+                        format!(
+                            "{}  {}: #{} {}\n",
+                            indent,
+                            i,
+                            bootstrap_method.method_ref,
+                            resolve(constant_pool, bootstrap_method.method_ref)
+                        )
+                        .as_str(),
+                    );
+                    output.push_str(format!("{}    Method arguments:\n", indent).as_str());
+                    for argument in &bootstrap_method.arguments {
+                        output.push_str(
+                            format!(
+                                "{}      #{} {}\n",
+                                indent,
+                                argument,
+                                resolve(constant_pool, *argument).trim_start()
+                            )
+                            .as_str(),
+                        );
+                    }
+                }
+            }
+            name => output.push_str(
+                format!(
+                    "{}{}: length = 0x{:X}\n",
+                    indent, name, attribute.attribute_length
+                )
+                .as_str(),
+            ),
+        }
+    }
+
+    output
+}
+
+fn class_declaration(class_file: &ClassFile) -> String {
+    let access_flags = class_file.access_flags;
+    let mut declaration = String::new();
+
+    if access_flags & 0x0001 != 0 {
+        declaration.push_str("public ");
+    }
+
+    if access_flags & 0x8000 != 0 {
+        declaration.push_str("module ");
+    } else if access_flags & 0x2000 != 0 {
+        declaration.push_str("@interface ");
+    } else if access_flags & 0x0200 != 0 {
+        declaration.push_str("interface ");
+    } else if access_flags & 0x4000 != 0 {
+        declaration.push_str("final enum ");
+    } else {
+        if access_flags & 0x0400 != 0 {
+            declaration.push_str("abstract ");
+        }
+        if access_flags & 0x0010 != 0 {
+            declaration.push_str("final ");
+        }
+        declaration.push_str("class ");
+    }
+
+    declaration.push_str(
+        resolve(&class_file.constant_pool, class_file.this_class)
+            .replace('/', ".")
+            .as_str(),
+    );
+
+    if class_file.super_class != 0 && access_flags & 0x0200 == 0 {
+        declaration.push_str(" extends ");
+        declaration.push_str(
+            resolve(&class_file.constant_pool, class_file.super_class)
+                .replace('/', ".")
+                .as_str(),
+        );
+    }
+
+    if class_file.interfaces.interface_count > 0 {
+        declaration.push_str(if access_flags & 0x0200 == 0 {
+            " implements "
+        } else {
+            " extends "
+        });
+        declaration.push_str(
+            class_file
+                .interfaces
+                .interfaces
+                .iter()
+                .map(|interface| resolve(&class_file.constant_pool, *interface).replace('/', "."))
+                .collect::<Vec<String>>()
+                .join(", ")
+                .as_str(),
+        );
+    }
+
+    declaration
+}
+
+fn modifiers(access_flags: u16) -> String {
+    let mut modifiers = String::new();
+
+    for (flag, modifier) in MODIFIERS.iter() {
+        if access_flags & flag != 0 {
+            modifiers.push_str(modifier);
+            modifiers.push(' ');
+        }
+    }
+
+    modifiers
+}
+
+fn format_flags(access_flags: u16, names: &[(u16, &str)]) -> String {
+    let names = names
+        .iter()
+        .filter(|(flag, _)| access_flags & flag != 0)
+        .map(|(_, name)| *name)
+        .collect::<Vec<&str>>();
+
+    if names.is_empty() {
+        format!("(0x{:04x})", access_flags)
+    } else {
+        format!("(0x{:04x}) {}", access_flags, names.join(", "))
+    }
+}
+
+fn with_comment(line: String, comment: String) -> String {
+    // javap aligns comments relative to the indentation of the line
+    let indent = line.len() - line.trim_start().len();
+    format!("{:<width$}// {}\n", line, comment, width = 40 + indent)
+}
+
+fn find_attribute<'a>(attributes: &'a [AttributeInfo], name: &str) -> Option<&'a AttributeInfo> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.get_name() == name)
+}
+
+fn get_utf8(constant_pool: &ConstantPool, index: u16) -> String {
+    match constant_pool.get_index(index) {
+        CPIndexType::Utf8(utf8) => utf8.string,
+        _ => panic!("Invalid Type in Constant Pool"),
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn special_float(value: f64) -> Option<&'static str> {
+    if value.is_nan() {
+        Some("NaN")
+    } else if value == f64::INFINITY {
+        Some("Infinity")
+    } else if value == f64::NEG_INFINITY {
+        Some("-Infinity")
+    } else {
+        None
+    }
+}
+
+fn escape(string: &str) -> String {
+    let mut escaped = String::new();
+
+    for char in string.chars() {
+        match char {
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\\' => escaped.push_str("\\\\"),
+            char if (char as u32) < 0x20 || char as u32 == 0x7f => {
+                escaped.push_str(format!("\\u{:04x}", char as u32).as_str())
+            }
+            char => escaped.push(char),
+        }
+    }
+
+    escaped
+}
+
+pub fn array_type_name(atype: u8) -> &'static str {
+    match atype {
+        4 => "boolean",
+        5 => "char",
+        6 => "float",
+        7 => "double",
+        8 => "byte",
+        9 => "short",
+        10 => "int",
+        11 => "long",
+        _ => panic!("Unknown array type: {}", atype),
+    }
+}
+
+pub fn reference_kind_name(reference_kind: u8) -> &'static str {
+    match reference_kind {
+        1 => "REF_getField",
+        2 => "REF_getStatic",
+        3 => "REF_putField",
+        4 => "REF_putStatic",
+        5 => "REF_invokeVirtual",
+        6 => "REF_invokeStatic",
+        7 => "REF_invokeSpecial",
+        8 => "REF_newInvokeSpecial",
+        9 => "REF_invokeInterface",
+        _ => panic!("Unknown reference kind: {}", reference_kind),
+    }
+}
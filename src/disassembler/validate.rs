@@ -0,0 +1,434 @@
+// Checks what a class file refers to when it is parsed, the printer and the decompiler
+// follow constant pool indexes and read attributes without looking at them again
+use super::attribute::{read_bootstrap_methods, read_inner_classes, read_module, AttributeInfo};
+use super::code::{ByteReader, CodeAttribute};
+use super::constant_pool::{CPIndexType, ConstantPool};
+use super::descriptor::{is_field_descriptor, is_method_descriptor};
+use super::instruction::{self, Instruction};
+use super::{invalid, ClassFile};
+
+use std::io;
+use std::io::BufReader;
+
+const MODULE: u16 = 0x8000;
+
+pub fn validate(class_file: &ClassFile) -> io::Result<()> {
+    let pool = Pool::new(&class_file.constant_pool);
+
+    for index in 1..=class_file.constant_pool.indexes.len() {
+        if !pool.placeholders[index - 1] {
+            pool.entry(index as u16)?;
+        }
+    }
+
+    pool.class(class_file.this_class)?;
+    // only java.lang.Object and module-info have none
+    if class_file.super_class != 0 {
+        pool.class(class_file.super_class)?;
+    }
+    for interface in &class_file.interfaces.interfaces {
+        pool.class(*interface)?;
+    }
+
+    for field in &class_file.fields.fields {
+        pool.utf8(field.name_index)?;
+        pool.descriptor(field.descriptor_index, is_field_descriptor)?;
+        pool.attributes(&field.attributes)?;
+    }
+    for method in &class_file.methods.methods {
+        pool.utf8(method.name_index)?;
+        pool.descriptor(method.descriptor_index, is_method_descriptor)?;
+        pool.attributes(&method.attributes)?;
+    }
+
+    let attributes = &class_file.attributes.attributes;
+    pool.attributes(attributes)?;
+    if class_file.access_flags & MODULE != 0 && read_module(attributes)?.is_none() {
+        return Err(invalid(
+            "module-info without a Module attribute".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+struct Pool<'a> {
+    constant_pool: &'a ConstantPool,
+    // the unusable entries after longs and doubles
+    placeholders: Vec<bool>,
+}
+
+impl<'a> Pool<'a> {
+    fn new(constant_pool: &'a ConstantPool) -> Self {
+        let mut placeholders = vec![false; constant_pool.indexes.len()];
+        let mut index = 0;
+        while index < constant_pool.indexes.len() {
+            match constant_pool.indexes[index] {
+                CPIndexType::Long(_) | CPIndexType::Double(_) => {
+                    if let Some(placeholder) = placeholders.get_mut(index + 1) {
+                        *placeholder = true;
+                    }
+                    index += 2;
+                }
+                _ => index += 1,
+            }
+        }
+
+        Pool {
+            constant_pool,
+            placeholders,
+        }
+    }
+
+    fn get(&self, index: u16) -> io::Result<&'a CPIndexType> {
+        match self.constant_pool.get(index) {
+            Some(entry) if !self.placeholders[index as usize - 1] => Ok(entry),
+            _ => Err(invalid(format!("invalid constant pool index {}", index))),
+        }
+    }
+
+    fn expect(
+        &self,
+        index: u16,
+        kind: &str,
+        accepts: fn(&CPIndexType) -> bool,
+    ) -> io::Result<&'a CPIndexType> {
+        let entry = self.get(index)?;
+        if accepts(entry) {
+            Ok(entry)
+        } else {
+            Err(invalid(format!(
+                "constant pool index {} is not a {}",
+                index, kind
+            )))
+        }
+    }
+
+    fn utf8(&self, index: u16) -> io::Result<&'a str> {
+        match self.get(index)? {
+            CPIndexType::Utf8(utf8) => Ok(utf8.string.as_str()),
+            _ => Err(invalid(format!(
+                "constant pool index {} is not a Utf8",
+                index
+            ))),
+        }
+    }
+
+    fn descriptor(&self, index: u16, accepts: fn(&str) -> bool) -> io::Result<()> {
+        let descriptor = self.utf8(index)?;
+        if accepts(descriptor) {
+            Ok(())
+        } else {
+            Err(invalid(format!("invalid descriptor {}", descriptor)))
+        }
+    }
+
+    fn class(&self, index: u16) -> io::Result<()> {
+        self.expect(index, "Class", |entry| {
+            matches!(entry, CPIndexType::Class(_))
+        })?;
+        Ok(())
+    }
+
+    fn loadable(&self, index: u16) -> io::Result<()> {
+        self.expect(index, "loadable constant", |entry| {
+            !matches!(
+                entry,
+                CPIndexType::Utf8(_)
+                    | CPIndexType::NameAndType { .. }
+                    | CPIndexType::FieldRef { .. }
+                    | CPIndexType::MethodRef { .. }
+                    | CPIndexType::InterfaceMethodRef { .. }
+                    | CPIndexType::InvokeDynamic { .. }
+                    | CPIndexType::Module(_)
+                    | CPIndexType::Package(_)
+            )
+        })?;
+        Ok(())
+    }
+
+    fn entry(&self, index: u16) -> io::Result<()> {
+        match *self.get(index)? {
+            CPIndexType::Class(name_index) => {
+                let name = self.utf8(name_index)?;
+                // array classes are named by their descriptor
+                if name.starts_with('[') && !is_field_descriptor(name) {
+                    return Err(invalid(format!("invalid class name {}", name)));
+                }
+            }
+            CPIndexType::FieldRef {
+                class_index,
+                name_and_type_index,
+            } => {
+                self.class(class_index)?;
+                self.name_and_type(name_and_type_index, is_field_descriptor)?;
+            }
+            CPIndexType::MethodRef {
+                class_index,
+                name_and_type_index,
+            }
+            | CPIndexType::InterfaceMethodRef {
+                class_index,
+                name_and_type_index,
+            } => {
+                self.class(class_index)?;
+                self.name_and_type(name_and_type_index, is_method_descriptor)?;
+            }
+            CPIndexType::String(index)
+            | CPIndexType::Module(index)
+            | CPIndexType::Package(index) => {
+                self.utf8(index)?;
+            }
+            CPIndexType::NameAndType {
+                name_index,
+                descriptor_index,
+            } => {
+                self.utf8(name_index)?;
+                self.descriptor(descriptor_index, |descriptor| {
+                    is_field_descriptor(descriptor) || is_method_descriptor(descriptor)
+                })?;
+            }
+            CPIndexType::MethodHandle {
+                reference_type,
+                reference_index,
+            } => {
+                if !(1..=9).contains(&reference_type) {
+                    return Err(invalid(format!(
+                        "invalid reference kind {}",
+                        reference_type
+                    )));
+                }
+                self.expect(reference_index, "member reference", |entry| {
+                    matches!(
+                        entry,
+                        CPIndexType::FieldRef { .. }
+                            | CPIndexType::MethodRef { .. }
+                            | CPIndexType::InterfaceMethodRef { .. }
+                    )
+                })?;
+            }
+            CPIndexType::MethodType { descriptor_index } => {
+                self.descriptor(descriptor_index, is_method_descriptor)?;
+            }
+            CPIndexType::InvokeDynamic {
+                name_and_type_index,
+                ..
+            } => self.name_and_type(name_and_type_index, is_method_descriptor)?,
+            CPIndexType::Integer(_)
+            | CPIndexType::Float(_)
+            | CPIndexType::Long(_)
+            | CPIndexType::Double(_)
+            | CPIndexType::Utf8(_) => {}
+        }
+        Ok(())
+    }
+
+    fn name_and_type(&self, index: u16, accepts: fn(&str) -> bool) -> io::Result<()> {
+        match *self.get(index)? {
+            CPIndexType::NameAndType {
+                descriptor_index, ..
+            } => self.descriptor(descriptor_index, accepts),
+            _ => Err(invalid(format!(
+                "constant pool index {} is not a NameAndType",
+                index
+            ))),
+        }
+    }
+
+    // The attributes the printer and the decompiler read, the others are kept as they are
+    fn attributes(&self, attributes: &[AttributeInfo]) -> io::Result<()> {
+        for attribute in attributes {
+            let reader = &mut ByteReader {
+                buf: &attribute.info,
+                index: 0,
+            };
+            let single = std::slice::from_ref(attribute);
+
+            match attribute.get_name() {
+                "SourceFile" | "Signature" => {
+                    self.utf8(reader.read_u16()?)?;
+                }
+                "ConstantValue" => {
+                    self.expect(reader.read_u16()?, "constant value", |entry| {
+                        matches!(
+                            entry,
+                            CPIndexType::Integer(_)
+                                | CPIndexType::Float(_)
+                                | CPIndexType::Long(_)
+                                | CPIndexType::Double(_)
+                                | CPIndexType::String(_)
+                        )
+                    })?;
+                }
+                "Exceptions" => {
+                    for _i in 0..reader.read_u16()? {
+                        self.class(reader.read_u16()?)?;
+                    }
+                }
+                "StackMapTable" => {
+                    reader.read_u16()?;
+                }
+                "InnerClasses" => {
+                    for inner in read_inner_classes(single)? {
+                        self.class(inner.inner_class)?;
+                        if inner.outer_class != 0 {
+                            self.class(inner.outer_class)?;
+                        }
+                        if inner.inner_name != 0 {
+                            self.utf8(inner.inner_name)?;
+                        }
+                    }
+                }
+                "BootstrapMethods" => {
+                    for bootstrap_method in read_bootstrap_methods(single)? {
+                        self.expect(bootstrap_method.method_ref, "MethodHandle", |entry| {
+                            matches!(entry, CPIndexType::MethodHandle { .. })
+                        })?;
+                        for argument in bootstrap_method.arguments {
+                            self.loadable(argument)?;
+                        }
+                    }
+                }
+                "Module" => self.module(single)?,
+                "ModulePackages" => {
+                    for _i in 0..reader.read_u16()? {
+                        self.package(reader.read_u16()?)?;
+                    }
+                }
+                "ModuleMainClass" => self.class(reader.read_u16()?)?,
+                "Code" => self.code(attribute)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn code(&self, attribute: &AttributeInfo) -> io::Result<()> {
+        let code = CodeAttribute::new(&attribute.info, self.constant_pool)?;
+        let code_stream = BufReader::new(code.code.as_slice());
+        for decoded in instruction::parse(code_stream, code.code.len())? {
+            self.operand(&decoded.instruction)?;
+        }
+
+        for entry in &code.exception_table {
+            // 0 catches everything
+            if entry.catch_type != 0 {
+                self.class(entry.catch_type)?;
+            }
+        }
+        for variable in code.local_variables() {
+            self.utf8(variable.name_index)?;
+            self.descriptor(variable.descriptor_index, is_field_descriptor)?;
+        }
+        for variable in code.local_variable_types() {
+            self.utf8(variable.name_index)?;
+            self.utf8(variable.descriptor_index)?;
+        }
+
+        self.attributes(&code.attributes)
+    }
+
+    fn operand(&self, instruction: &Instruction) -> io::Result<()> {
+        let expect = |index: u16, kind: &str, accepts: fn(&CPIndexType) -> bool| {
+            self.expect(index, kind, accepts).map(|_| ())
+        };
+
+        match *instruction {
+            Instruction::Ldc(index) => self.single_slot(index as u16),
+            Instruction::LdcW(index) => self.single_slot(index),
+            Instruction::Ldc2W(index) => expect(index, "long or double", |entry| {
+                matches!(entry, CPIndexType::Long(_) | CPIndexType::Double(_))
+            }),
+            Instruction::GetStatic(index)
+            | Instruction::PutStatic(index)
+            | Instruction::GetField(index)
+            | Instruction::PutField(index) => expect(index, "Fieldref", |entry| {
+                matches!(entry, CPIndexType::FieldRef { .. })
+            }),
+            Instruction::InvokeVirtual(index)
+            | Instruction::InvokeSpecial(index)
+            | Instruction::InvokeStatic(index) => expect(index, "Methodref", |entry| {
+                matches!(
+                    entry,
+                    CPIndexType::MethodRef { .. } | CPIndexType::InterfaceMethodRef { .. }
+                )
+            }),
+            Instruction::InvokeInterface(index, _) => {
+                expect(index, "InterfaceMethodref", |entry| {
+                    matches!(entry, CPIndexType::InterfaceMethodRef { .. })
+                })
+            }
+            Instruction::InvokeDynamic(index) => expect(index, "InvokeDynamic", |entry| {
+                matches!(entry, CPIndexType::InvokeDynamic { .. })
+            }),
+            Instruction::New(index)
+            | Instruction::ANewArray(index)
+            | Instruction::CheckCast(index)
+            | Instruction::InstanceOf(index)
+            | Instruction::MultiANewArray(index, _) => self.class(index),
+            Instruction::NewArray(atype) if !(4..=11).contains(&atype) => {
+                Err(invalid(format!("invalid array type {}", atype)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // ldc and ldc_w can't load longs and doubles
+    fn single_slot(&self, index: u16) -> io::Result<()> {
+        self.loadable(index)?;
+        match self.get(index)? {
+            CPIndexType::Long(_) | CPIndexType::Double(_) => Err(invalid(format!(
+                "constant pool index {} takes two slots",
+                index
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn package(&self, index: u16) -> io::Result<()> {
+        self.expect(index, "Package", |entry| {
+            matches!(entry, CPIndexType::Package(_))
+        })?;
+        Ok(())
+    }
+
+    fn module(&self, attribute: &[AttributeInfo]) -> io::Result<()> {
+        let module = match read_module(attribute)? {
+            Some(module) => module,
+            None => return Ok(()),
+        };
+        let name = |index: u16| {
+            self.expect(index, "Module", |entry| {
+                matches!(entry, CPIndexType::Module(_))
+            })
+            .map(|_| ())
+        };
+        let version = |index: u16| match index {
+            0 => Ok(()),
+            index => self.utf8(index).map(|_| ()),
+        };
+
+        name(module.name)?;
+        version(module.version)?;
+        for directive in &module.requires {
+            name(directive.index)?;
+            version(directive.targets[0])?;
+        }
+        for directive in module.exports.iter().chain(&module.opens) {
+            self.package(directive.index)?;
+            for target in &directive.targets {
+                name(*target)?;
+            }
+        }
+        for service in &module.uses {
+            self.class(*service)?;
+        }
+        for (service, implementations) in &module.provides {
+            self.class(*service)?;
+            for implementation in implementations {
+                self.class(*implementation)?;
+            }
+        }
+        Ok(())
+    }
+}
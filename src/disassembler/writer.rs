@@ -0,0 +1,312 @@
+use super::attribute::*;
+use super::constant_pool::*;
+use super::field::*;
+use super::interface::InterfacePool;
+use super::method::*;
+
+use super::ClassFile;
+
+use std::io;
+
+pub struct ClassFileWriter {
+    pub buf: Vec<u8>,
+}
+
+impl ClassFileWriter {
+    pub fn write(mut self, class_file: &ClassFile) -> io::Result<Vec<u8>> {
+        self.write_u32(class_file.magic);
+        self.write_u16(class_file.minor);
+        self.write_u16(class_file.major);
+        self.write_constant_pool(&class_file.constant_pool)?;
+        self.write_u16(class_file.access_flags);
+        self.write_u16(class_file.this_class);
+        self.write_u16(class_file.super_class);
+        self.write_interface_pool(&class_file.interfaces);
+        self.write_field_pool(&class_file.fields);
+        self.write_method_pool(&class_file.methods);
+        self.write_attribute_pool(&class_file.attributes);
+        Ok(self.buf)
+    }
+}
+
+// value write methods
+impl ClassFileWriter {
+    pub fn write_constant_pool(&mut self, constant_pool: &ConstantPool) -> io::Result<()> {
+        self.write_u16(constant_pool.index_count);
+
+        // Long and Double take up two slots, the second one is only a placeholder
+        let mut skip_next = false;
+        for index in &constant_pool.indexes {
+            if skip_next {
+                skip_next = false;
+                continue;
+            }
+
+            match index {
+                CPIndexType::Utf8(utf8) => {
+                    if utf8.bytes.len() > u16::MAX as usize {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("Utf8 constant of {} bytes is too long", utf8.bytes.len()),
+                        ));
+                    }
+                    self.write_u8(1);
+                    self.write_u16(utf8.bytes.len() as u16);
+                    self.buf.extend_from_slice(&utf8.bytes);
+                }
+                CPIndexType::Integer(value) => {
+                    self.write_u8(3);
+                    self.write_u32(*value);
+                }
+                CPIndexType::Float(value) => {
+                    self.write_u8(4);
+                    self.write_u32(value.to_bits());
+                }
+                CPIndexType::Long(value) => {
+                    self.write_u8(5);
+                    self.buf.extend_from_slice(&value.to_be_bytes());
+                    skip_next = true;
+                }
+                CPIndexType::Double(value) => {
+                    self.write_u8(6);
+                    self.buf.extend_from_slice(&value.to_be_bytes());
+                    skip_next = true;
+                }
+                CPIndexType::Class(name_index) => {
+                    self.write_u8(7);
+                    self.write_u16(*name_index);
+                }
+                CPIndexType::String(string_index) => {
+                    self.write_u8(8);
+                    self.write_u16(*string_index);
+                }
+                CPIndexType::FieldRef {
+                    class_index,
+                    name_and_type_index,
+                } => {
+                    self.write_u8(9);
+                    self.write_u16(*class_index);
+                    self.write_u16(*name_and_type_index);
+                }
+                CPIndexType::MethodRef {
+                    class_index,
+                    name_and_type_index,
+                } => {
+                    self.write_u8(10);
+                    self.write_u16(*class_index);
+                    self.write_u16(*name_and_type_index);
+                }
+                CPIndexType::InterfaceMethodRef {
+                    class_index,
+                    name_and_type_index,
+                } => {
+                    self.write_u8(11);
+                    self.write_u16(*class_index);
+                    self.write_u16(*name_and_type_index);
+                }
+                CPIndexType::NameAndType {
+                    name_index,
+                    descriptor_index,
+                } => {
+                    self.write_u8(12);
+                    self.write_u16(*name_index);
+                    self.write_u16(*descriptor_index);
+                }
+                CPIndexType::MethodHandle {
+                    reference_type,
+                    reference_index,
+                } => {
+                    self.write_u8(15);
+                    self.write_u8(*reference_type);
+                    self.write_u16(*reference_index);
+                }
+                CPIndexType::MethodType { descriptor_index } => {
+                    self.write_u8(16);
+                    self.write_u16(*descriptor_index);
+                }
+                CPIndexType::InvokeDynamic {
+                    boostrap_method_attr_index,
+                    name_and_type_index,
+                } => {
+                    self.write_u8(18);
+                    self.write_u16(*boostrap_method_attr_index);
+                    self.write_u16(*name_and_type_index);
+                }
+                CPIndexType::Module(name_index) => {
+                    self.write_u8(19);
+                    self.write_u16(*name_index);
+                }
+                CPIndexType::Package(name_index) => {
+                    self.write_u8(20);
+                    self.write_u16(*name_index);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn write_interface_pool(&mut self, interface_pool: &InterfacePool) {
+        self.write_u16(interface_pool.interface_count);
+
+        for interface in &interface_pool.interfaces {
+            self.write_u16(*interface);
+        }
+    }
+
+    pub fn write_field_pool(&mut self, field_pool: &FieldPool) {
+        self.write_u16(field_pool.field_count);
+
+        for field in &field_pool.fields {
+            self.write_u16(field.access_flags);
+            self.write_u16(field.name_index);
+            self.write_u16(field.descriptor_index);
+            self.write_u16(field.attributes_count);
+
+            for attribute in &field.attributes {
+                self.write_attribute(attribute);
+            }
+        }
+    }
+
+    pub fn write_method_pool(&mut self, method_pool: &MethodPool) {
+        self.write_u16(method_pool.method_count);
+
+        for method in &method_pool.methods {
+            self.write_u16(method.access_flags);
+            self.write_u16(method.name_index);
+            self.write_u16(method.descriptor_index);
+            self.write_u16(method.attributes_count);
+
+            for attribute in &method.attributes {
+                self.write_attribute(attribute);
+            }
+        }
+    }
+
+    pub fn write_attribute_pool(&mut self, attribute_pool: &AttributePool) {
+        self.write_u16(attribute_pool.attribute_count);
+
+        for attribute in &attribute_pool.attributes {
+            self.write_attribute(attribute);
+        }
+    }
+
+    fn write_attribute(&mut self, attribute: &AttributeInfo) {
+        self.write_u16(attribute.attribute_name_index.index);
+        self.write_u32(attribute.attribute_length);
+        self.buf.extend_from_slice(&attribute.info);
+    }
+}
+
+// byte write methods
+impl ClassFileWriter {
+    fn write_u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.buf.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::constant_pool::*;
+    use super::super::ClassFile;
+    use super::ClassFileWriter;
+
+    #[test]
+    fn round_trip_is_byte_identical() {
+        let bytes = include_bytes!("../../tests/fixtures/RoundTrip.class");
+        let class_file = ClassFile::new(bytes).unwrap();
+
+        assert_eq!(class_file.to_bytes().unwrap(), bytes.to_vec());
+    }
+
+    #[test]
+    fn truncated_class_file_is_an_error() {
+        let bytes = include_bytes!("../../tests/fixtures/RoundTrip.class");
+
+        for length in [0, 9, bytes.len() / 2, bytes.len() - 1] {
+            let error = ClassFile::new(&bytes[..length]).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+        }
+    }
+
+    #[test]
+    fn invalid_references_are_errors() {
+        let bytes = include_bytes!("../../tests/fixtures/RoundTrip.class");
+        let error = |change: fn(&mut ClassFile)| {
+            let mut class_file = ClassFile::new(bytes).unwrap();
+            change(&mut class_file);
+            let error = ClassFile::new(&class_file.to_bytes().unwrap()).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+            error.to_string()
+        };
+
+        assert_eq!(
+            error(|class_file| class_file.this_class = 0xffff),
+            "invalid constant pool index 65535"
+        );
+        assert_eq!(
+            error(|class_file| class_file.super_class = class_file.methods.methods[0].name_index),
+            format!(
+                "constant pool index {} is not a Class",
+                ClassFile::new(bytes).unwrap().methods.methods[0].name_index
+            )
+        );
+        assert_eq!(
+            error(|class_file| {
+                let method = &mut class_file.methods.methods[0];
+                method.descriptor_index = method.name_index;
+            }),
+            "invalid descriptor <init>"
+        );
+        // the first opcode of the constructor
+        assert_eq!(
+            error(|class_file| class_file.methods.methods[0].attributes[0].info[8] = 0xcb),
+            "unknown instruction: 203"
+        );
+    }
+
+    #[test]
+    fn unknown_constant_pool_tag_is_an_error() {
+        // magic, version and a constant pool with one entry of tag 2
+        let bytes = [0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 52, 0, 2, 2, 0, 0];
+        let error = ClassFile::new(&bytes).unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "unknown constant pool tag 2");
+    }
+
+    #[test]
+    fn unpaired_surrogates_are_kept() {
+        // "\uD800" in modified UTF-8
+        let utf8 = Utf8String::from_bytes(vec![0xed, 0xa0, 0x80]).unwrap();
+
+        assert_eq!(utf8.string, "\u{fffd}");
+        assert_eq!(utf8.bytes, vec![0xed, 0xa0, 0x80]);
+    }
+
+    #[test]
+    fn invalid_modified_utf8_is_an_error() {
+        // a lead byte of four bytes, and one that is cut off
+        for bytes in [vec![0xf0, 0x90, 0x80, 0x80], vec![b'a', 0xc3]] {
+            let error = Utf8String::from_bytes(bytes).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn too_long_utf8_constant_is_an_error() {
+        let mut constant_pool = ConstantPool::default();
+        constant_pool.add_utf8("a".repeat(u16::MAX as usize + 1).as_str());
+
+        let mut writer = ClassFileWriter { buf: Vec::new() };
+        assert!(writer.write_constant_pool(&constant_pool).is_err());
+    }
+}
@@ -0,0 +1,34 @@
+// Reads, writes, assembles and decompiles Java class files
+mod assembler;
+mod decompiler;
+mod disassembler;
+
+use std::io;
+use std::io::Read;
+
+pub use assembler::assemble;
+pub use decompiler::{
+    decompile, decompile_class_file, decompile_with, Decompilation, DecompilerOptions, Diagnostic, LineNumbers,
+    MethodMap, Naming, SourceMap, StatementMap,
+};
+pub use disassembler::ClassFile;
+
+const MAGIC: u32 = 0xCAFEBABE;
+
+// Fails on anything but a class file, or one that is cut off, has an unknown constant or refers
+// to constants, descriptors or instructions that aren't there
+pub fn parse(bytes: &[u8]) -> io::Result<ClassFile> {
+    match bytes {
+        [a, b, c, d, ..] if u32::from_be_bytes([*a, *b, *c, *d]) == MAGIC => ClassFile::new(bytes),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not a class file",
+        )),
+    }
+}
+
+pub fn read<R: Read>(mut reader: R) -> io::Result<ClassFile> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    parse(bytes.as_slice())
+}
@@ -1,18 +1,119 @@
-mod decompiler;
-mod disassembler;
-
+use java_decompiler::{ClassFile, DecompilerOptions, LineNumbers, Naming};
+use std::env;
 use std::fs;
 use std::io;
 use std::io::Read;
+use std::path::Path;
+use std::process;
 
 fn main() -> io::Result<()> {
-    let file = fs::File::open("Test.class")?;
+    let mut roundtrip = false;
+    let mut disassemble = false;
+    let mut assemble = false;
+    let mut options = DecompilerOptions::default();
+    let mut source_map = false;
+    let mut path = String::from("Test.class");
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--roundtrip" => roundtrip = true,
+            "--disassemble" => disassemble = true,
+            "--assemble" => assemble = true,
+            "--align-lines" => options.line_numbers = LineNumbers::Align,
+            "--line-comments" => options.line_numbers = LineNumbers::AlignOrComment,
+            "--source-map" => source_map = true,
+            "--no-imports" => options.imports = false,
+            "--hide-synthetic" => options.hide_synthetic = true,
+            "--hide-problems" => options.hide_problems = true,
+            "--slot-names" => options.naming = Naming::Slots,
+            _ => path = arg,
+        }
+    }
+
+    if assemble {
+        let source = fs::read_to_string(&path)?;
+        let class_file = match java_decompiler::assemble(source.as_str()) {
+            Ok(class_file) => class_file,
+            Err(message) => {
+                println!("{}: {}", path, message);
+                process::exit(1);
+            }
+        };
+
+        let output = Path::new(&path).with_extension("class");
+        fs::write(&output, class_file.to_bytes()?)?;
+        println!("Assembled {}", output.display());
+        return Ok(());
+    }
+
+    let file = fs::File::open(&path)?;
     // let file = fs::File::open("샒㕱쪪옸䤍칗.class")?;
     let mut reader = io::BufReader::new(file);
     let mut buf: Vec<u8> = Vec::new();
     reader.read_to_end(&mut buf)?;
-    let mut class_file = disassembler::ClassFile::new(buf.as_slice());
+    let class_file = java_decompiler::parse(buf.as_slice())?;
+
+    if roundtrip {
+        let mut written: Vec<u8> = Vec::new();
+        class_file.write(&mut written)?;
 
-    println!("{}", decompiler::decompile_class_file(&mut class_file));
+        if written == buf {
+            println!("Round trip successful");
+        } else {
+            println!("Round trip failed: written class file differs from the input");
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if disassemble {
+        print!("{}", class_file.disassemble());
+        return Ok(());
+    }
+
+    let classes = nested_classes(Path::new(&path));
+    let decompilation = java_decompiler::decompile_with(&class_file, &classes, &options);
+    if source_map {
+        let output = Path::new(&path).with_extension("map.json");
+        fs::write(&output, decompilation.source_map.to_json())?;
+        println!("{}", decompilation.source);
+        eprintln!("Wrote the source map to {}", output.display());
+        return Ok(());
+    }
+
+    println!("{}", decompilation.source);
     Ok(())
 }
+
+// The other classes of the source file next to the class, like `Outer$1.class` for `Outer.class`.
+// Ones that can't be read are left out.
+fn nested_classes(path: &Path) -> Vec<ClassFile> {
+    let outer = match path.file_stem().and_then(|stem| stem.to_str()) {
+        Some(stem) => format!("{}$", stem.split('$').next().unwrap_or(stem)),
+        None => return Vec::new(),
+    };
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|nested| {
+            nested
+                .extension()
+                .is_some_and(|extension| extension == "class")
+                && nested.file_stem() != path.file_stem()
+                && nested
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(outer.as_str()))
+        })
+        .filter_map(|nested| fs::read(nested).ok())
+        .filter_map(|bytes| java_decompiler::parse(bytes.as_slice()).ok())
+        .collect()
+}
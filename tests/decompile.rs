@@ -0,0 +1,353 @@
+// Decompiles the classes of each directory in tests/fixtures/decompile, compiles the output
+// again and checks that it prints the same as the original classes. The directories are named
+// after their class with the `main` method, the classes were compiled with `javac -g` from 17.
+// Without a JDK on the path the tests only check that decompiling works.
+use java_decompiler::{ClassFile, Decompilation, DecompilerOptions, Diagnostic, LineNumbers};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/decompile")
+        .join(name)
+}
+
+// The classes of the fixture, the synthetic ones like switch maps are compiled again by javac
+fn classes(directory: &Path) -> Vec<PathBuf> {
+    let mut classes: Vec<PathBuf> = fs::read_dir(directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_str().unwrap();
+            name.ends_with(".class") && !name.contains('$')
+        })
+        .collect();
+    classes.sort();
+    classes
+}
+
+// The classes the decompiler reads switch maps and the like from
+fn nested(directory: &Path) -> Vec<ClassFile> {
+    fs::read_dir(directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_str().unwrap();
+            name.ends_with(".class") && name.contains('$')
+        })
+        .map(|path| java_decompiler::parse(&fs::read(path).unwrap()).unwrap())
+        .collect()
+}
+
+fn decompile(name: &str, options: &DecompilerOptions) -> Vec<(String, Decompilation)> {
+    let nested = nested(&fixture(name));
+    classes(&fixture(name))
+        .iter()
+        .map(|path| {
+            let class_file = java_decompiler::parse(&fs::read(path).unwrap()).unwrap();
+            let decompilation = java_decompiler::decompile_with(&class_file, &nested, options);
+            assert_eq!(
+                decompilation.diagnostics,
+                Vec::new(),
+                "{}",
+                decompilation.source
+            );
+            let name = path.file_stem().unwrap().to_str().unwrap().to_string();
+            (name, decompilation)
+        })
+        .collect()
+}
+
+fn has_jdk() -> bool {
+    ["javac", "java"].iter().all(|tool| {
+        Command::new(tool)
+            .arg("-version")
+            .output()
+            .is_ok_and(|output| output.status.success())
+    })
+}
+
+fn run(classpath: &Path, class: &str) -> String {
+    let output = Command::new("java")
+        .arg("-ea")
+        .arg("-cp")
+        .arg(classpath)
+        .arg(class)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// Returns the decompiled sources
+fn check(name: &str, options: &DecompilerOptions) -> Vec<(String, Decompilation)> {
+    let decompiled = decompile(name, options);
+    if !has_jdk() {
+        eprintln!("no JDK on the path, {} is not compiled again", name);
+        return decompiled;
+    }
+
+    let output =
+        std::env::temp_dir().join(format!("java_decompiler_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&output);
+    let sources = output.join("src");
+    let compiled = output.join("classes");
+    fs::create_dir_all(&sources).unwrap();
+    fs::create_dir_all(&compiled).unwrap();
+
+    let mut javac = Command::new("javac");
+    javac.arg("-nowarn").arg("-d").arg(&compiled);
+    for (class, decompilation) in &decompiled {
+        let path = sources.join(format!("{}.java", class));
+        fs::write(&path, decompilation.source.as_str()).unwrap();
+        javac.arg(path);
+    }
+    let result = javac.output().unwrap();
+    let listing: Vec<&str> = decompiled
+        .iter()
+        .map(|(_, decompilation)| decompilation.source.as_str())
+        .collect();
+    assert!(
+        result.status.success(),
+        "{}\n{}",
+        String::from_utf8_lossy(&result.stderr),
+        listing.join("\n")
+    );
+
+    assert_eq!(
+        run(&compiled, name),
+        run(&fixture(name), name),
+        "{}",
+        listing.join("\n")
+    );
+    fs::remove_dir_all(&output).unwrap();
+    decompiled
+}
+
+fn roundtrip(name: &str) {
+    check(name, &DecompilerOptions::default());
+}
+
+#[test]
+fn expressions_and_loops() {
+    roundtrip("Basics");
+}
+
+#[test]
+fn variable_types() {
+    roundtrip("Types");
+}
+
+#[test]
+fn booleans_and_chars() {
+    roundtrip("Booleans");
+}
+
+#[test]
+fn comparisons() {
+    roundtrip("Comparisons");
+}
+
+#[test]
+fn conditionals_and_increments() {
+    roundtrip("Conditionals");
+}
+
+#[test]
+fn casts_and_patterns() {
+    roundtrip("Casts");
+}
+
+#[test]
+fn array_initializers() {
+    roundtrip("Arrays");
+}
+
+#[test]
+fn synchronized_blocks() {
+    roundtrip("Synchronized");
+}
+
+#[test]
+fn try_with_resources() {
+    roundtrip("Resources");
+}
+
+#[test]
+fn enhanced_for_loops() {
+    roundtrip("ForEach");
+}
+
+#[test]
+fn raw_iterables() {
+    roundtrip("Iterables");
+}
+
+#[test]
+fn array_arguments() {
+    let decompiled = check("ArrayArguments", &DecompilerOptions::default());
+    let source = &decompiled[0].1.source;
+    assert!(
+        source.contains("ArrayArguments.sum(ArrayArguments.next(), new int[]{1, 2})"),
+        "{}",
+        source
+    );
+}
+
+#[test]
+fn enum_switches() {
+    let decompiled = check("EnumSwitches", &DecompilerOptions::default());
+    let source = &decompiled[0].1.source;
+    assert!(source.contains("switch (day) {"), "{}", source);
+    assert!(source.contains("case MONDAY -> 1;"), "{}", source);
+    assert!(
+        !source.contains("IncompatibleClassChangeError"),
+        "{}",
+        source
+    );
+}
+
+#[test]
+fn boxing_and_varargs() {
+    roundtrip("Boxing");
+}
+
+#[test]
+fn assertions() {
+    roundtrip("Asserts");
+}
+
+#[test]
+fn field_initializers() {
+    roundtrip("Fields");
+}
+
+#[test]
+fn switches() {
+    roundtrip("Switches");
+}
+
+#[test]
+fn interface_methods() {
+    roundtrip("Interfaces");
+}
+
+#[test]
+fn imports() {
+    roundtrip("Imports");
+}
+
+#[test]
+fn overloaded_arguments() {
+    roundtrip("Overloads");
+}
+
+#[test]
+fn char_codes() {
+    roundtrip("CharCodes");
+}
+
+#[test]
+fn narrow_arguments() {
+    roundtrip("NarrowArguments");
+}
+
+#[test]
+fn conditional_types() {
+    roundtrip("Ternary");
+}
+
+#[test]
+fn resources_with_finally() {
+    roundtrip("ResourceFinally");
+}
+
+#[test]
+fn aligned_lines() {
+    let options = DecompilerOptions {
+        line_numbers: LineNumbers::Align,
+        ..DecompilerOptions::default()
+    };
+    for (_, decompilation) in check("Lines", &options) {
+        for method in &decompilation.source_map.methods {
+            for statement in &method.statements {
+                if let Some(line) = statement.line {
+                    assert_eq!(statement.lines.0, line as usize, "{}", decompilation.source);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn inlined_lambdas() {
+    let options = DecompilerOptions {
+        hide_synthetic: true,
+        ..DecompilerOptions::default()
+    };
+    for (_, decompilation) in check("Lambdas", &options) {
+        assert!(
+            !decompilation.source.contains("lambda$"),
+            "{}",
+            decompilation.source
+        );
+    }
+}
+
+#[test]
+fn unreadable_code_is_a_diagnostic() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/RoundTrip.class");
+    let mut class_file = java_decompiler::parse(&fs::read(path).unwrap()).unwrap();
+    // an opcode that doesn't exist at the start of main
+    class_file.methods.methods[1].attributes[0].info[8] = 0xcb;
+
+    let decompilation = java_decompiler::decompile(&class_file, &DecompilerOptions::default());
+    assert_eq!(
+        decompilation.diagnostics,
+        vec![Diagnostic {
+            method: "main".to_string(),
+            descriptor: "([Ljava/lang/String;)V".to_string(),
+            message: "unknown instruction: 203".to_string(),
+        }]
+    );
+    assert!(decompilation
+        .source
+        .contains("public static void main(String[] arg0) {\n\t}"));
+}
+
+#[test]
+fn unstructured_code_is_a_diagnostic() {
+    // a loop that is entered at both of its blocks
+    let class_file = java_decompiler::assemble(
+        ".class public Jumps
+.method public static f (I)I
+    iload_0
+    ifeq Second
+First:
+    iinc 0, -1
+Second:
+    iinc 0, -2
+    iload_0
+    ifgt First
+    iload_0
+    ireturn
+.end method",
+    )
+    .unwrap();
+
+    let decompilation = java_decompiler::decompile(&class_file, &DecompilerOptions::default());
+    assert!(decompilation.source.contains("goto L4;"));
+    assert_eq!(
+        decompilation.diagnostics,
+        vec![Diagnostic {
+            method: "f".to_string(),
+            descriptor: "(I)I".to_string(),
+            message: "Control flow could not be structured".to_string(),
+        }]
+    );
+}
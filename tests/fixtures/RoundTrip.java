@@ -0,0 +1,16 @@
+// Compiled with javac 17: `javac -g RoundTrip.java`
+public class RoundTrip {
+    static final long BIG = 1234567890123L;
+    static final double PI = 3.14159;
+    static final float HALF = 0.5f;
+    // unpaired surrogates, a surrogate pair and U+0000
+    static final String TEXT = "\uD800 \uDFFF \uD83D\uDE00 \u0000";
+
+    public static void main(String[] args) {
+        long sum = BIG;
+        for (String arg : args) {
+            sum += arg.length();
+        }
+        System.out.println(TEXT + sum + PI * HALF);
+    }
+}
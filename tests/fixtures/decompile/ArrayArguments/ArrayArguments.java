@@ -0,0 +1,24 @@
+public class ArrayArguments {
+    static int count = 0;
+
+    static int next() {
+        return ++count;
+    }
+
+    static int sum(int first, int[] rest) {
+        int sum = first;
+        for (int value : rest) {
+            sum = sum * 10 + value;
+        }
+        return sum;
+    }
+
+    public static void main(String[] args) {
+        int local = 4;
+        System.out.println(sum(next(), new int[]{1, 2}));
+        System.out.println(sum(next(), new int[]{local, local + 1}));
+        System.out.println(sum(next(), new int[]{next(), 2}));
+        System.out.println(sum(next(), new int[]{count, count + 1}));
+        System.out.println(sum(local = 7, new int[]{local, 3}));
+    }
+}
@@ -0,0 +1,22 @@
+public class Arrays {
+    static int sum(int[][] grid) {
+        int sum = 0;
+        for (int i = 0; i < grid.length; i++) {
+            for (int j = 0; j < grid[i].length; j++) {
+                sum += grid[i][j];
+            }
+        }
+        return sum;
+    }
+
+    public static void main(String[] args) {
+        int[] a = {3, 1, 4, 1, 5};
+        String[] words = {"x", "y"};
+        int[][] grid = {{1, 2}, {3}, {}};
+        long[][] empty = new long[2][3];
+        int[][] partial = new int[3][];
+        partial[1] = new int[] {7, 8};
+        System.out.println(a.length + words[1] + sum(grid) + empty[1].length + partial[1][1]);
+        System.out.println(java.util.Arrays.toString(new double[] {0.5, 1.5}));
+    }
+}
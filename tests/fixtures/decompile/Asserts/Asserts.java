@@ -0,0 +1,16 @@
+public class Asserts {
+    static int checked(int x) {
+        assert x >= 0 : "negative " + x;
+        assert x != 3;
+        return x * 2;
+    }
+
+    public static void main(String[] args) {
+        System.out.println(checked(4));
+        try {
+            checked(-1);
+        } catch (AssertionError e) {
+            System.out.println(e.getMessage());
+        }
+    }
+}
@@ -0,0 +1,56 @@
+public class Basics {
+    int total;
+
+    static int gcd(int a, int b) {
+        while (b != 0) {
+            int t = a % b;
+            a = b;
+            b = t;
+        }
+        return a;
+    }
+
+    int sum(int n) {
+        for (int i = 1; i <= n; i++) {
+            if (i % 3 == 0) {
+                continue;
+            }
+            total += i * 2 - 1;
+        }
+        return total;
+    }
+
+    static long power(long base, int exponent) {
+        long result = 1;
+        do {
+            result *= base;
+        } while (--exponent > 0);
+        return result;
+    }
+
+    static String describe(int x) {
+        if (x < 0) {
+            return "negative";
+        } else if (x == 0) {
+            return "zero";
+        }
+        return "positive " + (x >> 1) + " " + (x >>> 2) + " " + (~x & 0xff);
+    }
+
+    public static void main(String[] args) {
+        System.out.println(gcd(84, 36));
+        System.out.println(new Basics().sum(10));
+        System.out.println(power(3, 5));
+        System.out.println(describe(-4) + describe(0) + describe(77));
+        outer:
+        for (int i = 0; i < 4; i++) {
+            for (int j = 0; j < 4; j++) {
+                if (i * j == 6) {
+                    break outer;
+                }
+                System.out.print(i + j);
+            }
+        }
+        System.out.println();
+    }
+}
@@ -0,0 +1,36 @@
+public class Booleans {
+    boolean flag;
+
+    static boolean between(int x, int low, int high) {
+        return x >= low && x <= high;
+    }
+
+    static boolean either(boolean a, boolean b, boolean c) {
+        return a || b && !c;
+    }
+
+    static char next(char c) {
+        return c == 'z' ? 'a' : (char) (c + 1);
+    }
+
+    boolean toggle() {
+        flag = !flag;
+        return flag;
+    }
+
+    public static void main(String[] args) {
+        System.out.println(between(5, 1, 9) + " " + between(0, 1, 9));
+        System.out.println(either(false, true, false) + " " + either(false, true, true));
+        System.out.println(next('c'));
+        System.out.println(next('z'));
+        Booleans b = new Booleans();
+        System.out.println(b.toggle() + " " + b.toggle());
+        char[] letters = {'x', 'y'};
+        for (int i = 0; i < letters.length; i++) {
+            if (letters[i] != 'y' || i == 0) {
+                System.out.print(letters[i]);
+            }
+        }
+        System.out.println();
+    }
+}
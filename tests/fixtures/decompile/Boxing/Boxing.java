@@ -0,0 +1,29 @@
+import java.util.ArrayList;
+
+public class Boxing {
+    static int total(int... values) {
+        int total = 0;
+        for (int value : values) {
+            total += value;
+        }
+        return total;
+    }
+
+    static Integer twice(Integer x) {
+        return x * 2;
+    }
+
+    public static void main(String[] args) {
+        ArrayList<Integer> list = new ArrayList<>();
+        list.add(5);
+        list.add(7);
+        list.remove(Integer.valueOf(5));
+        int first = list.get(0);
+        System.out.println(first + " " + list.size());
+        System.out.println(total() + total(1) + total(1, 2, 3));
+        System.out.println(twice(21));
+        Long boxed = 4L;
+        System.out.println(boxed + 1);
+        System.out.println(String.format("%d-%s", 3, "x"));
+    }
+}
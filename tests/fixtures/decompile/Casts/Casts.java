@@ -0,0 +1,23 @@
+public class Casts {
+    static String describe(Object o) {
+        if (o instanceof String s && !s.isEmpty()) {
+            return "string " + s.length();
+        }
+        if (o instanceof Integer) {
+            return "integer " + ((Integer) o + 1);
+        }
+        return "other";
+    }
+
+    public static void main(String[] args) {
+        System.out.println(describe("abc") + describe(41) + describe(2.0));
+        double d = 3.99;
+        int i = (int) d;
+        long l = (long) (d * 1000);
+        byte b = (byte) 300;
+        System.out.println(i + " " + l + " " + b + " " + (short) 70000);
+        Object o = "text";
+        CharSequence cs = (CharSequence) o;
+        System.out.println(cs.length());
+    }
+}
@@ -0,0 +1,27 @@
+public class CharCodes {
+    static String f(int x) {
+        return "int " + x;
+    }
+
+    static String f(char x) {
+        return "char " + x;
+    }
+
+    static int code(char c) {
+        return c;
+    }
+
+    public static void main(String[] args) {
+        char c = 'd';
+        System.out.println((int) c);
+        System.out.println(c);
+        StringBuilder b = new StringBuilder();
+        b.append((int) c).append(c);
+        System.out.println(b);
+        System.out.println((int) c + " " + c);
+        System.out.println(f((int) c) + f(c));
+        System.out.println(code(c) + Integer.toString(c));
+        String s = "xy";
+        System.out.println((int) s.charAt(1));
+    }
+}
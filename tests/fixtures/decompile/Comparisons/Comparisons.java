@@ -0,0 +1,29 @@
+public class Comparisons {
+    static String order(long a, long b) {
+        if (a < b) {
+            return "less";
+        }
+        return a > b ? "greater" : "equal";
+    }
+
+    static boolean ordered(double a, double b) {
+        return a <= b;
+    }
+
+    static int count(float[] values, float limit) {
+        int count = 0;
+        for (float value : values) {
+            if (value > limit) {
+                count++;
+            }
+        }
+        return count;
+    }
+
+    public static void main(String[] args) {
+        System.out.println(order(1L, 2L) + order(5L, 2L) + order(3L, 3L));
+        System.out.println(ordered(1.0, 2.0) + " " + ordered(Double.NaN, 2.0));
+        System.out.println(count(new float[] {1f, Float.NaN, 3f, 4f}, 2f));
+        System.out.println(Long.compare(4L, 9L));
+    }
+}
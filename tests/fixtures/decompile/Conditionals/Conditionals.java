@@ -0,0 +1,27 @@
+public class Conditionals {
+    int count;
+    int[] cells = new int[4];
+
+    static int abs(int x) {
+        return x < 0 ? -x : x;
+    }
+
+    int bump() {
+        int old = count++;
+        cells[old % 4] += 3;
+        return ++count + old;
+    }
+
+    public static void main(String[] args) {
+        System.out.println(abs(-5) + abs(6));
+        Conditionals c = new Conditionals();
+        System.out.println(c.bump() + " " + c.bump());
+        int a, b;
+        a = b = 4;
+        a *= b + 1;
+        a <<= 1;
+        System.out.println(a + " " + b);
+        String s = a > 10 ? "big" : "small";
+        System.out.println(s + c.cells[0] + c.cells[2]);
+    }
+}
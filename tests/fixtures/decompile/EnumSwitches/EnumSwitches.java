@@ -0,0 +1,52 @@
+import java.time.DayOfWeek;
+
+public class EnumSwitches {
+    static int code(DayOfWeek day) {
+        return switch (day) {
+            case MONDAY -> 1;
+            case TUESDAY, WEDNESDAY, THURSDAY -> 2;
+            case FRIDAY -> 3;
+            case SATURDAY, SUNDAY -> 4;
+        };
+    }
+
+    static String name(DayOfWeek day) {
+        switch (day) {
+            case MONDAY:
+                return "monday";
+            case SUNDAY:
+                return "sunday";
+            default:
+                return "other";
+        }
+    }
+
+    static int partial(DayOfWeek day) {
+        int result = 0;
+        switch (day) {
+            case TUESDAY -> result = 5;
+            case FRIDAY -> result = 7;
+        }
+        return result;
+    }
+
+    static String state(DayOfWeek day, Thread.State state) {
+        return switch (day) {
+            case MONDAY -> switch (state) {
+                case NEW -> "new";
+                case TERMINATED -> "done";
+                default -> "running";
+            };
+            default -> "other";
+        };
+    }
+
+    public static void main(String[] args) {
+        for (DayOfWeek day : DayOfWeek.values()) {
+            System.out.println(code(day) + name(day) + partial(day));
+            for (Thread.State state : Thread.State.values()) {
+                System.out.println(state(day, state));
+            }
+        }
+    }
+}
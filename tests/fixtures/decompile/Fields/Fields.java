@@ -0,0 +1,21 @@
+public class Fields {
+    static final int LIMIT = 10;
+    static final String NAME = "fields";
+    static int counter = LIMIT * 2;
+    static int[] table = new int[] {1, 2, 3};
+    int instance = 5;
+    String label;
+
+    static {
+        counter++;
+    }
+
+    Fields(String label) {
+        this.label = label + instance;
+    }
+
+    public static void main(String[] args) {
+        System.out.println(NAME + LIMIT + counter + table[2]);
+        System.out.println(new Fields("x").label);
+    }
+}
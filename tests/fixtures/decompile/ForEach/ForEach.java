@@ -0,0 +1,25 @@
+public class ForEach {
+    static int sum(int[] values) {
+        int sum = 0;
+        for (int value : values) {
+            sum += value;
+        }
+        return sum;
+    }
+
+    public static void main(String[] args) {
+        System.out.println(sum(new int[] {1, 2, 3}));
+        String[] words = {"a", "bb", "ccc"};
+        for (String word : words) {
+            if (word.length() == 2) {
+                continue;
+            }
+            System.out.print(word);
+        }
+        System.out.println();
+        for (char[] row : new char[][] {{'x'}, {'y', 'z'}}) {
+            System.out.print(row.length);
+        }
+        System.out.println();
+    }
+}
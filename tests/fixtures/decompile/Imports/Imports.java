@@ -0,0 +1,16 @@
+import java.util.ArrayList;
+import java.util.HashMap;
+import java.util.List;
+import java.util.Map;
+
+public class Imports {
+    public static void main(String[] args) {
+        List<String> list = new ArrayList<>();
+        list.add("a");
+        Map<String, Integer> map = new HashMap<>();
+        map.put("k", 1);
+        java.awt.List awt = null;
+        System.out.println(list.size() + map.get("k") + (awt == null ? "" : "!"));
+        System.out.println(java.util.Collections.max(list));
+    }
+}
@@ -0,0 +1,29 @@
+public class Interfaces implements Greeter {
+    public String name() {
+        return "world";
+    }
+
+    public static void main(String[] args) {
+        Interfaces i = new Interfaces();
+        System.out.println(i.greet());
+        System.out.println(Greeter.shout("hi"));
+    }
+}
+
+interface Greeter {
+    String PREFIX = "hello ";
+
+    String name();
+
+    default String greet() {
+        return decorate(PREFIX + name());
+    }
+
+    static String shout(String s) {
+        return s.toUpperCase();
+    }
+
+    private String decorate(String s) {
+        return "<" + s + ">";
+    }
+}
@@ -0,0 +1,42 @@
+import java.util.*;
+
+public class Iterables {
+    static int total(List<Integer> numbers) {
+        int total = 0;
+        for (int number : numbers) {
+            total += number;
+        }
+        return total;
+    }
+
+    static String join(Iterable<String> words) {
+        StringBuilder builder = new StringBuilder();
+        for (String word : words) {
+            builder.append(word.length()).append(word);
+        }
+        return builder.toString();
+    }
+
+    static long longs(Set<Long> values) {
+        long sum = 0;
+        for (Long value : values) {
+            sum += value;
+        }
+        return sum;
+    }
+
+    public static void main(String[] args) {
+        List<Integer> numbers = new ArrayList<>(Arrays.asList(1, 2, 3));
+        System.out.println(total(numbers));
+        System.out.println(join(Arrays.asList("a", "bb")));
+        System.out.println(longs(new TreeSet<>(Arrays.asList(4L, 5L))));
+        for (Object object : numbers) {
+            System.out.print(object);
+        }
+        Map<String, Integer> map = new TreeMap<>();
+        map.put("x", 1);
+        for (Map.Entry<String, Integer> entry : map.entrySet()) {
+            System.out.println(entry.getKey() + entry.getValue());
+        }
+    }
+}
@@ -0,0 +1,40 @@
+import java.util.function.IntBinaryOperator;
+import java.util.function.IntSupplier;
+import java.util.function.IntUnaryOperator;
+
+public class Lambdas {
+    int base = 10;
+
+    IntUnaryOperator adder(int n) {
+        return x -> x + n + base;
+    }
+
+    static IntSupplier counter(int start) {
+        int[] count = {start};
+        return () -> {
+            count[0]++;
+            return count[0];
+        };
+    }
+
+    public static void main(String[] args) {
+        Runnable r = () -> System.out.println("run");
+        r.run();
+        int k = 3;
+        IntUnaryOperator twice = a -> a * 2 + k;
+        System.out.println(twice.applyAsInt(4));
+        IntBinaryOperator max = Math::max;
+        System.out.println(max.applyAsInt(2, 9));
+        IntSupplier next = counter(5);
+        next.getAsInt();
+        System.out.println(next.getAsInt());
+        System.out.println(new Lambdas().adder(5).applyAsInt(1));
+        Runnable empty = () -> {};
+        empty.run();
+        IntSupplier nested = () -> {
+            IntUnaryOperator inc = v -> v + k;
+            return inc.applyAsInt(1);
+        };
+        System.out.println(nested.getAsInt());
+    }
+}
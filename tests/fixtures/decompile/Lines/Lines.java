@@ -0,0 +1,22 @@
+public class Lines {
+    private final int x;
+    private final int y;
+
+    Lines(int x, int y) { this.x = x; this.y = y; }
+    Lines(int v) { this(v, v); }
+    Lines(String s) {
+        this(s.length());
+    }
+    int getX() { return x; }
+    int getY() { return y; }
+    int sum() {
+        int s = x;
+        if (s > 0) { s += y; }
+        return s;
+    }
+
+    public static void main(String[] args) {
+        System.out.println(new Lines(2, 3).sum() + new Lines(4).getX());
+        System.out.println(new Lines("abc").getY());
+    }
+}
@@ -0,0 +1,32 @@
+public class NarrowArguments {
+    final byte b;
+    final short s;
+
+    NarrowArguments(byte b, short s) {
+        this.b = b;
+        this.s = s;
+    }
+
+    static String k(byte b, short s, char c, boolean z) {
+        return b + " " + s + " " + c + " " + z;
+    }
+
+    static int t16(byte b, short s, char c) {
+        return b + s + c;
+    }
+
+    static float scale(float f, short s, byte b) {
+        return f * s + b;
+    }
+
+    public static void main(String[] args) {
+        System.out.println(k((byte) 1, (short) 2, 'c', true));
+        System.out.println(t16((byte) 127, (short) -32768, 'z'));
+        System.out.println(scale(1.5f, (short) 3, (byte) -2));
+        NarrowArguments n = new NarrowArguments((byte) -1, (short) 300);
+        System.out.println(n.b + n.s);
+        byte local = 5;
+        short other = 7;
+        System.out.println(t16(local, other, 'a'));
+    }
+}
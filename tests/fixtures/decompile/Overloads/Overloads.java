@@ -0,0 +1,19 @@
+public class Overloads {
+    static String o(Object x) { return "obj"; }
+    static String o(String x) { return "str"; }
+    static String p(Object x) { return "only"; }
+    static String q(CharSequence x) { return "seq"; }
+    static String q(String x) { return "str"; }
+
+    public static void main(String[] args) {
+        String s = "s";
+        System.out.println(o((Object) s) + o(s) + p(s));
+        System.out.println(o((Object) null) + o(null));
+        System.out.println(q((CharSequence) s) + q(s));
+        StringBuilder b = new StringBuilder("x");
+        System.out.println((Object) b);
+        System.out.println(String.valueOf((Object) "v"));
+        Object o = s;
+        System.out.println(o(o) + o((String) o));
+    }
+}
@@ -0,0 +1,45 @@
+import java.io.BufferedReader;
+import java.io.IOException;
+import java.io.StringReader;
+
+public class ResourceFinally {
+    static int counter = 5;
+
+    static String first(String text) throws IOException {
+        try (BufferedReader r = new BufferedReader(new StringReader(text))) {
+            return r.readLine();
+        } finally {
+            counter--;
+        }
+    }
+
+    static String both(String a, String b) throws IOException {
+        try (BufferedReader x = new BufferedReader(new StringReader(a));
+                BufferedReader y = new BufferedReader(new StringReader(b))) {
+            return x.readLine() + y.readLine();
+        } finally {
+            counter--;
+        }
+    }
+
+    static int lines(String text) {
+        int n = 0;
+        try (BufferedReader r = new BufferedReader(new StringReader(text))) {
+            while (r.readLine() != null) {
+                n++;
+            }
+        } catch (IOException e) {
+            n = -1;
+        } finally {
+            counter++;
+        }
+        return n;
+    }
+
+    public static void main(String[] args) throws IOException {
+        System.out.println(first("one\ntwo"));
+        System.out.println(both("a", "b"));
+        System.out.println(lines("a\nb"));
+        System.out.println(counter);
+    }
+}
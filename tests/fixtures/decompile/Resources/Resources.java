@@ -0,0 +1,43 @@
+import java.io.IOException;
+import java.io.StringReader;
+import java.io.BufferedReader;
+
+public class Resources {
+    static String first(String text) throws IOException {
+        try (BufferedReader reader = new BufferedReader(new StringReader(text))) {
+            return reader.readLine();
+        }
+    }
+
+    static int count(String text) {
+        int lines = 0;
+        try (BufferedReader reader = new BufferedReader(new StringReader(text))) {
+            while (reader.readLine() != null) {
+                lines++;
+            }
+        } catch (IOException e) {
+            return -1;
+        }
+        return lines;
+    }
+
+    static String attempt(int x) {
+        StringBuilder log = new StringBuilder();
+        try {
+            log.append("try ");
+            if (x > 0) {
+                return log.append("return ").toString();
+            }
+        } finally {
+            log.append("finally");
+        }
+        return log.toString();
+    }
+
+    public static void main(String[] args) throws IOException {
+        System.out.println(first("one\ntwo"));
+        System.out.println(count("a\nb\nc"));
+        System.out.println(attempt(0));
+        System.out.println(attempt(1));
+    }
+}
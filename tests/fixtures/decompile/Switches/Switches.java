@@ -0,0 +1,29 @@
+public class Switches {
+    static String name(int x) {
+        switch (x) {
+            case 1:
+                return "one";
+            case 2:
+            case 3:
+                return "few";
+            default:
+                return "many";
+        }
+    }
+
+    static int letters(String s) {
+        return switch (s) {
+            case "a", "b" -> 1;
+            case "c" -> 2;
+            default -> {
+                int n = s.length();
+                yield n * 10;
+            }
+        };
+    }
+
+    public static void main(String[] args) {
+        System.out.println(name(1) + name(3) + name(9));
+        System.out.println(letters("b") + letters("c") + letters("long"));
+    }
+}
@@ -0,0 +1,32 @@
+public class Synchronized {
+    private final Object lock = new Object();
+    private int value;
+
+    int increment() {
+        synchronized (lock) {
+            value++;
+            return value;
+        }
+    }
+
+    void add(int n) {
+        synchronized (this) {
+            if (n < 0) {
+                throw new IllegalArgumentException("negative");
+            }
+            value += n;
+        }
+    }
+
+    public static void main(String[] args) {
+        Synchronized s = new Synchronized();
+        s.increment();
+        s.add(5);
+        try {
+            s.add(-1);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+        System.out.println(s.increment());
+    }
+}
@@ -0,0 +1,21 @@
+public class Ternary {
+    static int compare(int a, int b) {
+        return a < b ? -1 : a == b ? 0 : 1;
+    }
+    static int pick(int a, int b) {
+        return a < b ? 2 : a == b ? 0 : 1;
+    }
+    static int pick2(int a, int b) {
+        int r = a > b ? (a == 3 ? 0 : 1) : 5;
+        return r;
+    }
+    static boolean flag(int a, int b) {
+        return a < b ? true : a == b;
+    }
+    public static void main(String[] args) {
+        System.out.println(compare(1, 2) + " " + compare(2, 2) + " " + compare(3, 2));
+        System.out.println(pick(1, 2) + " " + pick(2, 2) + " " + pick(3, 2));
+        System.out.println(pick2(3, 1) + " " + pick2(4, 1) + " " + pick2(0, 1));
+        System.out.println(flag(1, 2) + " " + flag(2, 2) + " " + flag(3, 2));
+    }
+}
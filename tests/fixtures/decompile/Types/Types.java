@@ -0,0 +1,26 @@
+public class Types {
+    static double average(int[] values) {
+        long sum = 0;
+        for (int i = 0; i < values.length; i++) {
+            sum += values[i];
+        }
+        return (double) sum / values.length;
+    }
+
+    public static void main(String[] args) {
+        int x = 7;
+        System.out.println(x);
+        String s = "reused";
+        System.out.println(s);
+        long big = 1L << 40;
+        System.out.println(big + x);
+        System.out.println(average(new int[] {1, 2, 4}));
+        char c = 'q';
+        c++;
+        System.out.println(c);
+        double d = x / 2;
+        System.out.println(d);
+        Object o = s;
+        System.out.println(o.hashCode() == s.hashCode());
+    }
+}